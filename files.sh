@@ -42,13 +42,13 @@ if ! [[ -e ${DEST}run.sh ]] || [[ $FORCE_CREATE == true ]]; then
     echo | sudo tee -a ${DEST}run.sh > /dev/null 2>&1
     echo "export PORTAL_UPDATE_SCRIPT=\"${DEST}update.sh\"" | sudo tee -a ${DEST}run.sh > /dev/null 2>&1
     echo "export PORTAL_DATABASE_PATH=\"${DEST}chronokeep-portal.sqlite\"" | sudo tee -a ${DEST}run.sh > /dev/null 2>&1
+    echo "export PORTAL_CAPTURE_PATH=\"${DEST}captures\"" | sudo tee -a ${DEST}run.sh > /dev/null 2>&1
     echo "export PORTAL_SCREEN_BUS=1" | sudo tee -a ${DEST}run.sh > /dev/null 2>&1
     echo "export PORTAL_LEFT_BUTTON=11" | sudo tee -a ${DEST}run.sh > /dev/null 2>&1
     echo "export PORTAL_UP_BUTTON=5" | sudo tee -a ${DEST}run.sh > /dev/null 2>&1
     echo "export PORTAL_DOWN_BUTTON=6" | sudo tee -a ${DEST}run.sh > /dev/null 2>&1
     echo "export PORTAL_RIGHT_BUTTON=13" | sudo tee -a ${DEST}run.sh > /dev/null 2>&1
     echo "export PORTAL_ENTER_BUTTON=26" | sudo tee -a ${DEST}run.sh > /dev/null 2>&1
-    echo "export PORTAL_ZEBRA_SHIFT=True" | sudo tee -a ${DEST}run.sh > /dev/null 2>&1
     echo | sudo tee -a ${DEST}run.sh > /dev/null 2>&1
     echo "now=\`date +%Y-%m-%d\`" | sudo tee -a ${DEST}run.sh > /dev/null 2>&1
     echo "${DEST}chronokeep-portal \$1 | ts '[%Y-%m-%d %H:%M:%S]' >> ${DEST}logs/\${now}-portal.log 2>&1" | sudo tee -a ${DEST}run.sh > /dev/null 2>&1
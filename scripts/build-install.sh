@@ -51,6 +51,7 @@ if ! [[ -e /portal/run.sh ]]; then
     echo >> /portal/run.sh
     echo "export PORTAL_UPDATE_SCRIPT=\"/portal/update_portal.sh\"" >> /portal/run.sh
     echo "export PORTAL_DATABASE_PATH=\"/portal/chronokeep-portal.sqlite\"" >> /portal/run.sh
+    echo "export PORTAL_CAPTURE_PATH=\"/portal/captures\"" >> /portal/run.sh
     echo "/portal/chronokeep-portal >> /portal/portal.log 2>> /portal/portal.log" >> /portal/run.sh
     sudo chown $USER:root /portal/run.sh
     sudo chmod +x /portal/run.sh
@@ -16,12 +16,17 @@ You should have received a copy of the GNU Affero General Public License
 along with this program.  If not, see <https://www.gnu.org/licenses/>.
  */
 
-use crate::{database::{self, sqlite, DBError, Database}, defaults, objects::setting, sound_board::{SoundBoard, Voice}};
+use std::collections::HashMap;
+
+use crate::{database::{self, sqlite, DBError, Database}, defaults, objects::{bibchip, read, setting}, sound_board::{SoundBoard, Voice}};
 
 pub mod socket;
 pub mod zero_conf;
 pub mod sound;
 
+#[cfg(test)]
+pub mod test;
+
 pub const SETTING_PORTAL_NAME: &str = "SETTING_PORTAL_NAME";
 pub const SETTING_CHIP_TYPE: &str = "SETTING_CHIP_TYPE";
 pub const SETTING_READ_WINDOW: &str = "SETTING_READ_WINDOW";
@@ -55,6 +60,9 @@ pub struct Control {
     pub battery: u8,
     pub screen_type: String,
     pub beep_ignore: u8,
+    // Bibs keyed by chip, chips are lowercase so hex chips match however they were imported.
+    pub bibchips: HashMap<String, String>,
+    pub last_bib: Option<String>,
 }
 
 impl Control {
@@ -101,6 +109,9 @@ impl Control {
         if self.beep_ignore != new_control.beep_ignore {
             self.beep_ignore = new_control.beep_ignore;
         }
+        if self.bibchips != new_control.bibchips {
+            self.bibchips = new_control.bibchips;
+        }
         if self.sound_board.get_voice() != new_control.sound_board.get_voice() {
             return self.sound_board.change_voice(new_control.sound_board.get_voice())
         }
@@ -125,6 +136,8 @@ impl Control {
             battery: 0,
             screen_type: String::from(defaults::DEFAULT_SCREEN_TYPE),
             beep_ignore: defaults::DEFAULT_BEEP_IGNORE,
+            bibchips: HashMap::new(),
+            last_bib: None,
         };
         match sqlite.get_setting(SETTING_PORTAL_NAME) {
             Ok(s) => {
@@ -441,6 +454,32 @@ impl Control {
                 return Err(e)
             }
         }
+        match sqlite.get_bibchips() {
+            Ok(bibchips) => output.set_bibchips(&bibchips),
+            Err(e) => return Err(e)
+        }
         Ok(output)
     }
+
+    pub fn set_bibchips(&mut self, bibchips: &[bibchip::BibChip]) {
+        self.bibchips = bibchips.iter().map(|bc| (bc.chip().to_lowercase(), bc.bib().to_string())).collect();
+    }
+
+    // Puts the bib on each read, reads that were entered by bib already know theirs.
+    pub fn resolve_bibs(&self, reads: &mut [read::Read]) {
+        for read in reads.iter_mut() {
+            if read.ident_type() == read::READ_IDENT_TYPE_BIB {
+                read.set_bib(Some(read.chip().to_string()));
+            } else {
+                read.set_bib(self.bibchips.get(&read.chip().to_lowercase()).cloned());
+            }
+        }
+    }
+
+    // Keeps the bib of the latest read that has one for the display.
+    pub fn bibs_seen(&mut self, reads: &[read::Read]) {
+        if let Some(read) = reads.iter().filter(|r| r.bib().is_some()).max_by_key(|r| (r.seconds(), r.milliseconds())) {
+            self.last_bib = read.bib().map(String::from);
+        }
+    }
 }
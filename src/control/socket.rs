@@ -28,9 +28,9 @@ use chrono::{DateTime, Local, TimeZone, Utc};
 use reqwest::header::{HeaderMap, CONTENT_TYPE, AUTHORIZATION};
 use socket2::{Socket, Type, Protocol, Domain};
 
-use crate::{control::{SETTING_AUTO_REMOTE, SETTING_PORTAL_NAME, socket::requests::AutoUploadQuery, sound::{self, SoundType}}, database::{Database, sqlite}, network::api::{self, Api}, notifier::{self, Notifier}, objects::{read, setting::{self, Setting}}, processor, reader::{self, MAX_ANTENNAS, auto_connect, reconnector::Reconnector, zebra}, remote::{self, remote_util, uploader::{self, Uploader, info::UploadInfo}}, sound_board::Voice};
+use crate::{control::{SETTING_AUTO_REMOTE, SETTING_PORTAL_NAME, socket::requests::AutoUploadQuery, sound::{self, SoundType}}, database::{Database, DBError, sqlite}, network::api::{self, Api}, notifier::{self, Notifier}, objects::{antenna::{AntennaConfig, AntennaMapping}, bibchip::BibChip, clock::ClockConfig, dedup::DedupConfig, filter::TagFilter, gpio::GpioConfig, profile::ProfileConfig, read, read_window::ReadWindowConfig, reconnect::ReconnectPolicy, report_buffer::ReportBufferConfig, setting::{self, Setting}, tag_data::TagDataConfig}, processor, reader::{self, MAX_ANTENNAS, auto_connect, listener, reconnector::Reconnector, zebra}, remote::{self, remote_util, uploader::{self, Uploader, info::UploadInfo}}, sound_board::Voice};
 
-use self::notifications::APINotification;
+use self::{notifications::APINotification, reader_config::ReaderConfig};
 
 use super::{sound::SoundNotifier, zero_conf::ZeroConf};
 
@@ -38,6 +38,7 @@ pub mod requests;
 pub mod responses;
 pub mod errors;
 pub mod notifications;
+pub mod reader_config;
 
 pub const MAX_CONNECTED: usize = 4;
 pub const CONNECTION_TYPE: &str = "chrono_portal";
@@ -216,6 +217,29 @@ pub fn control_loop(
     thread::spawn(move|| {
         auto_connector.run(quick);
     });
+
+    // start a thread to accept connections from readers that connect to us, if we've been told to
+    if let Some(port) = listener::listen_port() {
+        let reader_listener = listener::Listener::new(
+            port,
+            keepalive.clone(),
+            readers.clone(),
+            joiners.clone(),
+            control_sockets.clone(),
+            read_repeaters.clone(),
+            control.clone(),
+            sqlite.clone(),
+            read_saver.clone(),
+            sound_notifier.clone(),
+            notifier.clone(),
+        );
+        let l_joiner = thread::spawn(move|| {
+            reader_listener.run();
+        });
+        if let Ok(mut j) = joiners.lock() {
+            j.push(l_joiner);
+        }
+    }
     
     // Create an object for tracking upload status/errors.
     let upload_info = Arc::new(Mutex::new(UploadInfo::new(uploader::Status::Unknown, 0)));
@@ -780,6 +804,9 @@ fn handle_stream(
                                                     readers.clone(),
                                                 ) {
                                                     Ok(mut reader) => {
+                                                        // keep the errors the reader reported before and any capture that is running
+                                                        reader.errors = old_reader.errors.clone();
+                                                        reader.capture = old_reader.capture.clone();
                                                         let reconnector = Reconnector::new(
                                                             readers.clone(),
                                                             joiners.clone(),
@@ -1040,6 +1067,185 @@ fn handle_stream(
                         no_error = write_reader_list(&stream, &*u_readers) && no_error;
                     }
                 }
+                requests::Request::ReaderAntennaConfigGet { id } => {
+                    no_error = get_reader_config::<Vec<AntennaConfig>>(&stream, &sqlite, &readers, id) && no_error;
+                },
+                requests::Request::ReaderAntennaConfigSet { id, antennas } => {
+                    no_error = set_reader_config(&stream, &sqlite, &readers, id, antennas) && no_error;
+                },
+                requests::Request::ReaderAntennaMapGet { id } => {
+                    no_error = get_reader_config::<Vec<AntennaMapping>>(&stream, &sqlite, &readers, id) && no_error;
+                },
+                requests::Request::ReaderAntennaMapSet { id, map } => {
+                    no_error = set_reader_config(&stream, &sqlite, &readers, id, map) && no_error;
+                },
+                requests::Request::ReaderFiltersGet { id } => {
+                    no_error = get_reader_config::<Vec<TagFilter>>(&stream, &sqlite, &readers, id) && no_error;
+                },
+                requests::Request::ReaderFiltersSet { id, filters } => {
+                    no_error = set_reader_config(&stream, &sqlite, &readers, id, filters) && no_error;
+                },
+                requests::Request::ReaderGpioGet { id } => {
+                    no_error = get_reader_config::<GpioConfig>(&stream, &sqlite, &readers, id) && no_error;
+                },
+                requests::Request::ReaderGpioSet { id, gpio } => {
+                    no_error = set_reader_config(&stream, &sqlite, &readers, id, gpio) && no_error;
+                },
+                requests::Request::ReaderTagDataGet { id } => {
+                    no_error = get_reader_config::<TagDataConfig>(&stream, &sqlite, &readers, id) && no_error;
+                },
+                requests::Request::ReaderTagDataSet { id, tag_data } => {
+                    no_error = set_reader_config(&stream, &sqlite, &readers, id, tag_data) && no_error;
+                },
+                requests::Request::ReaderClockGet { id } => {
+                    no_error = get_reader_config::<ClockConfig>(&stream, &sqlite, &readers, id) && no_error;
+                },
+                requests::Request::ReaderClockSet { id, clock } => {
+                    no_error = set_reader_config(&stream, &sqlite, &readers, id, clock) && no_error;
+                },
+                requests::Request::ReaderReportBufferGet { id } => {
+                    no_error = get_reader_config::<ReportBufferConfig>(&stream, &sqlite, &readers, id) && no_error;
+                },
+                requests::Request::ReaderReportBufferSet { id, report_buffer } => {
+                    no_error = set_reader_config(&stream, &sqlite, &readers, id, report_buffer) && no_error;
+                },
+                requests::Request::ReaderProfilesGet { id } => {
+                    no_error = get_reader_config::<ProfileConfig>(&stream, &sqlite, &readers, id) && no_error;
+                },
+                requests::Request::ReaderProfilesSet { id, profiles } => {
+                    no_error = set_reader_config(&stream, &sqlite, &readers, id, profiles) && no_error;
+                },
+                requests::Request::ReaderProfileSwitch { id, name } => {
+                    no_error = reader_request(&stream, &sqlite, &readers, id, |sq, reader| {
+                        let mut profiles = ProfileConfig::load(sq, &id).map_err(|e| database_error("getting", ProfileConfig::NAME, e))?;
+                        profiles.set_active(name);
+                        profiles.check(reader)?;
+                        profiles.save(sq, &id).map_err(|e| database_error("saving", ProfileConfig::NAME, e))?;
+                        profiles.apply(reader);
+                        Ok(profiles.response(reader))
+                    }) && no_error;
+                },
+                requests::Request::ReaderReconnectPolicyGet { id } => {
+                    no_error = get_reader_config::<ReconnectPolicy>(&stream, &sqlite, &readers, id) && no_error;
+                },
+                requests::Request::ReaderReconnectPolicySet { id, reconnect } => {
+                    no_error = set_reader_config(&stream, &sqlite, &readers, id, reconnect) && no_error;
+                },
+                requests::Request::ReaderDedupGet { id } => {
+                    no_error = get_reader_config::<DedupConfig>(&stream, &sqlite, &readers, id) && no_error;
+                },
+                requests::Request::ReaderDedupSet { id, dedup } => {
+                    no_error = set_reader_config(&stream, &sqlite, &readers, id, dedup) && no_error;
+                },
+                requests::Request::ReaderReadWindowGet { id } => {
+                    no_error = get_reader_config::<ReadWindowConfig>(&stream, &sqlite, &readers, id) && no_error;
+                },
+                requests::Request::ReaderReadWindowSet { id, read_window } => {
+                    no_error = set_reader_config(&stream, &sqlite, &readers, id, read_window) && no_error;
+                },
+                requests::Request::ReaderCapabilities { id } => {
+                    if let Ok(u_readers) = readers.lock() {
+                        match u_readers.iter().find(|x| x.id() == id) {
+                            Some(reader) => {
+                                let mut caps = None;
+                                if let Ok(r_caps) = reader.capabilities.lock() {
+                                    caps = r_caps.clone();
+                                }
+                                no_error = write_response(&stream, &responses::Responses::ReaderCapabilities { reader_name: String::from(reader.nickname()), capabilities: caps }) && no_error;
+                            },
+                            None => {
+                                no_error = write_error(&stream, errors::Errors::NotFound);
+                            }
+                        }
+                    }
+                }
+                requests::Request::ReaderErrors { id } => {
+                    if let Ok(u_readers) = readers.lock() {
+                        match u_readers.iter().find(|x| x.id() == id) {
+                            Some(reader) => {
+                                let mut reader_errors = Vec::new();
+                                if let Ok(log) = reader.errors.lock() {
+                                    reader_errors = log.errors();
+                                }
+                                no_error = write_response(&stream, &responses::Responses::ReaderErrors { reader_name: String::from(reader.nickname()), errors: reader_errors }) && no_error;
+                            },
+                            None => {
+                                no_error = write_error(&stream, errors::Errors::NotFound);
+                            }
+                        }
+                    }
+                }
+                requests::Request::ReaderStats { id } => {
+                    if let Ok(u_readers) = readers.lock() {
+                        match u_readers.iter().find(|x| x.id() == id) {
+                            Some(reader) => {
+                                let mut reader_stats = None;
+                                if let Ok(tracker) = reader.stats.lock() {
+                                    reader_stats = Some(tracker.stats(reader::stats::now()));
+                                }
+                                match reader_stats {
+                                    Some(reader_stats) => {
+                                        no_error = write_response(&stream, &responses::Responses::ReaderStats { reader_name: String::from(reader.nickname()), stats: reader_stats }) && no_error;
+                                    },
+                                    None => {
+                                        no_error = write_error(&stream, errors::Errors::ServerError {
+                                            message: String::from("unable to get reader stats")
+                                        });
+                                    }
+                                }
+                            },
+                            None => {
+                                no_error = write_error(&stream, errors::Errors::NotFound);
+                            }
+                        }
+                    }
+                }
+                requests::Request::ReaderCaptureStart { id } => {
+                    if let Ok(u_readers) = readers.lock() {
+                        match u_readers.iter().find(|x| x.id() == id) {
+                            Some(reader) => {
+                                if let Ok(mut cap) = reader.capture.lock() {
+                                    if cap.is_running() {
+                                        no_error = write_error(&stream, errors::Errors::AlreadyRunning);
+                                    } else {
+                                        match cap.start(reader.nickname()) {
+                                            Ok(path) => {
+                                                println!("Capturing traffic for reader {} to {path}.", reader.nickname());
+                                                no_error = write_response(&stream, &responses::Responses::ReaderCapture { reader_name: String::from(reader.nickname()), running: true, path: Some(path) }) && no_error;
+                                            },
+                                            Err(message) => {
+                                                println!("Error starting capture. {message}");
+                                                no_error = write_error(&stream, errors::Errors::ServerError { message });
+                                            }
+                                        }
+                                    }
+                                }
+                            },
+                            None => {
+                                no_error = write_error(&stream, errors::Errors::NotFound);
+                            }
+                        }
+                    }
+                }
+                requests::Request::ReaderCaptureStop { id } => {
+                    if let Ok(u_readers) = readers.lock() {
+                        match u_readers.iter().find(|x| x.id() == id) {
+                            Some(reader) => {
+                                if let Ok(mut cap) = reader.capture.lock() {
+                                    if !cap.is_running() {
+                                        no_error = write_error(&stream, errors::Errors::NotRunning);
+                                    } else {
+                                        cap.stop();
+                                        no_error = write_response(&stream, &responses::Responses::ReaderCapture { reader_name: String::from(reader.nickname()), running: false, path: cap.path() }) && no_error;
+                                    }
+                                }
+                            },
+                            None => {
+                                no_error = write_error(&stream, errors::Errors::NotFound);
+                            }
+                        }
+                    }
+                }
                 requests::Request::SettingsGet => {
                     if let Ok(sq) = sqlite.lock() {
                         no_error = write_settings(&stream, &get_settings(&sq));
@@ -1644,6 +1850,89 @@ fn handle_stream(
                         }
                     }
                 },
+                requests::Request::BibChipsAdd { bibchips, replace } => {
+                    if let Some(Err(message)) = bibchips.iter().map(|bc| bc.validate()).find(|v| v.is_err()) {
+                        no_error = write_error(&stream, errors::Errors::InvalidBibChip { message });
+                    } else if let Ok(mut sq) = sqlite.lock() {
+                        no_error = save_bibchips(&stream, &mut sq, &control, &bibchips, replace);
+                    }
+                },
+                requests::Request::BibChipsGet => {
+                    if let Ok(sq) = sqlite.lock() {
+                        match sq.get_bibchips() {
+                            Ok(bibchips) => {
+                                no_error = write_response(&stream, &responses::Responses::BibChips { bibchips });
+                            },
+                            Err(e) => {
+                                println!("Error getting bibchips. {e}");
+                                no_error = write_error(&stream, errors::Errors::DatabaseError {
+                                    message: format!("error getting bibchips: {e}")
+                                });
+                            }
+                        }
+                    }
+                },
+                requests::Request::BibChipsDelete => {
+                    if let Ok(mut sq) = sqlite.lock() {
+                        match sq.delete_bibchips() {
+                            Ok(count) => {
+                                if let Ok(mut control) = control.lock() {
+                                    control.bibchips.clear();
+                                }
+                                no_error = write_success(&stream, count);
+                            },
+                            Err(e) => {
+                                println!("Error deleting bibchips. {e}");
+                                no_error = write_error(&stream, errors::Errors::DatabaseError {
+                                    message: format!("error deleting bibchips: {e}")
+                                });
+                            }
+                        }
+                    }
+                },
+                requests::Request::BibChipsRemoteLoad { slug, year } => {
+                    let mut remote_api: Option<api::Api> = None;
+                    if slug.trim().is_empty() || year.trim().is_empty() {
+                        no_error = write_error(&stream, errors::Errors::InvalidBibChip {
+                            message: String::from("an event slug and year are required to load bibchips")
+                        });
+                    } else if let Ok(sq) = sqlite.lock() {
+                        match sq.get_apis() {
+                            Ok(apis) => {
+                                remote_api = apis.into_iter().find(|api| api.kind() == api::API_TYPE_CHRONOKEEP_REMOTE || api.kind() == api::API_TYPE_CHRONOKEEP_REMOTE_SELF);
+                                if remote_api.is_none() {
+                                    no_error = write_error(&stream, errors::Errors::NoRemoteApi);
+                                }
+                            },
+                            Err(e) => {
+                                println!("error getting apis: {e}");
+                                no_error = write_error(&stream, errors::Errors::DatabaseError {
+                                    message: format!("error getting apis: {e}")
+                                });
+                            }
+                        }
+                    }
+                    // the remote api holds the whole mapping for the event, so it replaces ours
+                    if let Some(api) = remote_api {
+                        match download_bibchips(&http_client, &api, &slug, &year) {
+                            Ok(bibchips) => {
+                                // the old mapping is kept rather than replaced with part of the new one
+                                let invalid: Vec<String> = bibchips.iter().filter_map(|bc| bc.validate().err()).collect();
+                                if let Some(message) = invalid.first() {
+                                    no_error = write_error(&stream, errors::Errors::InvalidBibChip {
+                                        message: format!("{} of {} bibchips from the remote api are invalid, first: {message}", invalid.len(), bibchips.len())
+                                    });
+                                } else if let Ok(mut sq) = sqlite.lock() {
+                                    no_error = save_bibchips(&stream, &mut sq, &control, &bibchips, true);
+                                }
+                            },
+                            Err(e) => {
+                                println!("Error getting bibchips from remote api. {:?}", e);
+                                no_error = write_error(&stream, e);
+                            }
+                        }
+                    }
+                },
                 requests::Request::ReadsAdd { read } => {
                     if read.is_valid() == false {
                         no_error = write_error(&stream, errors::Errors::InvalidRead)
@@ -1651,6 +1940,10 @@ fn handle_stream(
                         if let Ok(mut sq) = sqlite.lock() {
                             let mut reads: Vec<read::Read> = Vec::new();
                             reads.push(read);
+                            if let Ok(mut control) = control.lock() {
+                                control.resolve_bibs(&mut reads);
+                                control.bibs_seen(&reads);
+                            }
                             match sq.save_reads(&reads) {
                                 Ok(_) => {
                                     if let Ok(sockets) = control_sockets.lock() {
@@ -1887,6 +2180,90 @@ fn handle_stream(
     }
 }
 
+// Saves the bibchips given, replacing the old mapping if asked, and loads the new mapping for the readers.
+fn save_bibchips(
+    stream: &TcpStream,
+    sq: &mut MutexGuard<sqlite::SQLite>,
+    control: &Arc<Mutex<super::Control>>,
+    bibchips: &[BibChip],
+    replace: bool,
+) -> bool {
+    let count = match sq.save_bibchips(bibchips, replace) {
+        Ok(count) => count,
+        Err(e) => {
+            println!("Error saving bibchips. {e}");
+            return write_error(stream, errors::Errors::DatabaseError {
+                message: format!("error saving bibchips: {e}")
+            });
+        }
+    };
+    match sq.get_bibchips() {
+        Ok(all) => {
+            if let Ok(mut control) = control.lock() {
+                control.set_bibchips(&all);
+            }
+        },
+        Err(e) => println!("Error getting bibchips. {e}"),
+    }
+    write_success(stream, count)
+}
+
+// Finds the reader a request is for and writes the response built for it, or the error if one couldn't be built.
+fn reader_request<F>(
+    stream: &TcpStream,
+    sqlite: &Arc<Mutex<sqlite::SQLite>>,
+    readers: &Arc<Mutex<Vec<reader::Reader>>>,
+    id: i64,
+    respond: F,
+) -> bool where F: FnOnce(&mut sqlite::SQLite, &reader::Reader) -> Result<responses::Responses, errors::Errors> {
+    if let Ok(mut sq) = sqlite.lock() {
+        if let Ok(u_readers) = readers.lock() {
+            return match u_readers.iter().find(|x| x.id() == id) {
+                Some(reader) => match respond(&mut sq, reader) {
+                    Ok(response) => write_response(stream, &response),
+                    Err(error) => write_error(stream, error),
+                },
+                None => write_error(stream, errors::Errors::NotFound),
+            }
+        }
+    }
+    true
+}
+
+fn database_error(action: &str, name: &str, e: DBError) -> errors::Errors {
+    println!("Error {action} {name}: {e}");
+    errors::Errors::DatabaseError {
+        message: format!("unexpected error {action} {name}: {e}")
+    }
+}
+
+fn get_reader_config<T: ReaderConfig>(
+    stream: &TcpStream,
+    sqlite: &Arc<Mutex<sqlite::SQLite>>,
+    readers: &Arc<Mutex<Vec<reader::Reader>>>,
+    id: i64,
+) -> bool {
+    reader_request(stream, sqlite, readers, id, |sq, reader| {
+        let config = T::load(sq, &id).map_err(|e| database_error("getting", T::NAME, e))?;
+        Ok(config.response(reader))
+    })
+}
+
+fn set_reader_config<T: ReaderConfig>(
+    stream: &TcpStream,
+    sqlite: &Arc<Mutex<sqlite::SQLite>>,
+    readers: &Arc<Mutex<Vec<reader::Reader>>>,
+    id: i64,
+    config: T,
+) -> bool {
+    reader_request(stream, sqlite, readers, id, |sq, reader| {
+        config.check(reader)?;
+        config.save(sq, &id).map_err(|e| database_error("saving", T::NAME, e))?;
+        config.apply(reader);
+        Ok(config.response(reader))
+    })
+}
+
 fn get_available_port() -> u16 {
     match (4488..5588).find(|port| {
         match TcpListener::bind(("0.0.0.0", *port)) {
@@ -2275,6 +2652,46 @@ fn write_api_list(
     true
 }
 
+pub fn write_response(
+    stream: &TcpStream,
+    response: &responses::Responses,
+) -> bool {
+    match serde_json::to_writer(stream, response) {
+        Ok(_) => {},
+        Err(e) => {
+            match e.io_error_kind() {
+                Some(ErrorKind::BrokenPipe) |
+                Some(ErrorKind::ConnectionReset) |
+                Some(ErrorKind::ConnectionAborted) => {
+                    return false;
+                },
+                _ => {
+                    println!("18/ Something went wrong writing to the socket. {e}");
+                    return false;
+                }
+            }
+        }
+    };
+    let mut writer = stream;
+    match writer.write_all(b"\n") {
+        Ok(_) => {},
+        Err(e) => {
+            match e.kind() {
+                ErrorKind::BrokenPipe |
+                ErrorKind::ConnectionReset |
+                ErrorKind::ConnectionAborted => {
+                    return false;
+                },
+                _ => {
+                    println!("18/ Something went wrong writing to the socket. {e}");
+                    return false;
+                }
+            }
+        }
+    };
+    true
+}
+
 pub fn write_reader_antennas(
     stream: &TcpStream,
     reader_name: String,
@@ -2614,9 +3031,7 @@ pub fn upload_reads(
     let url = api.uri();
     let response = match http_client.post(format!("{url}reads/add"))
         .headers(construct_headers(api.token()))
-        .json(&remote::requests::UploadReadsRequest {
-            reads: reads.to_vec()
-        })
+        .json(&remote::requests::UploadReadsRequest::new(reads))
         .send() {
             Ok(resp) => resp,
             Err(e) => {
@@ -2639,3 +3054,39 @@ pub fn upload_reads(
     };
     Ok(output)
 }
+
+pub fn download_bibchips(
+    http_client: &reqwest::blocking::Client,
+    api: &Api,
+    slug: &str,
+    year: &str,
+) -> Result<Vec<BibChip>, errors::Errors> {
+    let url = api.uri();
+    let response = match http_client.post(format!("{url}bibchips/get"))
+        .headers(construct_headers(api.token()))
+        .json(&remote::requests::GetBibChipsRequest {
+            slug: String::from(slug),
+            year: String::from(year),
+        })
+        .send() {
+            Ok(resp) => resp,
+            Err(e) => {
+                return Err(errors::Errors::ServerError { message: format!("error trying to talk to api: {e}") })
+            }
+        };
+    let output = match response.status() {
+        reqwest::StatusCode::OK => {
+            let resp_body: remote::responses::GetBibChipsResponse = match response.json() {
+                Ok(it) => it,
+                Err(e) => {
+                    return Err(errors::Errors::ServerError { message: format!("error trying to parse response from api: {e}") })
+                }
+            };
+            resp_body.bibchips
+        },
+        other => {
+            return Err(errors::Errors::ServerError { message: format!("invalid status code: {other}") })
+        }
+    };
+    Ok(output)
+}
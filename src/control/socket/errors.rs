@@ -54,4 +54,34 @@ pub enum Errors {
     NotAllowed {
         message: String,
     },
+    InvalidAntenna {
+        message: String,
+    },
+    InvalidFilter {
+        message: String,
+    },
+    InvalidGpio {
+        message: String,
+    },
+    InvalidTagData {
+        message: String,
+    },
+    InvalidClock {
+        message: String,
+    },
+    InvalidProfile {
+        message: String,
+    },
+    InvalidReconnect {
+        message: String,
+    },
+    InvalidDedup {
+        message: String,
+    },
+    InvalidReadWindow {
+        message: String,
+    },
+    InvalidBibChip {
+        message: String,
+    },
 }
@@ -0,0 +1,300 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::{database::{sqlite, DBError, Database}, objects::{antenna::{self, AntennaConfig, AntennaMapping}, clock::ClockConfig, dedup::DedupConfig, filter::TagFilter, gpio::GpioConfig, profile::ProfileConfig, read_window::ReadWindowConfig, reconnect::ReconnectPolicy, report_buffer::ReportBufferConfig, tag_data::TagDataConfig}, reader::{Reader, MAX_ANTENNAS}};
+
+use super::{errors::Errors, responses::Responses};
+
+// A setting saved for each reader that control sockets can get and set.
+pub trait ReaderConfig: Sized {
+    // What the setting is called in error messages.
+    const NAME: &'static str;
+
+    fn load(sqlite: &sqlite::SQLite, reader_id: &i64) -> Result<Self, DBError>;
+    fn save(&self, sqlite: &mut sqlite::SQLite, reader_id: &i64) -> Result<usize, DBError>;
+    fn response(self, reader: &Reader) -> Responses;
+
+    // Checked before saving, the reader is there to check against what it told us it can do.
+    fn check(&self, _reader: &Reader) -> Result<(), Errors> {
+        Ok(())
+    }
+
+    // Most settings are used the next time the reader connects, these let a running reader pick them up right away.
+    fn apply(&self, _reader: &Reader) {}
+}
+
+impl ReaderConfig for Vec<AntennaConfig> {
+    const NAME: &'static str = "antenna configuration";
+
+    fn load(sqlite: &sqlite::SQLite, reader_id: &i64) -> Result<Self, DBError> {
+        sqlite.get_antenna_configs(reader_id)
+    }
+
+    fn save(&self, sqlite: &mut sqlite::SQLite, reader_id: &i64) -> Result<usize, DBError> {
+        sqlite.save_antenna_configs(reader_id, self)
+    }
+
+    fn response(self, reader: &Reader) -> Responses {
+        Responses::ReaderAntennaConfig { reader_name: String::from(reader.nickname()), antennas: self }
+    }
+
+    fn check(&self, _reader: &Reader) -> Result<(), Errors> {
+        match self.iter().find(|x| !x.is_valid()) {
+            Some(invalid) => Err(Errors::InvalidAntenna {
+                message: format!("antenna {} is outside of the supported range of 1 to {MAX_ANTENNAS}", invalid.antenna())
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+impl ReaderConfig for Vec<AntennaMapping> {
+    const NAME: &'static str = "antenna map";
+
+    fn load(sqlite: &sqlite::SQLite, reader_id: &i64) -> Result<Self, DBError> {
+        sqlite.get_antenna_map(reader_id)
+    }
+
+    fn save(&self, sqlite: &mut sqlite::SQLite, reader_id: &i64) -> Result<usize, DBError> {
+        sqlite.save_antenna_map(reader_id, self)
+    }
+
+    fn response(self, reader: &Reader) -> Responses {
+        Responses::ReaderAntennaMap { reader_name: String::from(reader.nickname()), map: self }
+    }
+
+    fn check(&self, _reader: &Reader) -> Result<(), Errors> {
+        antenna::validate_map(self).map_err(|message| Errors::InvalidAntenna { message })
+    }
+}
+
+impl ReaderConfig for Vec<TagFilter> {
+    const NAME: &'static str = "tag filters";
+
+    fn load(sqlite: &sqlite::SQLite, reader_id: &i64) -> Result<Self, DBError> {
+        sqlite.get_tag_filters(reader_id)
+    }
+
+    fn save(&self, sqlite: &mut sqlite::SQLite, reader_id: &i64) -> Result<usize, DBError> {
+        sqlite.save_tag_filters(reader_id, self)
+    }
+
+    fn response(self, reader: &Reader) -> Responses {
+        Responses::ReaderFilters { reader_name: String::from(reader.nickname()), filters: self }
+    }
+
+    fn check(&self, _reader: &Reader) -> Result<(), Errors> {
+        match self.iter().find(|x| !x.is_valid()) {
+            Some(invalid) => Err(Errors::InvalidFilter {
+                message: format!("invalid filter with mask '{}' on memory bank {}", invalid.mask(), invalid.memory_bank())
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+impl ReaderConfig for GpioConfig {
+    const NAME: &'static str = "gpio configuration";
+
+    fn load(sqlite: &sqlite::SQLite, reader_id: &i64) -> Result<Self, DBError> {
+        sqlite.get_gpio_config(reader_id)
+    }
+
+    fn save(&self, sqlite: &mut sqlite::SQLite, reader_id: &i64) -> Result<usize, DBError> {
+        sqlite.save_gpio_config(reader_id, self)
+    }
+
+    fn response(self, reader: &Reader) -> Responses {
+        Responses::ReaderGpio { reader_name: String::from(reader.nickname()), gpio: self }
+    }
+
+    fn check(&self, reader: &Reader) -> Result<(), Errors> {
+        // check the ports against the reader if it's told us how many it has
+        let (mut gpis, mut gpos) = (0, 0);
+        if let Ok(caps) = reader.capabilities.lock() {
+            if let Some(caps) = &*caps {
+                (gpis, gpos) = (caps.gpis, caps.gpos);
+            }
+        }
+        self.validate(gpis, gpos).map_err(|message| Errors::InvalidGpio { message })
+    }
+}
+
+impl ReaderConfig for TagDataConfig {
+    const NAME: &'static str = "tag data configuration";
+
+    fn load(sqlite: &sqlite::SQLite, reader_id: &i64) -> Result<Self, DBError> {
+        sqlite.get_tag_data_config(reader_id)
+    }
+
+    fn save(&self, sqlite: &mut sqlite::SQLite, reader_id: &i64) -> Result<usize, DBError> {
+        sqlite.save_tag_data_config(reader_id, self)
+    }
+
+    fn response(self, reader: &Reader) -> Responses {
+        Responses::ReaderTagData { reader_name: String::from(reader.nickname()), tag_data: self }
+    }
+
+    fn check(&self, _reader: &Reader) -> Result<(), Errors> {
+        self.validate().map_err(|message| Errors::InvalidTagData { message })
+    }
+}
+
+impl ReaderConfig for ClockConfig {
+    const NAME: &'static str = "clock configuration";
+
+    fn load(sqlite: &sqlite::SQLite, reader_id: &i64) -> Result<Self, DBError> {
+        sqlite.get_clock_config(reader_id)
+    }
+
+    fn save(&self, sqlite: &mut sqlite::SQLite, reader_id: &i64) -> Result<usize, DBError> {
+        sqlite.save_clock_config(reader_id, self)
+    }
+
+    fn response(self, reader: &Reader) -> Responses {
+        let mut status = None;
+        if let Ok(tracker) = reader.clock.lock() {
+            status = tracker.status(reader.nickname());
+        }
+        Responses::ReaderClock { reader_name: String::from(reader.nickname()), clock: self, status }
+    }
+
+    fn check(&self, reader: &Reader) -> Result<(), Errors> {
+        // the reader would otherwise connect without ever setting it
+        if self.set_reader_clock() && !reader.can_set_clock() {
+            return Err(Errors::InvalidClock { message: format!("unable to set the clock on {} readers", reader.kind()) })
+        }
+        self.validate().map_err(|message| Errors::InvalidClock { message })
+    }
+}
+
+impl ReaderConfig for ReportBufferConfig {
+    const NAME: &'static str = "report buffer configuration";
+
+    fn load(sqlite: &sqlite::SQLite, reader_id: &i64) -> Result<Self, DBError> {
+        sqlite.get_report_buffer_config(reader_id)
+    }
+
+    fn save(&self, sqlite: &mut sqlite::SQLite, reader_id: &i64) -> Result<usize, DBError> {
+        sqlite.save_report_buffer_config(reader_id, self)
+    }
+
+    fn response(self, reader: &Reader) -> Responses {
+        Responses::ReaderReportBuffer { reader_name: String::from(reader.nickname()), report_buffer: self }
+    }
+}
+
+impl ReaderConfig for ProfileConfig {
+    const NAME: &'static str = "reading profiles";
+
+    fn load(sqlite: &sqlite::SQLite, reader_id: &i64) -> Result<Self, DBError> {
+        sqlite.get_profile_config(reader_id)
+    }
+
+    fn save(&self, sqlite: &mut sqlite::SQLite, reader_id: &i64) -> Result<usize, DBError> {
+        sqlite.save_profile_config(reader_id, self)
+    }
+
+    fn response(self, reader: &Reader) -> Responses {
+        Responses::ReaderProfiles { reader_name: String::from(reader.nickname()), profiles: self }
+    }
+
+    fn check(&self, reader: &Reader) -> Result<(), Errors> {
+        // check the antennas against the reader if it's told us how many it has
+        let mut max_antennas = 0;
+        if let Ok(caps) = reader.capabilities.lock() {
+            if let Some(caps) = &*caps {
+                max_antennas = caps.max_antennas;
+            }
+        }
+        self.validate(max_antennas).map_err(|message| Errors::InvalidProfile { message })
+    }
+
+    // a reader that's reading switches to the active profile
+    fn apply(&self, reader: &Reader) {
+        reader.change_profiles(self.clone());
+    }
+}
+
+impl ReaderConfig for ReconnectPolicy {
+    const NAME: &'static str = "reconnect policy";
+
+    fn load(sqlite: &sqlite::SQLite, reader_id: &i64) -> Result<Self, DBError> {
+        sqlite.get_reconnect_policy(reader_id)
+    }
+
+    // the policy is read before every attempt, so a reconnect in progress picks it up
+    fn save(&self, sqlite: &mut sqlite::SQLite, reader_id: &i64) -> Result<usize, DBError> {
+        sqlite.save_reconnect_policy(reader_id, self)
+    }
+
+    fn response(self, reader: &Reader) -> Responses {
+        Responses::ReaderReconnectPolicy { reader_name: String::from(reader.nickname()), reconnect: self }
+    }
+
+    fn check(&self, _reader: &Reader) -> Result<(), Errors> {
+        self.validate().map_err(|message| Errors::InvalidReconnect { message })
+    }
+}
+
+impl ReaderConfig for DedupConfig {
+    const NAME: &'static str = "dedup configuration";
+
+    fn load(sqlite: &sqlite::SQLite, reader_id: &i64) -> Result<Self, DBError> {
+        sqlite.get_dedup_config(reader_id)
+    }
+
+    fn save(&self, sqlite: &mut sqlite::SQLite, reader_id: &i64) -> Result<usize, DBError> {
+        sqlite.save_dedup_config(reader_id, self)
+    }
+
+    fn response(self, reader: &Reader) -> Responses {
+        Responses::ReaderDedup { reader_name: String::from(reader.nickname()), dedup: self }
+    }
+
+    fn check(&self, _reader: &Reader) -> Result<(), Errors> {
+        self.validate().map_err(|message| Errors::InvalidDedup { message })
+    }
+}
+
+impl ReaderConfig for ReadWindowConfig {
+    const NAME: &'static str = "read windows";
+
+    fn load(sqlite: &sqlite::SQLite, reader_id: &i64) -> Result<Self, DBError> {
+        sqlite.get_read_window_config(reader_id)
+    }
+
+    fn save(&self, sqlite: &mut sqlite::SQLite, reader_id: &i64) -> Result<usize, DBError> {
+        sqlite.save_read_window_config(reader_id, self)
+    }
+
+    fn response(self, reader: &Reader) -> Responses {
+        Responses::ReaderReadWindow { reader_name: String::from(reader.nickname()), read_window: self }
+    }
+
+    fn check(&self, _reader: &Reader) -> Result<(), Errors> {
+        self.validate().map_err(|message| Errors::InvalidReadWindow { message })
+    }
+
+    // a reader that's reading uses the new windows for the next tags it sees
+    fn apply(&self, reader: &Reader) {
+        if let Ok(mut windows) = reader.read_windows.lock() {
+            *windows = self.clone();
+        }
+    }
+}
@@ -18,7 +18,7 @@ along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 use serde::Deserialize;
 
-use crate::{network::api, objects::{read, setting::Setting}};
+use crate::{network::api, objects::{antenna::{AntennaConfig, AntennaMapping}, bibchip::BibChip, clock::ClockConfig, dedup::DedupConfig, filter::TagFilter, gpio::GpioConfig, profile::ProfileConfig, read, read_window::ReadWindowConfig, reconnect::ReconnectPolicy, report_buffer::ReportBufferConfig, setting::Setting, tag_data::TagDataConfig}};
 
 use super::notifications;
 
@@ -45,6 +45,18 @@ pub enum Request {
     ApiRemove {
         id: i64,
     },
+    // Bib and chip mapping requests, adding with replace set clears the old mapping first.
+    BibChipsAdd {
+        bibchips: Vec<BibChip>,
+        #[serde(default)]
+        replace: bool,
+    },
+    BibChipsGet,
+    BibChipsDelete,
+    BibChipsRemoteLoad {
+        slug: String,
+        year: String,
+    },
     // Connection or program related requests
     Connect {
         reads: bool,
@@ -82,6 +94,104 @@ pub enum Request {
     ReaderStartAll,
     ReaderStopAll,
     ReaderGetAll,
+    ReaderCapabilities {
+        id: i64,
+    },
+    ReaderAntennaConfigGet {
+        id: i64,
+    },
+    ReaderAntennaConfigSet {
+        id: i64,
+        antennas: Vec<AntennaConfig>,
+    },
+    ReaderAntennaMapGet {
+        id: i64,
+    },
+    ReaderAntennaMapSet {
+        id: i64,
+        map: Vec<AntennaMapping>,
+    },
+    ReaderFiltersGet {
+        id: i64,
+    },
+    ReaderFiltersSet {
+        id: i64,
+        filters: Vec<TagFilter>,
+    },
+    ReaderGpioGet {
+        id: i64,
+    },
+    ReaderGpioSet {
+        id: i64,
+        gpio: GpioConfig,
+    },
+    ReaderTagDataGet {
+        id: i64,
+    },
+    ReaderTagDataSet {
+        id: i64,
+        tag_data: TagDataConfig,
+    },
+    ReaderClockGet {
+        id: i64,
+    },
+    ReaderClockSet {
+        id: i64,
+        clock: ClockConfig,
+    },
+    ReaderReportBufferGet {
+        id: i64,
+    },
+    ReaderReportBufferSet {
+        id: i64,
+        report_buffer: ReportBufferConfig,
+    },
+    ReaderProfilesGet {
+        id: i64,
+    },
+    ReaderProfilesSet {
+        id: i64,
+        profiles: ProfileConfig,
+    },
+    // Switches to a saved profile, no name switches back to the default.
+    ReaderProfileSwitch {
+        id: i64,
+        #[serde(default)]
+        name: Option<String>,
+    },
+    ReaderReconnectPolicyGet {
+        id: i64,
+    },
+    ReaderReconnectPolicySet {
+        id: i64,
+        reconnect: ReconnectPolicy,
+    },
+    ReaderDedupGet {
+        id: i64,
+    },
+    ReaderDedupSet {
+        id: i64,
+        dedup: DedupConfig,
+    },
+    ReaderReadWindowGet {
+        id: i64,
+    },
+    ReaderReadWindowSet {
+        id: i64,
+        read_window: ReadWindowConfig,
+    },
+    ReaderErrors {
+        id: i64,
+    },
+    ReaderStats {
+        id: i64,
+    },
+    ReaderCaptureStart {
+        id: i64,
+    },
+    ReaderCaptureStop {
+        id: i64,
+    },
     // Reads related requests
     ReadsAdd {
         read: read::Read
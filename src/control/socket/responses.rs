@@ -18,7 +18,7 @@ along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 use serde::Serialize;
 
-use crate::{network::api, objects::{read, setting}, reader::MAX_ANTENNAS, remote::uploader};
+use crate::{network::api, objects::{antenna::{AntennaConfig, AntennaMapping}, bibchip::BibChip, clock::ClockConfig, dedup::DedupConfig, filter::TagFilter, gpio::GpioConfig, profile::ProfileConfig, read, read_window::ReadWindowConfig, reconnect::ReconnectPolicy, report_buffer::ReportBufferConfig, setting, tag_data::TagDataConfig}, reader::{capabilities::ReaderCapabilities, clock::ClockStatus, errors::ReaderError, stats::ReaderStats, MAX_ANTENNAS}, remote::uploader};
 
 use super::{errors, notifications};
 
@@ -32,6 +32,87 @@ pub enum Responses {
         reader_name: String,
         antennas: [u8;MAX_ANTENNAS],
     },
+    ReaderCapabilities {
+        reader_name: String,
+        capabilities: Option<ReaderCapabilities>,
+    },
+    ReaderAntennaConfig {
+        reader_name: String,
+        antennas: Vec<AntennaConfig>,
+    },
+    ReaderAntennaMap {
+        reader_name: String,
+        map: Vec<AntennaMapping>,
+    },
+    ReaderFilters {
+        reader_name: String,
+        filters: Vec<TagFilter>,
+    },
+    ReaderGpio {
+        reader_name: String,
+        gpio: GpioConfig,
+    },
+    ReaderTagData {
+        reader_name: String,
+        tag_data: TagDataConfig,
+    },
+    ReaderClock {
+        reader_name: String,
+        clock: ClockConfig,
+        status: Option<ClockStatus>,
+    },
+    ReaderClockWarning {
+        status: ClockStatus,
+    },
+    ReaderReportBuffer {
+        reader_name: String,
+        report_buffer: ReportBufferConfig,
+    },
+    ReaderProfiles {
+        reader_name: String,
+        profiles: ProfileConfig,
+    },
+    ReaderReconnectPolicy {
+        reader_name: String,
+        reconnect: ReconnectPolicy,
+    },
+    ReaderDedup {
+        reader_name: String,
+        dedup: DedupConfig,
+    },
+    ReaderReadWindow {
+        reader_name: String,
+        read_window: ReadWindowConfig,
+    },
+    // Sent to every control socket as each reconnect attempt starts, max attempts of 0 never gives up.
+    ReaderReconnecting {
+        reader_name: String,
+        attempt: u32,
+        max_attempts: u32,
+    },
+    ReaderReconnectFailed {
+        reader_name: String,
+        attempts: u32,
+    },
+    ReaderError {
+        reader_name: String,
+        state: String,
+        code: u16,
+        description: String,
+    },
+    ReaderErrors {
+        reader_name: String,
+        errors: Vec<ReaderError>,
+    },
+    ReaderStats {
+        reader_name: String,
+        stats: ReaderStats,
+    },
+    ReaderCapture {
+        reader_name: String,
+        running: bool,
+        path: Option<String>,
+    },
     Error {
         error: errors::Errors,
     },
@@ -55,6 +136,9 @@ pub enum Responses {
     Reads {
         list: Vec<read::Read>,
     },
+    BibChips {
+        bibchips: Vec<BibChip>,
+    },
     Success {
         count: usize,
     },
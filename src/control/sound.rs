@@ -18,7 +18,7 @@ along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 use std::{collections::HashMap, sync::{Arc, Condvar, Mutex}, time::{Duration, Instant, SystemTime}};
 
-use crate::{reader::zebra::TagData, sound_board};
+use crate::{reader::generic::TagData, sound_board};
 
 pub struct Sounds {
     control: Arc<Mutex<super::Control>>,
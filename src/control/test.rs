@@ -0,0 +1,82 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::{database::sqlite::tests::{finalize_tests, setup_tests}, objects::{bibchip::BibChip, read}};
+
+use super::Control;
+
+fn chip_read(chip: &str, seconds: i64) -> read::Read {
+    read::Read::new(0, String::from(chip), seconds, 0, seconds, 0, 1, String::from("reader"), String::from("-50"), 0)
+}
+
+#[test]
+fn test_resolve_bibs() {
+    let unique_path = "./test_control_resolve_bibs.sqlite";
+    let mut sqlite = setup_tests(unique_path);
+    let mut control = Control::new(&mut sqlite).unwrap();
+    control.set_bibchips(&[
+        BibChip::new(String::from("101"), String::from("E2001A")),
+        BibChip::new(String::from("102"), String::from("1002")),
+    ]);
+    let manual: read::Read = serde_json::from_str(r#"{"identifier":"250","seconds":100,"milliseconds":0,"reader_seconds":100,"reader_milliseconds":0,"antenna":0,"reader":"","rssi":"","ident_type":"bib","type":"manual"}"#).unwrap();
+    let mut reads = vec![
+        chip_read("e2001a", 100),
+        chip_read("1002", 101),
+        chip_read("9999", 102),
+        manual,
+    ];
+    control.resolve_bibs(&mut reads);
+    // chips match no matter the case they were imported with
+    assert_eq!(Some("101"), reads[0].bib());
+    assert_eq!(Some("102"), reads[1].bib());
+    assert_eq!(None, reads[2].bib());
+    // reads entered by bib keep the bib they were entered with
+    assert_eq!(Some("250"), reads[3].bib());
+    // a new mapping replaces the old one
+    control.set_bibchips(&[BibChip::new(String::from("103"), String::from("9999"))]);
+    control.resolve_bibs(&mut reads);
+    assert_eq!(None, reads[0].bib());
+    assert_eq!(Some("103"), reads[2].bib());
+    finalize_tests(unique_path);
+}
+
+#[test]
+fn test_bibs_seen() {
+    let unique_path = "./test_control_bibs_seen.sqlite";
+    let mut sqlite = setup_tests(unique_path);
+    let mut control = Control::new(&mut sqlite).unwrap();
+    assert_eq!(None, control.last_bib);
+    let mut reads = vec![
+        chip_read("1001", 200),
+        chip_read("1002", 300),
+        chip_read("1003", 100),
+        chip_read("9999", 400),
+    ];
+    reads[0].set_bib(Some(String::from("101")));
+    reads[1].set_bib(Some(String::from("102")));
+    reads[2].set_bib(Some(String::from("103")));
+    // the latest read with a bib wins even if it isn't last in the list or the latest read
+    control.bibs_seen(&reads);
+    assert_eq!(Some(String::from("102")), control.last_bib);
+    // reads without bibs leave the last bib alone
+    control.bibs_seen(&[chip_read("9999", 500)]);
+    assert_eq!(Some(String::from("102")), control.last_bib);
+    control.bibs_seen(&[]);
+    assert_eq!(Some(String::from("102")), control.last_bib);
+    finalize_tests(unique_path);
+}
@@ -16,7 +16,7 @@ You should have received a copy of the GNU Affero General Public License
 along with this program.  If not, see <https://www.gnu.org/licenses/>.
  */
 
-use crate::objects::{read, setting};
+use crate::objects::{antenna, bibchip, clock, dedup, filter, gpio, profile, read, read_window, reconnect, report_buffer, setting, tag_data};
 use crate::network::api;
 use crate::reader;
 use std::fmt;
@@ -63,6 +63,46 @@ pub trait Database {
     fn get_reader(&self, id: &i64) -> Result<reader::Reader, DBError>;
     fn get_readers(&self) -> Result<Vec<reader::Reader>, DBError>;
     fn delete_reader(&mut self, id: &i64) -> Result<usize, DBError>;
+    // Reader identifiers
+    fn save_reader_identifier(&mut self, reader_id: &i64, identifier: &str) -> Result<usize, DBError>;
+    fn get_reader_by_identifier(&self, identifier: &str) -> Result<reader::Reader, DBError>;
+    // Reader antenna configuration
+    fn save_antenna_configs(&mut self, reader_id: &i64, configs: &[antenna::AntennaConfig]) -> Result<usize, DBError>;
+    fn get_antenna_configs(&self, reader_id: &i64) -> Result<Vec<antenna::AntennaConfig>, DBError>;
+    // Reader antenna map
+    fn save_antenna_map(&mut self, reader_id: &i64, map: &[antenna::AntennaMapping]) -> Result<usize, DBError>;
+    fn get_antenna_map(&self, reader_id: &i64) -> Result<Vec<antenna::AntennaMapping>, DBError>;
+    // Reader tag filters
+    fn save_tag_filters(&mut self, reader_id: &i64, filters: &[filter::TagFilter]) -> Result<usize, DBError>;
+    fn get_tag_filters(&self, reader_id: &i64) -> Result<Vec<filter::TagFilter>, DBError>;
+    // Reader GPI triggers and GPO outputs
+    fn save_gpio_config(&mut self, reader_id: &i64, config: &gpio::GpioConfig) -> Result<usize, DBError>;
+    fn get_gpio_config(&self, reader_id: &i64) -> Result<gpio::GpioConfig, DBError>;
+    // Reader TID, user memory and tag metadata reporting
+    fn save_tag_data_config(&mut self, reader_id: &i64, config: &tag_data::TagDataConfig) -> Result<usize, DBError>;
+    fn get_tag_data_config(&self, reader_id: &i64) -> Result<tag_data::TagDataConfig, DBError>;
+    // Reader clock
+    fn save_clock_config(&mut self, reader_id: &i64, config: &clock::ClockConfig) -> Result<usize, DBError>;
+    fn get_clock_config(&self, reader_id: &i64) -> Result<clock::ClockConfig, DBError>;
+    // Reader report buffering
+    fn save_report_buffer_config(&mut self, reader_id: &i64, config: &report_buffer::ReportBufferConfig) -> Result<usize, DBError>;
+    fn get_report_buffer_config(&self, reader_id: &i64) -> Result<report_buffer::ReportBufferConfig, DBError>;
+    // Reader reading profiles
+    fn save_profile_config(&mut self, reader_id: &i64, config: &profile::ProfileConfig) -> Result<usize, DBError>;
+    fn get_profile_config(&self, reader_id: &i64) -> Result<profile::ProfileConfig, DBError>;
+    // Reader reconnect policy
+    fn save_reconnect_policy(&mut self, reader_id: &i64, policy: &reconnect::ReconnectPolicy) -> Result<usize, DBError>;
+    fn get_reconnect_policy(&self, reader_id: &i64) -> Result<reconnect::ReconnectPolicy, DBError>;
+    // Reader read window deduplication
+    fn save_dedup_config(&mut self, reader_id: &i64, config: &dedup::DedupConfig) -> Result<usize, DBError>;
+    fn get_dedup_config(&self, reader_id: &i64) -> Result<dedup::DedupConfig, DBError>;
+    // Reader read window overrides
+    fn save_read_window_config(&mut self, reader_id: &i64, config: &read_window::ReadWindowConfig) -> Result<usize, DBError>;
+    fn get_read_window_config(&self, reader_id: &i64) -> Result<read_window::ReadWindowConfig, DBError>;
+    // Bib and chip mapping
+    fn save_bibchips(&mut self, bibchips: &[bibchip::BibChip], replace: bool) -> Result<usize, DBError>;
+    fn get_bibchips(&self) -> Result<Vec<bibchip::BibChip>, DBError>;
+    fn delete_bibchips(&mut self) -> Result<usize, DBError>;
     // API information
     fn save_api(&mut self, api: &api::Api) -> Result<i64, DBError>;
     fn get_apis(&self) -> Result<Vec<api::Api>, DBError>;
@@ -71,6 +111,7 @@ pub trait Database {
     fn save_reads(&mut self, reads: &Vec<read::Read>) -> Result<usize, DBError>;
     fn get_reads(&self, start: i64, end: i64) -> Result<Vec<read::Read>, DBError>;
     fn get_all_reads(&self) -> Result<Vec<read::Read>, DBError>;
+    fn get_unknown_reads(&self, reads: &[read::Read], window_milliseconds: u64) -> Result<Vec<read::Read>, DBError>;
     fn delete_reads(&mut self, start: i64, end: i64) -> Result<usize, DBError>;
     fn delete_all_reads(&mut self) -> Result<usize, DBError>;
     fn reset_reads_upload(&mut self) -> Result<usize, DBError>;
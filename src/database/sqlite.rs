@@ -16,7 +16,7 @@ You should have received a copy of the GNU Affero General Public License
 along with this program.  If not, see <https://www.gnu.org/licenses/>.
  */
 
-use crate::objects::{setting, read};
+use crate::objects::{antenna, bibchip, clock, dedup, filter, gpio, profile, reconnect, report_buffer, setting, read, read_window, tag_data};
 use crate::network::api::{self, API_TYPE_CHRONOKEEP_REMOTE, API_TYPE_CHRONOKEEP_REMOTE_SELF};
 use crate::database::DBError;
 use crate::reader;
@@ -26,12 +26,15 @@ use std::path::Path;
 use std::str::FromStr;
 
 #[cfg(test)]
-pub(super) mod tests;
+pub(crate) mod tests;
 
 const DATABASE_URI: &str = "./chronokeep-portal.sqlite";
 
 const DATABASE_VERSION_SETTING: &str = "PORTAL_DATABASE_VERSION";
-const DATABASE_VERSION: u16 = 5;
+const DATABASE_VERSION: u16 = 6;
+
+const GPI_TRIGGER_START: &str = "start";
+const GPI_TRIGGER_STOP: &str = "stop";
 
 const DATABASE_PATH_ENV: &str = "PORTAL_DATABASE_PATH";
 
@@ -100,12 +103,173 @@ impl SQLite {
                     return Err(e)
                 }
             }
+            if old_version < 6 {
+                if let Err(e) = self.update_to_v6() {
+                    return Err(e)
+                }
+            }
         } else if new_version < old_version {
             return Err(DBError::DatabaseTooNew(String::from("database version is newer than our known version")))
         }
         return Ok(())
     }
 
+    fn update_to_v6(&mut self) -> Result<(), DBError> {
+        // the antenna shift environment variable is replaced by an antenna map for each reader
+        // so zebra readers get the map for our custom boxes if the variable is set
+        let shift = match env::var(reader::zebra::ZEBRA_SHIFT) {
+            Ok(val) => !val.is_empty(),
+            Err(_) => false,
+        };
+        if let Ok(tx) = self.conn.transaction() {
+            let updates = [
+                "CREATE TABLE IF NOT EXISTS reader_antennas (
+                    reader_id INTEGER NOT NULL,
+                    antenna INTEGER NOT NULL,
+                    enabled SMALLINT NOT NULL DEFAULT 1,
+                    transmit_power_index INTEGER NOT NULL DEFAULT 0,
+                    transmit_power_dbm REAL,
+                    receive_sensitivity_index INTEGER NOT NULL DEFAULT 0,
+                    UNIQUE (reader_id, antenna) ON CONFLICT REPLACE
+                );",
+                "CREATE TABLE IF NOT EXISTS reader_filters (
+                    reader_id INTEGER NOT NULL,
+                    position INTEGER NOT NULL,
+                    memory_bank INTEGER NOT NULL,
+                    pointer INTEGER NOT NULL,
+                    mask VARCHAR(200) NOT NULL,
+                    mask_bits INTEGER NOT NULL DEFAULT 0,
+                    action VARCHAR(20) NOT NULL,
+                    UNIQUE (reader_id, position) ON CONFLICT REPLACE
+                );",
+                "CREATE TABLE IF NOT EXISTS reader_identifiers (
+                    identifier VARCHAR(100) NOT NULL,
+                    reader_id INTEGER NOT NULL,
+                    UNIQUE (identifier) ON CONFLICT REPLACE
+                );",
+                "CREATE TABLE IF NOT EXISTS reader_antenna_map (
+                    reader_id INTEGER NOT NULL,
+                    port INTEGER NOT NULL,
+                    antenna INTEGER NOT NULL,
+                    UNIQUE (reader_id, port) ON CONFLICT REPLACE
+                );",
+                "CREATE TABLE IF NOT EXISTS reader_gpi_triggers (
+                    reader_id INTEGER NOT NULL,
+                    trigger VARCHAR(10) NOT NULL,
+                    port INTEGER NOT NULL,
+                    event SMALLINT NOT NULL,
+                    timeout INTEGER NOT NULL DEFAULT 0,
+                    UNIQUE (reader_id, trigger) ON CONFLICT REPLACE
+                );",
+                "CREATE TABLE IF NOT EXISTS reader_gpo_outputs (
+                    reader_id INTEGER NOT NULL,
+                    position INTEGER NOT NULL,
+                    port INTEGER NOT NULL,
+                    event VARCHAR(20) NOT NULL,
+                    duration INTEGER NOT NULL DEFAULT 0,
+                    UNIQUE (reader_id, position) ON CONFLICT REPLACE
+                );",
+                "CREATE TABLE IF NOT EXISTS reader_tag_data (
+                    reader_id INTEGER NOT NULL,
+                    tid_words INTEGER NOT NULL DEFAULT 0,
+                    user_memory_words INTEGER NOT NULL DEFAULT 0,
+                    report_metadata SMALLINT NOT NULL DEFAULT 0,
+                    UNIQUE (reader_id) ON CONFLICT REPLACE
+                );",
+                "CREATE TABLE IF NOT EXISTS reader_clock (
+                    reader_id INTEGER NOT NULL,
+                    source VARCHAR(20) NOT NULL DEFAULT 'portal',
+                    correct SMALLINT NOT NULL DEFAULT 0,
+                    warn_threshold INTEGER NOT NULL DEFAULT 1000,
+                    set_reader_clock SMALLINT NOT NULL DEFAULT 0,
+                    UNIQUE (reader_id) ON CONFLICT REPLACE
+                );",
+                "CREATE TABLE IF NOT EXISTS reader_report_buffer (
+                    reader_id INTEGER NOT NULL,
+                    enabled SMALLINT NOT NULL DEFAULT 0,
+                    UNIQUE (reader_id) ON CONFLICT REPLACE
+                );",
+                "CREATE TABLE IF NOT EXISTS reader_profiles (
+                    reader_id INTEGER NOT NULL,
+                    position INTEGER NOT NULL,
+                    name VARCHAR(50) NOT NULL,
+                    antennas VARCHAR(100) NOT NULL DEFAULT '',
+                    transmit_power_dbm REAL,
+                    duration INTEGER NOT NULL DEFAULT 0,
+                    active SMALLINT NOT NULL DEFAULT 0,
+                    UNIQUE (reader_id, position) ON CONFLICT REPLACE
+                );",
+                "CREATE TABLE IF NOT EXISTS reader_reconnect (
+                    reader_id INTEGER NOT NULL,
+                    max_attempts INTEGER NOT NULL,
+                    initial_delay INTEGER NOT NULL,
+                    backoff_multiplier REAL NOT NULL,
+                    max_delay INTEGER NOT NULL,
+                    UNIQUE (reader_id) ON CONFLICT REPLACE
+                );",
+                "CREATE TABLE IF NOT EXISTS reader_dedup (
+                    reader_id INTEGER NOT NULL,
+                    mode VARCHAR(20) NOT NULL,
+                    emit_first SMALLINT NOT NULL DEFAULT 0,
+                    UNIQUE (reader_id) ON CONFLICT REPLACE
+                );",
+                "CREATE TABLE IF NOT EXISTS reader_dedup_groups (
+                    reader_id INTEGER NOT NULL,
+                    position INTEGER NOT NULL,
+                    antennas VARCHAR(100) NOT NULL,
+                    mode VARCHAR(20) NOT NULL,
+                    UNIQUE (reader_id, position) ON CONFLICT REPLACE
+                );",
+                "CREATE TABLE IF NOT EXISTS reader_read_windows (
+                    reader_id INTEGER NOT NULL,
+                    antenna INTEGER NOT NULL,
+                    read_window INTEGER NOT NULL,
+                    UNIQUE (reader_id, antenna) ON CONFLICT REPLACE
+                );",
+                "CREATE TABLE IF NOT EXISTS bibchip (
+                    bib VARCHAR(50) NOT NULL,
+                    chip VARCHAR(100) NOT NULL COLLATE NOCASE,
+                    UNIQUE (chip) ON CONFLICT REPLACE
+                );",
+                "ALTER TABLE chip_reads ADD COLUMN tid VARCHAR(100);",
+                "ALTER TABLE chip_reads ADD COLUMN user_memory VARCHAR(300);",
+                "ALTER TABLE chip_reads ADD COLUMN channel_index INTEGER;",
+                "ALTER TABLE chip_reads ADD COLUMN seen_count INTEGER;",
+                "ALTER TABLE chip_reads ADD COLUMN last_seen_seconds BIGINT;",
+                "ALTER TABLE chip_reads ADD COLUMN last_seen_milliseconds INTEGER;",
+                "ALTER TABLE chip_reads ADD COLUMN bib VARCHAR(50);",
+                // recovered reads are checked against the reads saved around the same reader time
+                "CREATE INDEX IF NOT EXISTS chip_reads_chip_reader_time ON chip_reads (chip, reader, reader_seconds);",
+            ];
+            for table in updates {
+                if let Err(e) = tx.execute(table, ()) {
+                    return Err(DBError::DataInsertionError(e.to_string()))
+                }
+            }
+            if shift {
+                for (port, antenna) in reader::zebra::ZEBRA_SHIFT_MAP {
+                    if let Err(e) = tx.execute(
+                        "INSERT INTO reader_antenna_map (reader_id, port, antenna) SELECT reader_id, ?1, ?2 FROM readers WHERE kind=?3;",
+                        (port, antenna, reader::READER_KIND_ZEBRA)
+                    ) {
+                        return Err(DBError::DataInsertionError(e.to_string()))
+                    }
+                }
+            }
+            if let Err(e) = tx.execute(
+                "INSERT INTO settings (setting, value) VALUES (?1, ?2);",
+                (DATABASE_VERSION_SETTING, "6")
+            ) {
+                return Err(DBError::DataInsertionError(e.to_string()))
+            }
+            if let Err(e) = tx.commit() {
+                return Err(DBError::DataInsertionError(e.to_string()))
+            }
+            return Ok(())
+        }
+        Err(DBError::ConnectionError(String::from("unable to start transaction")))
+    }
+
     fn update_to_v5(&mut self) -> Result<(), DBError> {
         if let Ok(tx) = self.conn.transaction() {
             let updates = [
@@ -265,8 +429,125 @@ impl SQLite {
                     reader VARCHAR(75),
                     rssi VARCHAR(10),
                     uploaded SMALLINT NOT NULL DEFAULT 0,
+                    tid VARCHAR(100),
+                    user_memory VARCHAR(300),
+                    channel_index INTEGER,
+                    seen_count INTEGER,
+                    last_seen_seconds BIGINT,
+                    last_seen_milliseconds INTEGER,
+                    bib VARCHAR(50),
                     UNIQUE (chip, seconds, milliseconds) ON CONFLICT IGNORE
                 );",
+                "CREATE TABLE IF NOT EXISTS reader_antennas (
+                    reader_id INTEGER NOT NULL,
+                    antenna INTEGER NOT NULL,
+                    enabled SMALLINT NOT NULL DEFAULT 1,
+                    transmit_power_index INTEGER NOT NULL DEFAULT 0,
+                    transmit_power_dbm REAL,
+                    receive_sensitivity_index INTEGER NOT NULL DEFAULT 0,
+                    UNIQUE (reader_id, antenna) ON CONFLICT REPLACE
+                );",
+                "CREATE TABLE IF NOT EXISTS reader_filters (
+                    reader_id INTEGER NOT NULL,
+                    position INTEGER NOT NULL,
+                    memory_bank INTEGER NOT NULL,
+                    pointer INTEGER NOT NULL,
+                    mask VARCHAR(200) NOT NULL,
+                    mask_bits INTEGER NOT NULL DEFAULT 0,
+                    action VARCHAR(20) NOT NULL,
+                    UNIQUE (reader_id, position) ON CONFLICT REPLACE
+                );",
+                "CREATE TABLE IF NOT EXISTS reader_identifiers (
+                    identifier VARCHAR(100) NOT NULL,
+                    reader_id INTEGER NOT NULL,
+                    UNIQUE (identifier) ON CONFLICT REPLACE
+                );",
+                "CREATE TABLE IF NOT EXISTS reader_antenna_map (
+                    reader_id INTEGER NOT NULL,
+                    port INTEGER NOT NULL,
+                    antenna INTEGER NOT NULL,
+                    UNIQUE (reader_id, port) ON CONFLICT REPLACE
+                );",
+                "CREATE TABLE IF NOT EXISTS reader_gpi_triggers (
+                    reader_id INTEGER NOT NULL,
+                    trigger VARCHAR(10) NOT NULL,
+                    port INTEGER NOT NULL,
+                    event SMALLINT NOT NULL,
+                    timeout INTEGER NOT NULL DEFAULT 0,
+                    UNIQUE (reader_id, trigger) ON CONFLICT REPLACE
+                );",
+                "CREATE TABLE IF NOT EXISTS reader_gpo_outputs (
+                    reader_id INTEGER NOT NULL,
+                    position INTEGER NOT NULL,
+                    port INTEGER NOT NULL,
+                    event VARCHAR(20) NOT NULL,
+                    duration INTEGER NOT NULL DEFAULT 0,
+                    UNIQUE (reader_id, position) ON CONFLICT REPLACE
+                );",
+                "CREATE TABLE IF NOT EXISTS reader_tag_data (
+                    reader_id INTEGER NOT NULL,
+                    tid_words INTEGER NOT NULL DEFAULT 0,
+                    user_memory_words INTEGER NOT NULL DEFAULT 0,
+                    report_metadata SMALLINT NOT NULL DEFAULT 0,
+                    UNIQUE (reader_id) ON CONFLICT REPLACE
+                );",
+                "CREATE TABLE IF NOT EXISTS reader_clock (
+                    reader_id INTEGER NOT NULL,
+                    source VARCHAR(20) NOT NULL DEFAULT 'portal',
+                    correct SMALLINT NOT NULL DEFAULT 0,
+                    warn_threshold INTEGER NOT NULL DEFAULT 1000,
+                    set_reader_clock SMALLINT NOT NULL DEFAULT 0,
+                    UNIQUE (reader_id) ON CONFLICT REPLACE
+                );",
+                "CREATE TABLE IF NOT EXISTS reader_report_buffer (
+                    reader_id INTEGER NOT NULL,
+                    enabled SMALLINT NOT NULL DEFAULT 0,
+                    UNIQUE (reader_id) ON CONFLICT REPLACE
+                );",
+                "CREATE TABLE IF NOT EXISTS reader_profiles (
+                    reader_id INTEGER NOT NULL,
+                    position INTEGER NOT NULL,
+                    name VARCHAR(50) NOT NULL,
+                    antennas VARCHAR(100) NOT NULL DEFAULT '',
+                    transmit_power_dbm REAL,
+                    duration INTEGER NOT NULL DEFAULT 0,
+                    active SMALLINT NOT NULL DEFAULT 0,
+                    UNIQUE (reader_id, position) ON CONFLICT REPLACE
+                );",
+                "CREATE TABLE IF NOT EXISTS reader_reconnect (
+                    reader_id INTEGER NOT NULL,
+                    max_attempts INTEGER NOT NULL,
+                    initial_delay INTEGER NOT NULL,
+                    backoff_multiplier REAL NOT NULL,
+                    max_delay INTEGER NOT NULL,
+                    UNIQUE (reader_id) ON CONFLICT REPLACE
+                );",
+                "CREATE TABLE IF NOT EXISTS reader_dedup (
+                    reader_id INTEGER NOT NULL,
+                    mode VARCHAR(20) NOT NULL,
+                    emit_first SMALLINT NOT NULL DEFAULT 0,
+                    UNIQUE (reader_id) ON CONFLICT REPLACE
+                );",
+                "CREATE TABLE IF NOT EXISTS reader_dedup_groups (
+                    reader_id INTEGER NOT NULL,
+                    position INTEGER NOT NULL,
+                    antennas VARCHAR(100) NOT NULL,
+                    mode VARCHAR(20) NOT NULL,
+                    UNIQUE (reader_id, position) ON CONFLICT REPLACE
+                );",
+                "CREATE TABLE IF NOT EXISTS reader_read_windows (
+                    reader_id INTEGER NOT NULL,
+                    antenna INTEGER NOT NULL,
+                    read_window INTEGER NOT NULL,
+                    UNIQUE (reader_id, antenna) ON CONFLICT REPLACE
+                );",
+                "CREATE TABLE IF NOT EXISTS bibchip (
+                    bib VARCHAR(50) NOT NULL,
+                    chip VARCHAR(100) NOT NULL COLLATE NOCASE,
+                    UNIQUE (chip) ON CONFLICT REPLACE
+                );",
+                // recovered reads are checked against the reads saved around the same reader time
+                "CREATE INDEX IF NOT EXISTS chip_reads_chip_reader_time ON chip_reads (chip, reader, reader_seconds);",
             ];
             for table in database_tables {
                 if let Err(e) = tx.execute(table, ()) {
@@ -288,6 +569,32 @@ impl SQLite {
     }
 }
 
+// Builds a read from a chip_reads row selected with every column in table order.
+fn read_from_row(row: &rusqlite::Row) -> rusqlite::Result<read::Read> {
+    let mut output = read::Read::new(
+        row.get(0)?,
+        row.get(1)?,
+        row.get(2)?,
+        row.get(3)?,
+        row.get(4)?,
+        row.get(5)?,
+        row.get(6)?,
+        row.get(7)?,
+        row.get(8)?,
+        row.get(9)?,
+    );
+    output.set_details(read::ReadDetails {
+        tid: row.get(10)?,
+        user_memory: row.get(11)?,
+        channel_index: row.get(12)?,
+        seen_count: row.get(13)?,
+        last_seen_seconds: row.get(14)?,
+        last_seen_milliseconds: row.get(15)?,
+    });
+    output.set_bib(row.get(16)?);
+    Ok(output)
+}
+
 impl super::Database for SQLite {
     // Setup
     fn setup(&mut self) -> Result<(), DBError> {
@@ -354,8 +661,7 @@ impl super::Database for SQLite {
     // Readers
     fn save_reader(&mut self, reader: &reader::Reader) -> Result<i64, DBError> {
         match reader.kind() {
-            reader::READER_KIND_ZEBRA => {},
-            reader::READER_KIND_IMPINJ => return Err(DBError::DataInsertionError(String::from("not yet implemented"))),
+            reader::READER_KIND_ZEBRA | reader::READER_KIND_IMPINJ | reader::READER_KIND_LLRP => {},
             reader::READER_KIND_RFID => return Err(DBError::DataInsertionError(String::from("not yet implemented"))),
             _ => return Err(DBError::DataInsertionError(String::from("unknown reader kind specified")))
         }
@@ -455,113 +761,158 @@ impl super::Database for SQLite {
     }
 
     fn delete_reader(&mut self, id: &i64) -> Result<usize, DBError> {
+        if let Err(e) = self.conn.execute("DELETE FROM reader_antennas WHERE reader_id=?1", [id]) {
+            return Err(DBError::DataDeletionError(e.to_string()))
+        }
+        if let Err(e) = self.conn.execute("DELETE FROM reader_filters WHERE reader_id=?1", [id]) {
+            return Err(DBError::DataDeletionError(e.to_string()))
+        }
+        if let Err(e) = self.conn.execute("DELETE FROM reader_identifiers WHERE reader_id=?1", [id]) {
+            return Err(DBError::DataDeletionError(e.to_string()))
+        }
+        if let Err(e) = self.conn.execute("DELETE FROM reader_antenna_map WHERE reader_id=?1", [id]) {
+            return Err(DBError::DataDeletionError(e.to_string()))
+        }
+        if let Err(e) = self.conn.execute("DELETE FROM reader_gpi_triggers WHERE reader_id=?1", [id]) {
+            return Err(DBError::DataDeletionError(e.to_string()))
+        }
+        if let Err(e) = self.conn.execute("DELETE FROM reader_gpo_outputs WHERE reader_id=?1", [id]) {
+            return Err(DBError::DataDeletionError(e.to_string()))
+        }
+        if let Err(e) = self.conn.execute("DELETE FROM reader_tag_data WHERE reader_id=?1", [id]) {
+            return Err(DBError::DataDeletionError(e.to_string()))
+        }
+        if let Err(e) = self.conn.execute("DELETE FROM reader_clock WHERE reader_id=?1", [id]) {
+            return Err(DBError::DataDeletionError(e.to_string()))
+        }
+        if let Err(e) = self.conn.execute("DELETE FROM reader_report_buffer WHERE reader_id=?1", [id]) {
+            return Err(DBError::DataDeletionError(e.to_string()))
+        }
+        if let Err(e) = self.conn.execute("DELETE FROM reader_profiles WHERE reader_id=?1", [id]) {
+            return Err(DBError::DataDeletionError(e.to_string()))
+        }
+        if let Err(e) = self.conn.execute("DELETE FROM reader_reconnect WHERE reader_id=?1", [id]) {
+            return Err(DBError::DataDeletionError(e.to_string()))
+        }
+        if let Err(e) = self.conn.execute("DELETE FROM reader_dedup WHERE reader_id=?1", [id]) {
+            return Err(DBError::DataDeletionError(e.to_string()))
+        }
+        if let Err(e) = self.conn.execute("DELETE FROM reader_dedup_groups WHERE reader_id=?1", [id]) {
+            return Err(DBError::DataDeletionError(e.to_string()))
+        }
+        if let Err(e) = self.conn.execute("DELETE FROM reader_read_windows WHERE reader_id=?1", [id]) {
+            return Err(DBError::DataDeletionError(e.to_string()))
+        }
         match self.conn.execute("DELETE FROM readers WHERE reader_id=?1", [id]) {
             Ok(num) => return Ok(num),
             Err(e) => return Err(DBError::DataDeletionError(e.to_string()))
         }
     }
 
-    // Results API
-    fn save_api(&mut self, api: &api::Api) -> Result<i64, DBError> {
-        match api.kind() {
-            api::API_TYPE_CHRONOKEEP_REMOTE |
-            api::API_TYPE_CHRONOKEEP_REMOTE_SELF =>
-            {},
-            _ => return Err(DBError::DataInsertionError(String::from("invalid kind specified")))
-        }
-        if api.id() > 0 {
-            match self.conn.execute(
-                "UPDATE results_api SET 
-                        nickname=?1, 
-                        kind=?2, 
-                        token=?3, 
-                        uri=?4 
-                    WHERE api_id=?5",
-                (api.nickname(), api.kind(), api.token(), api.uri(), api.id()))
-            {
-                Ok(_) => return Ok(api.id()),
-                Err(e) => return Err(DBError::DataInsertionError(e.to_string()))
-            }
-        }
+    // Reader identifiers, the MAC address or EPC a reader reports when it connects to us
+    fn save_reader_identifier(&mut self, reader_id: &i64, identifier: &str) -> Result<usize, DBError> {
         match self.conn.execute(
-            "INSERT INTO results_api (
-                    nickname,
-                    kind,
-                    token,
-                    uri
-                ) VALUES (?1,?2,?3,?4);",
-            (api.nickname(), api.kind(), api.token(), api.uri())
+            "INSERT INTO reader_identifiers (identifier, reader_id) VALUES (?1, ?2);",
+            (identifier, reader_id)
         ) {
-            Ok(_) => return Ok(self.conn.last_insert_rowid()),
-            Err(e) => return Err(DBError::DataInsertionError(e.to_string()))
+            Ok(num) => Ok(num),
+            Err(e) => Err(DBError::DataInsertionError(e.to_string()))
         }
     }
 
-    fn get_apis(&self) -> Result<Vec<api::Api>, DBError> {
-        let mut stmt = match self.conn.prepare("SELECT api_id, nickname, kind, token, uri FROM results_api;") {
+    fn get_reader_by_identifier(&self, identifier: &str) -> Result<reader::Reader, DBError> {
+        match self.conn.query_row("SELECT reader_id FROM reader_identifiers WHERE identifier=?1;",
+            [identifier],
+            |row| {
+                row.get::<usize, i64>(0)
+        }) {
+            Ok(id) => self.get_reader(&id),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Err(DBError::NotFound),
+            Err(e) => Err(DBError::DataRetrievalError(e.to_string())),
+        }
+    }
+
+    // Reader antenna configuration
+    fn save_antenna_configs(&mut self, reader_id: &i64, configs: &[antenna::AntennaConfig]) -> Result<usize, DBError> {
+        if let Ok(tx) = self.conn.transaction() {
+            // the list we're given replaces whatever was there before
+            if let Err(e) = tx.execute("DELETE FROM reader_antennas WHERE reader_id=?1;", [reader_id]) {
+                return Err(DBError::DataDeletionError(e.to_string()))
+            }
+            let mut count = 0;
+            for c in configs {
+                match tx.execute(
+                    "INSERT INTO reader_antennas (
+                            reader_id,
+                            antenna,
+                            enabled,
+                            transmit_power_index,
+                            transmit_power_dbm,
+                            receive_sensitivity_index
+                        ) VALUES (?1,?2,?3,?4,?5,?6);",
+                    (reader_id, c.antenna(), c.enabled(), c.transmit_power_index(), c.transmit_power_dbm(), c.receive_sensitivity_index())
+                ) {
+                    Ok(val) => count += val,
+                    Err(e) => return Err(DBError::DataInsertionError(e.to_string()))
+                }
+            }
+            if let Err(e) = tx.commit() {
+                return Err(DBError::DataInsertionError(e.to_string()));
+            }
+            return Ok(count);
+        }
+        Err(DBError::ConnectionError(String::from("error starting transaction")))
+    }
+
+    fn get_antenna_configs(&self, reader_id: &i64) -> Result<Vec<antenna::AntennaConfig>, DBError> {
+        let mut stmt = match self.conn.prepare("SELECT antenna, enabled, transmit_power_index, transmit_power_dbm, receive_sensitivity_index FROM reader_antennas WHERE reader_id=?1 ORDER BY antenna;") {
             Ok(stmt) => stmt,
             Err(e) => return Err(DBError::ConnectionError(e.to_string()))
         };
         let results = match stmt.query_map(
-            [],
-            |row|{
-                Ok(api::Api::new(
+            [reader_id],
+            |row| {
+                Ok(antenna::AntennaConfig::new(
                     row.get(0)?,
                     row.get(1)?,
                     row.get(2)?,
                     row.get(3)?,
-                    row.get(4)?
+                    row.get(4)?,
                 ))
             }) {
                 Ok(r) => r,
                 Err(e) => return Err(DBError::DataRetrievalError(e.to_string()))
             };
-        let mut output: Vec<api::Api> = Vec::new();
+        let mut output: Vec<antenna::AntennaConfig> = Vec::new();
         for row in results {
             match row {
                 Ok(r) => {
-                    match r.kind() {
-                        api::API_TYPE_CHRONOKEEP_REMOTE |
-                        api::API_TYPE_CHRONOKEEP_REMOTE_SELF =>
-                        {
-                            output.push(r)
-                        },
-                        _ => return Err(DBError::DataRetrievalError(String::from("invalid api type")))
-                    }
+                    output.push(r);
                 },
                 Err(e) => return Err(DBError::DataRetrievalError(e.to_string()))
             }
         }
-        return Ok(output);
-    }
-
-    fn delete_api(&mut self, id: &i64) -> Result<usize, DBError> {
-        match self.conn.execute("DELETE FROM results_api WHERE api_id=?1", [id]) {
-            Ok(num) => return Ok(num),
-            Err(e) => return Err(DBError::DataRetrievalError(e.to_string()))
-        }
+        Ok(output)
     }
 
-    // Reads
-    fn save_reads(&mut self, reads: &Vec<read::Read>) -> Result<usize, DBError> {
+    // Reader antenna map
+    fn save_antenna_map(&mut self, reader_id: &i64, map: &[antenna::AntennaMapping]) -> Result<usize, DBError> {
         if let Ok(tx) = self.conn.transaction() {
+            // the map we're given replaces whatever was there before
+            if let Err(e) = tx.execute("DELETE FROM reader_antenna_map WHERE reader_id=?1;", [reader_id]) {
+                return Err(DBError::DataDeletionError(e.to_string()))
+            }
             let mut count = 0;
-            for r in reads {
+            for m in map {
                 match tx.execute(
-                    "INSERT INTO chip_reads (
-                            chip, 
-                            seconds,
-                            milliseconds,
-                            reader_seconds,
-                            reader_milliseconds,
-                            antenna,
-                            reader,
-                            rssi,
-                            uploaded
-                        ) VALUES (?1,?2,?3,?4,?5,?6,?7,?8,?9);",
-                    (r.chip(), r.seconds(), r.milliseconds(), r.reader_seconds(), r.reader_milliseconds(), r.antenna(), r.reader(), r.rssi(), r.uploaded())
+                    "INSERT INTO reader_antenna_map (
+                            reader_id,
+                            port,
+                            antenna
+                        ) VALUES (?1,?2,?3);",
+                    (reader_id, m.port(), m.antenna())
                 ) {
-                    Ok(val) => count = count + val,
+                    Ok(val) => count += val,
                     Err(e) => return Err(DBError::DataInsertionError(e.to_string()))
                 }
             }
@@ -570,34 +921,26 @@ impl super::Database for SQLite {
             }
             return Ok(count);
         }
-        return Err(DBError::ConnectionError(String::from("error starting transaction")));
+        Err(DBError::ConnectionError(String::from("error starting transaction")))
     }
 
-    fn get_reads(&self, start: i64, end: i64) -> Result<Vec<read::Read>, DBError> {
-        let mut stmt = match self.conn.prepare("SELECT chip_id, chip, seconds, milliseconds, reader_seconds, reader_milliseconds, antenna, reader, rssi, uploaded FROM chip_reads WHERE seconds >= ?1 AND seconds <= ?2;") {
+    fn get_antenna_map(&self, reader_id: &i64) -> Result<Vec<antenna::AntennaMapping>, DBError> {
+        let mut stmt = match self.conn.prepare("SELECT port, antenna FROM reader_antenna_map WHERE reader_id=?1 ORDER BY port;") {
             Ok(stmt) => stmt,
             Err(e) => return Err(DBError::ConnectionError(e.to_string()))
         };
         let results = match stmt.query_map(
-            [start, end],
+            [reader_id],
             |row| {
-                Ok(read::Read::new(
+                Ok(antenna::AntennaMapping::new(
                     row.get(0)?,
                     row.get(1)?,
-                    row.get(2)?,
-                    row.get(3)?,
-                    row.get(4)?,
-                    row.get(5)?,
-                    row.get(6)?,
-                    row.get(7)?,
-                    row.get(8)?,
-                    row.get(9)?,
                 ))
             }) {
                 Ok(r) => r,
                 Err(e) => return Err(DBError::DataRetrievalError(e.to_string()))
             };
-        let mut output: Vec<read::Read> = Vec::new();
+        let mut output: Vec<antenna::AntennaMapping> = Vec::new();
         for row in results {
             match row {
                 Ok(r) => {
@@ -606,34 +949,62 @@ impl super::Database for SQLite {
                 Err(e) => return Err(DBError::DataRetrievalError(e.to_string()))
             }
         }
-        return Ok(output);
+        Ok(output)
     }
 
-    fn get_all_reads(&self) -> Result<Vec<read::Read>, DBError> {
-        let mut stmt = match self.conn.prepare("SELECT chip_id, chip, seconds, milliseconds, reader_seconds, reader_milliseconds, antenna, reader, rssi, uploaded FROM chip_reads;") {
+    // Reader tag filters
+    fn save_tag_filters(&mut self, reader_id: &i64, filters: &[filter::TagFilter]) -> Result<usize, DBError> {
+        if let Ok(tx) = self.conn.transaction() {
+            // the list we're given replaces whatever was there before
+            if let Err(e) = tx.execute("DELETE FROM reader_filters WHERE reader_id=?1;", [reader_id]) {
+                return Err(DBError::DataDeletionError(e.to_string()))
+            }
+            let mut count = 0;
+            for (position, f) in filters.iter().enumerate() {
+                match tx.execute(
+                    "INSERT INTO reader_filters (
+                            reader_id,
+                            position,
+                            memory_bank,
+                            pointer,
+                            mask,
+                            mask_bits,
+                            action
+                        ) VALUES (?1,?2,?3,?4,?5,?6,?7);",
+                    (reader_id, position as i64, f.memory_bank(), f.pointer(), f.mask(), f.mask_bits(), f.action())
+                ) {
+                    Ok(val) => count += val,
+                    Err(e) => return Err(DBError::DataInsertionError(e.to_string()))
+                }
+            }
+            if let Err(e) = tx.commit() {
+                return Err(DBError::DataInsertionError(e.to_string()));
+            }
+            return Ok(count);
+        }
+        Err(DBError::ConnectionError(String::from("error starting transaction")))
+    }
+
+    fn get_tag_filters(&self, reader_id: &i64) -> Result<Vec<filter::TagFilter>, DBError> {
+        let mut stmt = match self.conn.prepare("SELECT memory_bank, pointer, mask, mask_bits, action FROM reader_filters WHERE reader_id=?1 ORDER BY position;") {
             Ok(stmt) => stmt,
             Err(e) => return Err(DBError::ConnectionError(e.to_string()))
         };
         let results = match stmt.query_map(
-            [],
+            [reader_id],
             |row| {
-                Ok(read::Read::new(
+                Ok(filter::TagFilter::new(
                     row.get(0)?,
                     row.get(1)?,
                     row.get(2)?,
                     row.get(3)?,
                     row.get(4)?,
-                    row.get(5)?,
-                    row.get(6)?,
-                    row.get(7)?,
-                    row.get(8)?,
-                    row.get(9)?,
                 ))
             }) {
                 Ok(r) => r,
                 Err(e) => return Err(DBError::DataRetrievalError(e.to_string()))
             };
-        let mut output: Vec<read::Read> = Vec::new();
+        let mut output: Vec<filter::TagFilter> = Vec::new();
         for row in results {
             match row {
                 Ok(r) => {
@@ -642,53 +1013,760 @@ impl super::Database for SQLite {
                 Err(e) => return Err(DBError::DataRetrievalError(e.to_string()))
             }
         }
-        return Ok(output);
-    }
-
-    fn delete_reads(&mut self, start: i64, end: i64) -> Result<usize, DBError> {
-        match self.conn.execute(
-            "DELETE FROM chip_reads WHERE seconds >= ?1 AND seconds <= ?2;",
-            [start, end]
-        ) {
-            Ok(num) => return Ok(num),
-            Err(e) => return Err(DBError::DataDeletionError(e.to_string()))
-        }
+        Ok(output)
     }
 
-    fn delete_all_reads(&mut self) -> Result<usize, DBError> {
-        match self.conn.execute(
-            "DELETE FROM chip_reads;",
-            []
-        ) {
-            Ok(num) => return Ok(num),
-            Err(e) => return Err(DBError::DataDeletionError(e.to_string()))
+    // Reader GPIO
+    fn save_gpio_config(&mut self, reader_id: &i64, config: &gpio::GpioConfig) -> Result<usize, DBError> {
+        if let Ok(tx) = self.conn.transaction() {
+            // the config we're given replaces whatever was there before
+            if let Err(e) = tx.execute("DELETE FROM reader_gpi_triggers WHERE reader_id=?1;", [reader_id]) {
+                return Err(DBError::DataDeletionError(e.to_string()))
+            }
+            if let Err(e) = tx.execute("DELETE FROM reader_gpo_outputs WHERE reader_id=?1;", [reader_id]) {
+                return Err(DBError::DataDeletionError(e.to_string()))
+            }
+            let mut count = 0;
+            for (trigger, gpi) in [(GPI_TRIGGER_START, config.start_trigger()), (GPI_TRIGGER_STOP, config.stop_trigger())] {
+                if let Some(gpi) = gpi {
+                    match tx.execute(
+                        "INSERT INTO reader_gpi_triggers (
+                                reader_id,
+                                trigger,
+                                port,
+                                event,
+                                timeout
+                            ) VALUES (?1,?2,?3,?4,?5);",
+                        (reader_id, trigger, gpi.port(), gpi.event(), gpi.timeout())
+                    ) {
+                        Ok(val) => count += val,
+                        Err(e) => return Err(DBError::DataInsertionError(e.to_string()))
+                    }
+                }
+            }
+            for (position, output) in config.outputs().iter().enumerate() {
+                match tx.execute(
+                    "INSERT INTO reader_gpo_outputs (
+                            reader_id,
+                            position,
+                            port,
+                            event,
+                            duration
+                        ) VALUES (?1,?2,?3,?4,?5);",
+                    (reader_id, position as i64, output.port(), output.event(), output.duration())
+                ) {
+                    Ok(val) => count += val,
+                    Err(e) => return Err(DBError::DataInsertionError(e.to_string()))
+                }
+            }
+            if let Err(e) = tx.commit() {
+                return Err(DBError::DataInsertionError(e.to_string()));
+            }
+            return Ok(count);
         }
+        Err(DBError::ConnectionError(String::from("error starting transaction")))
     }
 
-    fn get_not_uploaded_reads(&self) -> Result<Vec<read::Read>, DBError> {       
-        let mut stmt = match self.conn.prepare("SELECT chip_id, chip, seconds, milliseconds, reader_seconds, reader_milliseconds, antenna, reader, rssi, uploaded FROM chip_reads WHERE uploaded=?1;") {
+    fn get_gpio_config(&self, reader_id: &i64) -> Result<gpio::GpioConfig, DBError> {
+        let mut stmt = match self.conn.prepare("SELECT trigger, port, event, timeout FROM reader_gpi_triggers WHERE reader_id=?1;") {
             Ok(stmt) => stmt,
             Err(e) => return Err(DBError::ConnectionError(e.to_string()))
         };
         let results = match stmt.query_map(
-            [read::READ_UPLOADED_FALSE],
+            [reader_id],
             |row| {
-                Ok(read::Read::new(
-                    row.get(0)?,
-                    row.get(1)?,
-                    row.get(2)?,
-                    row.get(3)?,
-                    row.get(4)?,
-                    row.get(5)?,
-                    row.get(6)?,
-                    row.get(7)?,
-                    row.get(8)?,
-                    row.get(9)?,
+                Ok((
+                    row.get::<usize, String>(0)?,
+                    gpio::GpiTrigger::new(
+                        row.get(1)?,
+                        row.get(2)?,
+                        row.get(3)?,
+                    )
                 ))
             }) {
                 Ok(r) => r,
                 Err(e) => return Err(DBError::DataRetrievalError(e.to_string()))
             };
+        let mut start_trigger: Option<gpio::GpiTrigger> = None;
+        let mut stop_trigger: Option<gpio::GpiTrigger> = None;
+        for row in results {
+            match row {
+                Ok((trigger, gpi)) => {
+                    match trigger.as_str() {
+                        GPI_TRIGGER_START => start_trigger = Some(gpi),
+                        GPI_TRIGGER_STOP => stop_trigger = Some(gpi),
+                        _ => {},
+                    }
+                },
+                Err(e) => return Err(DBError::DataRetrievalError(e.to_string()))
+            }
+        }
+        let mut stmt = match self.conn.prepare("SELECT port, event, duration FROM reader_gpo_outputs WHERE reader_id=?1 ORDER BY position;") {
+            Ok(stmt) => stmt,
+            Err(e) => return Err(DBError::ConnectionError(e.to_string()))
+        };
+        let results = match stmt.query_map(
+            [reader_id],
+            |row| {
+                Ok(gpio::GpoOutput::new(
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                ))
+            }) {
+                Ok(r) => r,
+                Err(e) => return Err(DBError::DataRetrievalError(e.to_string()))
+            };
+        let mut outputs: Vec<gpio::GpoOutput> = Vec::new();
+        for row in results {
+            match row {
+                Ok(r) => {
+                    outputs.push(r);
+                },
+                Err(e) => return Err(DBError::DataRetrievalError(e.to_string()))
+            }
+        }
+        Ok(gpio::GpioConfig::new(start_trigger, stop_trigger, outputs))
+    }
+
+    // Reader tag data
+    fn save_tag_data_config(&mut self, reader_id: &i64, config: &tag_data::TagDataConfig) -> Result<usize, DBError> {
+        match self.conn.execute(
+            "INSERT INTO reader_tag_data (
+                    reader_id,
+                    tid_words,
+                    user_memory_words,
+                    report_metadata
+                ) VALUES (?1,?2,?3,?4);",
+            (reader_id, config.tid_words(), config.user_memory_words(), config.report_metadata())
+        ) {
+            Ok(num) => Ok(num),
+            Err(e) => Err(DBError::DataInsertionError(e.to_string()))
+        }
+    }
+
+    fn get_tag_data_config(&self, reader_id: &i64) -> Result<tag_data::TagDataConfig, DBError> {
+        match self.conn.query_row(
+            "SELECT tid_words, user_memory_words, report_metadata FROM reader_tag_data WHERE reader_id=?1;",
+            [reader_id],
+            |row| {
+                Ok(tag_data::TagDataConfig::new(
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                ))
+            }
+        ) {
+            Ok(config) => Ok(config),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(tag_data::TagDataConfig::default()),
+            Err(e) => Err(DBError::DataRetrievalError(e.to_string()))
+        }
+    }
+
+    // Reader clock
+    fn save_clock_config(&mut self, reader_id: &i64, config: &clock::ClockConfig) -> Result<usize, DBError> {
+        match self.conn.execute(
+            "INSERT INTO reader_clock (
+                    reader_id,
+                    source,
+                    correct,
+                    warn_threshold,
+                    set_reader_clock
+                ) VALUES (?1,?2,?3,?4,?5);",
+            (reader_id, config.source(), config.correct(), config.warn_threshold_milliseconds(), config.set_reader_clock())
+        ) {
+            Ok(num) => Ok(num),
+            Err(e) => Err(DBError::DataInsertionError(e.to_string()))
+        }
+    }
+
+    fn get_clock_config(&self, reader_id: &i64) -> Result<clock::ClockConfig, DBError> {
+        match self.conn.query_row(
+            "SELECT source, correct, warn_threshold, set_reader_clock FROM reader_clock WHERE reader_id=?1;",
+            [reader_id],
+            |row| {
+                Ok(clock::ClockConfig::new(
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                ))
+            }
+        ) {
+            Ok(config) => Ok(config),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(clock::ClockConfig::default()),
+            Err(e) => Err(DBError::DataRetrievalError(e.to_string()))
+        }
+    }
+
+    // Reader report buffering
+    fn save_report_buffer_config(&mut self, reader_id: &i64, config: &report_buffer::ReportBufferConfig) -> Result<usize, DBError> {
+        match self.conn.execute(
+            "INSERT INTO reader_report_buffer (
+                    reader_id,
+                    enabled
+                ) VALUES (?1,?2);",
+            (reader_id, config.enabled())
+        ) {
+            Ok(num) => Ok(num),
+            Err(e) => Err(DBError::DataInsertionError(e.to_string()))
+        }
+    }
+
+    fn get_report_buffer_config(&self, reader_id: &i64) -> Result<report_buffer::ReportBufferConfig, DBError> {
+        match self.conn.query_row(
+            "SELECT enabled FROM reader_report_buffer WHERE reader_id=?1;",
+            [reader_id],
+            |row| {
+                Ok(report_buffer::ReportBufferConfig::new(
+                    row.get(0)?,
+                ))
+            }
+        ) {
+            Ok(config) => Ok(config),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(report_buffer::ReportBufferConfig::default()),
+            Err(e) => Err(DBError::DataRetrievalError(e.to_string()))
+        }
+    }
+
+    // Reader reading profiles
+    fn save_profile_config(&mut self, reader_id: &i64, config: &profile::ProfileConfig) -> Result<usize, DBError> {
+        if let Ok(tx) = self.conn.transaction() {
+            // the profiles we're given replace whatever was there before
+            if let Err(e) = tx.execute("DELETE FROM reader_profiles WHERE reader_id=?1;", [reader_id]) {
+                return Err(DBError::DataDeletionError(e.to_string()))
+            }
+            let mut count = 0;
+            for (position, profile) in config.profiles().iter().enumerate() {
+                let antennas = profile.antennas().iter().map(|a| a.to_string()).collect::<Vec<String>>().join(",");
+                match tx.execute(
+                    "INSERT INTO reader_profiles (
+                            reader_id,
+                            position,
+                            name,
+                            antennas,
+                            transmit_power_dbm,
+                            duration,
+                            active
+                        ) VALUES (?1,?2,?3,?4,?5,?6,?7);",
+                    (reader_id, position as i64, profile.name(), antennas, profile.transmit_power_dbm(), profile.duration_milliseconds(), config.active() == Some(profile.name()))
+                ) {
+                    Ok(val) => count += val,
+                    Err(e) => return Err(DBError::DataInsertionError(e.to_string()))
+                }
+            }
+            if let Err(e) = tx.commit() {
+                return Err(DBError::DataInsertionError(e.to_string()));
+            }
+            return Ok(count);
+        }
+        Err(DBError::ConnectionError(String::from("error starting transaction")))
+    }
+
+    fn get_profile_config(&self, reader_id: &i64) -> Result<profile::ProfileConfig, DBError> {
+        let mut stmt = match self.conn.prepare("SELECT name, antennas, transmit_power_dbm, duration, active FROM reader_profiles WHERE reader_id=?1 ORDER BY position;") {
+            Ok(stmt) => stmt,
+            Err(e) => return Err(DBError::ConnectionError(e.to_string()))
+        };
+        let results = match stmt.query_map(
+            [reader_id],
+            |row| {
+                let antennas: String = row.get(1)?;
+                Ok((
+                    profile::ReadingProfile::new(
+                        row.get(0)?,
+                        antennas.split(',').filter_map(|a| a.trim().parse::<u16>().ok()).collect(),
+                        row.get(2)?,
+                        row.get(3)?,
+                    ),
+                    row.get::<usize, bool>(4)?
+                ))
+            }) {
+                Ok(r) => r,
+                Err(e) => return Err(DBError::DataRetrievalError(e.to_string()))
+            };
+        let mut profiles: Vec<profile::ReadingProfile> = Vec::new();
+        let mut active: Option<String> = None;
+        for row in results {
+            match row {
+                Ok((profile, is_active)) => {
+                    if is_active {
+                        active = Some(String::from(profile.name()));
+                    }
+                    profiles.push(profile);
+                },
+                Err(e) => return Err(DBError::DataRetrievalError(e.to_string()))
+            }
+        }
+        Ok(profile::ProfileConfig::new(profiles, active))
+    }
+
+    // Reader reconnect policy
+    fn save_reconnect_policy(&mut self, reader_id: &i64, policy: &reconnect::ReconnectPolicy) -> Result<usize, DBError> {
+        match self.conn.execute(
+            "INSERT INTO reader_reconnect (
+                    reader_id,
+                    max_attempts,
+                    initial_delay,
+                    backoff_multiplier,
+                    max_delay
+                ) VALUES (?1,?2,?3,?4,?5);",
+            (reader_id, policy.max_attempts(), policy.initial_delay_milliseconds(), policy.backoff_multiplier(), policy.max_delay_milliseconds())
+        ) {
+            Ok(num) => Ok(num),
+            Err(e) => Err(DBError::DataInsertionError(e.to_string()))
+        }
+    }
+
+    fn get_reconnect_policy(&self, reader_id: &i64) -> Result<reconnect::ReconnectPolicy, DBError> {
+        match self.conn.query_row(
+            "SELECT max_attempts, initial_delay, backoff_multiplier, max_delay FROM reader_reconnect WHERE reader_id=?1;",
+            [reader_id],
+            |row| {
+                Ok(reconnect::ReconnectPolicy::new(
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                ))
+            }
+        ) {
+            Ok(policy) => Ok(policy),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(reconnect::ReconnectPolicy::default()),
+            Err(e) => Err(DBError::DataRetrievalError(e.to_string()))
+        }
+    }
+
+    // Reader read window deduplication
+    fn save_dedup_config(&mut self, reader_id: &i64, config: &dedup::DedupConfig) -> Result<usize, DBError> {
+        if let Ok(tx) = self.conn.transaction() {
+            let mut count = match tx.execute(
+                "INSERT INTO reader_dedup (
+                        reader_id,
+                        mode,
+                        emit_first
+                    ) VALUES (?1,?2,?3);",
+                (reader_id, config.mode(), config.emit_first())
+            ) {
+                Ok(val) => val,
+                Err(e) => return Err(DBError::DataInsertionError(e.to_string()))
+            };
+            // the groups we're given replace whatever was there before
+            if let Err(e) = tx.execute("DELETE FROM reader_dedup_groups WHERE reader_id=?1;", [reader_id]) {
+                return Err(DBError::DataDeletionError(e.to_string()))
+            }
+            for (position, group) in config.groups().iter().enumerate() {
+                let antennas = group.antennas().iter().map(|a| a.to_string()).collect::<Vec<String>>().join(",");
+                match tx.execute(
+                    "INSERT INTO reader_dedup_groups (
+                            reader_id,
+                            position,
+                            antennas,
+                            mode
+                        ) VALUES (?1,?2,?3,?4);",
+                    (reader_id, position as i64, antennas, group.mode())
+                ) {
+                    Ok(val) => count += val,
+                    Err(e) => return Err(DBError::DataInsertionError(e.to_string()))
+                }
+            }
+            if let Err(e) = tx.commit() {
+                return Err(DBError::DataInsertionError(e.to_string()));
+            }
+            return Ok(count);
+        }
+        Err(DBError::ConnectionError(String::from("error starting transaction")))
+    }
+
+    fn get_dedup_config(&self, reader_id: &i64) -> Result<dedup::DedupConfig, DBError> {
+        let (mode, emit_first) = match self.conn.query_row(
+            "SELECT mode, emit_first FROM reader_dedup WHERE reader_id=?1;",
+            [reader_id],
+            |row| Ok((row.get::<usize, String>(0)?, row.get::<usize, bool>(1)?))
+        ) {
+            Ok(config) => config,
+            Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(dedup::DedupConfig::default()),
+            Err(e) => return Err(DBError::DataRetrievalError(e.to_string()))
+        };
+        let mut stmt = match self.conn.prepare("SELECT antennas, mode FROM reader_dedup_groups WHERE reader_id=?1 ORDER BY position;") {
+            Ok(stmt) => stmt,
+            Err(e) => return Err(DBError::ConnectionError(e.to_string()))
+        };
+        let results = match stmt.query_map(
+            [reader_id],
+            |row| {
+                let antennas: String = row.get(0)?;
+                Ok(dedup::DedupGroup::new(
+                    antennas.split(',').filter_map(|a| a.trim().parse::<u16>().ok()).collect(),
+                    row.get(1)?,
+                ))
+            }) {
+                Ok(r) => r,
+                Err(e) => return Err(DBError::DataRetrievalError(e.to_string()))
+            };
+        let mut groups: Vec<dedup::DedupGroup> = Vec::new();
+        for row in results {
+            match row {
+                Ok(group) => groups.push(group),
+                Err(e) => return Err(DBError::DataRetrievalError(e.to_string()))
+            }
+        }
+        Ok(dedup::DedupConfig::new(mode, emit_first, groups))
+    }
+
+    // Reader read window overrides
+    fn save_read_window_config(&mut self, reader_id: &i64, config: &read_window::ReadWindowConfig) -> Result<usize, DBError> {
+        if let Ok(tx) = self.conn.transaction() {
+            // the windows we're given replace whatever was there before
+            if let Err(e) = tx.execute("DELETE FROM reader_read_windows WHERE reader_id=?1;", [reader_id]) {
+                return Err(DBError::DataDeletionError(e.to_string()))
+            }
+            // antenna 0 holds the window for the whole reader
+            let mut windows: Vec<(u16, u8)> = config.antennas().iter().map(|a| (a.antenna(), a.read_window())).collect();
+            if let Some(window) = config.read_window() {
+                windows.insert(0, (0, window));
+            }
+            let mut count = 0;
+            for (antenna, window) in windows {
+                match tx.execute(
+                    "INSERT INTO reader_read_windows (
+                            reader_id,
+                            antenna,
+                            read_window
+                        ) VALUES (?1,?2,?3);",
+                    (reader_id, antenna, window)
+                ) {
+                    Ok(val) => count += val,
+                    Err(e) => return Err(DBError::DataInsertionError(e.to_string()))
+                }
+            }
+            if let Err(e) = tx.commit() {
+                return Err(DBError::DataInsertionError(e.to_string()));
+            }
+            return Ok(count);
+        }
+        Err(DBError::ConnectionError(String::from("error starting transaction")))
+    }
+
+    fn get_read_window_config(&self, reader_id: &i64) -> Result<read_window::ReadWindowConfig, DBError> {
+        let mut stmt = match self.conn.prepare("SELECT antenna, read_window FROM reader_read_windows WHERE reader_id=?1 ORDER BY antenna;") {
+            Ok(stmt) => stmt,
+            Err(e) => return Err(DBError::ConnectionError(e.to_string()))
+        };
+        let results = match stmt.query_map(
+            [reader_id],
+            |row| {
+                Ok((row.get::<usize, u16>(0)?, row.get::<usize, u8>(1)?))
+            }) {
+                Ok(r) => r,
+                Err(e) => return Err(DBError::DataRetrievalError(e.to_string()))
+            };
+        let mut read_window: Option<u8> = None;
+        let mut antennas: Vec<read_window::AntennaReadWindow> = Vec::new();
+        for row in results {
+            match row {
+                Ok((0, window)) => read_window = Some(window),
+                Ok((antenna, window)) => antennas.push(read_window::AntennaReadWindow::new(antenna, window)),
+                Err(e) => return Err(DBError::DataRetrievalError(e.to_string()))
+            }
+        }
+        Ok(read_window::ReadWindowConfig::new(read_window, antennas))
+    }
+
+    // Bib and chip mapping
+    fn save_bibchips(&mut self, bibchips: &[bibchip::BibChip], replace: bool) -> Result<usize, DBError> {
+        if let Ok(tx) = self.conn.transaction() {
+            // clear the old mapping in the same transaction so a failed save keeps it
+            if replace {
+                if let Err(e) = tx.execute("DELETE FROM bibchip;", ()) {
+                    return Err(DBError::DataDeletionError(e.to_string()));
+                }
+            }
+            let mut count = 0;
+            for bc in bibchips {
+                match tx.execute(
+                    "INSERT INTO bibchip (
+                            bib,
+                            chip
+                        ) VALUES (?1,?2);",
+                    (bc.bib(), bc.chip())
+                ) {
+                    Ok(val) => count += val,
+                    Err(e) => return Err(DBError::DataInsertionError(e.to_string()))
+                }
+            }
+            if let Err(e) = tx.commit() {
+                return Err(DBError::DataInsertionError(e.to_string()));
+            }
+            return Ok(count);
+        }
+        Err(DBError::ConnectionError(String::from("error starting transaction")))
+    }
+
+    fn get_bibchips(&self) -> Result<Vec<bibchip::BibChip>, DBError> {
+        let mut stmt = match self.conn.prepare("SELECT bib, chip FROM bibchip;") {
+            Ok(stmt) => stmt,
+            Err(e) => return Err(DBError::ConnectionError(e.to_string()))
+        };
+        let results = match stmt.query_map(
+            [],
+            |row| {
+                Ok(bibchip::BibChip::new(row.get(0)?, row.get(1)?))
+            }) {
+                Ok(r) => r,
+                Err(e) => return Err(DBError::DataRetrievalError(e.to_string()))
+            };
+        let mut output: Vec<bibchip::BibChip> = Vec::new();
+        for row in results {
+            match row {
+                Ok(bc) => output.push(bc),
+                Err(e) => return Err(DBError::DataRetrievalError(e.to_string()))
+            }
+        }
+        Ok(output)
+    }
+
+    fn delete_bibchips(&mut self) -> Result<usize, DBError> {
+        match self.conn.execute(
+            "DELETE FROM bibchip;",
+            ()
+        ) {
+            Ok(num) => Ok(num),
+            Err(e) => Err(DBError::DataDeletionError(e.to_string()))
+        }
+    }
+
+    // Results API
+    fn save_api(&mut self, api: &api::Api) -> Result<i64, DBError> {
+        match api.kind() {
+            api::API_TYPE_CHRONOKEEP_REMOTE |
+            api::API_TYPE_CHRONOKEEP_REMOTE_SELF =>
+            {},
+            _ => return Err(DBError::DataInsertionError(String::from("invalid kind specified")))
+        }
+        if api.id() > 0 {
+            match self.conn.execute(
+                "UPDATE results_api SET 
+                        nickname=?1, 
+                        kind=?2, 
+                        token=?3, 
+                        uri=?4 
+                    WHERE api_id=?5",
+                (api.nickname(), api.kind(), api.token(), api.uri(), api.id()))
+            {
+                Ok(_) => return Ok(api.id()),
+                Err(e) => return Err(DBError::DataInsertionError(e.to_string()))
+            }
+        }
+        match self.conn.execute(
+            "INSERT INTO results_api (
+                    nickname,
+                    kind,
+                    token,
+                    uri
+                ) VALUES (?1,?2,?3,?4);",
+            (api.nickname(), api.kind(), api.token(), api.uri())
+        ) {
+            Ok(_) => return Ok(self.conn.last_insert_rowid()),
+            Err(e) => return Err(DBError::DataInsertionError(e.to_string()))
+        }
+    }
+
+    fn get_apis(&self) -> Result<Vec<api::Api>, DBError> {
+        let mut stmt = match self.conn.prepare("SELECT api_id, nickname, kind, token, uri FROM results_api;") {
+            Ok(stmt) => stmt,
+            Err(e) => return Err(DBError::ConnectionError(e.to_string()))
+        };
+        let results = match stmt.query_map(
+            [],
+            |row|{
+                Ok(api::Api::new(
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?
+                ))
+            }) {
+                Ok(r) => r,
+                Err(e) => return Err(DBError::DataRetrievalError(e.to_string()))
+            };
+        let mut output: Vec<api::Api> = Vec::new();
+        for row in results {
+            match row {
+                Ok(r) => {
+                    match r.kind() {
+                        api::API_TYPE_CHRONOKEEP_REMOTE |
+                        api::API_TYPE_CHRONOKEEP_REMOTE_SELF =>
+                        {
+                            output.push(r)
+                        },
+                        _ => return Err(DBError::DataRetrievalError(String::from("invalid api type")))
+                    }
+                },
+                Err(e) => return Err(DBError::DataRetrievalError(e.to_string()))
+            }
+        }
+        return Ok(output);
+    }
+
+    fn delete_api(&mut self, id: &i64) -> Result<usize, DBError> {
+        match self.conn.execute("DELETE FROM results_api WHERE api_id=?1", [id]) {
+            Ok(num) => return Ok(num),
+            Err(e) => return Err(DBError::DataRetrievalError(e.to_string()))
+        }
+    }
+
+    // Reads
+    fn save_reads(&mut self, reads: &Vec<read::Read>) -> Result<usize, DBError> {
+        if let Ok(tx) = self.conn.transaction() {
+            let mut count = 0;
+            for r in reads {
+                match tx.execute(
+                    "INSERT INTO chip_reads (
+                            chip, 
+                            seconds,
+                            milliseconds,
+                            reader_seconds,
+                            reader_milliseconds,
+                            antenna,
+                            reader,
+                            rssi,
+                            uploaded,
+                            tid,
+                            user_memory,
+                            channel_index,
+                            seen_count,
+                            last_seen_seconds,
+                            last_seen_milliseconds,
+                            bib
+                        ) VALUES (?1,?2,?3,?4,?5,?6,?7,?8,?9,?10,?11,?12,?13,?14,?15,?16);",
+                    (
+                        r.chip(), r.seconds(), r.milliseconds(), r.reader_seconds(), r.reader_milliseconds(), r.antenna(), r.reader(), r.rssi(), r.uploaded(),
+                        &r.details().tid, &r.details().user_memory, r.details().channel_index, r.details().seen_count, r.details().last_seen_seconds, r.details().last_seen_milliseconds,
+                        r.bib()
+                    )
+                ) {
+                    Ok(val) => count = count + val,
+                    Err(e) => return Err(DBError::DataInsertionError(e.to_string()))
+                }
+            }
+            if let Err(e) = tx.commit() {
+                return Err(DBError::DataInsertionError(e.to_string()));
+            }
+            return Ok(count);
+        }
+        return Err(DBError::ConnectionError(String::from("error starting transaction")));
+    }
+
+    fn get_reads(&self, start: i64, end: i64) -> Result<Vec<read::Read>, DBError> {
+        let mut stmt = match self.conn.prepare("SELECT chip_id, chip, seconds, milliseconds, reader_seconds, reader_milliseconds, antenna, reader, rssi, uploaded, tid, user_memory, channel_index, seen_count, last_seen_seconds, last_seen_milliseconds, bib FROM chip_reads WHERE seconds >= ?1 AND seconds <= ?2;") {
+            Ok(stmt) => stmt,
+            Err(e) => return Err(DBError::ConnectionError(e.to_string()))
+        };
+        let results = match stmt.query_map(
+            [start, end],
+            read_from_row) {
+                Ok(r) => r,
+                Err(e) => return Err(DBError::DataRetrievalError(e.to_string()))
+            };
+        let mut output: Vec<read::Read> = Vec::new();
+        for row in results {
+            match row {
+                Ok(r) => {
+                    output.push(r);
+                },
+                Err(e) => return Err(DBError::DataRetrievalError(e.to_string()))
+            }
+        }
+        return Ok(output);
+    }
+
+    fn get_all_reads(&self) -> Result<Vec<read::Read>, DBError> {
+        let mut stmt = match self.conn.prepare("SELECT chip_id, chip, seconds, milliseconds, reader_seconds, reader_milliseconds, antenna, reader, rssi, uploaded, tid, user_memory, channel_index, seen_count, last_seen_seconds, last_seen_milliseconds, bib FROM chip_reads;") {
+            Ok(stmt) => stmt,
+            Err(e) => return Err(DBError::ConnectionError(e.to_string()))
+        };
+        let results = match stmt.query_map(
+            [],
+            read_from_row) {
+                Ok(r) => r,
+                Err(e) => return Err(DBError::DataRetrievalError(e.to_string()))
+            };
+        let mut output: Vec<read::Read> = Vec::new();
+        for row in results {
+            match row {
+                Ok(r) => {
+                    output.push(r);
+                },
+                Err(e) => return Err(DBError::DataRetrievalError(e.to_string()))
+            }
+        }
+        return Ok(output);
+    }
+
+    // The reads given that don't have a read of the same chip, from the same reader, saved within the window of
+    // their reader time. Reads without a reader time can't be matched so they're all returned.
+    fn get_unknown_reads(&self, reads: &[read::Read], window_milliseconds: u64) -> Result<Vec<read::Read>, DBError> {
+        let mut stmt = match self.conn.prepare(
+            "SELECT COUNT(*) FROM chip_reads WHERE chip=?1 AND reader=?2 AND reader_seconds BETWEEN ?3 AND ?4 AND (reader_seconds * 1000 + reader_milliseconds) BETWEEN ?5 AND ?6;"
+        ) {
+            Ok(stmt) => stmt,
+            Err(e) => return Err(DBError::ConnectionError(e.to_string()))
+        };
+        let mut output: Vec<read::Read> = Vec::new();
+        for r in reads {
+            if r.reader_seconds() == 0 {
+                output.push(r.clone());
+                continue;
+            }
+            let time = r.reader_seconds() * 1000 + r.reader_milliseconds() as i64;
+            let start = time - window_milliseconds as i64;
+            let end = time + window_milliseconds as i64;
+            // the seconds let the index narrow things down before the exact times are compared
+            match stmt.query_row(
+                (r.chip(), r.reader(), start.div_euclid(1000), end.div_euclid(1000), start, end),
+                |row| row.get::<usize, i64>(0)
+            ) {
+                Ok(0) => output.push(r.clone()),
+                Ok(_) => {},
+                Err(e) => return Err(DBError::DataRetrievalError(e.to_string()))
+            }
+        }
+        Ok(output)
+    }
+
+    fn delete_reads(&mut self, start: i64, end: i64) -> Result<usize, DBError> {
+        match self.conn.execute(
+            "DELETE FROM chip_reads WHERE seconds >= ?1 AND seconds <= ?2;",
+            [start, end]
+        ) {
+            Ok(num) => return Ok(num),
+            Err(e) => return Err(DBError::DataDeletionError(e.to_string()))
+        }
+    }
+
+    fn delete_all_reads(&mut self) -> Result<usize, DBError> {
+        match self.conn.execute(
+            "DELETE FROM chip_reads;",
+            []
+        ) {
+            Ok(num) => return Ok(num),
+            Err(e) => return Err(DBError::DataDeletionError(e.to_string()))
+        }
+    }
+
+    fn get_not_uploaded_reads(&self) -> Result<Vec<read::Read>, DBError> {       
+        let mut stmt = match self.conn.prepare("SELECT chip_id, chip, seconds, milliseconds, reader_seconds, reader_milliseconds, antenna, reader, rssi, uploaded, tid, user_memory, channel_index, seen_count, last_seen_seconds, last_seen_milliseconds, bib FROM chip_reads WHERE uploaded=?1;") {
+            Ok(stmt) => stmt,
+            Err(e) => return Err(DBError::ConnectionError(e.to_string()))
+        };
+        let results = match stmt.query_map(
+            [read::READ_UPLOADED_FALSE],
+            read_from_row) {
+                Ok(r) => r,
+                Err(e) => return Err(DBError::DataRetrievalError(e.to_string()))
+            };
         let mut output: Vec<read::Read> = Vec::new();
         for row in results {
             match row {
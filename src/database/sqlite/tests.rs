@@ -22,9 +22,20 @@ use super::SQLite;
 use crate::database::DBError;
 use crate::database::Database;
 use crate::network::api;
+use crate::objects::antenna;
+use crate::objects::bibchip;
+use crate::objects::clock;
+use crate::objects::dedup;
+use crate::objects::filter;
+use crate::objects::gpio;
+use crate::objects::profile;
 use crate::objects::read;
+use crate::objects::read_window;
+use crate::objects::reconnect;
+use crate::objects::report_buffer;
 use crate::objects::setting;
-use crate::reader::{self, zebra};
+use crate::objects::tag_data;
+use crate::reader::{self, generic, impinj, zebra};
 
 pub fn setup_tests(path: &str) -> SQLite {
     let new_conn = rusqlite::Connection::open(path).unwrap();
@@ -35,6 +46,21 @@ pub fn setup_tests(path: &str) -> SQLite {
         "DROP TABLE IF EXISTS readers;",
         "DROP TABLE IF EXISTS chip_reads;",
         "DROP TABLE IF EXISTS settings;",
+        "DROP TABLE IF EXISTS reader_antennas;",
+        "DROP TABLE IF EXISTS reader_filters;",
+        "DROP TABLE IF EXISTS reader_identifiers;",
+        "DROP TABLE IF EXISTS reader_antenna_map;",
+        "DROP TABLE IF EXISTS reader_gpi_triggers;",
+        "DROP TABLE IF EXISTS reader_gpo_outputs;",
+        "DROP TABLE IF EXISTS reader_tag_data;",
+        "DROP TABLE IF EXISTS reader_clock;",
+        "DROP TABLE IF EXISTS reader_report_buffer;",
+        "DROP TABLE IF EXISTS reader_profiles;",
+        "DROP TABLE IF EXISTS reader_reconnect;",
+        "DROP TABLE IF EXISTS reader_dedup;",
+        "DROP TABLE IF EXISTS reader_dedup_groups;",
+        "DROP TABLE IF EXISTS reader_read_windows;",
+        "DROP TABLE IF EXISTS bibchip;",
     ];
     for table in drop_tables {
         if let Err(v) = new_conn.execute(table, []) {
@@ -55,7 +81,7 @@ pub fn setup_tests(path: &str) -> SQLite {
     output
 }
 
-fn finalize_tests(path: &str) {
+pub fn finalize_tests(path: &str) {
     _ = fs::remove_file(path).is_ok();
 }
 
@@ -155,6 +181,61 @@ fn test_update() {
     finalize_tests(unique_path);
 }
 
+#[test]
+fn test_update_to_v6() {
+    let unique_path = "./test_update_to_v6.sqlite";
+    let mut sqlite = setup_tests(unique_path);
+    // put the database back the way version 5 left it
+    let v5_tables = [
+        "DROP TABLE reader_antennas;",
+        "DROP TABLE reader_filters;",
+        "DROP TABLE reader_identifiers;",
+        "DROP TABLE reader_antenna_map;",
+        "DROP TABLE reader_gpi_triggers;",
+        "DROP TABLE reader_gpo_outputs;",
+        "DROP TABLE reader_tag_data;",
+        "DROP TABLE reader_clock;",
+        "DROP TABLE reader_report_buffer;",
+        "DROP TABLE reader_profiles;",
+        "DROP TABLE reader_reconnect;",
+        "DROP TABLE reader_dedup;",
+        "DROP TABLE reader_dedup_groups;",
+        "DROP TABLE reader_read_windows;",
+        "DROP TABLE bibchip;",
+        "DROP TABLE chip_reads;",
+        "CREATE TABLE chip_reads (
+            chip_id INTEGER PRIMARY KEY AUTOINCREMENT,
+            chip VARCHAR(100) NOT NULL,
+            seconds BIGINT NOT NULL,
+            milliseconds INTEGER NOT NULL,
+            reader_seconds BIGINT NOT NULL,
+            reader_milliseconds INTEGER NOT NULL,
+            antenna INTEGER,
+            reader VARCHAR(75),
+            rssi VARCHAR(10),
+            uploaded SMALLINT NOT NULL DEFAULT 0,
+            UNIQUE (chip, seconds, milliseconds) ON CONFLICT IGNORE
+        );",
+    ];
+    for table in v5_tables {
+        sqlite.conn.execute(table, []).unwrap();
+    }
+    sqlite.set_setting(&setting::Setting::new(String::from(super::DATABASE_VERSION_SETTING), String::from("5"))).unwrap();
+    sqlite.update(5, super::DATABASE_VERSION).unwrap();
+    assert_eq!(super::DATABASE_VERSION.to_string(), sqlite.get_setting(super::DATABASE_VERSION_SETTING).unwrap().value());
+    // everything the update added can be used
+    let reader_id = save_test_reader(&mut sqlite, reader::READER_KIND_ZEBRA);
+    assert_eq!(1, sqlite.save_antenna_configs(&reader_id, &[antenna::AntennaConfig::new(1, true, 0, None, 0)]).unwrap());
+    assert_eq!(1, sqlite.save_dedup_config(&reader_id, &dedup::DedupConfig::default()).unwrap());
+    assert_eq!(1, sqlite.save_bibchips(&[bibchip::BibChip::new(String::from("100"), String::from("1000"))], false).unwrap());
+    let mut read = read::Read::new(0, String::from("1000"), 100, 0, 100, 0, 1, String::from("zebra-1"), String::from("-50"), read::READ_UPLOADED_FALSE);
+    read.set_bib(Some(String::from("100")));
+    assert_eq!(1, sqlite.save_reads(&vec![read]).unwrap());
+    assert_eq!(Some("100"), sqlite.get_all_reads().unwrap()[0].bib());
+    drop(sqlite);
+    finalize_tests(unique_path);
+}
+
 #[test]
 fn test_set_setting() {
     let unique_path = "./test_set_setting.sqlite";
@@ -312,6 +393,44 @@ fn test_save_reader() {
     assert_eq!(original.port(), first.port());
     assert_eq!(reader::AUTO_CONNECT_TRUE, first.auto_connect());
 
+    // Test impinj reader kind
+    let impinj = reader::Reader::new_no_repeaters(
+        0,
+        String::from(reader::READER_KIND_IMPINJ),
+        String::from("impinj-1"),
+        String::from("192.168.1.101"),
+        impinj::DEFAULT_IMPINJ_PORT,
+        reader::AUTO_CONNECT_FALSE
+    );
+    assert!(impinj.is_ok());
+    let impinj = impinj.unwrap();
+    let result = sqlite.save_reader(&impinj);
+    assert!(result.is_ok());
+    let readers = sqlite.get_readers().unwrap();
+    assert_eq!(2, readers.len());
+    let found = readers.iter().find(|r| r.nickname() == impinj.nickname()).unwrap();
+    assert_eq!(reader::READER_KIND_IMPINJ, found.kind());
+    assert_eq!(impinj.ip_address(), found.ip_address());
+    assert_eq!(impinj.port(), found.port());
+
+    // Test generic llrp reader kind
+    let llrp = reader::Reader::new_no_repeaters(
+        0,
+        String::from(reader::READER_KIND_LLRP),
+        String::from("llrp-1"),
+        String::from("192.168.1.102"),
+        generic::DEFAULT_LLRP_PORT,
+        reader::AUTO_CONNECT_FALSE
+    );
+    assert!(llrp.is_ok());
+    let llrp = llrp.unwrap();
+    let result = sqlite.save_reader(&llrp);
+    assert!(result.is_ok());
+    let readers = sqlite.get_readers().unwrap();
+    assert_eq!(3, readers.len());
+    let found = readers.iter().find(|r| r.nickname() == llrp.nickname()).unwrap();
+    assert_eq!(reader::READER_KIND_LLRP, found.kind());
+
     // Test invalid reader kind
     let result = sqlite.save_reader(&reader::Reader::new_internal(
         0,
@@ -473,6 +592,452 @@ fn test_delete_reader() {
     finalize_tests(unique_path);
 }
 
+// Saves a reader for the per-reader configuration tests to hang their settings off of.
+fn save_test_reader(sqlite: &mut SQLite, kind: &str) -> i64 {
+    let (nickname, ip_address, port) = match kind {
+        reader::READER_KIND_IMPINJ => ("impinj-1", "192.168.1.102", impinj::DEFAULT_IMPINJ_PORT),
+        _ => ("zebra-1", "192.168.1.101", zebra::DEFAULT_ZEBRA_PORT),
+    };
+    sqlite.save_reader(&reader::Reader::new_no_repeaters(
+        0,
+        String::from(kind),
+        String::from(nickname),
+        String::from(ip_address),
+        port,
+        reader::AUTO_CONNECT_FALSE
+    ).unwrap()).unwrap()
+}
+
+#[test]
+fn test_save_antenna_configs() {
+    let unique_path = "./test_save_antenna_configs.sqlite";
+    let mut sqlite = setup_tests(unique_path);
+    let reader_id = save_test_reader(&mut sqlite, reader::READER_KIND_ZEBRA);
+    let configs = vec![
+        antenna::AntennaConfig::new(1, true, 0, Some(27.5), 0),
+        antenna::AntennaConfig::new(2, false, 0, None, 0),
+        antenna::AntennaConfig::new(3, true, 81, None, 1),
+    ];
+    let result = sqlite.save_antenna_configs(&reader_id, &configs);
+    assert!(result.is_ok());
+    assert_eq!(3, result.unwrap());
+    let found = sqlite.get_antenna_configs(&reader_id).unwrap();
+    assert_eq!(configs, found);
+    // saving replaces the previous list
+    let configs = vec![
+        antenna::AntennaConfig::new(4, true, 10, None, 2),
+    ];
+    assert_eq!(1, sqlite.save_antenna_configs(&reader_id, &configs).unwrap());
+    let found = sqlite.get_antenna_configs(&reader_id).unwrap();
+    assert_eq!(configs, found);
+    // other readers have nothing saved
+    assert_eq!(0, sqlite.get_antenna_configs(&(reader_id + 1)).unwrap().len());
+    // deleting the reader removes its antenna configuration
+    assert_eq!(1, sqlite.delete_reader(&reader_id).unwrap());
+    assert_eq!(0, sqlite.get_antenna_configs(&reader_id).unwrap().len());
+    drop(sqlite);
+    finalize_tests(unique_path);
+}
+
+#[test]
+fn test_save_antenna_map() {
+    let unique_path = "./test_save_antenna_map.sqlite";
+    let mut sqlite = setup_tests(unique_path);
+    let reader_id = save_test_reader(&mut sqlite, reader::READER_KIND_ZEBRA);
+    let map = vec![
+        antenna::AntennaMapping::new(1, 2),
+        antenna::AntennaMapping::new(2, 1),
+    ];
+    assert_eq!(2, sqlite.save_antenna_map(&reader_id, &map).unwrap());
+    assert_eq!(map, sqlite.get_antenna_map(&reader_id).unwrap());
+    // saving replaces the previous map
+    assert_eq!(0, sqlite.save_antenna_map(&reader_id, &[]).unwrap());
+    assert_eq!(0, sqlite.get_antenna_map(&reader_id).unwrap().len());
+    // deleting the reader removes its map
+    sqlite.save_antenna_map(&reader_id, &map).unwrap();
+    assert_eq!(1, sqlite.delete_reader(&reader_id).unwrap());
+    assert_eq!(0, sqlite.get_antenna_map(&reader_id).unwrap().len());
+    drop(sqlite);
+    finalize_tests(unique_path);
+}
+
+#[test]
+fn test_save_gpio_config() {
+    let unique_path = "./test_save_gpio_config.sqlite";
+    let mut sqlite = setup_tests(unique_path);
+    let reader_id = save_test_reader(&mut sqlite, reader::READER_KIND_ZEBRA);
+    // nothing saved
+    assert_eq!(gpio::GpioConfig::default(), sqlite.get_gpio_config(&reader_id).unwrap());
+    let config = gpio::GpioConfig::new(
+        Some(gpio::GpiTrigger::new(1, true, 0)),
+        Some(gpio::GpiTrigger::new(1, false, 30000)),
+        vec![
+            gpio::GpoOutput::new(2, String::from(gpio::GPO_EVENT_READING), 500),
+            gpio::GpoOutput::new(3, String::from(gpio::GPO_EVENT_READER_LOST), 0),
+        ],
+    );
+    assert_eq!(4, sqlite.save_gpio_config(&reader_id, &config).unwrap());
+    assert_eq!(config, sqlite.get_gpio_config(&reader_id).unwrap());
+    // saving replaces the previous config
+    let config = gpio::GpioConfig::new(
+        None,
+        Some(gpio::GpiTrigger::new(2, true, 0)),
+        vec![
+            gpio::GpoOutput::new(1, String::from(gpio::GPO_EVENT_CONNECTED), 0),
+        ],
+    );
+    assert_eq!(2, sqlite.save_gpio_config(&reader_id, &config).unwrap());
+    assert_eq!(config, sqlite.get_gpio_config(&reader_id).unwrap());
+    // deleting the reader removes its config
+    assert_eq!(1, sqlite.delete_reader(&reader_id).unwrap());
+    assert_eq!(gpio::GpioConfig::default(), sqlite.get_gpio_config(&reader_id).unwrap());
+    drop(sqlite);
+    finalize_tests(unique_path);
+}
+
+#[test]
+fn test_save_tag_data_config() {
+    let unique_path = "./test_save_tag_data_config.sqlite";
+    let mut sqlite = setup_tests(unique_path);
+    let reader_id = save_test_reader(&mut sqlite, reader::READER_KIND_IMPINJ);
+    // nothing saved
+    assert_eq!(tag_data::TagDataConfig::default(), sqlite.get_tag_data_config(&reader_id).unwrap());
+    let config = tag_data::TagDataConfig::new(6, 0, true);
+    assert_eq!(1, sqlite.save_tag_data_config(&reader_id, &config).unwrap());
+    assert_eq!(config, sqlite.get_tag_data_config(&reader_id).unwrap());
+    // saving replaces the previous config
+    let config = tag_data::TagDataConfig::new(4, 2, false);
+    assert_eq!(1, sqlite.save_tag_data_config(&reader_id, &config).unwrap());
+    assert_eq!(config, sqlite.get_tag_data_config(&reader_id).unwrap());
+    // deleting the reader removes its config
+    assert_eq!(1, sqlite.delete_reader(&reader_id).unwrap());
+    assert_eq!(tag_data::TagDataConfig::default(), sqlite.get_tag_data_config(&reader_id).unwrap());
+    drop(sqlite);
+    finalize_tests(unique_path);
+}
+
+#[test]
+fn test_save_clock_config() {
+    let unique_path = "./test_save_clock_config.sqlite";
+    let mut sqlite = setup_tests(unique_path);
+    let reader_id = save_test_reader(&mut sqlite, reader::READER_KIND_IMPINJ);
+    // nothing saved
+    assert_eq!(clock::ClockConfig::default(), sqlite.get_clock_config(&reader_id).unwrap());
+    let config = clock::ClockConfig::new(String::from(clock::CLOCK_SOURCE_READER), true, 250, true);
+    assert_eq!(1, sqlite.save_clock_config(&reader_id, &config).unwrap());
+    assert_eq!(config, sqlite.get_clock_config(&reader_id).unwrap());
+    // saving replaces the previous config
+    let config = clock::ClockConfig::new(String::from(clock::CLOCK_SOURCE_PORTAL), false, 0, false);
+    assert_eq!(1, sqlite.save_clock_config(&reader_id, &config).unwrap());
+    assert_eq!(config, sqlite.get_clock_config(&reader_id).unwrap());
+    // deleting the reader removes its config
+    assert_eq!(1, sqlite.delete_reader(&reader_id).unwrap());
+    assert_eq!(clock::ClockConfig::default(), sqlite.get_clock_config(&reader_id).unwrap());
+    drop(sqlite);
+    finalize_tests(unique_path);
+}
+
+#[test]
+fn test_save_report_buffer_config() {
+    let unique_path = "./test_save_report_buffer_config.sqlite";
+    let mut sqlite = setup_tests(unique_path);
+    let reader_id = save_test_reader(&mut sqlite, reader::READER_KIND_ZEBRA);
+    // nothing saved
+    assert_eq!(report_buffer::ReportBufferConfig::default(), sqlite.get_report_buffer_config(&reader_id).unwrap());
+    let config = report_buffer::ReportBufferConfig::new(true);
+    assert_eq!(1, sqlite.save_report_buffer_config(&reader_id, &config).unwrap());
+    assert_eq!(config, sqlite.get_report_buffer_config(&reader_id).unwrap());
+    // saving replaces the previous config
+    let config = report_buffer::ReportBufferConfig::new(false);
+    assert_eq!(1, sqlite.save_report_buffer_config(&reader_id, &config).unwrap());
+    assert_eq!(config, sqlite.get_report_buffer_config(&reader_id).unwrap());
+    // deleting the reader removes its config
+    sqlite.save_report_buffer_config(&reader_id, &report_buffer::ReportBufferConfig::new(true)).unwrap();
+    assert_eq!(1, sqlite.delete_reader(&reader_id).unwrap());
+    assert_eq!(report_buffer::ReportBufferConfig::default(), sqlite.get_report_buffer_config(&reader_id).unwrap());
+    drop(sqlite);
+    finalize_tests(unique_path);
+}
+
+#[test]
+fn test_save_profile_config() {
+    let unique_path = "./test_save_profile_config.sqlite";
+    let mut sqlite = setup_tests(unique_path);
+    let reader_id = save_test_reader(&mut sqlite, reader::READER_KIND_ZEBRA);
+    // nothing saved
+    assert_eq!(profile::ProfileConfig::default(), sqlite.get_profile_config(&reader_id).unwrap());
+    let config = profile::ProfileConfig::new(
+        vec![
+            profile::ReadingProfile::new(String::from("finish line"), Vec::new(), Some(31.5), 0),
+            profile::ReadingProfile::new(String::from("chip check"), vec![3], Some(15.0), 2000),
+        ],
+        Some(String::from("chip check")),
+    );
+    assert_eq!(2, sqlite.save_profile_config(&reader_id, &config).unwrap());
+    assert_eq!(config, sqlite.get_profile_config(&reader_id).unwrap());
+    // saving replaces the previous profiles
+    let config = profile::ProfileConfig::new(
+        vec![profile::ReadingProfile::new(String::from("start line"), vec![1, 2], None, 0)],
+        None,
+    );
+    assert_eq!(1, sqlite.save_profile_config(&reader_id, &config).unwrap());
+    assert_eq!(config, sqlite.get_profile_config(&reader_id).unwrap());
+    // deleting the reader removes its profiles
+    assert_eq!(1, sqlite.delete_reader(&reader_id).unwrap());
+    assert_eq!(profile::ProfileConfig::default(), sqlite.get_profile_config(&reader_id).unwrap());
+    drop(sqlite);
+    finalize_tests(unique_path);
+}
+
+#[test]
+fn test_save_reconnect_policy() {
+    let unique_path = "./test_save_reconnect_policy.sqlite";
+    let mut sqlite = setup_tests(unique_path);
+    let reader_id = save_test_reader(&mut sqlite, reader::READER_KIND_ZEBRA);
+    // nothing saved
+    assert_eq!(reconnect::ReconnectPolicy::default(), sqlite.get_reconnect_policy(&reader_id).unwrap());
+    let policy = reconnect::ReconnectPolicy::new(0, 500, 2.0, 60000);
+    assert_eq!(1, sqlite.save_reconnect_policy(&reader_id, &policy).unwrap());
+    assert_eq!(policy, sqlite.get_reconnect_policy(&reader_id).unwrap());
+    // saving again replaces the policy
+    let policy = reconnect::ReconnectPolicy::new(10, 1000, 1.5, 5000);
+    assert_eq!(1, sqlite.save_reconnect_policy(&reader_id, &policy).unwrap());
+    assert_eq!(policy, sqlite.get_reconnect_policy(&reader_id).unwrap());
+    // deleting the reader removes its policy
+    assert_eq!(1, sqlite.delete_reader(&reader_id).unwrap());
+    assert_eq!(reconnect::ReconnectPolicy::default(), sqlite.get_reconnect_policy(&reader_id).unwrap());
+    drop(sqlite);
+    finalize_tests(unique_path);
+}
+
+#[test]
+fn test_save_dedup_config() {
+    let unique_path = "./test_save_dedup_config.sqlite";
+    let mut sqlite = setup_tests(unique_path);
+    let reader_id = save_test_reader(&mut sqlite, reader::READER_KIND_ZEBRA);
+    // nothing saved
+    assert_eq!(dedup::DedupConfig::default(), sqlite.get_dedup_config(&reader_id).unwrap());
+    let config = dedup::DedupConfig::new(
+        String::from(dedup::DEDUP_MODE_BEST_RSSI),
+        true,
+        vec![
+            dedup::DedupGroup::new(vec![1, 2], String::from(dedup::DEDUP_MODE_FIRST)),
+            dedup::DedupGroup::new(vec![4], String::from(dedup::DEDUP_MODE_ALL)),
+        ],
+    );
+    assert_eq!(3, sqlite.save_dedup_config(&reader_id, &config).unwrap());
+    assert_eq!(config, sqlite.get_dedup_config(&reader_id).unwrap());
+    // saving replaces the previous groups
+    let config = dedup::DedupConfig::new(String::from(dedup::DEDUP_MODE_LAST), false, Vec::new());
+    assert_eq!(1, sqlite.save_dedup_config(&reader_id, &config).unwrap());
+    assert_eq!(config, sqlite.get_dedup_config(&reader_id).unwrap());
+    // deleting the reader removes its settings
+    sqlite.save_dedup_config(&reader_id, &dedup::DedupConfig::new(String::from(dedup::DEDUP_MODE_LAST), false, vec![dedup::DedupGroup::new(vec![3], String::from(dedup::DEDUP_MODE_FIRST))])).unwrap();
+    assert_eq!(1, sqlite.delete_reader(&reader_id).unwrap());
+    assert_eq!(dedup::DedupConfig::default(), sqlite.get_dedup_config(&reader_id).unwrap());
+    drop(sqlite);
+    finalize_tests(unique_path);
+}
+
+#[test]
+fn test_save_read_window_config() {
+    let unique_path = "./test_save_read_window_config.sqlite";
+    let mut sqlite = setup_tests(unique_path);
+    let reader_id = save_test_reader(&mut sqlite, reader::READER_KIND_ZEBRA);
+    // nothing saved
+    assert_eq!(read_window::ReadWindowConfig::default(), sqlite.get_read_window_config(&reader_id).unwrap());
+    let config = read_window::ReadWindowConfig::new(
+        Some(20),
+        vec![
+            read_window::AntennaReadWindow::new(1, 5),
+            read_window::AntennaReadWindow::new(3, 40),
+        ],
+    );
+    assert_eq!(3, sqlite.save_read_window_config(&reader_id, &config).unwrap());
+    assert_eq!(config, sqlite.get_read_window_config(&reader_id).unwrap());
+    // saving replaces the previous windows
+    let config = read_window::ReadWindowConfig::new(None, vec![read_window::AntennaReadWindow::new(2, 10)]);
+    assert_eq!(1, sqlite.save_read_window_config(&reader_id, &config).unwrap());
+    assert_eq!(config, sqlite.get_read_window_config(&reader_id).unwrap());
+    // deleting the reader removes its windows
+    assert_eq!(1, sqlite.delete_reader(&reader_id).unwrap());
+    assert_eq!(read_window::ReadWindowConfig::default(), sqlite.get_read_window_config(&reader_id).unwrap());
+    drop(sqlite);
+    finalize_tests(unique_path);
+}
+
+#[test]
+fn test_save_bibchips() {
+    let unique_path = "./test_save_bibchips.sqlite";
+    let mut sqlite = setup_tests(unique_path);
+    assert_eq!(0, sqlite.get_bibchips().unwrap().len());
+    let bibchips = vec![
+        bibchip::BibChip::new(String::from("100"), String::from("1000")),
+        bibchip::BibChip::new(String::from("101"), String::from("1001")),
+        bibchip::BibChip::new(String::from("101"), String::from("1002")),
+    ];
+    assert_eq!(3, sqlite.save_bibchips(&bibchips, false).unwrap());
+    let mut saved = sqlite.get_bibchips().unwrap();
+    saved.sort_by(|a, b| a.chip().cmp(b.chip()));
+    assert_eq!(bibchips, saved);
+    // a chip only belongs to one bib
+    assert_eq!(1, sqlite.save_bibchips(&[bibchip::BibChip::new(String::from("102"), String::from("1000"))], false).unwrap());
+    let saved = sqlite.get_bibchips().unwrap();
+    assert_eq!(3, saved.len());
+    assert_eq!("102", saved.iter().find(|bc| bc.chip() == "1000").unwrap().bib());
+    // chips are matched without case, the same as reads are
+    assert_eq!(1, sqlite.save_bibchips(&[bibchip::BibChip::new(String::from("104"), String::from("ABC"))], false).unwrap());
+    assert_eq!(1, sqlite.save_bibchips(&[bibchip::BibChip::new(String::from("105"), String::from("abc"))], false).unwrap());
+    let saved = sqlite.get_bibchips().unwrap();
+    assert_eq!(4, saved.len());
+    assert_eq!("105", saved.iter().find(|bc| bc.chip().eq_ignore_ascii_case("abc")).unwrap().bib());
+    // reads keep the bib they were saved with
+    let mut read = read::Read::new(0, String::from("1000"), 100, 20, 100, 0, 1, String::from("reader"), String::from("-50"), read::READ_UPLOADED_FALSE);
+    read.set_bib(Some(String::from("102")));
+    let reads = vec![
+        read,
+        read::Read::new(0, String::from("1003"), 100, 30, 100, 10, 1, String::from("reader"), String::from("-50"), read::READ_UPLOADED_FALSE),
+    ];
+    assert_eq!(2, sqlite.save_reads(&reads).unwrap());
+    let mut saved = sqlite.get_all_reads().unwrap();
+    saved.sort_by(|a, b| a.chip().cmp(b.chip()));
+    assert_eq!(Some("102"), saved[0].bib());
+    assert_eq!(None, saved[1].bib());
+    // replacing the mapping drops the chips that aren't in the new one
+    let bibchips = vec![bibchip::BibChip::new(String::from("103"), String::from("1004"))];
+    assert_eq!(1, sqlite.save_bibchips(&bibchips, true).unwrap());
+    assert_eq!(bibchips, sqlite.get_bibchips().unwrap());
+    assert_eq!(1, sqlite.delete_bibchips().unwrap());
+    assert_eq!(0, sqlite.get_bibchips().unwrap().len());
+    drop(sqlite);
+    finalize_tests(unique_path);
+}
+
+#[test]
+fn test_get_unknown_reads() {
+    let unique_path = "./test_get_unknown_reads.sqlite";
+    let mut sqlite = setup_tests(unique_path);
+    let saved = vec![
+        read::Read::new(0, String::from("1000"), 100, 20, 100, 0, 1, String::from("reader"), String::from("-50"), read::READ_UPLOADED_FALSE),
+        read::Read::new(0, String::from("1001"), 200, 20, 200, 0, 1, String::from("reader"), String::from("-50"), read::READ_UPLOADED_FALSE),
+    ];
+    assert_eq!(2, sqlite.save_reads(&saved).unwrap());
+    let recovered = vec![
+        // same chip and reader inside the window, portal time doesn't matter
+        read::Read::new(0, String::from("1000"), 100, 0, 100, 400, 1, String::from("reader"), String::from("-45"), read::READ_UPLOADED_FALSE),
+        // outside the window
+        read::Read::new(0, String::from("1001"), 201, 0, 201, 0, 1, String::from("reader"), String::from("-50"), read::READ_UPLOADED_FALSE),
+        // inside the window from the second before
+        read::Read::new(0, String::from("1001"), 199, 600, 199, 600, 1, String::from("reader"), String::from("-50"), read::READ_UPLOADED_FALSE),
+        // another reader
+        read::Read::new(0, String::from("1000"), 100, 0, 100, 0, 1, String::from("other"), String::from("-50"), read::READ_UPLOADED_FALSE),
+        // never seen
+        read::Read::new(0, String::from("1002"), 100, 0, 100, 0, 1, String::from("reader"), String::from("-50"), read::READ_UPLOADED_FALSE),
+        // no reader time to match against
+        read::Read::new(0, String::from("1000"), 100, 20, 0, 0, 1, String::from("reader"), String::from("-50"), read::READ_UPLOADED_FALSE),
+    ];
+    let unknown = sqlite.get_unknown_reads(&recovered, 500).unwrap();
+    assert_eq!(4, unknown.len());
+    assert!(unknown.iter().all(|r| !(r.chip() == "1000" && r.reader() == "reader" && r.reader_seconds() == 100)));
+    assert!(unknown.iter().all(|r| !(r.chip() == "1001" && r.reader_seconds() == 199)));
+    // a smaller window doesn't reach the saved reads
+    assert_eq!(6, sqlite.get_unknown_reads(&recovered, 100).unwrap().len());
+    // looking up saved reads uses the index instead of scanning every read
+    let plan: String = sqlite.conn.query_row(
+        "EXPLAIN QUERY PLAN SELECT COUNT(*) FROM chip_reads WHERE chip=?1 AND reader=?2 AND reader_seconds BETWEEN ?3 AND ?4;",
+        ("1000", "reader", 99, 101),
+        |row| row.get(3)
+    ).unwrap();
+    assert!(plan.contains("chip_reads_chip_reader_time"));
+    drop(sqlite);
+    finalize_tests(unique_path);
+}
+
+#[test]
+fn test_save_read_details() {
+    let unique_path = "./test_save_read_details.sqlite";
+    let mut sqlite = setup_tests(unique_path);
+    let mut detailed = read::Read::new(0, String::from("1000"), 100, 0, 100, 0, 1, String::from("reader"), String::from("-50"), read::READ_UPLOADED_FALSE);
+    let details = read::ReadDetails {
+        tid: Some(String::from("E2801160200074CF")),
+        user_memory: Some(String::from("0102")),
+        channel_index: Some(12),
+        seen_count: Some(3),
+        last_seen_seconds: Some(101),
+        last_seen_milliseconds: Some(250),
+    };
+    detailed.set_details(details.clone());
+    let plain = read::Read::new(0, String::from("1001"), 100, 0, 100, 0, 1, String::from("reader"), String::from("-50"), read::READ_UPLOADED_FALSE);
+    assert_eq!(2, sqlite.save_reads(&vec![detailed, plain]).unwrap());
+    let reads = sqlite.get_all_reads().unwrap();
+    assert_eq!(2, reads.len());
+    for read in reads {
+        match read.chip() {
+            "1000" => assert_eq!(&details, read.details()),
+            _ => assert_eq!(&read::ReadDetails::default(), read.details()),
+        }
+    }
+    drop(sqlite);
+    finalize_tests(unique_path);
+}
+
+#[test]
+fn test_save_tag_filters() {
+    let unique_path = "./test_save_tag_filters.sqlite";
+    let mut sqlite = setup_tests(unique_path);
+    let reader_id = save_test_reader(&mut sqlite, reader::READER_KIND_IMPINJ);
+    let filters = vec![
+        filter::TagFilter::new(filter::FILTER_MEMORY_BANK_EPC, 32, String::from("E280"), 0, String::from(filter::FILTER_ACTION_INCLUDE)),
+        filter::TagFilter::new(filter::FILTER_MEMORY_BANK_EPC, 32, String::from("E2806894"), 0, String::from(filter::FILTER_ACTION_INCLUDE)),
+        filter::TagFilter::new(filter::FILTER_MEMORY_BANK_TID, 0, String::from("E2801"), 17, String::from(filter::FILTER_ACTION_EXCLUDE)),
+    ];
+    let result = sqlite.save_tag_filters(&reader_id, &filters);
+    assert!(result.is_ok());
+    assert_eq!(3, result.unwrap());
+    // filters come back in the order they were saved
+    let found = sqlite.get_tag_filters(&reader_id).unwrap();
+    assert_eq!(filters, found);
+    // saving replaces the previous list
+    assert_eq!(1, sqlite.save_tag_filters(&reader_id, &filters[2..]).unwrap());
+    assert_eq!(filters[2..].to_vec(), sqlite.get_tag_filters(&reader_id).unwrap());
+    assert_eq!(0, sqlite.save_tag_filters(&reader_id, &[]).unwrap());
+    assert_eq!(0, sqlite.get_tag_filters(&reader_id).unwrap().len());
+    // deleting the reader removes its filters
+    sqlite.save_tag_filters(&reader_id, &filters).unwrap();
+    assert_eq!(1, sqlite.delete_reader(&reader_id).unwrap());
+    assert_eq!(0, sqlite.get_tag_filters(&reader_id).unwrap().len());
+    drop(sqlite);
+    finalize_tests(unique_path);
+}
+
+#[test]
+fn test_save_reader_identifier() {
+    let unique_path = "./test_save_reader_identifier.sqlite";
+    let mut sqlite = setup_tests(unique_path);
+    let reader_id = save_test_reader(&mut sqlite, reader::READER_KIND_ZEBRA);
+    assert!(matches!(sqlite.get_reader_by_identifier("00:11:22:33:44:55"), Err(DBError::NotFound)));
+    assert_eq!(1, sqlite.save_reader_identifier(&reader_id, "00:11:22:33:44:55").unwrap());
+    let found = sqlite.get_reader_by_identifier("00:11:22:33:44:55").unwrap();
+    assert_eq!(reader_id, found.id());
+    assert_eq!("zebra-1", found.nickname());
+    // saving the identifier again moves it to the new reader
+    let other_id = sqlite.save_reader(&reader::Reader::new_no_repeaters(
+        0,
+        String::from(reader::READER_KIND_ZEBRA),
+        String::from("zebra-2"),
+        String::from("192.168.1.102"),
+        zebra::DEFAULT_ZEBRA_PORT,
+        reader::AUTO_CONNECT_FALSE
+    ).unwrap()).unwrap();
+    sqlite.save_reader_identifier(&other_id, "00:11:22:33:44:55").unwrap();
+    assert_eq!(other_id, sqlite.get_reader_by_identifier("00:11:22:33:44:55").unwrap().id());
+    // deleting the reader removes its identifiers
+    assert_eq!(1, sqlite.delete_reader(&other_id).unwrap());
+    assert!(matches!(sqlite.get_reader_by_identifier("00:11:22:33:44:55"), Err(DBError::NotFound)));
+    drop(sqlite);
+    finalize_tests(unique_path);
+}
+
 #[test]
 fn test_save_api() {
     let unique_path = "./test_save_api.sqlite";
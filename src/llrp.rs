@@ -19,3 +19,5 @@ along with this program.  If not, see <https://www.gnu.org/licenses/>.
 pub mod message_types;
 pub mod parameter_types;
 pub mod bit_masks;
+pub mod encoder;
+pub mod decoder;
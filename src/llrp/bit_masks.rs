@@ -54,7 +54,7 @@ pub const TV_RESERVED:      u16 = 0x8000; // 1000 0000 0000 0000
 pub const TV_TYPE:          u16 = 0x7F00; // 0111 1111 0000 0000
 
 // TLV parameter masks
-pub const PARAM_RESERVED:   u16 = 0xFA00; // 1111 1100 0000 0000
+pub const PARAM_RESERVED:   u16 = 0xFC00; // 1111 1100 0000 0000
 pub const PARAM_TYPE:       u16 = 0x03FF; // 0000 0011 1111 1111
 pub const PARAM_LENGTH:     u32 = 0xFFFF; // 1111 1111 1111 1111
 
@@ -0,0 +1,471 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::fmt;
+
+use super::{bit_masks, encoder::VERSION_1_1, message_types, parameter_types};
+
+#[cfg(test)]
+pub mod test;
+
+pub const HEADER_LENGTH: usize = 10;
+// Anything bigger than this is almost certainly a corrupt length field.
+pub const MAX_MESSAGE_LENGTH: usize = 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecodeError {
+    // The frame header can't be trusted, the stream has been resynchronized by dropping buffered data.
+    InvalidHeader(String),
+    // A parameter or field runs past the end of the message or parameter containing it.
+    Truncated(String),
+    // A parameter header is malformed or describes something we can't size.
+    InvalidParameter(String),
+    // A field has a value that isn't allowed.
+    InvalidField(String),
+    // A parameter that was expected wasn't found.
+    MissingParameter(String),
+}
+
+impl std::error::Error for DecodeError {}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::InvalidHeader(val) => write!(f, "Invalid Header: {val}"),
+            DecodeError::Truncated(val) => write!(f, "Truncated: {val}"),
+            DecodeError::InvalidParameter(val) => write!(f, "Invalid Parameter: {val}"),
+            DecodeError::InvalidField(val) => write!(f, "Invalid Field: {val}"),
+            DecodeError::MissingParameter(val) => write!(f, "Missing Parameter: {val}"),
+        }
+    }
+}
+
+// A complete LLRP message. Fields are the fixed values that come before any parameters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    pub version: u8,
+    pub kind: u16,
+    pub id: u32,
+    pub fields: Vec<u8>,
+    pub parameters: Vec<Parameter>,
+}
+
+// A TLV or TV parameter. Fields are the fixed values that come before any sub parameters.
+// Parameters that we don't know the layout of keep everything in fields.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parameter {
+    pub kind: u16,
+    pub tv: bool,
+    pub fields: Vec<u8>,
+    pub parameters: Vec<Parameter>,
+}
+
+// Shared by messages and parameters to read their fixed fields and find sub parameters.
+pub trait Fields {
+    fn fields(&self) -> &[u8];
+    fn parameters(&self) -> &[Parameter];
+
+    fn u8_at(&self, ix: usize) -> Result<u8, DecodeError> {
+        match self.fields().get(ix) {
+            Some(val) => Ok(*val),
+            None => Err(DecodeError::Truncated(format!("field at {ix} past end of {} bytes", self.fields().len()))),
+        }
+    }
+
+    fn u16_at(&self, ix: usize) -> Result<u16, DecodeError> {
+        let val = self.bytes_at(ix, 2)?;
+        Ok(u16::from_be_bytes([val[0], val[1]]))
+    }
+
+    fn u32_at(&self, ix: usize) -> Result<u32, DecodeError> {
+        let val = self.bytes_at(ix, 4)?;
+        Ok(u32::from_be_bytes([val[0], val[1], val[2], val[3]]))
+    }
+
+    fn u64_at(&self, ix: usize) -> Result<u64, DecodeError> {
+        let val = self.bytes_at(ix, 8)?;
+        Ok(u64::from_be_bytes([val[0], val[1], val[2], val[3], val[4], val[5], val[6], val[7]]))
+    }
+
+    fn bytes_at(&self, ix: usize, len: usize) -> Result<&[u8], DecodeError> {
+        match self.fields().get(ix..ix+len) {
+            Some(val) => Ok(val),
+            None => Err(DecodeError::Truncated(format!("{len} byte field at {ix} past end of {} bytes", self.fields().len()))),
+        }
+    }
+
+    fn find(&self, kind: u16) -> Option<&Parameter> {
+        self.parameters().iter().find(|p| p.kind == kind)
+    }
+
+    fn find_all(&self, kind: u16) -> Vec<&Parameter> {
+        self.parameters().iter().filter(|p| p.kind == kind).collect()
+    }
+}
+
+impl Fields for Message {
+    fn fields(&self) -> &[u8] {
+        &self.fields
+    }
+
+    fn parameters(&self) -> &[Parameter] {
+        &self.parameters
+    }
+}
+
+impl Fields for Parameter {
+    fn fields(&self) -> &[u8] {
+        &self.fields
+    }
+
+    fn parameters(&self) -> &[Parameter] {
+        &self.parameters
+    }
+}
+
+impl Message {
+    // Vendor and subtype of a CUSTOM_MESSAGE.
+    pub fn custom_info(&self) -> Result<(u32, u16), DecodeError> {
+        if self.kind != message_types::CUSTOM_MESSAGE {
+            return Err(DecodeError::InvalidField(format!("message type {} is not a custom message", self.kind)))
+        }
+        Ok((self.u32_at(0)?, self.u8_at(4)? as u16))
+    }
+}
+
+impl Parameter {
+    // Vendor and subtype of a custom parameter.
+    pub fn custom_info(&self) -> Result<(u32, u32), DecodeError> {
+        if self.kind != parameter_types::CUSTOM_PARAMETER {
+            return Err(DecodeError::InvalidField(format!("parameter type {} is not a custom parameter", self.kind)))
+        }
+        Ok((self.u32_at(0)?, self.u32_at(4)?))
+    }
+}
+
+// The default is a success with no description.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LLRPStatus {
+    pub code: u16,
+    pub description: String,
+    // field number and error code
+    pub field_error: Option<(u16, u16)>,
+    // parameter type and error code
+    pub parameter_error: Option<(u16, u16)>,
+}
+
+impl LLRPStatus {
+    pub fn success(&self) -> bool {
+        self.code == parameter_types::M_SUCCESS
+    }
+}
+
+pub fn llrp_status(param: &Parameter) -> Result<LLRPStatus, DecodeError> {
+    // status code (2 bytes), error description byte count (2 bytes), then the description
+    // optionally followed by FieldError and ParameterError parameters
+    if param.kind != parameter_types::LLRP_STATUS {
+        return Err(DecodeError::InvalidParameter(format!("expected llrp status parameter, found {}", param.kind)))
+    }
+    let code = param.u16_at(0)?;
+    let count = param.u16_at(2)? as usize;
+    let description = match std::str::from_utf8(param.bytes_at(4, count)?) {
+        Ok(desc) => desc.to_string(),
+        Err(_) => return Err(DecodeError::InvalidField(String::from("error description is not valid utf-8"))),
+    };
+    let field_error = match param.find(parameter_types::FIELD_ERROR) {
+        Some(field) => Some((field.u16_at(0)?, field.u16_at(2)?)),
+        None => None,
+    };
+    let parameter_error = match param.find(parameter_types::PARAMETER_EVENT) {
+        Some(p_err) => Some((p_err.u16_at(0)?, p_err.u16_at(2)?)),
+        None => None,
+    };
+    Ok(LLRPStatus {
+        code,
+        description,
+        field_error,
+        parameter_error,
+    })
+}
+
+// Streaming decoder. Push whatever bytes come off of the socket and pull complete messages out.
+#[derive(Debug, Default)]
+pub struct Decoder {
+    buffer: Vec<u8>,
+}
+
+impl Decoder {
+    pub fn new() -> Decoder {
+        Decoder {
+            buffer: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, chunk: &[u8]) {
+        self.buffer.extend_from_slice(chunk);
+    }
+
+    // Number of bytes waiting on the rest of a message.
+    pub fn pending(&self) -> usize {
+        self.buffer.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+    }
+
+    // Returns None when there isn't a full message buffered. A message with a bad header clears the
+    // buffer since we have no way of knowing where the next message starts. A message with a good header
+    // but bad parameters is dropped by itself.
+    pub fn next_message(&mut self) -> Option<Result<Message, DecodeError>> {
+        if self.buffer.len() < HEADER_LENGTH {
+            return None
+        }
+        let info = match bit_masks::get_msg_type(&self.buffer[..HEADER_LENGTH]) {
+            Ok(info) => info,
+            Err(e) => {
+                self.buffer.clear();
+                return Some(Err(DecodeError::InvalidHeader(e.to_string())))
+            }
+        };
+        let length = info.length as usize;
+        if !(HEADER_LENGTH..=MAX_MESSAGE_LENGTH).contains(&length) {
+            self.buffer.clear();
+            return Some(Err(DecodeError::InvalidHeader(format!("invalid message length {length}"))))
+        }
+        if self.buffer.len() < length {
+            return None
+        }
+        let frame: Vec<u8> = self.buffer.drain(..length).collect();
+        Some(decode_body(info.version as u8, info.kind, info.id, &frame[HEADER_LENGTH..]))
+    }
+}
+
+// Decodes a single complete message, header included.
+pub fn decode_message(buf: &[u8]) -> Result<Message, DecodeError> {
+    if buf.len() < HEADER_LENGTH {
+        return Err(DecodeError::Truncated(format!("message header needs {HEADER_LENGTH} bytes, found {}", buf.len())))
+    }
+    let info = match bit_masks::get_msg_type(&buf[..HEADER_LENGTH]) {
+        Ok(info) => info,
+        Err(e) => return Err(DecodeError::InvalidHeader(e.to_string())),
+    };
+    let length = info.length as usize;
+    if length < HEADER_LENGTH {
+        return Err(DecodeError::InvalidHeader(format!("invalid message length {length}")))
+    }
+    if length > buf.len() {
+        return Err(DecodeError::Truncated(format!("message length {length} longer than the {} bytes available", buf.len())))
+    }
+    decode_body(info.version as u8, info.kind, info.id, &buf[HEADER_LENGTH..length])
+}
+
+fn decode_body(version: u8, kind: u16, id: u32, body: &[u8]) -> Result<Message, DecodeError> {
+    let fields_length = match message_fields_length(version, kind) {
+        Some(len) if len <= body.len() => len,
+        Some(len) => return Err(DecodeError::Truncated(format!("message type {kind} needs {len} bytes of fields, found {}", body.len()))),
+        None => body.len(),
+    };
+    let parameters = if kind == message_types::CUSTOM_MESSAGE {
+        // vendor payloads aren't always made up of parameters, keep the raw bytes if they aren't
+        match decode_parameters(&body[fields_length..]) {
+            Ok(params) => params,
+            Err(_) => {
+                return Ok(Message {
+                    version,
+                    kind,
+                    id,
+                    fields: body.to_vec(),
+                    parameters: Vec::new(),
+                })
+            }
+        }
+    } else {
+        decode_parameters(&body[fields_length..])?
+    };
+    Ok(Message {
+        version,
+        kind,
+        id,
+        fields: body[..fields_length].to_vec(),
+        parameters,
+    })
+}
+
+// Decodes a run of parameters that should take up the entire buffer.
+pub fn decode_parameters(buf: &[u8]) -> Result<Vec<Parameter>, DecodeError> {
+    let mut output: Vec<Parameter> = Vec::new();
+    let mut ix = 0;
+    while ix < buf.len() {
+        let (param, length) = decode_parameter(&buf[ix..])?;
+        output.push(param);
+        ix += length;
+    }
+    Ok(output)
+}
+
+// Decodes the parameter at the start of the buffer, returning it and the number of bytes it used.
+pub fn decode_parameter(buf: &[u8]) -> Result<(Parameter, usize), DecodeError> {
+    if buf.is_empty() {
+        return Err(DecodeError::Truncated(String::from("no bytes left for parameter")))
+    }
+    if buf[0] & 0x80 != 0 {
+        let kind = (buf[0] & 0x7F) as u16;
+        let length = bit_masks::tv_length_dict(kind) as usize;
+        if length == 0 {
+            return Err(DecodeError::InvalidParameter(format!("unknown tv parameter type {kind}")))
+        }
+        if length > buf.len() {
+            return Err(DecodeError::Truncated(format!("tv parameter type {kind} needs {length} bytes, found {}", buf.len())))
+        }
+        return Ok((Parameter {
+            kind,
+            tv: true,
+            fields: buf[1..length].to_vec(),
+            parameters: Vec::new(),
+        }, length))
+    }
+    if buf.len() < 4 {
+        return Err(DecodeError::Truncated(format!("tlv parameter header needs 4 bytes, found {}", buf.len())))
+    }
+    let info = match bit_masks::get_param_type(&u32::from_be_bytes([buf[0], buf[1], buf[2], buf[3]])) {
+        Ok(info) => info,
+        Err(e) => return Err(DecodeError::InvalidParameter(e.to_string())),
+    };
+    let length = info.length as usize;
+    if length < 4 {
+        return Err(DecodeError::InvalidParameter(format!("tlv parameter type {} has invalid length {length}", info.kind)))
+    }
+    if length > buf.len() {
+        return Err(DecodeError::Truncated(format!("tlv parameter type {} needs {length} bytes, found {}", info.kind, buf.len())))
+    }
+    let body = &buf[4..length];
+    let (fields, parameters) = match parameter_fields_length(info.kind, body) {
+        Some(len) if len <= body.len() => (body[..len].to_vec(), decode_parameters(&body[len..])?),
+        Some(len) => return Err(DecodeError::Truncated(format!("tlv parameter type {} needs {len} bytes of fields, found {}", info.kind, body.len()))),
+        None => (body.to_vec(), Vec::new()),
+    };
+    Ok((Parameter {
+        kind: info.kind,
+        tv: false,
+        fields,
+        parameters,
+    }, length))
+}
+
+fn read_u16(buf: &[u8], ix: usize) -> Option<usize> {
+    buf.get(ix..ix+2).map(|val| u16::from_be_bytes([val[0], val[1]]) as usize)
+}
+
+// Number of bytes of fixed fields before the parameters in a message.
+// None means we don't know the layout and the whole body is kept as fields.
+fn message_fields_length(version: u8, kind: u16) -> Option<usize> {
+    match kind {
+        message_types::GET_READER_CAPABILITIES => Some(1),
+        message_types::GET_READER_CONFIG => Some(7),
+        message_types::SET_READER_CONFIG => Some(1),
+        message_types::ADD_ROSPEC |
+        message_types::GET_ROSPECS |
+        message_types::GET_ACCESS_SPECS |
+        message_types::ADD_ACCESS_SPEC |
+        message_types::CLOSE_CONNECTION |
+        message_types::GET_REPORT |
+        message_types::KEEPALIVE |
+        message_types::KEEPALIVE_ACK |
+        message_types::ENABLE_EVENTS_AND_REPORTS => Some(0),
+        message_types::DELETE_ROSPEC |
+        message_types::START_ROSPEC |
+        message_types::STOP_ROSPEC |
+        message_types::ENABLE_ROSPEC |
+        message_types::DISABLE_ROSPEC |
+        message_types::DELETE_ACCESS_SPEC |
+        message_types::ENABLE_ACCESS_SPEC |
+        message_types::DISABLE_ACCESS_SPEC => Some(4),
+        message_types::GET_READER_CAPABILITIES_RESPONSE |
+        message_types::GET_READER_CONFIG_RESPONSE |
+        message_types::SET_READER_CONFIG_RESPONSE |
+        message_types::CLOSE_CONNECTION_RESPONSE |
+        message_types::ADD_ROSPEC_RESPONSE |
+        message_types::DELETE_ROSPEC_RESPONSE |
+        message_types::START_ROSPEC_RESPONSE |
+        message_types::STOP_ROSPEC_RESPONSE |
+        message_types::ENABLE_ROSPEC_RESPONSE |
+        message_types::DISABLE_ROSPEC_RESPONSE |
+        message_types::GET_ROSPECS_RESPONSE |
+        message_types::ADD_ACCESS_SPEC_RESPONSE |
+        message_types::DELETE_ACCESS_SPEC_RESPONSE |
+        message_types::ENABLE_ACCESS_SPEC_RESPONSE |
+        message_types::DISABLE_ACCESS_SPEC_RESPONSE |
+        message_types::GET_ACCESS_SPECS_RESPONSE |
+        message_types::RO_ACCESS_REPORT |
+        message_types::READER_EVENT_NOTIFICATION |
+        message_types::ERROR_MESSAGE => Some(0),
+        // version management only exists from 1.1 on
+        message_types::GET_SUPPORTED_VERSION if version >= VERSION_1_1 => Some(0),
+        message_types::GET_SUPPORTED_VERSION_RESPONSE if version >= VERSION_1_1 => Some(2),
+        message_types::SET_PROTOCOL_VERSION if version >= VERSION_1_1 => Some(1),
+        message_types::SET_PROTOCOL_VERSION_RESPONSE if version >= VERSION_1_1 => Some(0),
+        // vendor id (4) and subtype (1)
+        message_types::CUSTOM_MESSAGE => Some(5),
+        _ => None,
+    }
+}
+
+// Number of bytes of fixed fields before the sub parameters in a TLV parameter.
+// None means the parameter has no sub parameters (or we don't know its layout) so the whole body is fields.
+fn parameter_fields_length(kind: u16, body: &[u8]) -> Option<usize> {
+    match kind {
+        // Capabilities
+        parameter_types::GENERAL_DEVICE_CAPABILITIES => {
+            // max antennas (2), flags (2), manufacturer (4), model (4), firmware byte count (2) and firmware
+            read_u16(body, 12).map(|count| 14 + count).or(Some(14))
+        },
+        parameter_types::REGULATORY_CAPABILITIES => Some(4),
+        parameter_types::UHF_BAND_CAPABILITIES => Some(0),
+        parameter_types::FREQUENCY_INFORMATION => Some(1),
+        parameter_types::C1G2_UHF_MODE_TABLE => Some(0),
+        // Reader operation
+        parameter_types::RO_SPEC => Some(6),
+        parameter_types::RO_BOUNDARY_SPEC => Some(0),
+        parameter_types::RO_SPEC_START_TRIGGER => Some(1),
+        parameter_types::PERIODIC_TRIGGER_VALUE => Some(8),
+        parameter_types::RO_SPEC_STOP_TRIGGER => Some(5),
+        parameter_types::AI_SPEC => read_u16(body, 0).map(|count| 2 + count * 2).or(Some(2)),
+        parameter_types::AI_SPEC_STOP_TRIGGER => Some(5),
+        parameter_types::INVENTORY_PARAMETER_SPEC => Some(3),
+        // Access operation
+        parameter_types::ACCESS_SPEC => Some(12),
+        parameter_types::ACCESS_COMMAND => Some(0),
+        parameter_types::C1G2_TAG_SPEC => Some(0),
+        // Configuration
+        parameter_types::ANTENNA_CONFIGURATION => Some(2),
+        parameter_types::C1G2_INVENTORY_COMMAND => Some(1),
+        parameter_types::C1G2_FILTER => Some(1),
+        parameter_types::C1G2_SINGULATION_CONTROL => Some(7),
+        // Reporting
+        parameter_types::RO_REPORT_SPEC => Some(3),
+        parameter_types::TAG_REPORT_CONTENT_SELECTOR => Some(2),
+        parameter_types::TAG_REPORT_DATA => Some(0),
+        parameter_types::READER_EVENT_NOTIFICATION_SPEC => Some(0),
+        parameter_types::READER_EVENT_NOTIFICATION_DATA => Some(0),
+        // Status
+        parameter_types::LLRP_STATUS => read_u16(body, 2).map(|count| 4 + count).or(Some(4)),
+        parameter_types::PARAMETER_EVENT => Some(4),
+        _ => None,
+    }
+}
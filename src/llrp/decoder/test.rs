@@ -0,0 +1,272 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::{llrp::{message_types, parameter_types}, reader::generic};
+
+use super::{decode_message, decode_parameter, llrp_status, DecodeError, Decoder, Fields};
+
+// KEEPALIVE from an FX9600
+const KEEPALIVE: [u8; 10] = [0x04, 0x3E, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x1B, 0x39];
+
+// RO_ACCESS_REPORT with two tags, EPC-96, RO Spec ID, Antenna ID, Peak RSSI, First Seen UTC
+const TAG_REPORT: [u8; 82] = [
+    0x04, 0x3D, 0x00, 0x00, 0x00, 0x52, 0x00, 0x00, 0x00, 0x00,
+    // TagReportData, length 36
+    0x00, 0xF0, 0x00, 0x24,
+        0x8D, 0xE2, 0x80, 0x68, 0x94, 0x00, 0x00, 0x50, 0x11, 0x2A, 0x3B, 0x4C, 0x5D,
+        0x89, 0x00, 0x00, 0x00, 0x64,
+        0x81, 0x00, 0x02,
+        0x86, 0xC4,
+        0x82, 0x00, 0x06, 0x0A, 0x2E, 0x61, 0x8F, 0xA0, 0x00,
+    // TagReportData, length 36
+    0x00, 0xF0, 0x00, 0x24,
+        0x8D, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0xD2,
+        0x89, 0x00, 0x00, 0x00, 0x64,
+        0x81, 0x00, 0x05,
+        0x86, 0xBA,
+        0x82, 0x00, 0x06, 0x0A, 0x2E, 0x61, 0x8F, 0xA1, 0x00,
+];
+
+// GET_READER_CONFIG_RESPONSE with a success status and two antennas, one connected
+const READER_CONFIG: [u8; 36] = [
+    0x04, 0x0C, 0x00, 0x00, 0x00, 0x24, 0x00, 0x00, 0x00, 0x05,
+    // LLRPStatus, length 8, success
+    0x01, 0x1F, 0x00, 0x08, 0x00, 0x00, 0x00, 0x00,
+    // Antenna Properties, length 9, connected, antenna 1, gain 0
+    0x00, 0xDD, 0x00, 0x09, 0x80, 0x00, 0x01, 0x00, 0x00,
+    // Antenna Properties, length 9, not connected, antenna 2, gain 0
+    0x00, 0xDD, 0x00, 0x09, 0x00, 0x00, 0x02, 0x00, 0x00,
+];
+
+// ADD_ROSPEC_RESPONSE with an error, a description, and a field error
+const ADD_ROSPEC_ERROR: [u8; 35] = [
+    0x04, 0x1E, 0x00, 0x00, 0x00, 0x23, 0x00, 0x00, 0x00, 0x07,
+    // LLRPStatus, length 25, M_FieldError (101), 9 byte description
+    0x01, 0x1F, 0x00, 0x19, 0x00, 0x65, 0x00, 0x09,
+        b'b', b'a', b'd', b' ', b'f', b'i', b'e', b'l', b'd',
+        // Field Error, length 8, field 2, A_OutOfRange (301)
+        0x01, 0x20, 0x00, 0x08, 0x00, 0x02, 0x01, 0x2D,
+];
+
+// Impinj enable extensions response
+const ENABLE_EXTENSIONS_RESPONSE: [u8; 23] = [
+    0x07, 0xFF, 0x00, 0x00, 0x00, 0x17, 0x00, 0x00, 0x00, 0x02,
+    0x00, 0x00, 0x63, 0xF4, 0x16,
+    0x01, 0x1F, 0x00, 0x08, 0x00, 0x00, 0x00, 0x00,
+];
+
+#[test]
+fn test_decode_keepalive() {
+    let msg = decode_message(&KEEPALIVE).unwrap();
+    assert_eq!(1, msg.version);
+    assert_eq!(message_types::KEEPALIVE, msg.kind);
+    assert_eq!(0x1B39, msg.id);
+    assert!(msg.fields.is_empty());
+    assert!(msg.parameters.is_empty());
+}
+
+#[test]
+fn test_decode_tag_report() {
+    let msg = decode_message(&TAG_REPORT).unwrap();
+    assert_eq!(message_types::RO_ACCESS_REPORT, msg.kind);
+    let reports = msg.find_all(parameter_types::TAG_REPORT_DATA);
+    assert_eq!(2, reports.len());
+    let first = reports[0];
+    assert_eq!(5, first.parameters.len());
+    let epc = first.find(parameter_types::EPC_96).unwrap();
+    assert!(epc.tv);
+    assert_eq!(&[0xE2, 0x80, 0x68, 0x94, 0x00, 0x00, 0x50, 0x11, 0x2A, 0x3B, 0x4C, 0x5D], epc.bytes_at(0, 12).unwrap());
+    assert_eq!(100, first.find(parameter_types::RO_SPEC_ID).unwrap().u32_at(0).unwrap());
+    assert_eq!(2, first.find(parameter_types::ANTENNA_ID).unwrap().u16_at(0).unwrap());
+    assert_eq!(-60, first.find(parameter_types::PEAK_RSSI).unwrap().u8_at(0).unwrap() as i8);
+    assert_eq!(0x00060A2E618FA000, first.find(parameter_types::FIRST_SEEN_TIMESTAMP_UTC).unwrap().u64_at(0).unwrap());
+    let second = reports[1];
+    assert_eq!(&[0x04, 0xD2], &second.find(parameter_types::EPC_96).unwrap().fields[10..]);
+    assert_eq!(5, second.find(parameter_types::ANTENNA_ID).unwrap().u16_at(0).unwrap());
+}
+
+#[test]
+fn test_decode_reader_config() {
+    let msg = decode_message(&READER_CONFIG).unwrap();
+    let status = llrp_status(msg.find(parameter_types::LLRP_STATUS).unwrap()).unwrap();
+    assert!(status.success());
+    assert_eq!("", status.description);
+    let antennas = msg.find_all(parameter_types::ANTENNA_PROPERTIES);
+    assert_eq!(2, antennas.len());
+    assert_eq!(0x80, antennas[0].u8_at(0).unwrap());
+    assert_eq!(1, antennas[0].u16_at(1).unwrap());
+    assert_eq!(0x00, antennas[1].u8_at(0).unwrap());
+    assert_eq!(2, antennas[1].u16_at(1).unwrap());
+}
+
+#[test]
+fn test_decode_status_error() {
+    let msg = decode_message(&ADD_ROSPEC_ERROR).unwrap();
+    let status = llrp_status(msg.find(parameter_types::LLRP_STATUS).unwrap()).unwrap();
+    assert!(!status.success());
+    assert_eq!(parameter_types::M_FIELD_ERROR, status.code);
+    assert_eq!("bad field", status.description);
+    assert_eq!(Some((2, parameter_types::A_OUT_OF_RANGE)), status.field_error);
+    assert_eq!(None, status.parameter_error);
+}
+
+#[test]
+fn test_decode_custom_message() {
+    let msg = decode_message(&ENABLE_EXTENSIONS_RESPONSE).unwrap();
+    assert_eq!((parameter_types::IMPINJ_VENDOR_ID, parameter_types::IMPINJ_ENABLE_EXTENSIONS_RESPONSE), msg.custom_info().unwrap());
+    assert!(llrp_status(msg.find(parameter_types::LLRP_STATUS).unwrap()).unwrap().success());
+    // vendor payloads that aren't parameters are kept as raw fields
+    let msg = decode_message(&[0x07, 0xFF, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0xA1, 0x03, 0x00]).unwrap();
+    assert_eq!((parameter_types::MOTOROLA_VENDOR_ID, parameter_types::MOTO_PURGE_TAGS), msg.custom_info().unwrap());
+    assert_eq!(6, msg.fields.len());
+    assert!(msg.parameters.is_empty());
+}
+
+#[test]
+fn test_streaming() {
+    let mut stream: Vec<u8> = Vec::new();
+    stream.extend_from_slice(&KEEPALIVE);
+    stream.extend_from_slice(&TAG_REPORT);
+    stream.extend_from_slice(&READER_CONFIG);
+    stream.extend_from_slice(&ADD_ROSPEC_ERROR);
+    // every chunk size should give us the same messages back
+    for chunk_size in [1, 3, 7, 10, 64, stream.len()] {
+        let mut decoder = Decoder::new();
+        let mut kinds: Vec<u16> = Vec::new();
+        for chunk in stream.chunks(chunk_size) {
+            decoder.push(chunk);
+            while let Some(res) = decoder.next_message() {
+                kinds.push(res.unwrap().kind);
+            }
+        }
+        assert_eq!(vec![
+            message_types::KEEPALIVE,
+            message_types::RO_ACCESS_REPORT,
+            message_types::GET_READER_CONFIG_RESPONSE,
+            message_types::ADD_ROSPEC_RESPONSE,
+        ], kinds);
+        assert_eq!(0, decoder.pending());
+    }
+    // partial message waits for the rest
+    let mut decoder = Decoder::new();
+    decoder.push(&TAG_REPORT[..40]);
+    assert!(decoder.next_message().is_none());
+    assert_eq!(40, decoder.pending());
+    decoder.push(&TAG_REPORT[40..]);
+    assert!(decoder.next_message().unwrap().is_ok());
+    assert!(decoder.next_message().is_none());
+}
+
+#[test]
+fn test_invalid_header() {
+    let mut decoder = Decoder::new();
+    // length shorter than the header
+    decoder.push(&[0x04, 0x3E, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x01]);
+    decoder.push(&KEEPALIVE);
+    assert!(matches!(decoder.next_message(), Some(Err(DecodeError::InvalidHeader(_)))));
+    // everything buffered is dropped since we can't find the start of the next message
+    assert_eq!(0, decoder.pending());
+    assert!(decoder.next_message().is_none());
+    // absurdly long
+    decoder.push(&[0x04, 0x3E, 0x7F, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01]);
+    assert!(matches!(decoder.next_message(), Some(Err(DecodeError::InvalidHeader(_)))));
+    // and recovers when good data comes in
+    decoder.push(&KEEPALIVE);
+    assert_eq!(message_types::KEEPALIVE, decoder.next_message().unwrap().unwrap().kind);
+}
+
+#[test]
+fn test_corrupt_parameters() {
+    // tag report data claims 40 bytes but the message only has 36 left
+    let mut corrupt = TAG_REPORT;
+    corrupt[13] = 0x28;
+    assert!(matches!(decode_message(&corrupt), Err(DecodeError::Truncated(_))));
+    // unknown tv parameter type
+    let mut corrupt = TAG_REPORT;
+    corrupt[14] = 0xFF;
+    assert!(matches!(decode_message(&corrupt), Err(DecodeError::InvalidParameter(_))));
+    // reserved bits set in a tlv parameter header
+    let mut corrupt = READER_CONFIG;
+    corrupt[18] = 0x40;
+    assert!(matches!(decode_message(&corrupt), Err(DecodeError::InvalidParameter(_))));
+    // tlv parameter with a length shorter than its header
+    let mut corrupt = READER_CONFIG;
+    corrupt[21] = 0x02;
+    assert!(matches!(decode_message(&corrupt), Err(DecodeError::InvalidParameter(_))));
+    // llrp status description longer than the parameter
+    let mut corrupt = ADD_ROSPEC_ERROR;
+    corrupt[17] = 0x30;
+    assert!(matches!(decode_message(&corrupt), Err(DecodeError::Truncated(_))));
+    // a bad message is dropped by itself, the next one still decodes
+    let mut decoder = Decoder::new();
+    let mut corrupt = TAG_REPORT;
+    corrupt[13] = 0x28;
+    decoder.push(&corrupt);
+    decoder.push(&KEEPALIVE);
+    assert!(matches!(decoder.next_message(), Some(Err(DecodeError::Truncated(_)))));
+    assert_eq!(message_types::KEEPALIVE, decoder.next_message().unwrap().unwrap().kind);
+    // truncated message
+    assert!(matches!(decode_message(&TAG_REPORT[..50]), Err(DecodeError::Truncated(_))));
+    // not enough for a tv parameter
+    assert!(matches!(decode_parameter(&[0x8D, 0x00]), Err(DecodeError::Truncated(_))));
+}
+
+#[test]
+fn test_custom_parameter() {
+    // Impinj peak rssi, -61.25 dBm
+    let (param, length) = decode_parameter(&[0x03, 0xFF, 0x00, 0x0E, 0x00, 0x00, 0x63, 0xF4, 0x00, 0x00, 0x00, 0x39, 0xE8, 0x13]).unwrap();
+    assert_eq!(14, length);
+    assert_eq!((parameter_types::IMPINJ_VENDOR_ID, parameter_types::IMPINJ_PEAK_RSSI as u32), param.custom_info().unwrap());
+    assert_eq!(-6125, param.u16_at(8).unwrap() as i16);
+    assert!(param.u16_at(10).is_err());
+}
+
+#[test]
+fn test_encoder_round_trip() {
+    let msg = decode_message(&generic::requests::add_rospec(&9, &generic::requests::rospec(&100)).encode()).unwrap();
+    assert_eq!(message_types::ADD_ROSPEC, msg.kind);
+    assert_eq!(9, msg.id);
+    let rospec = msg.find(parameter_types::RO_SPEC).unwrap();
+    assert_eq!(100, rospec.u32_at(0).unwrap());
+    assert_eq!(3, rospec.parameters.len());
+    let ai_spec = rospec.find(parameter_types::AI_SPEC).unwrap();
+    assert_eq!(1, ai_spec.u16_at(0).unwrap());
+    assert_eq!(19, ai_spec.find(parameter_types::INVENTORY_PARAMETER_SPEC).unwrap().u16_at(0).unwrap());
+    let selector = rospec.find(parameter_types::RO_REPORT_SPEC).unwrap().find(parameter_types::TAG_REPORT_CONTENT_SELECTOR).unwrap();
+    assert_eq!(0x9600, selector.u16_at(0).unwrap());
+    assert!(selector.find(parameter_types::C1G2_EPC_MEMORY_SELECTOR).is_some());
+}
+
+#[test]
+fn test_version_messages() {
+    // GET_SUPPORTED_VERSION_RESPONSE, current version 1, supported version 2, success
+    let response = [0x08, 0x38, 0x00, 0x00, 0x00, 0x14, 0x00, 0x00, 0x00, 0x07, 0x01, 0x02, 0x01, 0x1F, 0x00, 0x08, 0x00, 0x00, 0x00, 0x00];
+    let msg = decode_message(&response).unwrap();
+    assert_eq!(2, msg.version);
+    assert_eq!(message_types::GET_SUPPORTED_VERSION_RESPONSE, msg.kind);
+    assert_eq!(1, msg.u8_at(0).unwrap());
+    assert_eq!(2, msg.u8_at(1).unwrap());
+    assert!(llrp_status(msg.find(parameter_types::LLRP_STATUS).unwrap()).unwrap().success());
+    // version 1 doesn't have the message, so we don't know its layout
+    let mut response = response;
+    response[0] = 0x04;
+    let msg = decode_message(&response).unwrap();
+    assert_eq!(1, msg.version);
+    assert_eq!(0, msg.parameters.len());
+    assert_eq!(10, msg.fields.len());
+}
@@ -0,0 +1,853 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use super::parameter_types;
+
+#[cfg(test)]
+pub mod test;
+
+// LLRP 1.0.1
+pub const VERSION_1_0_1: u8 = 1;
+// LLRP 1.1 (2.0 of the standard)
+pub const VERSION_1_1: u8 = 2;
+// The newest version we know how to speak.
+pub const MAX_VERSION: u8 = VERSION_1_1;
+
+// ROSpec start trigger types
+pub const START_TRIGGER_NULL: u8 = 0;
+pub const START_TRIGGER_IMMEDIATE: u8 = 1;
+pub const START_TRIGGER_PERIODIC: u8 = 2;
+pub const START_TRIGGER_GPI: u8 = 3;
+
+// ROSpec stop trigger types
+pub const STOP_TRIGGER_NULL: u8 = 0;
+pub const STOP_TRIGGER_DURATION: u8 = 1;
+pub const STOP_TRIGGER_GPI: u8 = 2;
+
+// AISpec stop trigger types
+pub const AI_STOP_TRIGGER_NULL: u8 = 0;
+pub const AI_STOP_TRIGGER_DURATION: u8 = 1;
+pub const AI_STOP_TRIGGER_GPI: u8 = 2;
+pub const AI_STOP_TRIGGER_TAG_OBSERVATION: u8 = 3;
+
+// ROReportSpec triggers
+pub const REPORT_TRIGGER_NONE: u8 = 0;
+pub const REPORT_TRIGGER_END_OF_AI_SPEC: u8 = 1;
+pub const REPORT_TRIGGER_END_OF_RO_SPEC: u8 = 2;
+
+// ROSpec current state
+pub const ROSPEC_STATE_DISABLED: u8 = 0;
+pub const ROSPEC_STATE_INACTIVE: u8 = 1;
+pub const ROSPEC_STATE_ACTIVE: u8 = 2;
+
+// Air protocol
+pub const PROTOCOL_EPC_GLOBAL_C1G2: u8 = 1;
+
+// Keepalive trigger types
+pub const KEEPALIVE_NULL: u8 = 0;
+pub const KEEPALIVE_PERIODIC: u8 = 1;
+
+// C1G2 memory banks
+pub const MEMORY_BANK_RESERVED: u8 = 0;
+pub const MEMORY_BANK_EPC: u8 = 1;
+pub const MEMORY_BANK_TID: u8 = 2;
+pub const MEMORY_BANK_USER: u8 = 3;
+
+// Anything that can be written out as an LLRP parameter.
+pub trait Encode {
+    fn encode(&self, buf: &mut Vec<u8>);
+}
+
+// Writes the TLV header for the parameter type, lets the closure write the body (including any
+// sub parameters), then goes back and fills in the length of the whole parameter.
+pub fn tlv<F: FnOnce(&mut Vec<u8>)>(buf: &mut Vec<u8>, kind: u16, body: F) {
+    let start = buf.len();
+    // 6 reserved bits followed by 10 bits for the type
+    buf.extend_from_slice(&(kind & 0x03FF).to_be_bytes());
+    buf.extend_from_slice(&[0x00, 0x00]);
+    body(buf);
+    let length = (buf.len() - start) as u16;
+    buf[start+2..start+4].copy_from_slice(&length.to_be_bytes());
+}
+
+// TV parameters have a set length, the type is the only header and it is a single byte with the first bit set.
+pub fn tv(buf: &mut Vec<u8>, kind: u16, body: &[u8]) {
+    buf.push(0x80 | (kind & 0x7F) as u8);
+    buf.extend_from_slice(body);
+}
+
+// An LLRP message. The body is built up in order, then encode adds the header and computes the length.
+#[derive(Debug, Clone)]
+pub struct Message {
+    pub version: u8,
+    pub kind: u16,
+    pub id: u32,
+    body: Vec<u8>,
+}
+
+impl Message {
+    pub fn new(kind: u16, id: u32) -> Message {
+        Message {
+            version: VERSION_1_0_1,
+            kind,
+            id,
+            body: Vec::new(),
+        }
+    }
+
+    pub fn custom(vendor: u32, subtype: u8, id: u32) -> Message {
+        Message::new(super::message_types::CUSTOM_MESSAGE, id)
+            .u32(vendor)
+            .u8(subtype)
+    }
+
+    pub fn version(mut self, version: u8) -> Message {
+        self.version = version;
+        self
+    }
+
+    pub fn u8(mut self, value: u8) -> Message {
+        self.body.push(value);
+        self
+    }
+
+    pub fn u16(mut self, value: u16) -> Message {
+        self.body.extend_from_slice(&value.to_be_bytes());
+        self
+    }
+
+    pub fn u32(mut self, value: u32) -> Message {
+        self.body.extend_from_slice(&value.to_be_bytes());
+        self
+    }
+
+    pub fn bytes(mut self, value: &[u8]) -> Message {
+        self.body.extend_from_slice(value);
+        self
+    }
+
+    pub fn param(mut self, param: &dyn Encode) -> Message {
+        param.encode(&mut self.body);
+        self
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let length = (self.body.len() + 10) as u32;
+        // 3 reserved bits, 3 version bits, 10 message type bits
+        let header: u16 = ((self.version as u16 & 0x07) << 10) + (self.kind & 0x03FF);
+        let mut output: Vec<u8> = Vec::with_capacity(length as usize);
+        output.extend_from_slice(&header.to_be_bytes());
+        output.extend_from_slice(&length.to_be_bytes());
+        output.extend_from_slice(&self.id.to_be_bytes());
+        output.extend_from_slice(&self.body);
+        output
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ROSpec {
+    pub id: u32,
+    pub priority: u8,       // 0-7, lower is higher
+    pub current_state: u8,
+    pub boundary: ROBoundarySpec,
+    pub ai_specs: Vec<AISpec>,
+    pub report: Option<ROReportSpec>,
+}
+
+impl Encode for ROSpec {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        tlv(buf, parameter_types::RO_SPEC, |buf| {
+            buf.extend_from_slice(&self.id.to_be_bytes());
+            buf.push(self.priority & 0x07);
+            buf.push(self.current_state);
+            self.boundary.encode(buf);
+            for spec in self.ai_specs.iter() {
+                spec.encode(buf);
+            }
+            if let Some(report) = &self.report {
+                report.encode(buf);
+            }
+        });
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ROBoundarySpec {
+    pub start_trigger: ROSpecStartTrigger,
+    pub stop_trigger: ROSpecStopTrigger,
+}
+
+impl Encode for ROBoundarySpec {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        tlv(buf, parameter_types::RO_BOUNDARY_SPEC, |buf| {
+            self.start_trigger.encode(buf);
+            self.stop_trigger.encode(buf);
+        });
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ROSpecStartTrigger {
+    pub kind: u8,
+    pub periodic: Option<PeriodicTriggerValue>,
+    pub gpi: Option<GPITriggerValue>,
+}
+
+impl Encode for ROSpecStartTrigger {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        tlv(buf, parameter_types::RO_SPEC_START_TRIGGER, |buf| {
+            buf.push(self.kind);
+            if let Some(periodic) = &self.periodic {
+                periodic.encode(buf);
+            }
+            if let Some(gpi) = &self.gpi {
+                gpi.encode(buf);
+            }
+        });
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PeriodicTriggerValue {
+    pub offset: u32,
+    pub period: u32,
+    pub utc_timestamp: Option<u64>,
+}
+
+impl Encode for PeriodicTriggerValue {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        tlv(buf, parameter_types::PERIODIC_TRIGGER_VALUE, |buf| {
+            buf.extend_from_slice(&self.offset.to_be_bytes());
+            buf.extend_from_slice(&self.period.to_be_bytes());
+            if let Some(timestamp) = self.utc_timestamp {
+                tlv(buf, parameter_types::UTC_TIMESTAMP, |buf| {
+                    buf.extend_from_slice(&timestamp.to_be_bytes());
+                });
+            }
+        });
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GPITriggerValue {
+    pub port: u16,
+    pub event: bool,
+    pub timeout: u32,
+}
+
+impl Encode for GPITriggerValue {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        tlv(buf, parameter_types::GPI_TRIGGER_VALUE, |buf| {
+            buf.extend_from_slice(&self.port.to_be_bytes());
+            buf.push(if self.event { 0x80 } else { 0x00 });
+            buf.extend_from_slice(&self.timeout.to_be_bytes());
+        });
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GPOWriteData {
+    pub port: u16,
+    pub data: bool,
+}
+
+impl Encode for GPOWriteData {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        tlv(buf, parameter_types::GPO_WRITE_DATA, |buf| {
+            buf.extend_from_slice(&self.port.to_be_bytes());
+            buf.push(if self.data { 0x80 } else { 0x00 });
+        });
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ROSpecStopTrigger {
+    pub kind: u8,
+    // ignored when the trigger type isn't duration
+    pub duration: u32,
+    pub gpi: Option<GPITriggerValue>,
+}
+
+impl Encode for ROSpecStopTrigger {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        tlv(buf, parameter_types::RO_SPEC_STOP_TRIGGER, |buf| {
+            buf.push(self.kind);
+            buf.extend_from_slice(&self.duration.to_be_bytes());
+            if let Some(gpi) = &self.gpi {
+                gpi.encode(buf);
+            }
+        });
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AISpec {
+    // a single antenna id of 0 means all antennas
+    pub antenna_ids: Vec<u16>,
+    pub stop_trigger: AISpecStopTrigger,
+    pub inventory_specs: Vec<InventoryParameterSpec>,
+}
+
+impl Encode for AISpec {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        tlv(buf, parameter_types::AI_SPEC, |buf| {
+            buf.extend_from_slice(&(self.antenna_ids.len() as u16).to_be_bytes());
+            for id in self.antenna_ids.iter() {
+                buf.extend_from_slice(&id.to_be_bytes());
+            }
+            self.stop_trigger.encode(buf);
+            for spec in self.inventory_specs.iter() {
+                spec.encode(buf);
+            }
+        });
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AISpecStopTrigger {
+    pub kind: u8,
+    pub duration: u32,
+    pub gpi: Option<GPITriggerValue>,
+}
+
+impl Encode for AISpecStopTrigger {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        tlv(buf, parameter_types::AI_SPEC_STOP_TRIGGER, |buf| {
+            buf.push(self.kind);
+            buf.extend_from_slice(&self.duration.to_be_bytes());
+            if let Some(gpi) = &self.gpi {
+                gpi.encode(buf);
+            }
+        });
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct InventoryParameterSpec {
+    pub id: u16,
+    pub protocol: u8,
+    pub antenna_configs: Vec<AntennaConfiguration>,
+    pub custom: Vec<CustomParameter>,
+}
+
+impl Encode for InventoryParameterSpec {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        tlv(buf, parameter_types::INVENTORY_PARAMETER_SPEC, |buf| {
+            buf.extend_from_slice(&self.id.to_be_bytes());
+            buf.push(self.protocol);
+            for config in self.antenna_configs.iter() {
+                config.encode(buf);
+            }
+            for custom in self.custom.iter() {
+                custom.encode(buf);
+            }
+        });
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AntennaConfiguration {
+    // 0 applies the configuration to all antennas
+    pub antenna_id: u16,
+    pub receiver: Option<RFReceiver>,
+    pub transmitter: Option<RFTransmitter>,
+    pub inventory_command: Option<C1G2InventoryCommand>,
+}
+
+impl Encode for AntennaConfiguration {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        tlv(buf, parameter_types::ANTENNA_CONFIGURATION, |buf| {
+            buf.extend_from_slice(&self.antenna_id.to_be_bytes());
+            if let Some(receiver) = &self.receiver {
+                receiver.encode(buf);
+            }
+            if let Some(transmitter) = &self.transmitter {
+                transmitter.encode(buf);
+            }
+            if let Some(command) = &self.inventory_command {
+                command.encode(buf);
+            }
+        });
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RFReceiver {
+    // index into the receive sensitivity table from the reader capabilities
+    pub sensitivity: u16,
+}
+
+impl Encode for RFReceiver {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        tlv(buf, parameter_types::RF_RECEIVER, |buf| {
+            buf.extend_from_slice(&self.sensitivity.to_be_bytes());
+        });
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RFTransmitter {
+    pub hop_table_id: u16,
+    pub channel_index: u16,
+    // index into the transmit power table from the reader capabilities
+    pub transmit_power: u16,
+}
+
+impl Encode for RFTransmitter {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        tlv(buf, parameter_types::RF_TRANSMITTER, |buf| {
+            buf.extend_from_slice(&self.hop_table_id.to_be_bytes());
+            buf.extend_from_slice(&self.channel_index.to_be_bytes());
+            buf.extend_from_slice(&self.transmit_power.to_be_bytes());
+        });
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct C1G2InventoryCommand {
+    pub tag_inventory_state_aware: bool,
+    pub filters: Vec<C1G2Filter>,
+    pub rf_control: Option<C1G2RFControl>,
+    pub singulation_control: Option<C1G2SingulationControl>,
+}
+
+impl Encode for C1G2InventoryCommand {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        tlv(buf, parameter_types::C1G2_INVENTORY_COMMAND, |buf| {
+            buf.push(if self.tag_inventory_state_aware { 0x80 } else { 0x00 });
+            for filter in self.filters.iter() {
+                filter.encode(buf);
+            }
+            if let Some(control) = &self.rf_control {
+                control.encode(buf);
+            }
+            if let Some(control) = &self.singulation_control {
+                control.encode(buf);
+            }
+        });
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct C1G2Filter {
+    // 0 unspecified, 1 do not truncate, 2 truncate
+    pub truncate: u8,
+    pub mask: C1G2TagInventoryMask,
+    // state unaware action - 0 select/unselect, 1 select/do nothing, 2 do nothing/unselect
+    // 3 unselect/do nothing, 4 unselect/select, 5 do nothing/select
+    pub unaware_action: Option<u8>,
+}
+
+impl Encode for C1G2Filter {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        tlv(buf, parameter_types::C1G2_FILTER, |buf| {
+            // 2 bits for truncate followed by 6 reserved bits
+            buf.push((self.truncate & 0x03) << 6);
+            self.mask.encode(buf);
+            if let Some(action) = self.unaware_action {
+                tlv(buf, parameter_types::C1G2_TAG_INVENTORY_STATE_UNAWARE_FILTER_ACTION, |buf| {
+                    buf.push(action);
+                });
+            }
+        });
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct C1G2TagInventoryMask {
+    pub memory_bank: u8,
+    // bit offset into the memory bank
+    pub pointer: u16,
+    pub mask_bits: u16,
+    // mask_bits worth of data, padded to a full byte
+    pub mask: Vec<u8>,
+}
+
+impl Encode for C1G2TagInventoryMask {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        tlv(buf, parameter_types::C1G2_TAG_INVENTORY_MAST, |buf| {
+            // 2 bits for the memory bank followed by 6 reserved bits
+            buf.push((self.memory_bank & 0x03) << 6);
+            buf.extend_from_slice(&self.pointer.to_be_bytes());
+            buf.extend_from_slice(&self.mask_bits.to_be_bytes());
+            let bytes = (self.mask_bits as usize).div_ceil(8);
+            for ix in 0..bytes {
+                buf.push(*self.mask.get(ix).unwrap_or(&0));
+            }
+        });
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct C1G2RFControl {
+    pub mode_index: u16,
+    pub tari: u16,
+}
+
+impl Encode for C1G2RFControl {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        tlv(buf, parameter_types::C1G2_RF_CONTROL, |buf| {
+            buf.extend_from_slice(&self.mode_index.to_be_bytes());
+            buf.extend_from_slice(&self.tari.to_be_bytes());
+        });
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct C1G2SingulationControl {
+    pub session: u8,
+    pub tag_population: u16,
+    pub tag_transit_time: u32,
+}
+
+impl Encode for C1G2SingulationControl {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        tlv(buf, parameter_types::C1G2_SINGULATION_CONTROL, |buf| {
+            // 2 bits for the session followed by 6 reserved bits
+            buf.push((self.session & 0x03) << 6);
+            buf.extend_from_slice(&self.tag_population.to_be_bytes());
+            buf.extend_from_slice(&self.tag_transit_time.to_be_bytes());
+        });
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AccessSpec {
+    pub id: u32,
+    // 0 is all antennas
+    pub antenna_id: u16,
+    pub protocol: u8,
+    // false is disabled, true is active
+    pub current_state: bool,
+    // 0 applies to every ROSpec
+    pub rospec_id: u32,
+    pub stop_trigger: AccessSpecStopTrigger,
+    pub command: AccessCommand,
+    pub report: Option<AccessReportSpec>,
+}
+
+impl Encode for AccessSpec {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        tlv(buf, parameter_types::ACCESS_SPEC, |buf| {
+            buf.extend_from_slice(&self.id.to_be_bytes());
+            buf.extend_from_slice(&self.antenna_id.to_be_bytes());
+            buf.push(self.protocol);
+            buf.push(if self.current_state { 0x80 } else { 0x00 });
+            buf.extend_from_slice(&self.rospec_id.to_be_bytes());
+            self.stop_trigger.encode(buf);
+            self.command.encode(buf);
+            if let Some(report) = &self.report {
+                report.encode(buf);
+            }
+        });
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AccessSpecStopTrigger {
+    // 0 null, 1 operation count
+    pub kind: u8,
+    pub operation_count: u16,
+}
+
+impl Encode for AccessSpecStopTrigger {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        tlv(buf, parameter_types::ACCESS_SPEC_STOP_TRIGGER, |buf| {
+            buf.push(self.kind);
+            buf.extend_from_slice(&self.operation_count.to_be_bytes());
+        });
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AccessCommand {
+    pub tag_spec: C1G2TagSpec,
+    pub reads: Vec<C1G2Read>,
+}
+
+impl Encode for AccessCommand {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        tlv(buf, parameter_types::ACCESS_COMMAND, |buf| {
+            self.tag_spec.encode(buf);
+            for read in self.reads.iter() {
+                read.encode(buf);
+            }
+        });
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct C1G2TagSpec {
+    // one or two target tags, a tag has to match all of them
+    pub targets: Vec<C1G2TargetTag>,
+}
+
+impl Encode for C1G2TagSpec {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        tlv(buf, parameter_types::C1G2_TAG_SPEC, |buf| {
+            for target in self.targets.iter() {
+                target.encode(buf);
+            }
+        });
+    }
+}
+
+// A target with no mask and no data matches every tag.
+#[derive(Debug, Clone, Default)]
+pub struct C1G2TargetTag {
+    pub memory_bank: u8,
+    // true matches tags with the data, false matches tags without it
+    pub matching: bool,
+    // bit offset into the memory bank
+    pub pointer: u16,
+    pub mask_bits: u16,
+    pub mask: Vec<u8>,
+    pub data_bits: u16,
+    pub data: Vec<u8>,
+}
+
+impl Encode for C1G2TargetTag {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        tlv(buf, parameter_types::C1G2_TARGET_TAG, |buf| {
+            // 2 bits for the memory bank, 1 for match, and 5 reserved bits
+            buf.push(((self.memory_bank & 0x03) << 6) | if self.matching { 0x20 } else { 0x00 });
+            buf.extend_from_slice(&self.pointer.to_be_bytes());
+            buf.extend_from_slice(&self.mask_bits.to_be_bytes());
+            for ix in 0..(self.mask_bits as usize).div_ceil(8) {
+                buf.push(*self.mask.get(ix).unwrap_or(&0));
+            }
+            buf.extend_from_slice(&self.data_bits.to_be_bytes());
+            for ix in 0..(self.data_bits as usize).div_ceil(8) {
+                buf.push(*self.data.get(ix).unwrap_or(&0));
+            }
+        });
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct C1G2Read {
+    pub op_spec_id: u16,
+    pub access_password: u32,
+    pub memory_bank: u8,
+    // word (16 bit) offset into the memory bank
+    pub word_pointer: u16,
+    // 0 reads the whole memory bank on readers that support it
+    pub word_count: u16,
+}
+
+impl Encode for C1G2Read {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        tlv(buf, parameter_types::C1G2_READ, |buf| {
+            buf.extend_from_slice(&self.op_spec_id.to_be_bytes());
+            buf.extend_from_slice(&self.access_password.to_be_bytes());
+            // 2 bits for the memory bank followed by 6 reserved bits
+            buf.push((self.memory_bank & 0x03) << 6);
+            buf.extend_from_slice(&self.word_pointer.to_be_bytes());
+            buf.extend_from_slice(&self.word_count.to_be_bytes());
+        });
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AccessReportSpec {
+    // 0 report with every tag report, 1 report at the end of the AccessSpec
+    pub trigger: u8,
+}
+
+impl Encode for AccessReportSpec {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        tlv(buf, parameter_types::ACCESS_REPORT_SPEC, |buf| {
+            buf.push(self.trigger);
+        });
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ROReportSpec {
+    pub trigger: u8,
+    pub n: u16,
+    pub content_selector: TagReportContentSelector,
+    pub custom: Vec<CustomParameter>,
+}
+
+impl Encode for ROReportSpec {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        tlv(buf, parameter_types::RO_REPORT_SPEC, |buf| {
+            buf.push(self.trigger);
+            buf.extend_from_slice(&self.n.to_be_bytes());
+            self.content_selector.encode(buf);
+            for custom in self.custom.iter() {
+                custom.encode(buf);
+            }
+        });
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TagReportContentSelector {
+    pub enable_rospec_id: bool,
+    pub enable_spec_index: bool,
+    pub enable_inventory_spec_id: bool,
+    pub enable_antenna_id: bool,
+    pub enable_channel_index: bool,
+    pub enable_peak_rssi: bool,
+    pub enable_first_seen: bool,
+    pub enable_last_seen: bool,
+    pub enable_tag_seen_count: bool,
+    pub enable_access_spec_id: bool,
+    pub memory_selectors: Vec<C1G2EPCMemorySelector>,
+}
+
+impl Encode for TagReportContentSelector {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        tlv(buf, parameter_types::TAG_REPORT_CONTENT_SELECTOR, |buf| {
+            // ten flags starting at the highest bit, the last 6 bits are reserved
+            let flags = [
+                self.enable_rospec_id,
+                self.enable_spec_index,
+                self.enable_inventory_spec_id,
+                self.enable_antenna_id,
+                self.enable_channel_index,
+                self.enable_peak_rssi,
+                self.enable_first_seen,
+                self.enable_last_seen,
+                self.enable_tag_seen_count,
+                self.enable_access_spec_id,
+            ];
+            let mut value: u16 = 0;
+            for (ix, flag) in flags.iter().enumerate() {
+                if *flag {
+                    value |= 0x8000 >> ix;
+                }
+            }
+            buf.extend_from_slice(&value.to_be_bytes());
+            for selector in self.memory_selectors.iter() {
+                selector.encode(buf);
+            }
+        });
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct C1G2EPCMemorySelector {
+    pub enable_crc: bool,
+    pub enable_pc_bits: bool,
+    pub enable_xpc_bits: bool,
+}
+
+impl Encode for C1G2EPCMemorySelector {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        tlv(buf, parameter_types::C1G2_EPC_MEMORY_SELECTOR, |buf| {
+            let mut value: u8 = 0;
+            if self.enable_crc {
+                value |= 0x80;
+            }
+            if self.enable_pc_bits {
+                value |= 0x40;
+            }
+            if self.enable_xpc_bits {
+                value |= 0x20;
+            }
+            buf.push(value);
+        });
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct KeepaliveSpec {
+    pub kind: u8,
+    // milliseconds
+    pub interval: u32,
+}
+
+impl Encode for KeepaliveSpec {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        tlv(buf, parameter_types::KEEPALIVE_SPEC, |buf| {
+            buf.push(self.kind);
+            buf.extend_from_slice(&self.interval.to_be_bytes());
+        });
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ReaderEventNotificationSpec {
+    pub states: Vec<EventNotificationState>,
+}
+
+impl Encode for ReaderEventNotificationSpec {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        tlv(buf, parameter_types::READER_EVENT_NOTIFICATION_SPEC, |buf| {
+            for state in self.states.iter() {
+                state.encode(buf);
+            }
+        });
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EventNotificationState {
+    // 0 upon hopping, 1 gpi, 2 rospec, 3 report buffer fill warning, 4 reader exception,
+    // 5 rf survey, 6 aispec, 7 aispec with details, 8 antenna, 9 spec loop
+    pub event: u16,
+    pub enabled: bool,
+}
+
+impl Encode for EventNotificationState {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        tlv(buf, parameter_types::EVENT_NOTIFICATION_STATE, |buf| {
+            buf.extend_from_slice(&self.event.to_be_bytes());
+            buf.push(if self.enabled { 0x80 } else { 0x00 });
+        });
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EventsAndReports {
+    // hold events and reports upon reconnect
+    pub hold: bool,
+}
+
+impl Encode for EventsAndReports {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        tlv(buf, parameter_types::EVENTS_AND_REPORTS, |buf| {
+            buf.push(if self.hold { 0x80 } else { 0x00 });
+        });
+    }
+}
+
+// Vendor specific parameter. The data is written directly after the subtype, followed by any sub parameters.
+#[derive(Debug, Clone, Default)]
+pub struct CustomParameter {
+    pub vendor: u32,
+    pub subtype: u32,
+    pub data: Vec<u8>,
+    pub parameters: Vec<CustomParameter>,
+}
+
+impl Encode for CustomParameter {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        tlv(buf, parameter_types::CUSTOM_PARAMETER, |buf| {
+            buf.extend_from_slice(&self.vendor.to_be_bytes());
+            buf.extend_from_slice(&self.subtype.to_be_bytes());
+            buf.extend_from_slice(&self.data);
+            for param in self.parameters.iter() {
+                param.encode(buf);
+            }
+        });
+    }
+}
@@ -0,0 +1,292 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::{llrp::{message_types, parameter_types}, reader::{generic, impinj, zebra}};
+
+use super::{tlv, tv, AntennaConfiguration, C1G2Filter, C1G2InventoryCommand, C1G2TagInventoryMask, CustomParameter, Encode, GPITriggerValue, GPOWriteData, Message, RFReceiver, RFTransmitter, MEMORY_BANK_EPC, MEMORY_BANK_USER, VERSION_1_1};
+
+fn param_length(buf: &[u8], ix: usize) -> u16 {
+    u16::from_be_bytes([buf[ix+2], buf[ix+3]])
+}
+
+fn message_length(buf: &[u8]) -> u32 {
+    u32::from_be_bytes([buf[2], buf[3], buf[4], buf[5]])
+}
+
+#[test]
+fn test_message_header() {
+    let msg = Message::new(message_types::KEEPALIVE_ACK, 0x01020304).encode();
+    assert_eq!(vec![0x04, 0x48, 0x00, 0x00, 0x00, 0x0A, 0x01, 0x02, 0x03, 0x04], msg);
+    let msg = Message::new(message_types::KEEPALIVE_ACK, 5).version(VERSION_1_1).u32(9).encode();
+    assert_eq!(vec![0x08, 0x48, 0x00, 0x00, 0x00, 0x0E, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00, 0x09], msg);
+    assert_eq!(generic::requests::keepalive_ack(&0x01020304).encode(), Message::new(message_types::KEEPALIVE_ACK, 0x01020304).encode());
+    assert_eq!(vec![0x04, 0x15, 0x00, 0x00, 0x00, 0x0E, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x64], generic::requests::delete_rospec(&2, &100).encode());
+    assert_eq!(vec![0x04, 0x3C, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x03], generic::requests::get_report(&3).encode());
+}
+
+#[test]
+fn test_version_messages() {
+    // version messages are always sent as 1.1
+    assert_eq!(vec![0x08, 0x2E, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x03], generic::requests::get_supported_version(&3).encode());
+    assert_eq!(vec![0x08, 0x2F, 0x00, 0x00, 0x00, 0x0B, 0x00, 0x00, 0x00, 0x04, 0x02], generic::requests::set_protocol_version(&4, VERSION_1_1).encode());
+    // everything else is sent with the version negotiated with the reader
+    let msg = generic::requests::delete_rospec(&2, &100).version(VERSION_1_1).encode();
+    assert_eq!(0x08, msg[0]);
+    assert_eq!(0x15, msg[1]);
+    assert_eq!(14, message_length(&msg));
+}
+
+#[test]
+fn test_tlv_tv() {
+    let mut buf: Vec<u8> = Vec::new();
+    tlv(&mut buf, parameter_types::CUSTOM_PARAMETER, |buf| {
+        buf.push(0x01);
+        tlv(buf, parameter_types::RF_RECEIVER, |buf| {
+            buf.extend_from_slice(&[0x00, 0x02]);
+        });
+    });
+    assert_eq!(vec![0x03, 0xFF, 0x00, 0x0B, 0x01, 0x00, 0xDF, 0x00, 0x06, 0x00, 0x02], buf);
+    let mut buf: Vec<u8> = Vec::new();
+    tv(&mut buf, parameter_types::ANTENNA_ID, &[0x00, 0x03]);
+    assert_eq!(vec![0x81, 0x00, 0x03], buf);
+}
+
+#[test]
+fn test_default_add_rospec() {
+    let msg = generic::requests::add_rospec(&7, &generic::requests::rospec(&100)).encode();
+    assert_eq!(vec![
+        // header, length 80, id 7
+        0x04, 0x14, 0x00, 0x00, 0x00, 0x50, 0x00, 0x00, 0x00, 0x07,
+        // RO Spec, length 70, rospec id 100, priority 0, state disabled
+        0x00, 0xB1, 0x00, 0x46, 0x00, 0x00, 0x00, 0x64, 0x00, 0x00,
+            // RO Boundary Spec, length 18
+            0x00, 0xB2, 0x00, 0x12,
+                // RO Spec Start Trigger, length 5, null
+                0x00, 0xB3, 0x00, 0x05, 0x00,
+                // RO Spec Stop Trigger, length 9, null, duration 0
+                0x00, 0xB6, 0x00, 0x09, 0x00, 0x00, 0x00, 0x00, 0x00,
+            // AI Spec, length 24, 1 antenna, id 0
+            0x00, 0xB7, 0x00, 0x18, 0x00, 0x01, 0x00, 0x00,
+                // AI Spec Stop Trigger, length 9, null, duration 0
+                0x00, 0xB8, 0x00, 0x09, 0x00, 0x00, 0x00, 0x00, 0x00,
+                // Inventory Parameter Spec, length 7, id 19, protocol 1
+                0x00, 0xBA, 0x00, 0x07, 0x00, 0x13, 0x01,
+            // RO Report Spec, length 18, trigger 2, n 1
+            0x00, 0xED, 0x00, 0x12, 0x02, 0x00, 0x01,
+                // Tag Report Content Selector, length 11, rospec id/antenna id/peak rssi/first seen
+                0x00, 0xEE, 0x00, 0x0B, 0x96, 0x00,
+                    // C1G2 EPC Memory Selector, length 5, nothing enabled
+                    0x01, 0x5C, 0x00, 0x05, 0x00,
+    ], msg);
+}
+
+#[test]
+fn test_vendor_add_rospec() {
+    let msg = generic::requests::add_rospec(&1, &zebra::requests::rospec(&100)).encode();
+    assert_eq!(96, msg.len());
+    assert_eq!(96, message_length(&msg));
+    // ro spec
+    assert_eq!(86, param_length(&msg, 10));
+    // ro report spec
+    assert_eq!(34, param_length(&msg, 62));
+    // moto tag report content selector
+    assert_eq!(16, param_length(&msg, 80));
+    assert_eq!(&[0x03, 0xFF, 0x00, 0x10, 0x00, 0x00, 0x00, 0xA1, 0x00, 0x00, 0x02, 0xC4, 0x00, 0x00, 0x00, 0x00], &msg[80..]);
+    let msg = generic::requests::add_rospec(&1, &impinj::requests::rospec(&100)).encode();
+    assert_eq!(106, msg.len());
+    assert_eq!(106, message_length(&msg));
+    assert_eq!(96, param_length(&msg, 10));
+    assert_eq!(44, param_length(&msg, 62));
+    // impinj tag report content selector with enable peak rssi inside of it
+    assert_eq!(26, param_length(&msg, 80));
+    assert_eq!(14, param_length(&msg, 92));
+    assert_eq!(&[0x03, 0xFF, 0x00, 0x0E, 0x00, 0x00, 0x63, 0xF4, 0x00, 0x00, 0x00, 0x35, 0x00, 0x01], &msg[92..]);
+}
+
+#[test]
+fn test_set_reader_config() {
+    let msg = generic::requests::set_reader_config(&3, &[]).encode();
+    assert_eq!(vec![
+        0x04, 0x03, 0x00, 0x00, 0x00, 0x29, 0x00, 0x00, 0x00, 0x03, 0x00,
+        // Reader Event Notification Spec, length 25
+        0x00, 0xF4, 0x00, 0x19,
+            0x00, 0xF5, 0x00, 0x07, 0x00, 0x02, 0x80,
+            0x00, 0xF5, 0x00, 0x07, 0x00, 0x03, 0x80,
+            0x00, 0xF5, 0x00, 0x07, 0x00, 0x04, 0x80,
+        // Events and Reports, length 5
+        0x00, 0xE2, 0x00, 0x05, 0x80
+    ], msg);
+    // antenna configuration goes between the event notification spec and events and reports
+    let msg = generic::requests::set_reader_config(&3, &[AntennaConfiguration {
+        antenna_id: 2,
+        receiver: Some(RFReceiver { sensitivity: 1 }),
+        transmitter: Some(RFTransmitter { hop_table_id: 1, channel_index: 1, transmit_power: 81 }),
+        inventory_command: None,
+    }]).encode();
+    assert_eq!(63, message_length(&msg));
+    assert_eq!(&[
+        // Antenna Configuration, length 22
+        0x00, 0xDE, 0x00, 0x16, 0x00, 0x02,
+            // RF Receiver, length 6
+            0x00, 0xDF, 0x00, 0x06, 0x00, 0x01,
+            // RF Transmitter, length 10
+            0x00, 0xE0, 0x00, 0x0A, 0x00, 0x01, 0x00, 0x01, 0x00, 0x51,
+    ], &msg[36..58]);
+    assert_eq!(&[0x00, 0xE2, 0x00, 0x05, 0x80], &msg[58..]);
+    let msg = generic::requests::set_keepalive(&3).encode();
+    assert_eq!(vec![
+        0x04, 0x03, 0x00, 0x00, 0x00, 0x14, 0x00, 0x00, 0x00, 0x03, 0x00,
+        0x00, 0xDC, 0x00, 0x09, 0x01, 0x00, 0x00, 0x07, 0xD0
+    ], msg);
+}
+
+#[test]
+fn test_gpio() {
+    let msg = generic::requests::set_gpo(&3, &[
+        GPOWriteData { port: 1, data: true },
+        GPOWriteData { port: 2, data: false },
+    ]).encode();
+    assert_eq!(vec![
+        0x04, 0x03, 0x00, 0x00, 0x00, 0x19, 0x00, 0x00, 0x00, 0x03, 0x00,
+        // GPO Write Data, length 7
+        0x00, 0xDB, 0x00, 0x07, 0x00, 0x01, 0x80,
+        0x00, 0xDB, 0x00, 0x07, 0x00, 0x02, 0x00,
+    ], msg);
+    let mut buf: Vec<u8> = Vec::new();
+    GPITriggerValue { port: 2, event: true, timeout: 30000 }.encode(&mut buf);
+    assert_eq!(vec![0x00, 0xB5, 0x00, 0x0B, 0x00, 0x02, 0x80, 0x00, 0x00, 0x75, 0x30], buf);
+}
+
+#[test]
+fn test_access_spec() {
+    let spec = generic::requests::access_spec(&200, &100, 4, 0);
+    let msg = generic::requests::add_access_spec(&3, &spec).encode();
+    assert_eq!(vec![
+        0x04, 0x28, 0x00, 0x00, 0x00, 0x48, 0x00, 0x00, 0x00, 0x03,
+        // AccessSpec, length 62, id 200, all antennas, C1G2, disabled, rospec 100
+        0x00, 0xCF, 0x00, 0x3E, 0x00, 0x00, 0x00, 0xC8, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x64,
+        // AccessSpecStopTrigger, null
+        0x00, 0xD0, 0x00, 0x07, 0x00, 0x00, 0x00,
+        // AccessCommand, length 34
+        0x00, 0xD1, 0x00, 0x22,
+        // C1G2TagSpec with a target matching every tag
+        0x01, 0x52, 0x00, 0x0F, 0x01, 0x53, 0x00, 0x0B, 0x60, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        // C1G2Read, op spec 1, no password, TID, word 0, 4 words
+        0x01, 0x55, 0x00, 0x0F, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x80, 0x00, 0x00, 0x00, 0x04,
+        // AccessReportSpec, whenever the ROSpec reports
+        0x00, 0xEF, 0x00, 0x05, 0x00,
+    ], msg);
+    // both memory banks
+    let spec = generic::requests::access_spec(&200, &100, 4, 2);
+    assert_eq!(2, spec.command.reads.len());
+    assert_eq!(MEMORY_BANK_USER, spec.command.reads[1].memory_bank);
+    assert_eq!(2, spec.command.reads[1].word_count);
+    let msg = generic::requests::enable_access_spec(&4, &200).encode();
+    assert_eq!(vec![0x04, 0x2A, 0x00, 0x00, 0x00, 0x0E, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0xC8], msg);
+}
+
+#[test]
+fn test_custom_messages() {
+    let msg = zebra::requests::purge_tags(&4).encode();
+    assert_eq!(vec![0x07, 0xFF, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0xA1, 0x03, 0x00], msg);
+    let msg = impinj::requests::enable_extensions(&4).encode();
+    assert_eq!(vec![0x07, 0xFF, 0x00, 0x00, 0x00, 0x13, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x63, 0xF4, 0x15, 0x00, 0x00, 0x00, 0x00], msg);
+    let msg = zebra::requests::set_no_filter(&4).encode();
+    assert_eq!(27, message_length(&msg));
+    assert_eq!(16, param_length(&msg, 11));
+    let msg = impinj::requests::get_reader_config(&4).encode();
+    assert_eq!(33, msg.len());
+    assert_eq!(33, message_length(&msg));
+    assert_eq!(&[0x03, 0xFF, 0x00, 0x10, 0x00, 0x00, 0x63, 0xF4, 0x00, 0x00, 0x00, 0x15, 0x00, 0x00, 0x07, 0xD0], &msg[17..]);
+    // the time goes in a UTCTimestamp inside the custom parameter
+    let msg = zebra::requests::set_clock(&4, 0x0102030405060708).encode();
+    assert_eq!(vec![
+        0x04, 0x03, 0x00, 0x00, 0x00, 0x23, 0x00, 0x00, 0x00, 0x04, 0x00,
+        // Custom Parameter, length 24, vendor 161, subtype 712
+        0x03, 0xFF, 0x00, 0x18, 0x00, 0x00, 0x00, 0xA1, 0x00, 0x00, 0x02, 0xC8,
+            // UTC Timestamp, length 12
+            0x00, 0x80, 0x00, 0x0C, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08
+    ], msg);
+}
+
+#[test]
+fn test_c1g2_filter() {
+    let filter = C1G2Filter {
+        truncate: 1,
+        mask: C1G2TagInventoryMask {
+            memory_bank: MEMORY_BANK_EPC,
+            pointer: 32,
+            mask_bits: 12,
+            mask: vec![0xAB, 0xC0],
+        },
+        unaware_action: Some(1),
+    };
+    let mut buf: Vec<u8> = Vec::new();
+    filter.encode(&mut buf);
+    assert_eq!(vec![
+        // C1G2 Filter, length 21, do not truncate
+        0x01, 0x4B, 0x00, 0x15, 0x40,
+            // Tag Inventory Mask, length 11, epc bank, pointer 32, 12 bits
+            0x01, 0x4C, 0x00, 0x0B, 0x40, 0x00, 0x20, 0x00, 0x0C, 0xAB, 0xC0,
+            // State Unaware Filter Action, length 5
+            0x01, 0x4E, 0x00, 0x05, 0x01,
+    ], buf);
+    // mask data shorter than the bit count is padded out
+    let mask = C1G2TagInventoryMask {
+        memory_bank: MEMORY_BANK_EPC,
+        pointer: 32,
+        mask_bits: 17,
+        mask: vec![0xFF],
+    };
+    let mut buf: Vec<u8> = Vec::new();
+    mask.encode(&mut buf);
+    assert_eq!(12, param_length(&buf, 0));
+    assert_eq!(&[0xFF, 0x00, 0x00], &buf[9..]);
+}
+
+#[test]
+fn test_antenna_configuration() {
+    let config = AntennaConfiguration {
+        antenna_id: 2,
+        receiver: Some(RFReceiver { sensitivity: 1 }),
+        transmitter: Some(RFTransmitter { hop_table_id: 1, channel_index: 0, transmit_power: 81 }),
+        inventory_command: Some(C1G2InventoryCommand::default()),
+    };
+    let mut buf: Vec<u8> = Vec::new();
+    config.encode(&mut buf);
+    assert_eq!(vec![
+        // Antenna Configuration, length 27, antenna 2
+        0x00, 0xDE, 0x00, 0x1B, 0x00, 0x02,
+            // RF Receiver, length 6
+            0x00, 0xDF, 0x00, 0x06, 0x00, 0x01,
+            // RF Transmitter, length 10
+            0x00, 0xE0, 0x00, 0x0A, 0x00, 0x01, 0x00, 0x00, 0x00, 0x51,
+            // C1G2 Inventory Command, length 5
+            0x01, 0x4A, 0x00, 0x05, 0x00,
+    ], buf);
+    let custom = CustomParameter {
+        vendor: 1,
+        subtype: 2,
+        data: vec![0x03],
+        parameters: vec![CustomParameter { vendor: 1, subtype: 4, data: Vec::new(), parameters: Vec::new() }],
+    };
+    let mut buf: Vec<u8> = Vec::new();
+    custom.encode(&mut buf);
+    assert_eq!(25, param_length(&buf, 0));
+    assert_eq!(12, param_length(&buf, 13));
+}
@@ -212,6 +212,32 @@ pub const MOTO_UPDATE_RADIO_CONFIG: u16 = 12;
 pub const MOTO_UPDATE_RADIO_CONFIG_RESPONSE: u16 = 13;
 pub const MOTO_GET_RADIO_UPDATE_STATUS: u16 = 14;
 pub const MOTO_GET_RADIO_UPDATE_STATUS_RESPONSE: u16 = 15;
+//      -- MOTOROLA (Zebra) Parameter Subtypes
+// Not yet checked against Zebra's LLRP extension definitions or a capture from a real reader, the simulator
+// only echoes it back. A reader that doesn't know it rejects the SET_READER_CONFIG and we connect with its clock as is.
+pub const MOTO_UTC_CLOCK: u16 = 712;
+
+pub const IMPINJ_VENDOR_ID: u32 = 25588;
+//      -- IMPINJ Message Subtypes
+pub const IMPINJ_ENABLE_EXTENSIONS: u16 = 21;
+pub const IMPINJ_ENABLE_EXTENSIONS_RESPONSE: u16 = 22;
+pub const IMPINJ_SAVE_SETTINGS: u16 = 23;
+pub const IMPINJ_SAVE_SETTINGS_RESPONSE: u16 = 24;
+//      -- IMPINJ Parameter Subtypes
+pub const IMPINJ_REQUESTED_DATA: u16 = 21;
+pub const IMPINJ_TAG_REPORT_CONTENT_SELECTOR: u16 = 50;
+pub const IMPINJ_ENABLE_PEAK_RSSI: u16 = 53;
+pub const IMPINJ_PEAK_RSSI: u16 = 57;
+pub const IMPINJ_HUB_VERSIONS: u16 = 1537;
+pub const IMPINJ_HUB_CONFIGURATION: u16 = 1538;
+//      -- IMPINJ Requested Data values
+pub const IMPINJ_REQUESTED_DATA_ALL_CONFIGURATION: u32 = 2000;
+//      -- IMPINJ Hub Connected values
+pub const IMPINJ_HUB_CONNECTED_UNKNOWN: u16 = 0;
+pub const IMPINJ_HUB_CONNECTED_DISCONNECTED: u16 = 1;
+pub const IMPINJ_HUB_CONNECTED_CONNECTED: u16 = 2;
+//      -- IMPINJ Hub Fault values
+pub const IMPINJ_HUB_FAULT_NONE: u16 = 0;
 
 pub fn get_parameter_name(kind: u16) -> Option<&'static str> {
     match kind {
@@ -385,6 +411,10 @@ pub fn get_llrp_custom_message_name(vendor: u32, kind: u16) -> &'static str {
         (MOTOROLA_VENDOR_ID, MOTO_UPDATE_RADIO_CONFIG_RESPONSE) => "CUSTOM MOTOROLA - MOTO_UPDATE_RADIO_CONFIG_RESPONSE",
         (MOTOROLA_VENDOR_ID, MOTO_GET_RADIO_UPDATE_STATUS) => "CUSTOM MOTOROLA - MOTO_GET_RADIO_UPDATE_STATUS",
         (MOTOROLA_VENDOR_ID, MOTO_GET_RADIO_UPDATE_STATUS_RESPONSE) => "CUSTOM MOTOROLA - MOTO_GET_RADIO_UPDATE_STATUS_RESPONSE",
+        (IMPINJ_VENDOR_ID, IMPINJ_ENABLE_EXTENSIONS) => "CUSTOM IMPINJ - IMPINJ_ENABLE_EXTENSIONS",
+        (IMPINJ_VENDOR_ID, IMPINJ_ENABLE_EXTENSIONS_RESPONSE) => "CUSTOM IMPINJ - IMPINJ_ENABLE_EXTENSIONS_RESPONSE",
+        (IMPINJ_VENDOR_ID, IMPINJ_SAVE_SETTINGS) => "CUSTOM IMPINJ - IMPINJ_SAVE_SETTINGS",
+        (IMPINJ_VENDOR_ID, IMPINJ_SAVE_SETTINGS_RESPONSE) => "CUSTOM IMPINJ - IMPINJ_SAVE_SETTINGS_RESPONSE",
         (_, _) => "UNKNOWN CUSTOM MESSAGE",
     }
 }
@@ -18,6 +18,7 @@ along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 use core::panic;
 use std::env;
+use std::net::SocketAddr;
 use std::sync::Arc;
 use std::sync::Mutex;
 use dotenv::dotenv;
@@ -55,6 +56,23 @@ fn main() {
     if args.len() > 1 && (args[1].eq_ignore_ascii_case("--quick") || args[1].eq_ignore_ascii_case("-q")) {
         quick = true;
     }
+    // run a simulated reader for development instead of starting up
+    if args.len() > 1 && args[1].eq_ignore_ascii_case("--simulate") {
+        let port = args.get(2).and_then(|p| p.parse().ok()).unwrap_or(reader::simulator::DEFAULT_SIMULATOR_PORT);
+        let kind = args.get(3).map(|k| k.to_uppercase()).unwrap_or(String::from(reader::READER_KIND_LLRP));
+        let config = reader::simulator::SimulatorConfig {
+            manufacturer: reader::simulator::manufacturer(&kind),
+            ..Default::default()
+        };
+        match reader::simulator::Simulator::bind(SocketAddr::from(([0, 0, 0, 0], port)), config) {
+            Ok(simulator) => {
+                println!("Simulated {kind} reader listening on port {port}.");
+                simulator.run();
+            },
+            Err(e) => println!("Unable to start simulated reader. {e}"),
+        }
+        return;
+    }
     let restore = sqlite::SQLite::already_exists() == false;
     let mut sqlite = sqlite::SQLite::new().unwrap();
     match sqlite.setup() {
@@ -204,6 +222,23 @@ fn main() {
             Err(_) => (),
         };
     }
+    // replay a capture taken from a reader and print the reads it produces instead of starting up
+    if args.len() > 2 && args[1].eq_ignore_ascii_case("--replay") {
+        let control = control::Control::new(&mut sqlite).unwrap();
+        match reader::capture::replay(&args[2], &sqlite, &control) {
+            Ok(reads) => {
+                for read in reads.iter() {
+                    match serde_json::to_string(read) {
+                        Ok(line) => println!("{line}"),
+                        Err(e) => println!("Error printing read. {e}"),
+                    }
+                }
+                println!("{} reads found in capture.", reads.len());
+            },
+            Err(e) => println!("Error replaying capture. {e}"),
+        }
+        return;
+    }
     let control = Arc::new(Mutex::new(control::Control::new(&mut sqlite).unwrap()));
     let sqlite = Arc::new(Mutex::new(sqlite));
     println!("Control values retrieved from database.");
@@ -35,6 +35,7 @@ pub enum Notification {
     UnableToStartReading,
     Location,
     Shutdown,
+    ReaderClock { reader_name: String, offset_milliseconds: i64 },
 }
 
 #[derive(Clone)]
@@ -178,6 +179,11 @@ impl Notifier {
                     Notification::Shutdown => {
                         tag = String::from("stop_sign");
                         format!("{time} - {name} is shutting down.")
+                    },
+                    Notification::ReaderClock { reader_name, offset_milliseconds } => {
+                        tag = String::from("alarm_clock");
+                        priority = 4;
+                        format!("{time} - The clock on reader {reader_name} connected to {name} is off by {offset_milliseconds} milliseconds.")
                     }
                 };
                 if enabled && !url.is_empty() && !topic.is_empty() && !user.is_empty() && !pass.is_empty() {
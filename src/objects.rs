@@ -20,3 +20,14 @@ pub mod setting;
 pub mod read;
 pub mod backup;
 pub mod notification;
+pub mod antenna;
+pub mod filter;
+pub mod gpio;
+pub mod tag_data;
+pub mod clock;
+pub mod report_buffer;
+pub mod profile;
+pub mod reconnect;
+pub mod dedup;
+pub mod read_window;
+pub mod bibchip;
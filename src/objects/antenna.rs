@@ -0,0 +1,140 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use serde::{Serialize, Deserialize};
+
+use crate::reader::MAX_ANTENNAS;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all="snake_case")]
+pub struct AntennaConfig {
+    // Antenna port on the reader, starting at 1.
+    antenna: u16,
+    enabled: bool,
+    // Index into the readers transmit power table. 0 leaves the reader at its default power.
+    #[serde(default)]
+    transmit_power_index: u16,
+    // Power in dBm, when set this is used instead of the index once we know the readers power table.
+    #[serde(default)]
+    transmit_power_dbm: Option<f32>,
+    // Index into the readers receive sensitivity table. 0 leaves the reader at its default sensitivity.
+    #[serde(default)]
+    receive_sensitivity_index: u16,
+}
+
+impl AntennaConfig {
+    pub fn new(
+        antenna: u16,
+        enabled: bool,
+        transmit_power_index: u16,
+        transmit_power_dbm: Option<f32>,
+        receive_sensitivity_index: u16,
+    ) -> AntennaConfig {
+        AntennaConfig {
+            antenna,
+            enabled,
+            transmit_power_index,
+            transmit_power_dbm,
+            receive_sensitivity_index,
+        }
+    }
+
+    pub fn antenna(&self) -> u16 {
+        self.antenna
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn transmit_power_index(&self) -> u16 {
+        self.transmit_power_index
+    }
+
+    pub fn transmit_power_dbm(&self) -> Option<f32> {
+        self.transmit_power_dbm
+    }
+
+    pub fn receive_sensitivity_index(&self) -> u16 {
+        self.receive_sensitivity_index
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.antenna > 0 && self.antenna as usize <= MAX_ANTENNAS
+    }
+}
+
+// Maps a port on the reader to the antenna number its reads and status are reported as.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all="snake_case")]
+pub struct AntennaMapping {
+    port: u16,
+    antenna: u16,
+}
+
+impl AntennaMapping {
+    pub fn new(
+        port: u16,
+        antenna: u16,
+    ) -> AntennaMapping {
+        AntennaMapping {
+            port,
+            antenna,
+        }
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    pub fn antenna(&self) -> u16 {
+        self.antenna
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.port > 0 && self.port as usize <= MAX_ANTENNAS
+            && self.antenna > 0 && self.antenna as usize <= MAX_ANTENNAS
+    }
+}
+
+// The antenna number a port is reported as, ports without a mapping keep their own number.
+pub fn mapped_antenna(map: &[AntennaMapping], port: u16) -> u16 {
+    match map.iter().find(|m| m.port == port) {
+        Some(m) => m.antenna,
+        None => port,
+    }
+}
+
+// Checks that every mapping is in range and that no two ports end up reported as the same antenna.
+pub fn validate_map(map: &[AntennaMapping]) -> Result<(), String> {
+    if let Some(invalid) = map.iter().find(|m| !m.is_valid()) {
+        return Err(format!("port {} and antenna {} must both be in the supported range of 1 to {MAX_ANTENNAS}", invalid.port, invalid.antenna))
+    }
+    for (ix, m) in map.iter().enumerate() {
+        if map[..ix].iter().any(|other| other.port == m.port) {
+            return Err(format!("port {} is mapped more than once", m.port))
+        }
+    }
+    for port in 1..=MAX_ANTENNAS as u16 {
+        let antenna = mapped_antenna(map, port);
+        if (1..port).any(|other| mapped_antenna(map, other) == antenna) {
+            return Err(format!("more than one port is reported as antenna {antenna}"))
+        }
+    }
+    Ok(())
+}
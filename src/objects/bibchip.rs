@@ -0,0 +1,63 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use serde::{Serialize, Deserialize};
+
+pub const MAX_BIB_LENGTH: usize = 50;
+pub const MAX_CHIP_LENGTH: usize = 100;
+
+// The bib a chip is assigned to. A chip only belongs to one bib, but a bib can have more than one chip.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all="snake_case")]
+pub struct BibChip {
+    bib: String,
+    chip: String,
+}
+
+impl BibChip {
+    pub fn new(
+        bib: String,
+        chip: String,
+    ) -> BibChip {
+        BibChip {
+            bib,
+            chip,
+        }
+    }
+
+    pub fn bib(&self) -> &str {
+        &self.bib
+    }
+
+    pub fn chip(&self) -> &str {
+        &self.chip
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.bib.trim().is_empty() || self.chip.trim().is_empty() {
+            return Err(String::from("bib and chip must both be set"))
+        }
+        if self.bib.len() > MAX_BIB_LENGTH {
+            return Err(format!("bib {} is longer than {MAX_BIB_LENGTH} characters", self.bib))
+        }
+        if self.chip.len() > MAX_CHIP_LENGTH {
+            return Err(format!("chip {} is longer than {MAX_CHIP_LENGTH} characters", self.chip))
+        }
+        Ok(())
+    }
+}
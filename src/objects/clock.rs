@@ -0,0 +1,108 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+
+use serde::{Serialize, Deserialize};
+
+// Which clock the seconds and milliseconds saved with a read come from.
+pub const CLOCK_SOURCE_PORTAL: &str = "portal";
+pub const CLOCK_SOURCE_READER: &str = "reader";
+
+// Offset between the reader and portal clocks we warn about unless told otherwise.
+pub const DEFAULT_WARN_THRESHOLD_MILLISECONDS: u32 = 1000;
+
+// How a reader's clock is reconciled with the portal clock.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all="snake_case")]
+pub struct ClockConfig {
+    // The clock used for the time saved on reads, the other is still saved as the reader time.
+    #[serde(default = "default_source")]
+    source: String,
+    // Shift reader timestamps by the measured offset so they line up with the portal clock.
+    // Only applies when the reader's clock is used.
+    #[serde(default)]
+    correct: bool,
+    // Warn when the reader and portal clocks are further apart than this, 0 never warns.
+    #[serde(default = "default_warn_threshold")]
+    warn_threshold_milliseconds: u32,
+    // Set the reader's clock to the portal's time when connecting, for readers that lose it when powered off.
+    #[serde(default)]
+    set_reader_clock: bool,
+}
+
+fn default_source() -> String {
+    String::from(CLOCK_SOURCE_PORTAL)
+}
+
+fn default_warn_threshold() -> u32 {
+    DEFAULT_WARN_THRESHOLD_MILLISECONDS
+}
+
+impl Default for ClockConfig {
+    fn default() -> Self {
+        ClockConfig {
+            source: default_source(),
+            correct: false,
+            warn_threshold_milliseconds: DEFAULT_WARN_THRESHOLD_MILLISECONDS,
+            set_reader_clock: false,
+        }
+    }
+}
+
+impl ClockConfig {
+    pub fn new(
+        source: String,
+        correct: bool,
+        warn_threshold_milliseconds: u32,
+        set_reader_clock: bool,
+    ) -> ClockConfig {
+        ClockConfig {
+            source,
+            correct,
+            warn_threshold_milliseconds,
+            set_reader_clock,
+        }
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn correct(&self) -> bool {
+        self.correct
+    }
+
+    pub fn warn_threshold_milliseconds(&self) -> u32 {
+        self.warn_threshold_milliseconds
+    }
+
+    pub fn set_reader_clock(&self) -> bool {
+        self.set_reader_clock
+    }
+
+    pub fn uses_reader_clock(&self) -> bool {
+        self.source == CLOCK_SOURCE_READER
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        match self.source.as_str() {
+            CLOCK_SOURCE_PORTAL | CLOCK_SOURCE_READER => Ok(()),
+            other => Err(format!("unknown clock source '{other}', expected '{CLOCK_SOURCE_PORTAL}' or '{CLOCK_SOURCE_READER}'")),
+        }
+    }
+}
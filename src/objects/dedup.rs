@@ -0,0 +1,158 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use serde::{Serialize, Deserialize};
+
+use crate::reader::MAX_ANTENNAS;
+
+// Which sighting of a tag inside the read window is kept as the read.
+pub const DEDUP_MODE_FIRST: &str = "first";
+pub const DEDUP_MODE_LAST: &str = "last";
+pub const DEDUP_MODE_BEST_RSSI: &str = "best_rssi";
+// Every sighting is saved as a read, for diagnostics.
+pub const DEDUP_MODE_ALL: &str = "all";
+
+// Antennas that share a read window and how their sightings are deduplicated. A tag seen on antennas in
+// different groups gets a read for each group.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all="snake_case")]
+pub struct DedupGroup {
+    // Antennas in the group, as they're reported after any antenna mapping.
+    antennas: Vec<u16>,
+    mode: String,
+}
+
+impl DedupGroup {
+    pub fn new(
+        antennas: Vec<u16>,
+        mode: String,
+    ) -> DedupGroup {
+        DedupGroup {
+            antennas,
+            mode,
+        }
+    }
+
+    pub fn antennas(&self) -> &[u16] {
+        &self.antennas
+    }
+
+    pub fn mode(&self) -> &str {
+        &self.mode
+    }
+}
+
+// How the sightings of a tag inside the read window are turned into reads for a reader.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all="snake_case")]
+pub struct DedupConfig {
+    // Mode for antennas that aren't in a group.
+    #[serde(default = "default_mode")]
+    mode: String,
+    // Send the first sighting of a tag as soon as it's seen instead of waiting for the window to close.
+    // The read the mode keeps is still sent when the window closes if it isn't that first sighting.
+    #[serde(default)]
+    emit_first: bool,
+    #[serde(default)]
+    groups: Vec<DedupGroup>,
+}
+
+fn default_mode() -> String {
+    String::from(DEDUP_MODE_BEST_RSSI)
+}
+
+impl Default for DedupConfig {
+    fn default() -> Self {
+        DedupConfig {
+            mode: default_mode(),
+            emit_first: false,
+            groups: Vec::new(),
+        }
+    }
+}
+
+impl DedupConfig {
+    pub fn new(
+        mode: String,
+        emit_first: bool,
+        groups: Vec<DedupGroup>,
+    ) -> DedupConfig {
+        DedupConfig {
+            mode,
+            emit_first,
+            groups,
+        }
+    }
+
+    pub fn mode(&self) -> &str {
+        &self.mode
+    }
+
+    pub fn emit_first(&self) -> bool {
+        self.emit_first
+    }
+
+    pub fn groups(&self) -> &[DedupGroup] {
+        &self.groups
+    }
+
+    // The read window an antenna belongs to, 0 for antennas that aren't in a group.
+    pub fn group(&self, antenna: u16) -> usize {
+        match self.groups.iter().position(|group| group.antennas.contains(&antenna)) {
+            Some(ix) => ix + 1,
+            None => 0,
+        }
+    }
+
+    // The mode used by the read window given.
+    pub fn group_mode(&self, group: usize) -> &str {
+        match group.checked_sub(1).and_then(|ix| self.groups.get(ix)) {
+            Some(group) => &group.mode,
+            None => &self.mode,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        validate_mode(&self.mode)?;
+        if self.groups.len() > MAX_ANTENNAS {
+            return Err(format!("no more than {MAX_ANTENNAS} groups are allowed"))
+        }
+        for (ix, group) in self.groups.iter().enumerate() {
+            validate_mode(&group.mode)?;
+            if group.antennas.is_empty() {
+                return Err(String::from("groups need at least one antenna"))
+            }
+            for antenna in group.antennas.iter() {
+                if *antenna == 0 || *antenna as usize > MAX_ANTENNAS {
+                    return Err(format!("antenna {antenna} is not a valid antenna"))
+                }
+                if self.groups[..ix].iter().any(|other| other.antennas.contains(antenna)) {
+                    return Err(format!("antenna {antenna} is in more than one group"))
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn validate_mode(mode: &str) -> Result<(), String> {
+    match mode {
+        DEDUP_MODE_FIRST | DEDUP_MODE_LAST | DEDUP_MODE_BEST_RSSI | DEDUP_MODE_ALL => Ok(()),
+        other => Err(format!("unknown mode '{other}', expected '{DEDUP_MODE_FIRST}', '{DEDUP_MODE_LAST}', '{DEDUP_MODE_BEST_RSSI}' or '{DEDUP_MODE_ALL}'")),
+    }
+}
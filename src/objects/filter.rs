@@ -0,0 +1,119 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use serde::{Serialize, Deserialize};
+
+pub const FILTER_ACTION_INCLUDE: &str = "include";
+pub const FILTER_ACTION_EXCLUDE: &str = "exclude";
+
+// Memory banks a filter can match against. 0 is the reserved bank which holds passwords.
+pub const FILTER_MEMORY_BANK_EPC: u8 = 1;
+pub const FILTER_MEMORY_BANK_TID: u8 = 2;
+pub const FILTER_MEMORY_BANK_USER: u8 = 3;
+
+// A mask the reader matches tags against before it inventories them.
+// Filters are applied in order, tags matching an include filter are read and tags matching an exclude filter are not.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all="snake_case")]
+pub struct TagFilter {
+    memory_bank: u8,
+    // Bit offset into the memory bank. The EPC starts at bit 32 of the EPC bank, after the CRC and PC bits.
+    pointer: u16,
+    // Hex value to match.
+    mask: String,
+    // Number of bits of the mask to match. 0 matches every bit in the mask.
+    #[serde(default)]
+    mask_bits: u16,
+    action: String,
+}
+
+impl TagFilter {
+    pub fn new(
+        memory_bank: u8,
+        pointer: u16,
+        mask: String,
+        mask_bits: u16,
+        action: String,
+    ) -> TagFilter {
+        TagFilter {
+            memory_bank,
+            pointer,
+            mask,
+            mask_bits,
+            action,
+        }
+    }
+
+    pub fn memory_bank(&self) -> u8 {
+        self.memory_bank
+    }
+
+    pub fn pointer(&self) -> u16 {
+        self.pointer
+    }
+
+    pub fn mask(&self) -> &str {
+        &self.mask
+    }
+
+    pub fn mask_bits(&self) -> u16 {
+        self.mask_bits
+    }
+
+    pub fn action(&self) -> &str {
+        &self.action
+    }
+
+    pub fn include(&self) -> bool {
+        self.action == FILTER_ACTION_INCLUDE
+    }
+
+    // The mask as bytes, an odd number of hex digits is padded out with a 0.
+    pub fn mask_bytes(&self) -> Option<Vec<u8>> {
+        let mut digits: Vec<u8> = Vec::new();
+        for c in self.mask.chars() {
+            digits.push(c.to_digit(16)? as u8);
+        }
+        if digits.len() % 2 == 1 {
+            digits.push(0);
+        }
+        Some(digits.chunks(2).map(|pair| (pair[0] << 4) | pair[1]).collect())
+    }
+
+    // The number of bits the reader should match.
+    pub fn match_bits(&self) -> u16 {
+        match self.mask_bits {
+            0 => (self.mask.len() * 4) as u16,
+            bits => bits,
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        match self.action.as_str() {
+            FILTER_ACTION_INCLUDE | FILTER_ACTION_EXCLUDE => {},
+            _ => return false,
+        }
+        match self.memory_bank {
+            FILTER_MEMORY_BANK_EPC | FILTER_MEMORY_BANK_TID | FILTER_MEMORY_BANK_USER => {},
+            _ => return false,
+        }
+        !self.mask.is_empty()
+            && self.mask_bytes().is_some()
+            && self.match_bits() as usize <= self.mask.len() * 4
+    }
+}
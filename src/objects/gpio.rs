@@ -0,0 +1,178 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+
+use serde::{Serialize, Deserialize};
+
+// Portal events a GPO port can follow.
+// Reading is on while tags are coming in, connected is on while the reader is reading,
+// and reader_lost is on for a while after any reader on the portal loses its connection.
+pub const GPO_EVENT_READING: &str = "reading";
+pub const GPO_EVENT_CONNECTED: &str = "connected";
+pub const GPO_EVENT_READER_LOST: &str = "reader_lost";
+
+// How long an output stays on after the last event when a duration isn't given, in milliseconds.
+pub const DEFAULT_READING_DURATION: u32 = 1000;
+pub const DEFAULT_READER_LOST_DURATION: u32 = 5000;
+
+// A GPI port state that starts or stops reading.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all="snake_case")]
+pub struct GpiTrigger {
+    // GPI port on the reader, starting at 1.
+    port: u16,
+    // True when the trigger is the port going high, false when it goes low.
+    event: bool,
+    // Milliseconds to wait for the port before giving up, 0 waits forever.
+    // A stop trigger that times out stops reading the same as if the port changed.
+    #[serde(default)]
+    timeout: u32,
+}
+
+impl GpiTrigger {
+    pub fn new(
+        port: u16,
+        event: bool,
+        timeout: u32,
+    ) -> GpiTrigger {
+        GpiTrigger {
+            port,
+            event,
+            timeout,
+        }
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    pub fn event(&self) -> bool {
+        self.event
+    }
+
+    pub fn timeout(&self) -> u32 {
+        self.timeout
+    }
+}
+
+// A GPO port on the reader that follows a portal event.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all="snake_case")]
+pub struct GpoOutput {
+    // GPO port on the reader, starting at 1.
+    port: u16,
+    event: String,
+    // Milliseconds the port stays on after the last event. 0 uses the default for the event.
+    // Ignored for the connected event.
+    #[serde(default)]
+    duration: u32,
+}
+
+impl GpoOutput {
+    pub fn new(
+        port: u16,
+        event: String,
+        duration: u32,
+    ) -> GpoOutput {
+        GpoOutput {
+            port,
+            event,
+            duration,
+        }
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    pub fn event(&self) -> &str {
+        &self.event
+    }
+
+    pub fn duration(&self) -> u32 {
+        self.duration
+    }
+
+    // The time the port stays on, with the default for the event filled in.
+    pub fn hold(&self) -> u32 {
+        match (self.duration, self.event.as_str()) {
+            (0, GPO_EVENT_READING) => DEFAULT_READING_DURATION,
+            (0, GPO_EVENT_READER_LOST) => DEFAULT_READER_LOST_DURATION,
+            (duration, _) => duration,
+        }
+    }
+}
+
+// GPIO settings for a reader. Without a start trigger reading starts as soon as the reader connects,
+// without a stop trigger it runs until it's stopped.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all="snake_case")]
+pub struct GpioConfig {
+    #[serde(default)]
+    start_trigger: Option<GpiTrigger>,
+    #[serde(default)]
+    stop_trigger: Option<GpiTrigger>,
+    #[serde(default)]
+    outputs: Vec<GpoOutput>,
+}
+
+impl GpioConfig {
+    pub fn new(
+        start_trigger: Option<GpiTrigger>,
+        stop_trigger: Option<GpiTrigger>,
+        outputs: Vec<GpoOutput>,
+    ) -> GpioConfig {
+        GpioConfig {
+            start_trigger,
+            stop_trigger,
+            outputs,
+        }
+    }
+
+    pub fn start_trigger(&self) -> Option<&GpiTrigger> {
+        self.start_trigger.as_ref()
+    }
+
+    pub fn stop_trigger(&self) -> Option<&GpiTrigger> {
+        self.stop_trigger.as_ref()
+    }
+
+    pub fn outputs(&self) -> &[GpoOutput] {
+        &self.outputs
+    }
+
+    // Checks the ports are real and the events are ones we know. Port counts of 0 mean we don't know
+    // how many ports the reader has.
+    pub fn validate(&self, gpis: u16, gpos: u16) -> Result<(), String> {
+        for trigger in [&self.start_trigger, &self.stop_trigger].into_iter().flatten() {
+            if trigger.port == 0 || (gpis > 0 && trigger.port > gpis) {
+                return Err(format!("gpi port {} is not on the reader", trigger.port))
+            }
+        }
+        for output in self.outputs.iter() {
+            match output.event.as_str() {
+                GPO_EVENT_READING | GPO_EVENT_CONNECTED | GPO_EVENT_READER_LOST => {},
+                other => return Err(format!("unknown gpo event '{other}'"))
+            }
+            if output.port == 0 || (gpos > 0 && output.port > gpos) {
+                return Err(format!("gpo port {} is not on the reader", output.port))
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,155 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use serde::{Serialize, Deserialize};
+
+use crate::reader::MAX_ANTENNAS;
+
+pub const MAX_PROFILES: usize = 8;
+pub const MAX_PROFILE_NAME_LENGTH: usize = 50;
+
+// A named way of reading, such as a finish line on every antenna at full power or a chip check on
+// one antenna at low power. Each profile is sent to the reader as its own ROSpec.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all="snake_case")]
+pub struct ReadingProfile {
+    name: String,
+    // Antenna ports to read on, starting at 1. Empty reads on every enabled antenna.
+    #[serde(default)]
+    antennas: Vec<u16>,
+    // Power in dBm for every antenna in the profile, matched to the closest entry in the readers power table.
+    // When not set the antennas keep the power from the antenna configuration.
+    #[serde(default)]
+    transmit_power_dbm: Option<f32>,
+    // Milliseconds to read for each time the profile is started, 0 reads until stopped.
+    #[serde(default)]
+    duration_milliseconds: u32,
+}
+
+impl ReadingProfile {
+    pub fn new(
+        name: String,
+        antennas: Vec<u16>,
+        transmit_power_dbm: Option<f32>,
+        duration_milliseconds: u32,
+    ) -> ReadingProfile {
+        ReadingProfile {
+            name,
+            antennas,
+            transmit_power_dbm,
+            duration_milliseconds,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn antennas(&self) -> &[u16] {
+        &self.antennas
+    }
+
+    pub fn transmit_power_dbm(&self) -> Option<f32> {
+        self.transmit_power_dbm
+    }
+
+    pub fn duration_milliseconds(&self) -> u32 {
+        self.duration_milliseconds
+    }
+}
+
+// The reading profiles set up for a reader and the one it's reading with. Without an active profile
+// the reader reads the way it always has, on every enabled antenna until it's stopped.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all="snake_case")]
+pub struct ProfileConfig {
+    #[serde(default)]
+    profiles: Vec<ReadingProfile>,
+    #[serde(default)]
+    active: Option<String>,
+}
+
+impl ProfileConfig {
+    pub fn new(
+        profiles: Vec<ReadingProfile>,
+        active: Option<String>,
+    ) -> ProfileConfig {
+        ProfileConfig {
+            profiles,
+            active,
+        }
+    }
+
+    pub fn profiles(&self) -> &[ReadingProfile] {
+        &self.profiles
+    }
+
+    pub fn active(&self) -> Option<&str> {
+        self.active.as_deref()
+    }
+
+    pub fn set_active(&mut self, active: Option<String>) {
+        self.active = active;
+    }
+
+    // The position of the active profile, used to give each profile its own ROSpec id.
+    pub fn active_index(&self) -> Option<usize> {
+        let active = self.active.as_ref()?;
+        self.profiles.iter().position(|profile| &profile.name == active)
+    }
+
+    pub fn active_profile(&self) -> Option<&ReadingProfile> {
+        self.active_index().map(|ix| &self.profiles[ix])
+    }
+
+    // Checks the names are usable, the antennas are real, and the active profile is one we know.
+    // An antenna count of 0 means we don't know how many antennas the reader has.
+    pub fn validate(&self, max_antennas: u16) -> Result<(), String> {
+        if self.profiles.len() > MAX_PROFILES {
+            return Err(format!("no more than {MAX_PROFILES} profiles are allowed"))
+        }
+        let max_antennas = if max_antennas == 0 { MAX_ANTENNAS as u16 } else { max_antennas.min(MAX_ANTENNAS as u16) };
+        for (ix, profile) in self.profiles.iter().enumerate() {
+            if profile.name.trim().is_empty() {
+                return Err(String::from("profile names can't be empty"))
+            }
+            if profile.name.len() > MAX_PROFILE_NAME_LENGTH {
+                return Err(format!("profile name '{}' is longer than {MAX_PROFILE_NAME_LENGTH} characters", profile.name))
+            }
+            if self.profiles[..ix].iter().any(|other| other.name == profile.name) {
+                return Err(format!("more than one profile is named '{}'", profile.name))
+            }
+            for antenna in profile.antennas.iter() {
+                if *antenna == 0 || *antenna > max_antennas {
+                    return Err(format!("antenna {antenna} in profile '{}' is not on the reader", profile.name))
+                }
+            }
+            if let Some(dbm) = profile.transmit_power_dbm {
+                if !dbm.is_finite() {
+                    return Err(format!("transmit power for profile '{}' is not a number", profile.name))
+                }
+            }
+        }
+        if let Some(active) = &self.active {
+            if self.active_index().is_none() {
+                return Err(format!("unknown profile '{active}'"))
+            }
+        }
+        Ok(())
+    }
+}
@@ -48,6 +48,32 @@ pub struct Read {
     kind: String,
     #[serde(skip)]
     uploaded: u8,
+    // Bib the chip is assigned to, if we know it.
+    #[serde(default, skip_serializing_if="Option::is_none")]
+    bib: Option<String>,
+    // Extra tag data, only there when the reader is set up to report it.
+    #[serde(flatten, default)]
+    details: ReadDetails,
+}
+
+// Optional tag data used for spotting cloned chips and for diagnostics.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all="snake_case")]
+pub struct ReadDetails {
+    // TID and user memory as hex strings.
+    #[serde(default, skip_serializing_if="Option::is_none")]
+    pub tid: Option<String>,
+    #[serde(default, skip_serializing_if="Option::is_none")]
+    pub user_memory: Option<String>,
+    #[serde(default, skip_serializing_if="Option::is_none")]
+    pub channel_index: Option<u16>,
+    #[serde(default, skip_serializing_if="Option::is_none")]
+    pub seen_count: Option<u16>,
+    // Reader time the tag was last seen.
+    #[serde(default, skip_serializing_if="Option::is_none")]
+    pub last_seen_seconds: Option<i64>,
+    #[serde(default, skip_serializing_if="Option::is_none")]
+    pub last_seen_milliseconds: Option<u32>,
 }
 
 impl Read {
@@ -75,7 +101,9 @@ impl Read {
                 rssi,
                 uploaded,
                 ident_type: String::from(READ_IDENT_TYPE_CHIP),
-                kind: String::from(READ_KIND_CHIP)
+                kind: String::from(READ_KIND_CHIP),
+                bib: None,
+                details: ReadDetails::default(),
             }
     }
 
@@ -134,6 +162,22 @@ impl Read {
         self.uploaded = uploaded;
     }
 
+    pub fn details(&self) -> &ReadDetails {
+        &self.details
+    }
+
+    pub fn set_details(&mut self, details: ReadDetails) {
+        self.details = details;
+    }
+
+    pub fn bib(&self) -> Option<&str> {
+        self.bib.as_deref()
+    }
+
+    pub fn set_bib(&mut self, bib: Option<String>) {
+        self.bib = bib;
+    }
+
     pub fn ident_type(&self) -> &str {
         &self.ident_type
     }
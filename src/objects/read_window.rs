@@ -0,0 +1,120 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use serde::{Serialize, Deserialize};
+
+use crate::reader::MAX_ANTENNAS;
+
+// A read window for one antenna, in tenths of a second like the global read window.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all="snake_case")]
+pub struct AntennaReadWindow {
+    // Antenna as it's reported after any antenna mapping.
+    antenna: u16,
+    read_window: u8,
+}
+
+impl AntennaReadWindow {
+    pub fn new(
+        antenna: u16,
+        read_window: u8,
+    ) -> AntennaReadWindow {
+        AntennaReadWindow {
+            antenna,
+            read_window,
+        }
+    }
+
+    pub fn antenna(&self) -> u16 {
+        self.antenna
+    }
+
+    pub fn read_window(&self) -> u8 {
+        self.read_window
+    }
+}
+
+// Read windows that override the global read window for a reader. An antenna without its own window uses
+// the reader's, and a reader without one uses the global window.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all="snake_case")]
+pub struct ReadWindowConfig {
+    #[serde(default)]
+    read_window: Option<u8>,
+    #[serde(default)]
+    antennas: Vec<AntennaReadWindow>,
+}
+
+impl ReadWindowConfig {
+    pub fn new(
+        read_window: Option<u8>,
+        antennas: Vec<AntennaReadWindow>,
+    ) -> ReadWindowConfig {
+        ReadWindowConfig {
+            read_window,
+            antennas,
+        }
+    }
+
+    pub fn read_window(&self) -> Option<u8> {
+        self.read_window
+    }
+
+    pub fn antennas(&self) -> &[AntennaReadWindow] {
+        &self.antennas
+    }
+
+    pub fn antenna_read_window(&self, antenna: u16) -> Option<u8> {
+        self.antennas.iter().find(|a| a.antenna == antenna).map(|a| a.read_window)
+    }
+
+    pub fn set_read_window(&mut self, read_window: Option<u8>) {
+        self.read_window = read_window;
+    }
+
+    pub fn set_antenna_read_window(&mut self, antenna: u16, read_window: Option<u8>) {
+        self.antennas.retain(|a| a.antenna != antenna);
+        if let Some(read_window) = read_window {
+            self.antennas.push(AntennaReadWindow::new(antenna, read_window));
+            self.antennas.sort_by_key(|a| a.antenna);
+        }
+    }
+
+    // The longest read window any antenna on the reader might use.
+    pub fn max_window(&self, global: u8) -> u8 {
+        self.antennas.iter().map(|a| a.read_window).fold(self.read_window.unwrap_or(global), u8::max)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.read_window == Some(0) {
+            return Err(String::from("read window must be more than 0"))
+        }
+        for (ix, antenna) in self.antennas.iter().enumerate() {
+            if antenna.antenna == 0 || antenna.antenna as usize > MAX_ANTENNAS {
+                return Err(format!("antenna {} is not a valid antenna", antenna.antenna))
+            }
+            if antenna.read_window == 0 {
+                return Err(format!("read window for antenna {} must be more than 0", antenna.antenna))
+            }
+            if self.antennas[..ix].iter().any(|other| other.antenna == antenna.antenna) {
+                return Err(format!("antenna {} has more than one read window", antenna.antenna))
+            }
+        }
+        Ok(())
+    }
+}
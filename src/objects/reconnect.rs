@@ -0,0 +1,114 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use serde::{Serialize, Deserialize};
+
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 30;
+pub const DEFAULT_INITIAL_DELAY_MILLISECONDS: u32 = 1000;
+pub const DEFAULT_BACKOFF_MULTIPLIER: f64 = 1.0;
+pub const DEFAULT_MAX_DELAY_MILLISECONDS: u32 = 1000;
+
+pub const MAX_BACKOFF_MULTIPLIER: f64 = 10.0;
+pub const MAX_DELAY_MILLISECONDS: u32 = 3600000;
+
+// How we try to get a reader back after its connection is lost. Attempts start right away and
+// each failed one waits a little longer than the last before the next, up to the cap.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all="snake_case", default)]
+pub struct ReconnectPolicy {
+    // Attempts to make before giving up, 0 keeps trying until the reader is stopped.
+    max_attempts: u32,
+    // Wait after the first failed attempt.
+    initial_delay_milliseconds: u32,
+    // What each wait is multiplied by for the next one, 1 keeps the wait the same.
+    backoff_multiplier: f64,
+    // Longest we'll ever wait between attempts.
+    max_delay_milliseconds: u32,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        ReconnectPolicy {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            initial_delay_milliseconds: DEFAULT_INITIAL_DELAY_MILLISECONDS,
+            backoff_multiplier: DEFAULT_BACKOFF_MULTIPLIER,
+            max_delay_milliseconds: DEFAULT_MAX_DELAY_MILLISECONDS,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    pub fn new(
+        max_attempts: u32,
+        initial_delay_milliseconds: u32,
+        backoff_multiplier: f64,
+        max_delay_milliseconds: u32,
+    ) -> ReconnectPolicy {
+        ReconnectPolicy {
+            max_attempts,
+            initial_delay_milliseconds,
+            backoff_multiplier,
+            max_delay_milliseconds,
+        }
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    pub fn initial_delay_milliseconds(&self) -> u32 {
+        self.initial_delay_milliseconds
+    }
+
+    pub fn backoff_multiplier(&self) -> f64 {
+        self.backoff_multiplier
+    }
+
+    pub fn max_delay_milliseconds(&self) -> u32 {
+        self.max_delay_milliseconds
+    }
+
+    // Whether we've made every attempt we're allowed to.
+    pub fn gives_up(&self, attempt: u32) -> bool {
+        self.max_attempts != 0 && attempt > self.max_attempts
+    }
+
+    // How long to wait after the given attempt fails before making the next one.
+    pub fn delay(&self, attempt: u32) -> u64 {
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let delay = self.initial_delay_milliseconds as f64 * self.backoff_multiplier.powi(exponent);
+        if delay.is_finite() && delay < self.max_delay_milliseconds as f64 {
+            delay as u64
+        } else {
+            self.max_delay_milliseconds as u64
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.backoff_multiplier.is_finite() || self.backoff_multiplier < 1.0 || self.backoff_multiplier > MAX_BACKOFF_MULTIPLIER {
+            return Err(format!("backoff multiplier must be between 1 and {MAX_BACKOFF_MULTIPLIER}"))
+        }
+        if self.max_delay_milliseconds > MAX_DELAY_MILLISECONDS {
+            return Err(format!("max delay can't be more than {MAX_DELAY_MILLISECONDS} milliseconds"))
+        }
+        if self.initial_delay_milliseconds > self.max_delay_milliseconds {
+            return Err(String::from("initial delay can't be more than the max delay"))
+        }
+        Ok(())
+    }
+}
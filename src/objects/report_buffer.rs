@@ -0,0 +1,41 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use serde::{Serialize, Deserialize};
+
+// Whether a reader keeps its tag reports while the connection to us is down, so we can collect them once
+// we're connected again.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all="snake_case")]
+pub struct ReportBufferConfig {
+    // Leave the reader reading when the connection is lost, and ask it for the reports it held on reconnect.
+    #[serde(default)]
+    enabled: bool,
+}
+
+impl ReportBufferConfig {
+    pub fn new(enabled: bool) -> ReportBufferConfig {
+        ReportBufferConfig {
+            enabled,
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+}
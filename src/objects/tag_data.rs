@@ -0,0 +1,80 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+
+use serde::{Serialize, Deserialize};
+
+// Most words of TID or user memory we'll ask a reader for. Readers can refuse large reads and
+// every extra word slows down singulation.
+pub const MAX_MEMORY_WORDS: u16 = 32;
+
+// Extra tag data for a reader to report with each read.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all="snake_case")]
+pub struct TagDataConfig {
+    // 16 bit words of TID memory to read, 0 doesn't read the TID.
+    #[serde(default)]
+    tid_words: u16,
+    // 16 bit words of user memory to read, 0 doesn't read user memory.
+    #[serde(default)]
+    user_memory_words: u16,
+    // Report channel index, tag seen count and last seen time.
+    #[serde(default)]
+    report_metadata: bool,
+}
+
+impl TagDataConfig {
+    pub fn new(
+        tid_words: u16,
+        user_memory_words: u16,
+        report_metadata: bool,
+    ) -> TagDataConfig {
+        TagDataConfig {
+            tid_words,
+            user_memory_words,
+            report_metadata,
+        }
+    }
+
+    pub fn tid_words(&self) -> u16 {
+        self.tid_words
+    }
+
+    pub fn user_memory_words(&self) -> u16 {
+        self.user_memory_words
+    }
+
+    pub fn report_metadata(&self) -> bool {
+        self.report_metadata
+    }
+
+    // Memory reads need an AccessSpec on the reader.
+    pub fn reads_memory(&self) -> bool {
+        self.tid_words > 0 || self.user_memory_words > 0
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.tid_words > MAX_MEMORY_WORDS {
+            return Err(format!("tid words must be {MAX_MEMORY_WORDS} or less"))
+        }
+        if self.user_memory_words > MAX_MEMORY_WORDS {
+            return Err(format!("user memory words must be {MAX_MEMORY_WORDS} or less"))
+        }
+        Ok(())
+    }
+}
@@ -21,16 +21,27 @@ use std::{io::Write, net::TcpStream, sync::{self, Arc, Mutex}, thread::JoinHandl
 use reconnector::Reconnector;
 use serde::{Deserialize, Serialize};
 
-use crate::{control::{self, socket::MAX_CONNECTED, sound::{SoundNotifier, SoundType}}, database::{sqlite, DBError}, notifier, processor};
+use crate::{control::{self, socket::MAX_CONNECTED, sound::{SoundNotifier, SoundType}}, database::{sqlite, DBError}, llrp::encoder::VERSION_1_0_1, notifier, objects::{profile::ProfileConfig, read, read_window::ReadWindowConfig}, processor};
 
+pub mod generic;
 pub mod zebra;
+pub mod impinj;
 pub mod auto_connect;
 pub mod reconnector;
 pub mod helpers;
+pub mod capabilities;
+pub mod errors;
+pub mod listener;
+pub mod capture;
+pub mod simulator;
+pub mod gpio;
+pub mod clock;
+pub mod stats;
 
 pub const READER_KIND_ZEBRA: &str = "ZEBRA";
 pub const READER_KIND_RFID: &str = "RFID";
 pub const READER_KIND_IMPINJ: &str = "IMPINJ";
+pub const READER_KIND_LLRP: &str = "LLRP";
 
 pub const AUTO_CONNECT_TRUE: u8 = 1;
 pub const AUTO_CONNECT_FALSE: u8 = 0;
@@ -44,7 +55,12 @@ pub const ANTENNA_STATUS_CONNECTED: u8 = 2;
 pub enum ReaderStatus {
     Disconnected,
     Errored,
+    ConnectingGetSupportedVersion,
+    ConnectingSetProtocolVersion,
     ConnectingKeepalive,
+    ConnectingGetReaderCapabilities,
+    ConnectingGetReport,
+    ConnectingEnableExtensions,
     ConnectingPurgeTags,
     ConnectingSetNoFilter,
     ConnectingSetReaderConfig,
@@ -52,7 +68,10 @@ pub enum ReaderStatus {
     ConnectingGetReaderConfig,
     ConnectingDeleteAccessSpec,
     ConnectingDeleteRospec,
+    ConnectingSetClock,
     ConnectingAddRospec,
+    ConnectingAddAccessSpec,
+    ConnectingEnableAccessSpec,
     ConnectingEnableRospec,
     ConnectingStartRospec,
     Connected,
@@ -76,6 +95,12 @@ pub struct Reader {
 
     #[serde(skip)]
     pub antennas: Arc<Mutex<[u8;MAX_ANTENNAS]>>,
+    #[serde(skip)]
+    pub capabilities: Arc<Mutex<Option<capabilities::ReaderCapabilities>>>,
+    #[serde(skip)]
+    pub errors: Arc<Mutex<errors::ErrorLog>>,
+    #[serde(skip)]
+    pub capture: Arc<Mutex<capture::Capture>>,
 
     #[serde(skip)]
     pub socket: sync::Mutex<Option<TcpStream>>,
@@ -83,6 +108,24 @@ pub struct Reader {
     pub keepalive: Arc<sync::Mutex<bool>>,
     #[serde(skip)]
     pub msg_id: Arc<sync::Mutex<u32>>,
+    // LLRP version in use on the connection to the reader.
+    #[serde(skip)]
+    pub llrp_version: Arc<sync::Mutex<u8>>,
+    // Offset between the reader's clock and ours.
+    #[serde(skip)]
+    pub clock: Arc<sync::Mutex<clock::ClockTracker>>,
+    // Reading profiles saved while connected that the reader hasn't switched to yet.
+    #[serde(skip)]
+    pub profile_change: Arc<sync::Mutex<Option<ProfileConfig>>>,
+    // Read rates, keepalives and reconnects, kept across reconnects.
+    #[serde(skip)]
+    pub stats: Arc<sync::Mutex<stats::StatsTracker>>,
+    // Reconnect attempt in progress, 0 when we aren't trying to reconnect.
+    #[serde(skip)]
+    pub reconnect_attempt: Arc<sync::Mutex<u32>>,
+    // Read windows that override the global read window, changes are used right away.
+    #[serde(skip)]
+    pub read_windows: Arc<sync::Mutex<ReadWindowConfig>>,
 
     #[serde(skip)]
     pub status: Arc<sync::Mutex<ReaderStatus>>,
@@ -107,9 +150,18 @@ impl Clone for Reader {
             port: self.port.clone(),
             auto_connect: self.auto_connect.clone(),
             antennas: self.antennas.clone(),
+            capabilities: self.capabilities.clone(),
+            errors: self.errors.clone(),
+            capture: self.capture.clone(),
             socket: Mutex::new(None),
             keepalive: self.keepalive.clone(),
             msg_id: self.msg_id.clone(),
+            llrp_version: self.llrp_version.clone(),
+            clock: self.clock.clone(),
+            profile_change: self.profile_change.clone(),
+            stats: self.stats.clone(),
+            reconnect_attempt: self.reconnect_attempt.clone(),
+            read_windows: self.read_windows.clone(),
             status: self.status.clone(),
             status_retries: self.status_retries.clone(),
             control_sockets: self.control_sockets.clone(),
@@ -137,12 +189,21 @@ impl Reader {
             socket: Mutex::new(None),
             keepalive: Arc::new(Mutex::new(true)),
             msg_id: Arc::new(Mutex::new(0)),
+            llrp_version: Arc::new(Mutex::new(VERSION_1_0_1)),
+            clock: Arc::new(Mutex::new(clock::ClockTracker::new())),
+            profile_change: Arc::new(Mutex::new(None)),
+            stats: Arc::new(Mutex::new(stats::StatsTracker::new())),
+            reconnect_attempt: Arc::new(Mutex::new(0)),
+            read_windows: Arc::new(Mutex::new(ReadWindowConfig::default())),
             status: Arc::new(Mutex::new(ReaderStatus::Disconnected)),
             status_retries: Arc::new(Mutex::new(0)),
             auto_connect,
             control_sockets: Arc::new(Mutex::new(Default::default())),
             read_repeaters: Arc::new(Mutex::new(Default::default())),
             antennas: Arc::new(Mutex::new([0;MAX_ANTENNAS])),
+            capabilities: Arc::new(Mutex::new(None)),
+            errors: Arc::new(Mutex::new(errors::ErrorLog::new())),
+            capture: Arc::new(Mutex::new(capture::Capture::new())),
             readers: Arc::new(Mutex::new(Vec::new()))
         }
     }
@@ -156,10 +217,9 @@ impl Reader {
         auto_connect: u8,
     ) -> Result<Reader, DBError> {
         match kind.as_str() {
-            READER_KIND_ZEBRA => {
+            READER_KIND_ZEBRA | READER_KIND_IMPINJ | READER_KIND_LLRP => {
                 return Ok(Reader::new_internal(id, kind, nickname, ip_address, port, auto_connect))
             },
-            READER_KIND_IMPINJ => return Err(DBError::DataRetrievalError(String::from("not yet implemented"))),
             READER_KIND_RFID => return Err(DBError::DataRetrievalError(String::from("not yet implemented"))),
             _ => return Err(DBError::DataRetrievalError(String::from("unknown reader kind specified")))
         }
@@ -177,7 +237,7 @@ impl Reader {
         readers: Arc<Mutex<Vec<Reader>>>,
     ) -> Result<Reader, DBError> {
         match kind.as_str() {
-            READER_KIND_ZEBRA => {
+            READER_KIND_ZEBRA | READER_KIND_IMPINJ | READER_KIND_LLRP => {
                 Ok(Reader {
                     id,
                     kind,
@@ -187,16 +247,24 @@ impl Reader {
                     socket: sync::Mutex::new(None),
                     keepalive: Arc::new(sync::Mutex::new(true)),
                     msg_id: Arc::new(sync::Mutex::new(0)),
+                    llrp_version: Arc::new(sync::Mutex::new(VERSION_1_0_1)),
+                    clock: Arc::new(sync::Mutex::new(clock::ClockTracker::new())),
+                    profile_change: Arc::new(sync::Mutex::new(None)),
+                    stats: Arc::new(sync::Mutex::new(stats::StatsTracker::new())),
+                    reconnect_attempt: Arc::new(sync::Mutex::new(0)),
+                    read_windows: Arc::new(sync::Mutex::new(ReadWindowConfig::default())),
                     status: Arc::new(sync::Mutex::new(ReaderStatus::Disconnected)),
                     status_retries: Arc::new(Mutex::new(0)),
                     auto_connect,
                     control_sockets,
                     read_repeaters,
                     antennas: Arc::new(Mutex::new([0;MAX_ANTENNAS])),
+                    capabilities: Arc::new(Mutex::new(None)),
+                    errors: Arc::new(Mutex::new(errors::ErrorLog::new())),
+                    capture: Arc::new(Mutex::new(capture::Capture::new())),
                     readers
                 })
             },
-            READER_KIND_IMPINJ => return Err(DBError::DataRetrievalError(String::from("not yet implemented"))),
             READER_KIND_RFID => return Err(DBError::DataRetrievalError(String::from("not yet implemented"))),
             _ => return Err(DBError::DataRetrievalError(String::from("unknown reader kind specified")))
         }
@@ -226,6 +294,11 @@ impl Reader {
         self.kind.as_str()
     }
 
+    // Only Zebra readers have a way for us to set their clock, see Extensions::can_set_clock.
+    pub fn can_set_clock(&self) -> bool {
+        self.kind == READER_KIND_ZEBRA
+    }
+
     pub fn set_ip_address(&mut self, ip_address: String) {
         self.ip_address = ip_address
     }
@@ -250,6 +323,13 @@ impl Reader {
         self.auto_connect
     }
 
+    pub fn llrp_version(&self) -> u8 {
+        match self.llrp_version.lock() {
+            Ok(version) => *version,
+            Err(_) => VERSION_1_0_1,
+        }
+    }
+
     pub fn set_control_sockets(&mut self, c_sockets: Arc<Mutex<[Option<TcpStream>;MAX_CONNECTED + 1]>>) {
         self.control_sockets = c_sockets
     }
@@ -281,8 +361,34 @@ impl Reader {
         self.is_connected()
     }
 
+    // Has a reader that's connected, or on its way there, switch to the profiles given once it's reading.
+    // Readers that aren't connected pick up the saved profiles the next time they connect.
+    pub fn change_profiles(&self, config: ProfileConfig) {
+        let mut running = false;
+        if let Ok(stat) = self.status.lock() {
+            running = *stat != ReaderStatus::Disconnected && *stat != ReaderStatus::Errored;
+        }
+        if running {
+            if let Ok(mut change) = self.profile_change.lock() {
+                *change = Some(config);
+            }
+        }
+    }
+
+    // The reconnect attempt we're on, if we're trying to get the reader back.
+    pub fn reconnecting(&self) -> Option<u32> {
+        match self.reconnect_attempt.lock() {
+            Ok(attempt) if *attempt > 0 => Some(*attempt),
+            _ => None,
+        }
+    }
+
     pub fn disconnect(&mut self) -> Result<(), &'static str> {
         _ = self.stop();
+        // disconnecting on purpose stops any attempts to reconnect
+        if let Ok(mut attempt) = self.reconnect_attempt.lock() {
+            *attempt = 0;
+        }
         if let Ok(mut keepalive) = self.keepalive.lock() {
             *keepalive = false;
         };
@@ -334,6 +440,12 @@ impl Reader {
             READER_KIND_ZEBRA => {
                 zebra::connect(self, sqlite, control, read_saver, sound, reconnector, notifier)
             }
+            READER_KIND_IMPINJ => {
+                impinj::connect(self, sqlite, control, read_saver, sound, reconnector, notifier)
+            }
+            READER_KIND_LLRP => {
+                generic::connect(self, sqlite, control, read_saver, sound, reconnector, notifier)
+            }
             _ => {
                 sound.notify_custom(SoundType::Malfunction);
                 Err("reader type not supported")
@@ -341,10 +453,61 @@ impl Reader {
         }
     }
 
-    pub fn stop(&mut self) -> Result<(), &'static str>  {
+    // Starts reading on a connection the reader opened to us.
+    pub fn start(
+        &mut self,
+        tcp_stream: TcpStream,
+        sqlite: &Arc<Mutex<sqlite::SQLite>>,
+        control: &Arc<Mutex<control::Control>>,
+        read_saver: &Arc<processor::ReadSaver>,
+        sound: Arc<SoundNotifier>,
+        reconnector: Option<Reconnector>,
+        notifier: notifier::Notifier,
+    ) -> Result<JoinHandle<()>, &'static str> {
         match self.kind.as_str() {
             READER_KIND_ZEBRA => {
-                zebra::stop_reader(self)
+                zebra::start(self, tcp_stream, sqlite, control, read_saver, sound, reconnector, notifier)
+            }
+            READER_KIND_IMPINJ => {
+                impinj::start(self, tcp_stream, sqlite, control, read_saver, sound, reconnector, notifier)
+            }
+            READER_KIND_LLRP => {
+                generic::start(self, tcp_stream, sqlite, control, read_saver, sound, reconnector, notifier)
+            }
+            _ => {
+                sound.notify_custom(SoundType::Malfunction);
+                Err("reader type not supported")
+            }
+        }
+    }
+
+    // Feeds a capture taken from this reader back through tag processing.
+    pub fn replay(
+        &self,
+        records: &[capture::Record],
+        sqlite: &sqlite::SQLite,
+        control: &control::Control,
+    ) -> Result<Vec<read::Read>, &'static str> {
+        match self.kind.as_str() {
+            READER_KIND_ZEBRA => {
+                zebra::replay(self, records, sqlite, control)
+            }
+            READER_KIND_IMPINJ => {
+                impinj::replay(self, records, sqlite, control)
+            }
+            READER_KIND_LLRP => {
+                generic::replay(self, records, sqlite, control)
+            }
+            _ => {
+                Err("reader type not supported")
+            }
+        }
+    }
+
+    pub fn stop(&mut self) -> Result<(), &'static str>  {
+        match self.kind.as_str() {
+            READER_KIND_ZEBRA | READER_KIND_IMPINJ | READER_KIND_LLRP => {
+                generic::stop_reader(self)
             }
             _ => {
                 Err("reader type not supported")
@@ -0,0 +1,131 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use serde::{Deserialize, Serialize};
+
+use crate::llrp::{decoder::{self, DecodeError, Fields}, parameter_types};
+
+#[cfg(test)]
+pub mod test;
+
+// What the reader told us it can do in its GET_READER_CAPABILITIES_RESPONSE.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct ReaderCapabilities {
+    pub manufacturer: u32,
+    pub model: u32,
+    pub firmware: String,
+    pub max_antennas: u16,
+    pub can_set_antenna_properties: bool,
+    pub has_utc_clock: bool,
+    pub transmit_power: Vec<TransmitPowerEntry>,
+    pub receive_sensitivity: Vec<ReceiveSensitivityEntry>,
+    pub max_receive_sensitivity: Option<i16>,
+    pub country_code: u16,
+    pub communications_standard: u16,
+    pub region: String,
+    // Number of GPI and GPO ports, 0 if the reader didn't tell us.
+    #[serde(default)]
+    pub gpis: u16,
+    #[serde(default)]
+    pub gpos: u16,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct TransmitPowerEntry {
+    pub index: u16,
+    pub dbm: f32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct ReceiveSensitivityEntry {
+    pub index: u16,
+    pub db: i16,
+}
+
+impl ReaderCapabilities {
+    // The index of the entry in the transmit power table closest to the power requested.
+    pub fn transmit_power_index(&self, dbm: f32) -> Option<u16> {
+        self.transmit_power.iter()
+            .min_by(|a, b| (a.dbm - dbm).abs().total_cmp(&(b.dbm - dbm).abs()))
+            .map(|entry| entry.index)
+    }
+}
+
+// Parses a GET_READER_CAPABILITIES_RESPONSE.
+pub fn from_response(msg: &decoder::Message) -> Result<ReaderCapabilities, DecodeError> {
+    let mut output = ReaderCapabilities::default();
+    let general = match msg.find(parameter_types::GENERAL_DEVICE_CAPABILITIES) {
+        Some(param) => param,
+        None => return Err(DecodeError::MissingParameter(String::from("general device capabilities"))),
+    };
+    // bytes 0 and 1 are the max number of antennas
+    // byte 2 holds the can set antenna properties bit (0x80) and the has utc clock bit (0x40)
+    // bytes 4-7 are the manufacturer (IANA private enterprise number), bytes 8-11 the model
+    // bytes 12 and 13 are the length of the firmware version string that follows
+    output.max_antennas = general.u16_at(0)?;
+    let flags = general.u8_at(2)?;
+    output.can_set_antenna_properties = flags & 0x80 != 0;
+    output.has_utc_clock = flags & 0x40 != 0;
+    output.manufacturer = general.u32_at(4)?;
+    output.model = general.u32_at(8)?;
+    let count = general.u16_at(12)? as usize;
+    output.firmware = String::from_utf8_lossy(general.bytes_at(14, count)?).to_string();
+    for entry in general.find_all(parameter_types::RECEIVE_SENSITIVITY_TABLE_ENTRY) {
+        output.receive_sensitivity.push(ReceiveSensitivityEntry {
+            index: entry.u16_at(0)?,
+            db: entry.u16_at(2)? as i16,
+        });
+    }
+    if let Some(max) = general.find(parameter_types::MAXIMUM_RECEIVE_SENSITIVITY) {
+        output.max_receive_sensitivity = Some(max.u16_at(0)? as i16);
+    }
+    if let Some(gpio) = general.find(parameter_types::GPIO_CAPABILITIES) {
+        output.gpis = gpio.u16_at(0)?;
+        output.gpos = gpio.u16_at(2)?;
+    }
+    if let Some(regulatory) = msg.find(parameter_types::REGULATORY_CAPABILITIES) {
+        output.country_code = regulatory.u16_at(0)?;
+        output.communications_standard = regulatory.u16_at(2)?;
+        output.region = String::from(region_name(output.communications_standard));
+        if let Some(band) = regulatory.find(parameter_types::UHF_BAND_CAPABILITIES) {
+            // transmit power values are reported in hundredths of a dBm
+            for entry in band.find_all(parameter_types::TRANSMIT_POWER_LEVEL_TABLE_ENTRY) {
+                output.transmit_power.push(TransmitPowerEntry {
+                    index: entry.u16_at(0)?,
+                    dbm: entry.u16_at(2)? as i16 as f32 / 100.0,
+                });
+            }
+        }
+    }
+    Ok(output)
+}
+
+pub fn region_name(communications_standard: u16) -> &'static str {
+    match communications_standard {
+        1 => "US FCC Part 15",
+        2 => "ETSI EN 302 208",
+        3 => "ETSI EN 300 220",
+        4 => "Australia LIPD 1W",
+        5 => "Australia LIPD 4W",
+        6 => "Japan ARIB STD-T89",
+        7 => "Hong Kong OFTA 1049",
+        8 => "Taiwan DGT LP0002",
+        9 => "Korea MIC Article 5-2",
+        _ => "Unspecified",
+    }
+}
@@ -0,0 +1,106 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::llrp::decoder::{decode_message, DecodeError};
+
+use super::{from_response, region_name, ReaderCapabilities, ReceiveSensitivityEntry, TransmitPowerEntry};
+
+// GET_READER_CAPABILITIES_RESPONSE, four antennas, FCC, three transmit power levels
+const CAPABILITIES: [u8; 105] = [
+    0x04, 0x0B, 0x00, 0x00, 0x00, 0x69, 0x00, 0x00, 0x00, 0x01,
+    // LLRPStatus, success
+    0x01, 0x1F, 0x00, 0x08, 0x00, 0x00, 0x00, 0x00,
+    // GeneralDeviceCapabilities, length 46
+    0x00, 0x89, 0x00, 0x2E,
+        0x00, 0x04, 0xC0, 0x00,
+        0x00, 0x00, 0x00, 0xA1,
+        0x00, 0x00, 0x25, 0x80,
+        0x00, 0x06, b'3', b'.', b'9', b'.', b'1', b'6',
+        // ReceiveSensitivityTableEntry
+        0x00, 0x8B, 0x00, 0x08, 0x00, 0x01, 0x00, 0x00,
+        0x00, 0x8B, 0x00, 0x08, 0x00, 0x02, 0x00, 0x0A,
+        // MaximumReceiveSensitivity, -80
+        0x01, 0x6B, 0x00, 0x06, 0xFF, 0xB0,
+    // RegulatoryCapabilities, length 41
+    0x00, 0x8F, 0x00, 0x29,
+        0x03, 0x48, 0x00, 0x01,
+        // UHFBandCapabilities, length 33
+        0x00, 0x90, 0x00, 0x21,
+            // TransmitPowerLevelTableEntry
+            0x00, 0x91, 0x00, 0x08, 0x00, 0x01, 0x03, 0xE8,
+            0x00, 0x91, 0x00, 0x08, 0x00, 0x02, 0x0A, 0x8C,
+            0x00, 0x91, 0x00, 0x08, 0x00, 0x03, 0x0B, 0xB8,
+            // FrequencyInformation, hopping
+            0x00, 0x92, 0x00, 0x05, 0x80,
+];
+
+#[test]
+fn test_from_response() {
+    let msg = decode_message(&CAPABILITIES).unwrap();
+    let caps = from_response(&msg).unwrap();
+    assert_eq!(4, caps.max_antennas);
+    assert!(caps.can_set_antenna_properties);
+    assert!(caps.has_utc_clock);
+    assert_eq!(161, caps.manufacturer);
+    assert_eq!(9600, caps.model);
+    assert_eq!("3.9.16", caps.firmware);
+    assert_eq!(vec![
+        ReceiveSensitivityEntry { index: 1, db: 0 },
+        ReceiveSensitivityEntry { index: 2, db: 10 },
+    ], caps.receive_sensitivity);
+    assert_eq!(Some(-80), caps.max_receive_sensitivity);
+    assert_eq!(840, caps.country_code);
+    assert_eq!(1, caps.communications_standard);
+    assert_eq!("US FCC Part 15", caps.region);
+    assert_eq!(vec![
+        TransmitPowerEntry { index: 1, dbm: 10.0 },
+        TransmitPowerEntry { index: 2, dbm: 27.0 },
+        TransmitPowerEntry { index: 3, dbm: 30.0 },
+    ], caps.transmit_power);
+}
+
+#[test]
+fn test_from_response_missing_general() {
+    // only the header and the LLRPStatus
+    let mut buf = CAPABILITIES[..18].to_vec();
+    buf[5] = 18;
+    let msg = decode_message(&buf).unwrap();
+    match from_response(&msg) {
+        Err(DecodeError::MissingParameter(_)) => {},
+        other => panic!("expected missing parameter, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_transmit_power_index() {
+    let caps = from_response(&decode_message(&CAPABILITIES).unwrap()).unwrap();
+    assert_eq!(Some(1), caps.transmit_power_index(10.0));
+    assert_eq!(Some(1), caps.transmit_power_index(0.0));
+    assert_eq!(Some(2), caps.transmit_power_index(25.0));
+    assert_eq!(Some(3), caps.transmit_power_index(29.0));
+    assert_eq!(Some(3), caps.transmit_power_index(33.0));
+    assert_eq!(None, ReaderCapabilities::default().transmit_power_index(20.0));
+}
+
+#[test]
+fn test_region_name() {
+    assert_eq!("Unspecified", region_name(0));
+    assert_eq!("ETSI EN 302 208", region_name(2));
+    assert_eq!("Korea MIC Article 5-2", region_name(9));
+    assert_eq!("Unspecified", region_name(200));
+}
@@ -0,0 +1,208 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+
+use std::{env, fs::{self, File, OpenOptions}, io::{BufRead, BufReader, Write}, path::{Path, PathBuf}, sync::{Arc, Mutex}, time::{SystemTime, UNIX_EPOCH}};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::{control, database::{sqlite, Database}, objects::read};
+
+use super::{Reader, AUTO_CONNECT_FALSE, READER_KIND_LLRP};
+
+#[cfg(test)]
+pub mod test;
+
+// Directory captures are written to.
+pub const CAPTURE_PATH_ENV: &str = "PORTAL_CAPTURE_PATH";
+pub const DEFAULT_CAPTURE_PATH: &str = "./captures";
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all="snake_case")]
+pub enum Direction {
+    Received,
+    Sent,
+}
+
+// A single chunk of LLRP traffic, captures are written as one JSON record per line.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Record {
+    // time since 00:00:00 UTC Jan 1 1970 in microseconds
+    pub time: u64,
+    pub direction: Direction,
+    pub reader: String,
+    // the raw bytes as hex
+    pub data: String,
+}
+
+impl Record {
+    pub fn new(time: u64, direction: Direction, reader: &str, data: &[u8]) -> Record {
+        Record {
+            time,
+            direction,
+            reader: String::from(reader),
+            data: data.iter().map(|b| format!("{:02x}", b)).collect(),
+        }
+    }
+
+    pub fn bytes(&self) -> Result<Vec<u8>, &'static str> {
+        if self.data.len() % 2 == 1 {
+            return Err("odd number of hex digits in capture data")
+        }
+        let mut output: Vec<u8> = Vec::with_capacity(self.data.len() / 2);
+        for ix in (0..self.data.len()).step_by(2) {
+            match self.data.get(ix..ix + 2).and_then(|digits| u8::from_str_radix(digits, 16).ok()) {
+                Some(b) => output.push(b),
+                None => return Err("invalid hex digits in capture data"),
+            }
+        }
+        Ok(output)
+    }
+}
+
+// A capture of everything sent to and received from a reader, started and stopped while the reader is running.
+// The file is kept open while the capture is running.
+#[derive(Debug, Default)]
+pub struct Capture {
+    reader_name: String,
+    path: Option<PathBuf>,
+    file: Option<File>,
+}
+
+impl Capture {
+    pub fn new() -> Capture {
+        Capture::default()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.file.is_some()
+    }
+
+    pub fn path(&self) -> Option<String> {
+        self.path.as_ref().map(|p| p.display().to_string())
+    }
+
+    // Starts a new capture file in the capture directory.
+    pub fn start(&mut self, reader_name: &str) -> Result<String, String> {
+        let dir = match env::var(CAPTURE_PATH_ENV) {
+            Ok(val) if !val.is_empty() => val,
+            _ => String::from(DEFAULT_CAPTURE_PATH),
+        };
+        if let Err(e) = fs::create_dir_all(&dir) {
+            return Err(format!("unable to create capture directory {dir}: {e}"))
+        }
+        let date_time: DateTime<Local> = Local::now();
+        let name: String = reader_name.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect();
+        let path = Path::new(&dir).join(format!("{name}_{}.jsonl", date_time.format("%Y%m%d_%H%M%S")));
+        self.start_file(reader_name, path)
+    }
+
+    pub fn start_file(&mut self, reader_name: &str, path: PathBuf) -> Result<String, String> {
+        let file = match OpenOptions::new().append(true).create(true).open(&path) {
+            Ok(file) => file,
+            Err(e) => return Err(format!("unable to open capture file {}: {e}", path.display())),
+        };
+        self.reader_name = String::from(reader_name);
+        self.file = Some(file);
+        self.path = Some(path);
+        Ok(self.path().unwrap_or_default())
+    }
+
+    // Stops the capture, the path of the last file is kept.
+    pub fn stop(&mut self) {
+        if let Some(mut file) = self.file.take() {
+            if let Err(e) = file.flush() {
+                println!("Error flushing capture file. {e}");
+            }
+        }
+    }
+
+    pub fn record(&mut self, direction: Direction, data: &[u8]) {
+        let file = match self.file.as_mut() {
+            Some(file) => file,
+            None => return,
+        };
+        let time = match SystemTime::now().duration_since(UNIX_EPOCH) {
+            Ok(v) => v.as_micros() as u64,
+            Err(_) => 0,
+        };
+        let record = Record::new(time, direction, &self.reader_name, data);
+        let result = match serde_json::to_string(&record) {
+            Ok(line) => writeln!(file, "{line}").map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        // a capture that can't be written to isn't worth keeping running
+        if let Err(e) = result {
+            println!("Error writing to capture file, stopping capture. {e}");
+            self.stop();
+        }
+    }
+}
+
+// Records the data if the reader is capturing.
+pub fn record(capture: &Arc<Mutex<Capture>>, direction: Direction, data: &[u8]) {
+    if let Ok(mut capture) = capture.lock() {
+        capture.record(direction, data);
+    };
+}
+
+pub fn load(path: &str) -> Result<Vec<Record>, String> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) => return Err(format!("unable to open capture file {path}: {e}")),
+    };
+    let mut output: Vec<Record> = Vec::new();
+    for (ix, line) in BufReader::new(file).lines().enumerate() {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => return Err(format!("unable to read capture file {path}: {e}")),
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<Record>(&line) {
+            Ok(record) => output.push(record),
+            Err(e) => return Err(format!("invalid record on line {}: {e}", ix + 1)),
+        }
+    }
+    Ok(output)
+}
+
+// Replays a capture using the settings saved for the reader it was taken from, if we know about that
+// reader, and the read window and chip type the portal is set to. Reads aren't saved.
+pub fn replay(path: &str, sqlite: &sqlite::SQLite, control: &control::Control) -> Result<Vec<read::Read>, String> {
+    let records = load(path)?;
+    let reader_name = match records.first() {
+        Some(record) => record.reader.clone(),
+        None => return Ok(Vec::new()),
+    };
+    let reader = match sqlite.get_readers() {
+        Ok(readers) => readers.into_iter().find(|r| r.nickname() == reader_name),
+        Err(e) => return Err(format!("unable to get readers from database: {e}")),
+    };
+    let reader = match reader {
+        Some(reader) => reader,
+        None => {
+            println!("Reader {reader_name} not found, replaying as a generic LLRP reader without any saved settings.");
+            Reader::new_internal(0, String::from(READER_KIND_LLRP), reader_name, String::new(), 0, AUTO_CONNECT_FALSE)
+        }
+    };
+    let records: Vec<Record> = records.into_iter().filter(|r| r.reader == reader.nickname()).collect();
+    reader.replay(&records, sqlite, control).map_err(String::from)
+}
@@ -0,0 +1,83 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+
+use std::{env, fs, sync::{Arc, Mutex}};
+
+use super::{load, record, Capture, Direction, Record};
+
+#[test]
+fn test_record_bytes() {
+    let data = [0x04, 0x3D, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0xFF];
+    let rec = Record::new(1700000000000000, Direction::Received, "Zebra 1", &data);
+    assert_eq!("043d0000000a000000ff", rec.data);
+    assert_eq!(data.to_vec(), rec.bytes().unwrap());
+    let line = serde_json::to_string(&rec).unwrap();
+    assert!(line.contains("\"direction\":\"received\""));
+    assert_eq!(rec, serde_json::from_str::<Record>(&line).unwrap());
+    let mut bad = rec.clone();
+    bad.data = String::from("043");
+    assert!(bad.bytes().is_err());
+    bad.data = String::from("04zz");
+    assert!(bad.bytes().is_err());
+}
+
+#[test]
+fn test_capture() {
+    let path = env::temp_dir().join(format!("chronokeep_capture_test_{}.jsonl", std::process::id()));
+    _ = fs::remove_file(&path);
+    let mut capture = Capture::new();
+    assert!(!capture.is_running());
+    // nothing is written until the capture is started
+    capture.record(Direction::Received, &[0x01]);
+    capture.start_file("Reader 1", path.clone()).unwrap();
+    assert!(capture.is_running());
+    capture.record(Direction::Sent, &[0x04, 0x3E]);
+    capture.record(Direction::Received, &[0x04, 0x48]);
+    capture.stop();
+    assert!(!capture.is_running());
+    capture.record(Direction::Received, &[0x02]);
+    assert_eq!(Some(path.display().to_string()), capture.path());
+    let records = load(path.to_str().unwrap()).unwrap();
+    assert_eq!(2, records.len());
+    assert_eq!(Direction::Sent, records[0].direction);
+    assert_eq!("Reader 1", records[0].reader);
+    assert_eq!(vec![0x04, 0x3E], records[0].bytes().unwrap());
+    assert_eq!(Direction::Received, records[1].direction);
+    assert!(records[0].time <= records[1].time);
+    _ = fs::remove_file(&path);
+}
+
+#[test]
+fn test_record_shared() {
+    let path = env::temp_dir().join(format!("chronokeep_capture_shared_test_{}.jsonl", std::process::id()));
+    _ = fs::remove_file(&path);
+    let capture = Arc::new(Mutex::new(Capture::new()));
+    let other = Arc::new(Mutex::new(Capture::new()));
+    capture.lock().unwrap().start_file("Reader 2", path.clone()).unwrap();
+    record(&capture, Direction::Sent, &[0x04, 0x3E]);
+    // traffic for other readers isn't recorded
+    record(&other, Direction::Sent, &[0x01]);
+    capture.lock().unwrap().stop();
+    record(&capture, Direction::Sent, &[0x02]);
+    let records = load(path.to_str().unwrap()).unwrap();
+    assert_eq!(1, records.len());
+    assert_eq!("Reader 2", records[0].reader);
+    assert_eq!(vec![0x04, 0x3E], records[0].bytes().unwrap());
+    _ = fs::remove_file(&path);
+}
@@ -0,0 +1,129 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+#[cfg(test)]
+pub mod test;
+
+// Measurements need to be this far apart, in microseconds, before we'll guess at the drift.
+const MIN_DRIFT_SPAN: u128 = 60 * 1000000;
+const MICROSECONDS_PER_HOUR: f64 = 3600.0 * 1000000.0;
+
+// How far a reader's clock is from the portal clock, sent to clients when asked or when it's too far off.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ClockStatus {
+    pub reader_name: String,
+    // Reader clock minus portal clock.
+    pub offset_milliseconds: i64,
+    // How quickly the offset is changing, once there's been long enough to tell.
+    pub drift_milliseconds_per_hour: Option<f64>,
+    pub time: String,
+}
+
+// Tracks the offset between a reader's clock and the portal clock using the timestamps the reader puts on
+// tag reports and events. Everything the reader sends takes some time to reach us, so within each period
+// between keepalives the sample with the largest offset is the one closest to the real offset.
+#[derive(Debug, Default, Clone)]
+pub struct ClockTracker {
+    pending: Option<i64>,
+    // Portal time and offset of the first and latest measurements, all in microseconds.
+    first: Option<(u128, i64)>,
+    last: Option<(u128, i64)>,
+    warned: bool,
+}
+
+impl ClockTracker {
+    pub fn new() -> ClockTracker {
+        ClockTracker::default()
+    }
+
+    // A timestamp from the reader and the portal time it was received at, microseconds since the epoch.
+    pub fn sample(&mut self, reader_time: u128, portal_time: u128) {
+        if reader_time == 0 {
+            return
+        }
+        let offset = reader_time as i64 - portal_time as i64;
+        self.pending = Some(match self.pending {
+            Some(best) => best.max(offset),
+            None => offset,
+        });
+    }
+
+    // Ends the current period, returns true if there was a sample to measure the offset with.
+    pub fn keepalive(&mut self, portal_time: u128) -> bool {
+        match self.pending.take() {
+            Some(offset) => {
+                if self.first.is_none() {
+                    self.first = Some((portal_time, offset));
+                }
+                self.last = Some((portal_time, offset));
+                true
+            },
+            None => false,
+        }
+    }
+
+    // Reader clock minus portal clock in microseconds.
+    pub fn offset(&self) -> Option<i64> {
+        self.last.map(|(_, offset)| offset)
+    }
+
+    pub fn drift_milliseconds_per_hour(&self) -> Option<f64> {
+        let (first_time, first_offset) = self.first?;
+        let (last_time, last_offset) = self.last?;
+        if last_time < first_time + MIN_DRIFT_SPAN {
+            return None
+        }
+        let change = (last_offset - first_offset) as f64 / 1000.0;
+        Some(change / ((last_time - first_time) as f64 / MICROSECONDS_PER_HOUR))
+    }
+
+    // A reader timestamp moved onto the portal clock, left alone until we've measured the offset.
+    pub fn correct(&self, reader_time: u128) -> u128 {
+        match self.offset() {
+            Some(offset) => (reader_time as i128 - offset as i128).max(0) as u128,
+            None => reader_time,
+        }
+    }
+
+    pub fn status(&self, reader_name: &str) -> Option<ClockStatus> {
+        let offset = self.offset()?;
+        let date_time: DateTime<Local> = Local::now();
+        Some(ClockStatus {
+            reader_name: String::from(reader_name),
+            offset_milliseconds: offset / 1000,
+            drift_milliseconds_per_hour: self.drift_milliseconds_per_hour(),
+            time: format!("{}", date_time.format("%Y/%m/%d %T")),
+        })
+    }
+
+    // True when the offset has just gone past the threshold. We only warn once until the offset comes back under it.
+    pub fn check_threshold(&mut self, threshold_milliseconds: u32) -> bool {
+        let offset = match self.offset() {
+            Some(offset) => offset,
+            None => return false,
+        };
+        let over = threshold_milliseconds > 0 && offset.unsigned_abs() > threshold_milliseconds as u64 * 1000;
+        let warn = over && !self.warned;
+        self.warned = over;
+        warn
+    }
+}
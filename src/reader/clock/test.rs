@@ -0,0 +1,79 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+
+use super::ClockTracker;
+
+const SECOND: u128 = 1000000;
+const START: u128 = 1700000000 * SECOND;
+
+#[test]
+fn test_offset() {
+    let mut tracker = ClockTracker::new();
+    assert_eq!(None, tracker.offset());
+    assert!(!tracker.keepalive(START));
+    // reader is 2 seconds ahead, reports take between 10 and 300 milliseconds to get to us
+    tracker.sample(START + 2 * SECOND, START + 300000);
+    tracker.sample(START + 3 * SECOND, START + SECOND + 10000);
+    // reports without a reader timestamp are ignored
+    tracker.sample(0, START + SECOND);
+    assert_eq!(None, tracker.offset());
+    assert!(tracker.keepalive(START + 2 * SECOND));
+    assert_eq!(Some(1990000), tracker.offset());
+    assert_eq!(None, tracker.drift_milliseconds_per_hour());
+    // reader timestamps are moved onto the portal clock
+    assert_eq!(START + 10000, tracker.correct(START + 2 * SECOND));
+    // the next period replaces the offset
+    tracker.sample(START - SECOND, START);
+    assert!(tracker.keepalive(START + 3 * SECOND));
+    assert_eq!(Some(-1000000), tracker.offset());
+    assert_eq!(-1000, tracker.status("Reader").unwrap().offset_milliseconds);
+}
+
+#[test]
+fn test_drift() {
+    let mut tracker = ClockTracker::new();
+    tracker.sample(START, START);
+    tracker.keepalive(START);
+    // 100 milliseconds over half an hour
+    let later = START + 1800 * SECOND;
+    tracker.sample(later + 100000, later);
+    tracker.keepalive(later);
+    let drift = tracker.drift_milliseconds_per_hour().unwrap();
+    assert!((drift - 200.0).abs() < 0.001);
+}
+
+#[test]
+fn test_threshold() {
+    let mut tracker = ClockTracker::new();
+    // nothing measured yet
+    assert!(!tracker.check_threshold(1000));
+    tracker.sample(START + 500000, START);
+    tracker.keepalive(START);
+    assert!(!tracker.check_threshold(1000));
+    tracker.sample(START - 1500000, START);
+    tracker.keepalive(START);
+    // warned once while it stays over the threshold
+    assert!(tracker.check_threshold(1000));
+    assert!(!tracker.check_threshold(1000));
+    // a threshold of 0 never warns
+    assert!(!tracker.check_threshold(0));
+    tracker.sample(START + 1500000, START);
+    tracker.keepalive(START);
+    assert!(tracker.check_threshold(1000));
+}
@@ -0,0 +1,107 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::collections::VecDeque;
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::llrp::{decoder::LLRPStatus, parameter_types};
+
+#[cfg(test)]
+pub mod test;
+
+// Number of errors kept for each reader, older errors are dropped as new ones come in.
+pub const MAX_READER_ERRORS: usize = 50;
+
+// An error the reader reported to us, either through a non-success LLRPStatus or an ERROR_MESSAGE.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ReaderError {
+    pub reader_name: String,
+    // The reader status we were in when the error was reported.
+    pub state: String,
+    pub code: u16,
+    pub description: String,
+    pub time: String,
+}
+
+impl ReaderError {
+    pub fn new(reader_name: &str, state: String, status: &LLRPStatus) -> ReaderError {
+        let date_time: DateTime<Local> = Local::now();
+        ReaderError {
+            reader_name: String::from(reader_name),
+            state,
+            code: status.code,
+            description: describe(status),
+            time: format!("{}", date_time.format("%Y/%m/%d %T")),
+        }
+    }
+}
+
+// The status name and description the reader gave us along with any field or parameter errors.
+pub fn describe(status: &LLRPStatus) -> String {
+    let status_name = parameter_types::get_llrp_status_name(status.code).unwrap_or("UNKNOWN");
+    let mut output = match status.description.is_empty() {
+        true => String::from(status_name),
+        false => format!("{status_name}: {}", status.description),
+    };
+    if let Some((field, code)) = status.field_error {
+        let error_name = parameter_types::get_llrp_status_name(code).unwrap_or("UNKNOWN");
+        output.push_str(&format!(" (field {field} - {error_name})"));
+    }
+    if let Some((kind, code)) = status.parameter_error {
+        let parameter_name = parameter_types::get_parameter_name(kind).unwrap_or("UNKNOWN");
+        let error_name = parameter_types::get_llrp_status_name(code).unwrap_or("UNKNOWN");
+        output.push_str(&format!(" (parameter {parameter_name} - {error_name})"));
+    }
+    output
+}
+
+// Fixed size list of the most recent errors reported by a reader.
+#[derive(Debug, Default)]
+pub struct ErrorLog {
+    errors: VecDeque<ReaderError>,
+}
+
+impl ErrorLog {
+    pub fn new() -> ErrorLog {
+        ErrorLog {
+            errors: VecDeque::with_capacity(MAX_READER_ERRORS),
+        }
+    }
+
+    pub fn push(&mut self, error: ReaderError) {
+        while self.errors.len() >= MAX_READER_ERRORS {
+            self.errors.pop_front();
+        }
+        self.errors.push_back(error);
+    }
+
+    // Errors from oldest to newest.
+    pub fn errors(&self) -> Vec<ReaderError> {
+        self.errors.iter().cloned().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
@@ -0,0 +1,85 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::llrp::{decoder::{decode_message, llrp_status, LLRPStatus}, message_types, parameter_types};
+
+use super::{describe, ErrorLog, ReaderError, MAX_READER_ERRORS};
+
+// ERROR_MESSAGE telling us the reader doesn't support a message we sent
+const ERROR_MESSAGE: [u8; 29] = [
+    0x04, 0x64, 0x00, 0x00, 0x00, 0x1D, 0x00, 0x00, 0x00, 0x03,
+    // LLRPStatus, length 19, M_UnsupportedMessage (109), 11 byte description
+    0x01, 0x1F, 0x00, 0x13, 0x00, 0x6D, 0x00, 0x0B,
+        b'u', b'n', b's', b'u', b'p', b'p', b'o', b'r', b't', b'e', b'd',
+];
+
+// ADD_ROSPEC_RESPONSE with a parameter error and no description
+const ADD_ROSPEC_ERROR: [u8; 26] = [
+    0x04, 0x1E, 0x00, 0x00, 0x00, 0x1A, 0x00, 0x00, 0x00, 0x07,
+    // LLRPStatus, length 16, M_ParameterError (100), no description
+    0x01, 0x1F, 0x00, 0x10, 0x00, 0x64, 0x00, 0x00,
+        // Parameter Error, length 8, AntennaConfiguration (222), M_MissingParameter (103)
+        0x01, 0x21, 0x00, 0x08, 0x00, 0xDE, 0x00, 0x67,
+];
+
+fn status(code: u16, description: &str) -> LLRPStatus {
+    LLRPStatus {
+        code,
+        description: String::from(description),
+        field_error: None,
+        parameter_error: None,
+    }
+}
+
+#[test]
+fn test_error_message() {
+    let msg = decode_message(&ERROR_MESSAGE).unwrap();
+    assert_eq!(message_types::ERROR_MESSAGE, msg.kind);
+    let status = llrp_status(&msg.parameters[0]).unwrap();
+    let error = ReaderError::new("Reader 1", String::from("ConnectingAddRospec"), &status);
+    assert_eq!("Reader 1", error.reader_name);
+    assert_eq!("ConnectingAddRospec", error.state);
+    assert_eq!(parameter_types::M_UNSUPPORTED_MESSAGE, error.code);
+    assert_eq!("M_UNSUPPORTED_MESSAGE: unsupported", error.description);
+}
+
+#[test]
+fn test_describe() {
+    let msg = decode_message(&ADD_ROSPEC_ERROR).unwrap();
+    let status = llrp_status(&msg.parameters[0]).unwrap();
+    assert_eq!("M_PARAMETER_ERROR (parameter ANTENNA_CONFIGURATION - M_MISSING_PARAMETER)", describe(&status));
+    let mut status = status;
+    status.parameter_error = None;
+    status.field_error = Some((2, parameter_types::M_FIELD_ERROR));
+    status.description = String::from("bad field");
+    assert_eq!("M_PARAMETER_ERROR: bad field (field 2 - M_FIELD_ERROR)", describe(&status));
+}
+
+#[test]
+fn test_error_log() {
+    let mut log = ErrorLog::new();
+    assert!(log.is_empty());
+    for ix in 0..(MAX_READER_ERRORS + 5) {
+        log.push(ReaderError::new("Reader 1", String::from("Connected"), &status(parameter_types::R_DEVICE_ERROR, &format!("error {ix}"))));
+    }
+    assert_eq!(MAX_READER_ERRORS, log.len());
+    let errors = log.errors();
+    // the oldest errors are dropped first
+    assert_eq!("R_DEVICE_ERROR: error 5", errors[0].description);
+    assert_eq!(format!("R_DEVICE_ERROR: error {}", MAX_READER_ERRORS + 4), errors[MAX_READER_ERRORS - 1].description);
+}
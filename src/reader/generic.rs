@@ -0,0 +1,2298 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use core::str;
+use std::{collections::HashMap, io::{ErrorKind, Read, Write}, net::{IpAddr, Shutdown, SocketAddr, TcpStream}, str::FromStr, sync::{self, Arc, Mutex}, thread::{self, JoinHandle}, time::{SystemTime, UNIX_EPOCH}};
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+
+use crate::{control::{self, socket::{self, responses::Responses, MAX_CONNECTED}, sound::{SoundNotifier, SoundType}}, database::{sqlite, Database}, defaults, llrp::{self, decoder::{self, DecodeError, Decoder, Fields, LLRPStatus}, encoder::{AntennaConfiguration, C1G2Filter, C1G2InventoryCommand, C1G2TagInventoryMask, GPITriggerValue, GPOWriteData, Message, RFReceiver, RFTransmitter, ROSpec, ROSpecStartTrigger, ROSpecStopTrigger, MAX_VERSION, VERSION_1_0_1}, message_types::{self, get_message_name}, parameter_types}, notifier, objects::{antenna::{self, AntennaConfig, AntennaMapping}, clock::ClockConfig, dedup::{DedupConfig, DEDUP_MODE_ALL, DEDUP_MODE_FIRST, DEDUP_MODE_LAST}, filter::TagFilter, gpio::{GpiTrigger, GpioConfig}, profile::ProfileConfig, read, read_window::ReadWindowConfig, report_buffer::ReportBufferConfig, tag_data::TagDataConfig}, processor, reader::ANTENNA_STATUS_NONE, types};
+
+use super::{capabilities::{self, ReaderCapabilities}, capture::{self, Capture, Direction}, clock::{ClockStatus, ClockTracker}, errors::{ErrorLog, ReaderError}, gpio, reconnector::Reconnector, stats::{self, StatsTracker}, ReaderStatus, ANTENNA_STATUS_CONNECTED, ANTENNA_STATUS_DISCONNECTED, MAX_ANTENNAS};
+
+pub mod requests;
+
+#[cfg(test)]
+pub mod test;
+
+pub const DEFAULT_LLRP_PORT: u16 = 5084;
+pub const BUFFER_SIZE: usize = 65536;
+pub const ROSPEC_ID: u32 = 100;
+pub const ACCESS_SPEC_ID: u32 = 200;
+// RFTransmitter needs both a hop table and a channel, a hopping reader uses the first and ignores the second and a
+// fixed frequency (ETSI) reader does the opposite. Both tables are numbered from 1, so 1 is always there on
+// either kind of reader. We don't read the frequency tables from the capabilities to pick anything else.
+pub const HOP_TABLE_ID: u16 = 1;
+pub const CHANNEL_INDEX: u16 = 1;
+
+pub const STREAM_TIMOUT_MILLISECONDS: u64 = 100;
+
+pub(super) struct ReadData {
+    tags: Vec<TagData>,
+    antenna_data: bool,
+    antennas: [u8;MAX_ANTENNAS],
+    capabilities: Option<ReaderCapabilities>,
+    supported_version: Option<u8>,
+    // Reader timestamps and the portal time they were received at.
+    clock_samples: Vec<(u128, u128)>,
+    keepalive: bool,
+    last_ka_received_at: u64,
+    status_messages: Vec<(u16, LLRPStatus)>
+}
+
+// Reader settings saved in the database that get applied while connecting.
+struct ReaderSettings {
+    antennas: Vec<AntennaConfig>,
+    antenna_map: Vec<AntennaMapping>,
+    filters: Vec<TagFilter>,
+    gpio: GpioConfig,
+    tag_data: TagDataConfig,
+    clock: ClockConfig,
+    clock_tracker: Arc<sync::Mutex<ClockTracker>>,
+    report_buffer: ReportBufferConfig,
+    profiles: ProfileConfig,
+    dedup: DedupConfig,
+    read_windows: Arc<sync::Mutex<ReadWindowConfig>>,
+    capabilities: Arc<sync::Mutex<Option<ReaderCapabilities>>>,
+    llrp_version: Arc<sync::Mutex<u8>>,
+    supported_version: Arc<sync::Mutex<u8>>,
+}
+
+impl ReaderSettings {
+    // Settings saved in the database for the reader, anything we can't retrieve is left empty.
+    fn load(reader: &super::Reader, db: &sqlite::SQLite) -> ReaderSettings {
+        let mut settings = ReaderSettings::load_none(reader);
+        match db.get_antenna_configs(&reader.id) {
+            Ok(configs) => settings.antennas = configs,
+            Err(e) => println!("Error retrieving antenna configuration. {e}"),
+        }
+        match db.get_antenna_map(&reader.id) {
+            Ok(map) => settings.antenna_map = map,
+            Err(e) => println!("Error retrieving antenna map. {e}"),
+        }
+        match db.get_tag_filters(&reader.id) {
+            Ok(f) => settings.filters = f,
+            Err(e) => println!("Error retrieving tag filters. {e}"),
+        }
+        match db.get_gpio_config(&reader.id) {
+            Ok(config) => settings.gpio = config,
+            Err(e) => println!("Error retrieving gpio configuration. {e}"),
+        }
+        match db.get_tag_data_config(&reader.id) {
+            Ok(config) => settings.tag_data = config,
+            Err(e) => println!("Error retrieving tag data configuration. {e}"),
+        }
+        match db.get_clock_config(&reader.id) {
+            Ok(config) => settings.clock = config,
+            Err(e) => println!("Error retrieving clock configuration. {e}"),
+        }
+        match db.get_report_buffer_config(&reader.id) {
+            Ok(config) => settings.report_buffer = config,
+            Err(e) => println!("Error retrieving report buffer configuration. {e}"),
+        }
+        match db.get_profile_config(&reader.id) {
+            Ok(config) => settings.profiles = config,
+            Err(e) => println!("Error retrieving reading profiles. {e}"),
+        }
+        match db.get_dedup_config(&reader.id) {
+            Ok(config) => settings.dedup = config,
+            Err(e) => println!("Error retrieving dedup configuration. {e}"),
+        }
+        match db.get_read_window_config(&reader.id) {
+            Ok(config) => {
+                if let Ok(mut windows) = settings.read_windows.lock() {
+                    *windows = config;
+                }
+            },
+            Err(e) => println!("Error retrieving read windows. {e}"),
+        }
+        settings
+    }
+
+    // Settings for a reader when we can't get to the database.
+    fn load_none(reader: &super::Reader) -> ReaderSettings {
+        ReaderSettings {
+            antennas: Vec::new(),
+            antenna_map: Vec::new(),
+            filters: Vec::new(),
+            gpio: GpioConfig::default(),
+            tag_data: TagDataConfig::default(),
+            clock: ClockConfig::default(),
+            clock_tracker: reader.clock.clone(),
+            report_buffer: ReportBufferConfig::default(),
+            profiles: ProfileConfig::default(),
+            dedup: DedupConfig::default(),
+            read_windows: reader.read_windows.clone(),
+            capabilities: reader.capabilities.clone(),
+            llrp_version: reader.llrp_version.clone(),
+            supported_version: Arc::new(sync::Mutex::new(VERSION_1_0_1)),
+        }
+    }
+
+    // The read window in microseconds for reads from the antenna given, the global window is used when
+    // neither the antenna nor the reader has its own.
+    fn read_window(&self, antenna: u16, global: u128) -> u128 {
+        if let Ok(windows) = self.read_windows.lock() {
+            if let Some(window) = windows.antenna_read_window(antenna).or(windows.read_window()) {
+                return (window as u128) * 100000
+            }
+        }
+        global
+    }
+
+    // The time saved on a read, from whichever clock the reader is set to use.
+    fn read_time(&self, tag: &TagData) -> u128 {
+        if !self.clock.uses_reader_clock() || tag.reader_time == 0 {
+            return tag.portal_time
+        }
+        if self.clock.correct() {
+            if let Ok(tracker) = self.clock_tracker.lock() {
+                return tracker.correct(tag.reader_time)
+            }
+        }
+        tag.reader_time
+    }
+
+    // The highest LLRP version both the reader and the portal support.
+    fn negotiated_version(&self) -> u8 {
+        let supported = match self.supported_version.lock() {
+            Ok(v) => *v,
+            Err(_) => VERSION_1_0_1,
+        };
+        supported.min(MAX_VERSION)
+    }
+
+    // The LLRP version currently in use on the connection.
+    fn current_version(&self) -> u8 {
+        match self.llrp_version.lock() {
+            Ok(v) => *v,
+            Err(_) => VERSION_1_0_1,
+        }
+    }
+
+    // The AntennaConfiguration parameters for any antenna with a power or sensitivity set.
+    // Power given in dBm is matched to the closest entry in the power table the reader gave us.
+    fn antenna_configurations(&self) -> Vec<AntennaConfiguration> {
+        let caps = match self.capabilities.lock() {
+            Ok(caps) => caps.clone(),
+            Err(_) => None,
+        };
+        let mut output: Vec<AntennaConfiguration> = Vec::new();
+        for config in self.antennas.iter() {
+            let mut transmit_power = config.transmit_power_index();
+            if let (Some(dbm), Some(caps)) = (config.transmit_power_dbm(), &caps) {
+                if let Some(ix) = caps.transmit_power_index(dbm) {
+                    transmit_power = ix;
+                }
+            }
+            // 0 isn't a valid table index so we leave those at the reader defaults
+            let receiver = match config.receive_sensitivity_index() {
+                0 => None,
+                sensitivity => Some(RFReceiver { sensitivity }),
+            };
+            let transmitter = match transmit_power {
+                0 => None,
+                transmit_power => Some(RFTransmitter { hop_table_id: HOP_TABLE_ID, channel_index: CHANNEL_INDEX, transmit_power }),
+            };
+            if receiver.is_none() && transmitter.is_none() {
+                continue;
+            }
+            output.push(AntennaConfiguration {
+                antenna_id: config.antenna(),
+                receiver,
+                transmitter,
+                inventory_command: None,
+            });
+        }
+        output
+    }
+
+    // The C1G2Filter parameters for the tag filters. Every tag starts out selected for inventory, the first
+    // filter decides what happens to the tags it doesn't match and each one after only changes the tags it matches.
+    fn c1g2_filters(&self) -> Vec<C1G2Filter> {
+        let mut output: Vec<C1G2Filter> = Vec::new();
+        for filter in self.filters.iter() {
+            let mask = match filter.mask_bytes() {
+                Some(mask) => mask,
+                None => {
+                    println!("Ignoring tag filter with an invalid mask: {}", filter.mask());
+                    continue;
+                }
+            };
+            let unaware_action = match (output.is_empty(), filter.include()) {
+                // select matching, unselect the rest
+                (true, true) => 0,
+                // select matching, leave the rest
+                (false, true) => 1,
+                // unselect matching, select the rest
+                (true, false) => 4,
+                // unselect matching, leave the rest
+                (false, false) => 3,
+            };
+            output.push(C1G2Filter {
+                // don't truncate
+                truncate: 1,
+                mask: C1G2TagInventoryMask {
+                    memory_bank: filter.memory_bank(),
+                    pointer: filter.pointer(),
+                    mask_bits: filter.match_bits(),
+                    mask,
+                },
+                unaware_action: Some(unaware_action),
+            });
+        }
+        output
+    }
+
+    // The ROSpec start and stop triggers for the GPI ports set up on this reader. Without a start trigger
+    // the ROSpec waits for START_ROSPEC, without a stop trigger it runs until it's disabled.
+    fn boundary_triggers(&self) -> (ROSpecStartTrigger, ROSpecStopTrigger) {
+        let gpi_value = |trigger: &GpiTrigger| GPITriggerValue {
+            port: trigger.port(),
+            event: trigger.event(),
+            timeout: trigger.timeout(),
+        };
+        let start = match self.gpio.start_trigger() {
+            Some(trigger) => ROSpecStartTrigger {
+                kind: llrp::encoder::START_TRIGGER_GPI,
+                periodic: None,
+                gpi: Some(gpi_value(trigger)),
+            },
+            None => ROSpecStartTrigger::default(),
+        };
+        let duration = self.profiles.active_profile().map(|profile| profile.duration_milliseconds()).unwrap_or(0);
+        let stop = match self.gpio.stop_trigger() {
+            // a profile that only reads for a while is done once its time is up
+            _ if duration > 0 => ROSpecStopTrigger {
+                kind: llrp::encoder::STOP_TRIGGER_DURATION,
+                duration,
+                gpi: None,
+            },
+            Some(trigger) => ROSpecStopTrigger {
+                kind: llrp::encoder::STOP_TRIGGER_GPI,
+                duration: 0,
+                gpi: Some(gpi_value(trigger)),
+            },
+            None => ROSpecStopTrigger::default(),
+        };
+        (start, stop)
+    }
+
+    // Readers with a GPI start trigger start reading on their own, START_ROSPEC would start them early.
+    fn starts_on_gpi(&self) -> bool {
+        self.gpio.start_trigger().is_some()
+    }
+
+    // The antenna number reads and status from a port on the reader are reported as.
+    fn reported_antenna(&self, port: u16) -> u16 {
+        antenna::mapped_antenna(&self.antenna_map, port)
+    }
+
+    // Antennas the user has turned off for this reader.
+    fn disabled_antennas(&self) -> Vec<u16> {
+        self.antennas.iter()
+            .filter(|config| !config.enabled())
+            .map(|config| config.antenna())
+            .collect()
+    }
+
+    // The antenna ids to put in the AISpec. Antennas without a saved configuration are enabled,
+    // so if nothing has been turned off we ask for every antenna (id 0).
+    fn antenna_ids(&self) -> Vec<u16> {
+        let disabled = self.disabled_antennas();
+        if disabled.is_empty() {
+            return vec![0]
+        }
+        let mut max_antennas: usize = 0;
+        if let Ok(caps) = self.capabilities.lock() {
+            if let Some(caps) = &*caps {
+                max_antennas = (caps.max_antennas as usize).min(MAX_ANTENNAS);
+            }
+        }
+        let output: Vec<u16> = if max_antennas > 0 {
+            (1..=max_antennas as u16).filter(|ix| !disabled.contains(ix)).collect()
+        } else {
+            // without the capabilities we don't know how many antennas the reader has
+            self.antennas.iter()
+                .filter(|config| config.enabled())
+                .map(|config| config.antenna())
+                .collect()
+        };
+        if output.is_empty() {
+            println!("Every antenna is disabled for this reader, reading from all of them instead.");
+            return vec![0]
+        }
+        output
+    }
+
+    // Each profile gets its own ROSpec so the reader never mixes one up with another.
+    fn rospec_id(&self) -> u32 {
+        match self.profiles.active_index() {
+            Some(ix) => ROSPEC_ID + 1 + ix as u32,
+            None => ROSPEC_ID,
+        }
+    }
+
+    // The antennas the ROSpec reads from, the active profile can narrow down the enabled antennas.
+    fn rospec_antenna_ids(&self) -> Vec<u16> {
+        let profile = match self.profiles.active_profile() {
+            Some(profile) if !profile.antennas().is_empty() => profile,
+            _ => return self.antenna_ids(),
+        };
+        let disabled = self.disabled_antennas();
+        let output: Vec<u16> = profile.antennas().iter()
+            .filter(|antenna| !disabled.contains(antenna))
+            .copied()
+            .collect();
+        if output.is_empty() {
+            println!("Every antenna in profile {} is disabled for this reader, reading from the enabled antennas instead.", profile.name());
+            return self.antenna_ids()
+        }
+        output
+    }
+
+    // The transmit power the active profile reads at, matched to the closest entry in the power table the reader gave us.
+    fn rospec_transmitter(&self) -> Option<RFTransmitter> {
+        let dbm = self.profiles.active_profile()?.transmit_power_dbm()?;
+        let index = match self.capabilities.lock() {
+            Ok(caps) => caps.as_ref().and_then(|caps| caps.transmit_power_index(dbm)),
+            Err(_) => None,
+        };
+        match index {
+            Some(transmit_power) => Some(RFTransmitter { hop_table_id: HOP_TABLE_ID, channel_index: CHANNEL_INDEX, transmit_power }),
+            None => {
+                println!("Unable to find a transmit power for {dbm} dBm, the reader hasn't told us what it can do.");
+                None
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TagData {
+    pub(super) tag: u128,              // 96 bits possible
+    pub(super) antenna: u16,           // short integer
+    pub(super) rssi: i8,               // possible values -128 to +127
+    pub(super) first_seen: u128,       // time since 00:00:00 UTC Jan 1 1970 in microseconds (1,000,000 per second, 1,000 per millisecond)
+    pub(super) last_seen: u128,        // time since 00:00:00 UTC Jan 1 1970 in microseconds, 0 when the reader doesn't report it
+    pub(super) reader_time: u128,
+    pub(super) portal_time: u128,      // time since 00:00:00 UTC Jan 1 1970 in microseconds (1,000,000 per second, 1,000 per millisecond)
+    pub(super) channel_index: Option<u16>,
+    pub(super) seen_count: Option<u16>,
+    pub(super) tid: Option<String>,           // hex
+    pub(super) user_memory: Option<String>,   // hex
+}
+
+impl TagData {
+    pub fn tag(&self) -> u128 {
+        return self.tag;
+    }
+
+    fn to_read(&self, settings: &ReaderSettings, chip_type: &str, r_name: &str) -> read::Read {
+        let chip = if chip_type == types::TYPE_CHIP_DEC {format!("{}", self.tag)} else {format!("{:x}", self.tag)};
+        let time = settings.read_time(self);
+        let mut output = read::Read::new(
+            0,
+            chip,
+            (time / 1000000) as i64,
+            ((time / 1000) % 1000) as u32,
+            (self.reader_time / 1000000) as i64,
+            ((self.reader_time / 1000) % 1000) as u32,
+            self.antenna as u32,
+            String::from(r_name),
+            format!("{}", self.rssi),
+            read::READ_UPLOADED_FALSE
+        );
+        output.set_details(read::ReadDetails {
+            tid: self.tid.clone(),
+            user_memory: self.user_memory.clone(),
+            channel_index: self.channel_index,
+            seen_count: self.seen_count,
+            last_seen_seconds: if self.last_seen > 0 { Some((self.last_seen / 1000000) as i64) } else { None },
+            last_seen_milliseconds: if self.last_seen > 0 { Some(((self.last_seen / 1000) % 1000) as u32) } else { None },
+        });
+        output
+    }
+}
+
+// Vendor specific additions to the standard LLRP connection process.
+// Every method has a default that sticks to the ratified LLRP 1.0.1 message set.
+pub(super) trait Extensions: Send {
+    // Connecting states, in order, that the vendor needs after the keepalive is set and before SET_READER_CONFIG.
+    fn setup_steps(&self) -> &'static [ReaderStatus] {
+        &[]
+    }
+
+    // Sends the request for one of the vendor setup steps.
+    fn send_setup_step(&mut self, _status: &ReaderStatus, _tcp_stream: &mut TcpStream, _capture: &Arc<sync::Mutex<Capture>>, _msg_id: &Arc<sync::Mutex<u32>>, _version: u8) -> Result<(), &'static str> {
+        Err("unknown setup step")
+    }
+
+    // The message type the reader responds with for one of the vendor setup steps.
+    fn setup_step_response(&self, _status: &ReaderStatus) -> u16 {
+        message_types::CUSTOM_MESSAGE
+    }
+
+    // The ROSpec to add to the reader, vendors can add their own parameters to it.
+    fn rospec(&self, rospec_id: &u32) -> ROSpec {
+        requests::rospec(rospec_id)
+    }
+
+    fn get_reader_capabilities(&self, id: &u32) -> Message {
+        requests::get_reader_capabilities(id)
+    }
+
+    fn get_reader_config(&self, id: &u32) -> Message {
+        // get antenna properties (config == 2)
+        // this will report back information on the antennas
+        // gpi_port and gpo_port values should be ignored in this query
+        requests::get_reader_config(id, &0, &2, &0, &0)
+    }
+
+    // Whether the reader's UTC clock can be set, LLRP itself has no way to do it.
+    fn can_set_clock(&self) -> bool {
+        false
+    }
+
+    // The SET_READER_CONFIG that sets the reader's UTC clock to the time given in microseconds since the epoch.
+    fn set_clock(&self, _id: &u32, _time: u64) -> Result<Message, &'static str> {
+        Err("reader clock can't be set")
+    }
+
+    // Whether a CUSTOM_MESSAGE with the vendor and subtype is a response containing an LLRPStatus parameter.
+    fn is_custom_response(&self, _vendor: u32, _subtype: u16) -> bool {
+        false
+    }
+
+    // Called with each custom parameter found in a TagReportData parameter after the standard parameters are processed.
+    fn process_tag_custom_parameter(&self, _param: &decoder::Parameter, _data: &mut TagData) { }
+
+    // Called with each custom parameter found in a GET_READER_CONFIG_RESPONSE after the standard parameters are processed.
+    // Returns true if any antenna information was updated.
+    fn process_config_custom_parameter(&self, _param: &decoder::Parameter, _antennas: &mut [u8;MAX_ANTENNAS]) -> bool {
+        false
+    }
+
+    // Called whenever tags have been received from the reader.
+    fn tags_received(&mut self, _count: usize, _tcp_stream: &mut TcpStream, _capture: &Arc<sync::Mutex<Capture>>, _msg_id: &Arc<sync::Mutex<u32>>, _version: u8) { }
+}
+
+pub(super) struct NoExtensions;
+
+impl Extensions for NoExtensions {}
+
+pub fn connect(
+    reader: &mut super::Reader,
+    sqlite: &Arc<Mutex<sqlite::SQLite>>,
+    control: &Arc<Mutex<control::Control>>,
+    read_saver: &Arc<processor::ReadSaver>,
+    sound: Arc<SoundNotifier>,
+    reconnector: Option<Reconnector>,
+    notifier: notifier::Notifier,
+) -> Result<JoinHandle<()>, &'static str> {
+    connect_with(reader, sqlite, control, read_saver, sound, reconnector, notifier, Box::new(NoExtensions))
+}
+
+pub fn start(
+    reader: &mut super::Reader,
+    tcp_stream: TcpStream,
+    sqlite: &Arc<Mutex<sqlite::SQLite>>,
+    control: &Arc<Mutex<control::Control>>,
+    read_saver: &Arc<processor::ReadSaver>,
+    sound: Arc<SoundNotifier>,
+    reconnector: Option<Reconnector>,
+    notifier: notifier::Notifier,
+) -> Result<JoinHandle<()>, &'static str> {
+    start_with(reader, tcp_stream, sqlite, control, read_saver, sound, reconnector, notifier, Box::new(NoExtensions))
+}
+
+pub(super) fn connect_with(
+    reader: &mut super::Reader,
+    sqlite: &Arc<Mutex<sqlite::SQLite>>,
+    control: &Arc<Mutex<control::Control>>,
+    read_saver: &Arc<processor::ReadSaver>,
+    sound: Arc<SoundNotifier>,
+    reconnector: Option<Reconnector>,
+    notifier: notifier::Notifier,
+    ext: Box<dyn Extensions>,
+) -> Result<JoinHandle<()>, &'static str> {
+    let ip_addr = match IpAddr::from_str(&reader.ip_address) {
+        Ok(addr) => addr,
+        Err(e) => {
+            println!("Error parsing ip address. {e}");
+            return Err("error parsing reader ip address")
+        }
+    };
+    let res = TcpStream::connect_timeout(&SocketAddr::new(ip_addr, reader.port), Duration::from_millis(STREAM_TIMOUT_MILLISECONDS));
+    match res {
+        Err(_) => {
+            sound.notify_custom(SoundType::Disconnected);
+            return Err("unable to connect")
+        },
+        Ok(tcp_stream) => start_with(reader, tcp_stream, sqlite, control, read_saver, sound, reconnector, notifier, ext),
+    }
+}
+
+// Runs the connection process and reading loop on a stream that's already connected to the reader.
+// The stream is either one we opened to the reader or one the reader opened to us.
+pub(super) fn start_with(
+    reader: &mut super::Reader,
+    mut tcp_stream: TcpStream,
+    sqlite: &Arc<Mutex<sqlite::SQLite>>,
+    control: &Arc<Mutex<control::Control>>,
+    read_saver: &Arc<processor::ReadSaver>,
+    sound: Arc<SoundNotifier>,
+    reconnector: Option<Reconnector>,
+    notifier: notifier::Notifier,
+    mut ext: Box<dyn Extensions>,
+) -> Result<JoinHandle<()>, &'static str> {
+    match tcp_stream.set_read_timeout(Some(Duration::from_millis(STREAM_TIMOUT_MILLISECONDS))) {
+        Ok(_) => {},
+        Err(e) => println!("unexpected error setting read timeout on tcp stream: {e}")
+    }
+    match tcp_stream.set_write_timeout(Some(Duration::from_millis(STREAM_TIMOUT_MILLISECONDS))) {
+        Ok(_) => {},
+        Err(e) => println!("unexpected error setting write timeout on tcp stream: {e}")
+    }
+    // anything waiting to be switched to has already been saved, so it's in the settings we load
+    if let Ok(mut change) = reader.profile_change.lock() {
+        *change = None;
+    }
+    let mut settings = match sqlite.lock() {
+        Ok(db) => ReaderSettings::load(reader, &db),
+        Err(_) => ReaderSettings::load_none(reader),
+    };
+    // Set reader status to Initial connection state.
+    if let Ok(mut con) = reader.status.lock() {
+        *con = ReaderStatus::ConnectingGetSupportedVersion;
+    }
+    // every connection starts out on 1.0.1 until the reader tells us it can do better
+    if let Ok(mut ver) = reader.llrp_version.lock() {
+        *ver = VERSION_1_0_1;
+    }
+    // the reader's clock may have been changed since we last talked to it, but the last offset we measured
+    // is the best guess for when the reports it held for us were read
+    let mut previous_clock = ClockTracker::new();
+    if let Ok(mut clock) = reader.clock.lock() {
+        previous_clock = clock.clone();
+        *clock = ClockTracker::new();
+    }
+    if let Ok(mut att) = reader.status_retries.lock() {
+        *att = 0;
+    }
+    // try to send connection messages
+    match send_get_supported_version(&mut tcp_stream, &reader.capture, &reader.msg_id) {
+        Ok(_) => println!("Connection process started on reader {}.", reader.nickname()),
+        Err(e) => {
+            return Err(e)
+        },
+    };
+    // copy tcp stream into the mutex
+    reader.socket = match tcp_stream.try_clone() {
+        Ok(stream) => sync::Mutex::new(Some(stream)),
+        Err(_) => {
+            if let Ok(mut con) = reader.status.lock() {
+                *con = ReaderStatus::Errored;
+            }
+            sound.notify_custom(SoundType::Disconnected);
+            return Err("error copying stream to thread")
+        }
+    };
+    // copy values for out thread
+    let mut t_stream = tcp_stream;
+    let t_mutex = reader.keepalive.clone();
+    let msg_id = reader.msg_id.clone();
+    let t_reader_name = reader.nickname.clone();
+    let t_sqlite = sqlite.clone();
+    let t_control = control.clone();
+    let t_sound = sound.clone();
+    let t_antennas = reader.antennas.clone();
+    let t_read_saver = read_saver.clone();
+    let t_reader_status = reader.status.clone();
+    let t_reader_status_retries = reader.status_retries.clone();
+    let t_control_sockets = reader.control_sockets.clone();
+    let t_errors = reader.errors.clone();
+    let t_readers = reader.readers.clone();
+    let t_read_repeaters = reader.read_repeaters.clone();
+    let t_reconnector = reconnector.clone();
+    let t_profile_change = reader.profile_change.clone();
+    let t_stats = reader.stats.clone();
+    let t_capture = reader.capture.clone();
+
+    let output = thread::spawn(move|| {
+        let buf: &mut [u8; BUFFER_SIZE] = &mut [0; BUFFER_SIZE];
+        let mut decoder = Decoder::new();
+        match t_stream.set_read_timeout(Some(Duration::from_millis(STREAM_TIMOUT_MILLISECONDS))) {
+            Ok(_) => (),
+            Err(e) => {
+                println!("Error setting read timeout. {e}")
+            }
+        }
+        let mut read_map: HashMap<(usize, u128), WindowedTag> = HashMap::new();
+        let disabled_antennas = settings.disabled_antennas();
+        let mut last_ka_received_at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let mut reconnect = false;
+        let mut reconnect_reason = stats::RECONNECT_CONNECTION_LOST;
+        let mut connection_lost = false;
+        let mut unsaved_reads: Vec<read::Read> = Vec::new();
+        let mut gpo = gpio::Outputs::new(settings.gpio.outputs().to_vec());
+        // everything the reader sends before we're connected is from while we were away
+        let mut recovering = settings.report_buffer.enabled();
+        let mut recovered: Vec<TagData> = Vec::new();
+        loop {
+            /*
+                Start of reading loop
+             */
+            if let Ok(keepalive) = t_mutex.lock() {
+                // check if we've been told to quit
+                if *keepalive == false {
+                    break;
+                };
+            }
+            let mut starting_status = ReaderStatus::Unknown;
+            if let Ok(stat) = t_reader_status.lock()  {
+                starting_status = stat.clone();
+            }
+            if let Ok(mut tracker) = t_stats.lock() {
+                tracker.status(stats::now(), &starting_status);
+            }
+            match read(&mut t_stream, &t_capture, settings.current_version(), buf, &mut decoder, last_ka_received_at, &*ext) {
+                Ok(data) => {
+                    // capabilities need to be saved before we send anything that depends on them
+                    if let Some(caps) = data.capabilities {
+                        println!("Reader capabilities received. Firmware {} - {} antennas - region {}.", caps.firmware, caps.max_antennas, caps.region);
+                        if let Ok(mut t_caps) = settings.capabilities.lock() {
+                            *t_caps = Some(caps);
+                        }
+                    }
+                    // the version needs to be known before we decide whether to ask the reader to change it
+                    if let Some(supported) = data.supported_version {
+                        println!("Reader supports LLRP version {supported}.");
+                        if let Ok(mut t_supported) = settings.supported_version.lock() {
+                            *t_supported = supported;
+                        }
+                    }
+                    // process any status messages
+                    if data.status_messages.len() > 0 {
+                        let mut attempt = 0;
+                        if let Ok(att) = t_reader_status_retries.lock() {
+                            attempt = *att;
+                        }
+                        let mut reader_errors: Vec<ReaderError> = Vec::new();
+                        for (msg_kind, status) in data.status_messages {
+                            let success = status.success();
+                            if let Ok(mut stat) = t_reader_status.lock() {
+                                let msg_name = get_message_name(msg_kind).unwrap_or("UNKNOWN");
+                                // readers that only know 1.0.1 can't answer the version request, that's not worth reporting
+                                if !success && *stat != ReaderStatus::ConnectingGetSupportedVersion {
+                                    reader_errors.push(ReaderError::new(t_reader_name.as_str(), format!("{:?}", *stat), &status));
+                                }
+                                match *stat {
+                                    // responses to anything sent while reading, purging tags for example
+                                    ReaderStatus::Connected => {
+                                        if !success {
+                                            println!("{msg_name} reported an error while connected.");
+                                        }
+                                        continue;
+                                    },
+                                    ReaderStatus::Disconnected | ReaderStatus::Errored | ReaderStatus::Unknown => {
+                                        continue;
+                                    },
+                                    // outside of GET_REPORT a tag report is just tags
+                                    ReaderStatus::ConnectingGetReport => {},
+                                    _ if msg_kind == message_types::RO_ACCESS_REPORT => {
+                                        continue;
+                                    },
+                                    _ => {},
+                                }
+                                // make sure we're looking at the response for the request we sent
+                                // an ERROR_MESSAGE means the reader couldn't process the request we sent
+                                if msg_kind != expected_response(&*ext, &stat) && msg_kind != message_types::ERROR_MESSAGE {
+                                    println!("unexpected {msg_name} received while in status {:?}", *stat);
+                                    continue;
+                                }
+                                attempt += 1;
+                                // when stopping we want to move on even if the reader isn't happy with us
+                                let proceed = success || match *stat {
+                                    // 1.0.1 readers don't know the version messages, they'll just keep using 1.0.1
+                                    ReaderStatus::ConnectingGetSupportedVersion |
+                                    ReaderStatus::ConnectingSetProtocolVersion => true,
+                                    // older readers may not tell us what they can do, that shouldn't stop us from reading
+                                    ReaderStatus::ConnectingGetReaderCapabilities => true,
+                                    // the clock being wrong is better than not reading at all
+                                    ReaderStatus::ConnectingSetClock => true,
+                                    // and so is losing the reads from while we were disconnected
+                                    ReaderStatus::ConnectingGetReport => true,
+                                    ReaderStatus::StoppingDisableRospec |
+                                    ReaderStatus::StoppingDeleteRospec => true,
+                                    _ => false,
+                                };
+                                if proceed {
+                                    attempt = 0;
+                                    if success && *stat == ReaderStatus::ConnectingSetProtocolVersion {
+                                        let negotiated = settings.negotiated_version();
+                                        if let Ok(mut ver) = settings.llrp_version.lock() {
+                                            *ver = negotiated;
+                                        }
+                                        println!("-- LLRP version {negotiated} set on reader.");
+                                    }
+                                    // anything we measured was against the old clock
+                                    if success && *stat == ReaderStatus::ConnectingSetClock {
+                                        if let Ok(mut tracker) = settings.clock_tracker.lock() {
+                                            *tracker = ClockTracker::new();
+                                        }
+                                        println!("-- Reader clock set.");
+                                    }
+                                    let next = next_status(&*ext, &settings, &stat);
+                                    *stat = next.clone();
+                                    match next {
+                                        ReaderStatus::Connected => {
+                                            println!("-- Reader status set to connected.")
+                                        },
+                                        ReaderStatus::Disconnected => {
+                                            println!("-- Reader successfully disconnected.");
+                                            break;
+                                        },
+                                        ReaderStatus::Errored => {
+                                            println!("unknown reader status while processing {msg_name}")
+                                        },
+                                        _ => {
+                                            if let Err(e) = send_status_request(&mut *ext, &settings, &next, true, &mut t_stream, &t_capture, &msg_id) {
+                                                *stat = ReaderStatus::Errored;
+                                                eprintln!("error sending request for {:?}: {e}", next);
+                                            }
+                                        },
+                                    }
+                                } else if attempt > 5 {
+                                    *stat = ReaderStatus::Errored;
+                                } else {
+                                    let current = stat.clone();
+                                    if let Err(e) = send_status_request(&mut *ext, &settings, &current, false, &mut t_stream, &t_capture, &msg_id) {
+                                        *stat = ReaderStatus::Errored;
+                                        eprintln!("error sending request for {:?}: {e}", current);
+                                    }
+                                }
+                            }
+                        }
+                        if let Ok(mut att) = t_reader_status_retries.lock() {
+                            *att = attempt;
+                        }
+                        for reader_error in reader_errors {
+                            println!("Reader error while in status {}. {}", reader_error.state, reader_error.description);
+                            if let Err(e) = send_error(reader_error, &t_errors, &t_control_sockets) {
+                                println!("Error sending reader error to sockets. {e}");
+                            }
+                        }
+                    }
+                    // held reports are saved once we're connected, with the time the reader saw each tag
+                    if !data.tags.is_empty() && recovering {
+                        let mut tags = data.tags;
+                        for tag in tags.iter_mut() {
+                            if tag.reader_time > 0 {
+                                tag.portal_time = previous_clock.correct(tag.reader_time);
+                            }
+                        }
+                        recovered.append(&mut tags);
+                    // process tags if we were told there were some
+                    } else if data.tags.len() > 0 {
+                        if let Ok(mut tracker) = t_stats.lock() {
+                            tracker.tags_received(stats::now(), data.tags.len());
+                        }
+                        ext.tags_received(data.tags.len(), &mut t_stream, &t_capture, &msg_id, settings.current_version());
+                        gpo.tags_received(gpio::now());
+                        let mut ignore: u8 = defaults::DEFAULT_BEEP_IGNORE;
+                        if let Ok(control) = t_control.lock() {
+                            ignore = control.beep_ignore;
+                        }
+                        let mut tags = data.tags;
+                        t_sound.notify_tags(&tags, ignore);
+                        match process_tags(&mut read_map, &mut tags, &settings, &mut unsaved_reads, &t_control, &t_read_saver, t_reader_name.as_str()) {
+                            Ok(new_reads) => {
+                                reads_saved(&t_stats, new_reads.len());
+                                if new_reads.len() > 0 {
+                                    match send_new(new_reads, &t_control_sockets, &t_read_repeaters) {
+                                        Ok(_) => {},
+                                        Err(e) => {
+                                            println!("error sending new reads to repeaters: {e}")
+                                        }
+                                    }
+                                }
+                            },
+                            Err(e) => println!("Error processing tags. {e}"),
+                        };
+                    }
+                    // if antenna data exists then we can update the readers antennas
+                    if data.antenna_data {
+                        let mut updated = false;
+                        if let Ok(mut ant) = t_antennas.lock() {
+                            for ix in 0..MAX_ANTENNAS {
+                                if data.antennas[ix] != ANTENNA_STATUS_NONE {
+                                    let ix_shift = settings.reported_antenna(ix as u16 + 1) as usize - 1;
+                                    if ix_shift < MAX_ANTENNAS {
+                                        // antennas that are turned off shouldn't show up as disconnected
+                                        ant[ix_shift] = if disabled_antennas.contains(&(ix as u16 + 1)) {
+                                            ANTENNA_STATUS_NONE
+                                        } else {
+                                            data.antennas[ix]
+                                        };
+                                    }
+                                }
+                            }
+                            updated = true;
+                        }
+                        // send out notification that we updated the readers
+                        if updated {
+                            match send_antennas(t_reader_name.as_str(), &t_antennas, &t_control_sockets) {
+                                Ok(_) => {},
+                                Err(e) => {
+                                    println!("error sending antennas to control sockets: {e}")
+                                }
+                            }
+                        }
+                    }
+                    // reader timestamps tell us how far the reader's clock is from ours, held reports are too old to tell us anything
+                    let clock_samples = if recovering { Vec::new() } else { data.clock_samples };
+                    if !clock_samples.is_empty() || data.keepalive {
+                        let mut status = None;
+                        if let Ok(mut tracker) = settings.clock_tracker.lock() {
+                            for (reader_time, portal_time) in clock_samples.iter() {
+                                tracker.sample(*reader_time, *portal_time);
+                            }
+                            let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_micros();
+                            if data.keepalive && tracker.keepalive(now) && tracker.check_threshold(settings.clock.warn_threshold_milliseconds()) {
+                                status = tracker.status(t_reader_name.as_str());
+                            }
+                        }
+                        if let Some(status) = status {
+                            println!("Reader clock is off by {} milliseconds.", status.offset_milliseconds);
+                            let date_time: DateTime<Local> = SystemTime::now().into();
+                            notifier.send_notification(notifier::Notification::ReaderClock {
+                                reader_name: t_reader_name.clone(),
+                                offset_milliseconds: status.offset_milliseconds,
+                            }, format!("{}", date_time.format("%Y/%m/%d %T")));
+                            if let Err(e) = send_clock_warning(status, &t_control_sockets) {
+                                println!("Error sending clock warning to sockets. {e}");
+                            }
+                        }
+                    }
+                    if data.keepalive {
+                        if let Ok(mut tracker) = t_stats.lock() {
+                            tracker.keepalive(stats::now());
+                        }
+                    }
+                    if last_ka_received_at < data.last_ka_received_at {
+                        last_ka_received_at = data.last_ka_received_at
+                    }
+                    let right_now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+                    if right_now - 5 > last_ka_received_at {
+                        println!("no keep alive message received in the last 5 seconds");
+                        if let Ok(stat) = t_reader_status.lock() {
+                            if *stat != ReaderStatus::Disconnected && *stat != ReaderStatus::StoppingDeleteRospec && *stat != ReaderStatus::StoppingDisableRospec {
+                                reconnect = true;
+                                reconnect_reason = stats::RECONNECT_KEEPALIVE_TIMEOUT;
+                            }
+                        }
+                        break;
+                    }
+                },
+                Err(e) => {
+                    match e.kind() {
+                        ErrorKind::ConnectionAborted | ErrorKind::ConnectionReset => {
+                            decoder.clear();
+                            println!("connection aborted/reset");
+                            reconnect = true;
+                            connection_lost = true;
+                            let date_time: DateTime<Local> = SystemTime::now().into();
+                            notifier.send_notification(notifier::Notification::StopReading, format!("{}", date_time.format("%Y/%m/%d %T")));
+                            break;
+                        }
+                        // TimedOut == Windows, WouldBlock == Linux
+                        ErrorKind::TimedOut | ErrorKind::WouldBlock => {
+                            match process_tags(&mut read_map, &mut Vec::new(), &settings, &mut unsaved_reads, &t_control, &t_read_saver, t_reader_name.as_str()) {
+                                Ok(new_reads) => {
+                                    reads_saved(&t_stats, new_reads.len());
+                                    if new_reads.len() > 0 {
+                                        match send_new(new_reads, &t_control_sockets, &t_read_repeaters) {
+                                            Ok(_) => {},
+                                            Err(e) => {
+                                                println!("error sending new reads to repeaters: {e}")
+                                            }
+                                        }
+                                    }
+                                },
+                                Err(e) => println!("Error processing tags. {e}"),
+                            }
+                            let right_now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+                            if right_now - 5 > last_ka_received_at {
+                                println!("no keep alive message received in the last 5 seconds");
+                                reconnect = true;
+                                reconnect_reason = stats::RECONNECT_KEEPALIVE_TIMEOUT;
+                                let date_time: DateTime<Local> = SystemTime::now().into();
+                                notifier.send_notification(notifier::Notification::StopReading, format!("{}", date_time.format("%Y/%m/%d %T")));
+                                break;
+                            }
+                        },
+                        _ => {
+                            decoder.clear();
+                            println!("Error reading from reader. {e}")
+                        },
+                    }
+                }
+            }
+            // GPO ports are only changed while reading so the responses don't get mixed up with connecting or stopping
+            if !gpo.is_empty() {
+                let mut connected = false;
+                if let Ok(stat) = t_reader_status.lock() {
+                    connected = *stat == ReaderStatus::Connected;
+                }
+                if connected {
+                    if let Err(e) = send_set_gpo(&mut t_stream, &t_capture, &msg_id, settings.current_version(), &gpo.update(gpio::now(), gpio::last_lost())) {
+                        println!("Error setting gpo ports. {e}");
+                    }
+                }
+            }
+            // a new profile is only sent once we're reading, the connection process sends the one we loaded
+            let mut connected = false;
+            if let Ok(stat) = t_reader_status.lock() {
+                connected = *stat == ReaderStatus::Connected;
+            }
+            if connected {
+                let mut change = None;
+                if let Ok(mut t_change) = t_profile_change.lock() {
+                    change = t_change.take();
+                }
+                if let Some(profiles) = change {
+                    settings.profiles = profiles;
+                    match settings.profiles.active() {
+                        Some(name) => println!("Switching reader {} to profile {name}.", t_reader_name),
+                        None => println!("Switching reader {} to the default profile.", t_reader_name),
+                    }
+                    if let Err(e) = send_profile_change(&mut t_stream, &t_capture, &msg_id, &*ext, &settings) {
+                        println!("Error changing reading profile. {e}");
+                    }
+                }
+            }
+            let mut send_reader_list = false;
+            if let Ok(stat) = t_reader_status.lock()  {
+                // Check if we had a valid starting status and it's changed to Disconnected/Connected
+                // Then update the screen if we did.
+                if starting_status != ReaderStatus::Unknown
+                && starting_status != *stat
+                {
+                    // Changed to disconnected then close the socket.
+                    if *stat == ReaderStatus::Disconnected {
+                        break;
+                    } else if *stat == ReaderStatus::Errored {
+                        reconnect = true;
+                        reconnect_reason = stats::RECONNECT_READER_ERROR;
+                        break;
+                    } else if *stat == ReaderStatus::Connected {
+                        send_reader_list = true;
+                    }
+                }
+            }
+            if send_reader_list && recovering {
+                recovering = false;
+                let reads = save_recovered(&mut recovered, &settings, &t_control, &t_sqlite, &mut unsaved_reads, &t_read_saver, t_reader_name.as_str());
+                reads_saved(&t_stats, reads.len());
+                if !reads.is_empty() {
+                    if let Err(e) = send_new(reads, &t_control_sockets, &t_read_repeaters) {
+                        println!("error sending recovered reads to repeaters: {e}");
+                    }
+                }
+            }
+            if send_reader_list {
+                if let Ok(u_readers) = t_readers.lock() {
+                    if let Ok(c_socks) = t_control_sockets.lock() {
+                        for sock in c_socks.iter() {
+                            if let Some(sock) = sock {
+                                println!("Sending reader list!");
+                                _ = socket::write_reader_list(&sock, &*u_readers);
+                            }
+                        }
+                    }
+                }
+            }
+            /*
+                End of reading loop
+             */
+        }
+        // don't leave any lights on
+        _ = send_set_gpo(&mut t_stream, &t_capture, &msg_id, settings.current_version(), &gpo.all_off());
+        // a reader holding reports for us has to keep reading until we're back, but only if we lost the connection
+        // to it, a reader that errored or stopped sending keepalives is stopped and closed like any other
+        if connection_lost && settings.report_buffer.enabled() {
+            println!("Leaving reader {} reading until we reconnect.", t_reader_name);
+        } else {
+            stop(&mut t_stream, &t_capture, settings.current_version(), &t_reader_status, &t_reader_name, &msg_id);
+            finalize(&mut t_stream, &t_capture, settings.current_version(), &msg_id, &t_reader_status, last_ka_received_at, &*ext);
+        }
+        save_reads(&mut read_map, &settings, &t_control, &t_sqlite, t_reader_name.as_str());
+        save_recovered(&mut recovered, &settings, &t_control, &t_sqlite, &mut unsaved_reads, &t_read_saver, t_reader_name.as_str());
+        if let Ok(mut db) = t_sqlite.lock() {
+            match db.save_reads(&unsaved_reads) {
+                Ok(_num) => { },
+                Err(e) => println!("Error saving reads. {e}"),
+            }
+        }
+        if let Err(e) = t_stream.shutdown(Shutdown::Both) {
+            println!("Error shutting down socket. {e}");
+        }
+        if let Ok(mut con) = t_reader_status.lock() {
+            *con = ReaderStatus::Disconnected;
+        }
+        if let Ok(mut tracker) = t_stats.lock() {
+            tracker.status(stats::now(), &ReaderStatus::Disconnected);
+            tracker.connection_closed();
+            if reconnect {
+                tracker.reconnect(reconnect_reason);
+            }
+        }
+        if let Ok(u_readers) = t_readers.lock() {
+            if let Ok(c_socks) = t_control_sockets.lock() {
+                for sock in c_socks.iter() {
+                    if let Some(sock) = sock {
+                        println!("Sending reader list!");
+                        _ = socket::write_reader_list(&sock, &*u_readers);
+                    }
+                }
+            }
+        }
+        if reconnect == true {
+            gpio::connection_lost();
+            if let Some(rec) = t_reconnector {
+                rec.run();
+            }
+        }
+        sound.notify_custom(SoundType::Disconnected);
+        println!("Thread reading from this reader has now closed.");
+    });
+    Ok(output)
+}
+
+// The status we move to once the reader has accepted the request sent for the current status.
+// Connecting goes GetSupportedVersion -> SetProtocolVersion -> Keepalive -> GetReaderCapabilities -> GetReport -> vendor setup steps -> SetReaderConfig
+// -> DeleteAccessSpec -> DeleteRospec -> SetClock -> AddRospec -> AddAccessSpec -> EnableAccessSpec -> EnableRospec -> StartRospec -> Connected.
+// The AccessSpec steps are skipped unless tag memory is being read, StartRospec is skipped when a GPI starts reading, and SetProtocolVersion is
+// skipped when the reader can't do better than the version we're already using. SetClock only happens when the reader
+// is set to have its clock set and we know how to set it. GetReport only happens when the reader holds reports for us,
+// and comes before the vendor steps since those can purge the reports.
+fn next_status(ext: &dyn Extensions, settings: &ReaderSettings, status: &ReaderStatus) -> ReaderStatus {
+    let steps = ext.setup_steps();
+    match status {
+        ReaderStatus::ConnectingGetSupportedVersion => {
+            if settings.negotiated_version() > settings.current_version() {
+                ReaderStatus::ConnectingSetProtocolVersion
+            } else {
+                ReaderStatus::ConnectingKeepalive
+            }
+        },
+        ReaderStatus::ConnectingSetProtocolVersion => ReaderStatus::ConnectingKeepalive,
+        ReaderStatus::ConnectingKeepalive => ReaderStatus::ConnectingGetReaderCapabilities,
+        ReaderStatus::ConnectingGetReaderCapabilities if settings.report_buffer.enabled() => ReaderStatus::ConnectingGetReport,
+        ReaderStatus::ConnectingGetReaderCapabilities |
+        ReaderStatus::ConnectingGetReport => {
+            match steps.first() {
+                Some(step) => step.clone(),
+                None => ReaderStatus::ConnectingSetReaderConfig,
+            }
+        },
+        ReaderStatus::ConnectingSetReaderConfig => ReaderStatus::ConnectingDeleteAccessSpec,
+        ReaderStatus::ConnectingDeleteAccessSpec => ReaderStatus::ConnectingDeleteRospec,
+        ReaderStatus::ConnectingDeleteRospec => {
+            if settings.clock.set_reader_clock() && ext.can_set_clock() {
+                ReaderStatus::ConnectingSetClock
+            } else {
+                ReaderStatus::ConnectingAddRospec
+            }
+        },
+        ReaderStatus::ConnectingSetClock => ReaderStatus::ConnectingAddRospec,
+        ReaderStatus::ConnectingAddRospec => {
+            if settings.tag_data.reads_memory() {
+                ReaderStatus::ConnectingAddAccessSpec
+            } else {
+                ReaderStatus::ConnectingEnableRospec
+            }
+        },
+        ReaderStatus::ConnectingAddAccessSpec => ReaderStatus::ConnectingEnableAccessSpec,
+        ReaderStatus::ConnectingEnableAccessSpec => ReaderStatus::ConnectingEnableRospec,
+        ReaderStatus::ConnectingEnableRospec => {
+            if settings.starts_on_gpi() {
+                ReaderStatus::Connected
+            } else {
+                ReaderStatus::ConnectingStartRospec
+            }
+        },
+        ReaderStatus::ConnectingStartRospec => ReaderStatus::Connected,
+        ReaderStatus::StoppingDisableRospec => ReaderStatus::StoppingDeleteRospec,
+        ReaderStatus::StoppingDeleteRospec => ReaderStatus::Disconnected,
+        other => {
+            match steps.iter().position(|s| s == other) {
+                Some(ix) => {
+                    match steps.get(ix + 1) {
+                        Some(step) => step.clone(),
+                        None => ReaderStatus::ConnectingSetReaderConfig,
+                    }
+                },
+                None => ReaderStatus::Errored,
+            }
+        }
+    }
+}
+
+// The response the reader sends for the request sent while in a status.
+fn expected_response(ext: &dyn Extensions, status: &ReaderStatus) -> u16 {
+    match status {
+        ReaderStatus::ConnectingGetSupportedVersion => message_types::GET_SUPPORTED_VERSION_RESPONSE,
+        ReaderStatus::ConnectingSetProtocolVersion => message_types::SET_PROTOCOL_VERSION_RESPONSE,
+        ReaderStatus::ConnectingKeepalive |
+        ReaderStatus::ConnectingSetClock |
+        ReaderStatus::ConnectingSetReaderConfig => message_types::SET_READER_CONFIG_RESPONSE,
+        ReaderStatus::ConnectingGetReaderCapabilities => message_types::GET_READER_CAPABILITIES_RESPONSE,
+        // the reader answers with the reports it held for us
+        ReaderStatus::ConnectingGetReport => message_types::RO_ACCESS_REPORT,
+        ReaderStatus::ConnectingDeleteAccessSpec => message_types::DELETE_ACCESS_SPEC_RESPONSE,
+        ReaderStatus::ConnectingDeleteRospec |
+        ReaderStatus::StoppingDeleteRospec => message_types::DELETE_ROSPEC_RESPONSE,
+        ReaderStatus::ConnectingAddRospec => message_types::ADD_ROSPEC_RESPONSE,
+        ReaderStatus::ConnectingAddAccessSpec => message_types::ADD_ACCESS_SPEC_RESPONSE,
+        ReaderStatus::ConnectingEnableAccessSpec => message_types::ENABLE_ACCESS_SPEC_RESPONSE,
+        ReaderStatus::ConnectingEnableRospec => message_types::ENABLE_ROSPEC_RESPONSE,
+        ReaderStatus::ConnectingStartRospec => message_types::START_ROSPEC_RESPONSE,
+        ReaderStatus::StoppingDisableRospec => message_types::DISABLE_ROSPEC_RESPONSE,
+        other => ext.setup_step_response(other),
+    }
+}
+
+// Sends the request for a status. First is set when we've just moved into the status, otherwise we're retrying.
+fn send_status_request(
+    ext: &mut dyn Extensions,
+    settings: &ReaderSettings,
+    status: &ReaderStatus,
+    first: bool,
+    tcp_stream: &mut TcpStream,
+    capture: &Arc<sync::Mutex<Capture>>,
+    msg_id: &Arc<sync::Mutex<u32>>
+) -> Result<(), &'static str> {
+    let version = settings.current_version();
+    match status {
+        ReaderStatus::ConnectingGetSupportedVersion => {
+            send_get_supported_version(tcp_stream, capture, msg_id)?;
+            println!("-- Get Supported Version request on connection sent.");
+        },
+        ReaderStatus::ConnectingSetProtocolVersion => {
+            send_set_protocol_version(tcp_stream, capture, msg_id, settings.negotiated_version())?;
+            println!("-- Set Protocol Version request on connection sent.");
+        },
+        ReaderStatus::ConnectingKeepalive => {
+            send_set_keepalive(tcp_stream, capture, msg_id, version)?;
+            println!("-- Set Keepalive request on connection sent.");
+        },
+        ReaderStatus::ConnectingGetReaderCapabilities => {
+            send_get_reader_capabilities(tcp_stream, capture, msg_id, version, ext)?;
+            println!("-- Get Reader Capabilities request on connection sent.");
+        },
+        ReaderStatus::ConnectingGetReport => {
+            if first {
+                // held reports aren't sent until events and reports are enabled
+                send_enable_events_and_reports(tcp_stream, capture, msg_id, version)?;
+                println!("-- Send Enable Events and Reports request on connection sent.");
+            }
+            send_get_report(tcp_stream, capture, msg_id, version)?;
+            println!("-- Get Report request on connection sent.");
+        },
+        ReaderStatus::ConnectingSetReaderConfig => {
+            send_set_reader_config(tcp_stream, capture, msg_id, version, settings)?;
+            println!("-- Set Reader Config request on connection sent.");
+        },
+        ReaderStatus::ConnectingDeleteAccessSpec => {
+            if first {
+                // ENABLE_EVENTS_AND_REPORTS and GET_READER_CONFIG fail to report success from the reader
+                send_enable_events_and_reports(tcp_stream, capture, msg_id, version)?;
+                println!("-- Send Enable Events and Reports request on connection sent.");
+                send_get_reader_config(tcp_stream, capture, msg_id, version, ext)?;
+                println!("-- Get Reader Config request on connection sent.");
+            }
+            send_delete_access_spec(tcp_stream, capture, msg_id, version)?;
+            println!("-- Delete Access Spec request on connection sent.");
+        },
+        ReaderStatus::ConnectingDeleteRospec => {
+            send_delete_rospec(tcp_stream, capture, msg_id, version)?;
+            println!("-- Delete Rospec request on connection sent.");
+        },
+        ReaderStatus::ConnectingSetClock => {
+            send_set_clock(tcp_stream, capture, msg_id, version, ext)?;
+            println!("-- Set Clock request on connection sent.");
+        },
+        ReaderStatus::ConnectingAddRospec => {
+            send_add_rospec(tcp_stream, capture, msg_id, version, ext, settings)?;
+            println!("-- Add Rospec request on connection sent.");
+        },
+        ReaderStatus::ConnectingAddAccessSpec => {
+            send_add_access_spec(tcp_stream, capture, msg_id, version, settings)?;
+            println!("-- Add Access Spec request on connection sent.");
+        },
+        ReaderStatus::ConnectingEnableAccessSpec => {
+            send_enable_access_spec(tcp_stream, capture, msg_id, version)?;
+            println!("-- Enable Access Spec request on connection sent.");
+        },
+        ReaderStatus::ConnectingEnableRospec => {
+            send_enable_rospec(tcp_stream, capture, msg_id, version, settings)?;
+            println!("-- Enable Rospec request on connection sent.");
+        },
+        ReaderStatus::ConnectingStartRospec => {
+            send_start_rospec(tcp_stream, capture, msg_id, version, settings)?;
+            println!("-- Start Rospec request on connection sent.");
+        },
+        ReaderStatus::StoppingDisableRospec => {
+            stop_reading(tcp_stream, capture, next_msg_id(msg_id), version)?;
+            println!("-- Disable Rospec request on disconnect sent.");
+        },
+        ReaderStatus::StoppingDeleteRospec => {
+            send_delete_rospec(tcp_stream, capture, msg_id, version)?;
+            println!("-- Delete Rospec request on disconnect sent.");
+        },
+        other => {
+            ext.send_setup_step(other, tcp_stream, capture, msg_id, version)?;
+        },
+    }
+    Ok(())
+}
+
+pub(super) fn next_msg_id(msg_id: &Arc<sync::Mutex<u32>>) -> u32 {
+    match msg_id.lock() {
+        Ok(mut id) => {
+            *id += 1;
+            *id - 1
+        },
+        Err(_) => 0,
+    }
+}
+
+pub(super) fn write_request(tcp_stream: &mut TcpStream, capture: &Arc<sync::Mutex<Capture>>, msg: &Message) -> Result<(), &'static str> {
+    let buf = msg.encode();
+    match tcp_stream.write_all(&buf) {
+        Ok(_) => {
+            capture::record(capture, Direction::Sent, &buf);
+            Ok(())
+        },
+        Err(_) => Err("unable to write to stream"),
+    }
+}
+
+fn send_get_supported_version(tcp_stream: &mut TcpStream, capture: &Arc<sync::Mutex<Capture>>, msg_id: &Arc<sync::Mutex<u32>>) -> Result<(), &'static str> {
+    let local_id = next_msg_id(msg_id);
+    write_request(tcp_stream, capture, &requests::get_supported_version(&local_id))
+}
+
+fn send_set_protocol_version(tcp_stream: &mut TcpStream, capture: &Arc<sync::Mutex<Capture>>, msg_id: &Arc<sync::Mutex<u32>>, version: u8) -> Result<(), &'static str> {
+    let local_id = next_msg_id(msg_id);
+    write_request(tcp_stream, capture, &requests::set_protocol_version(&local_id, version))
+}
+
+fn send_set_keepalive(tcp_stream: &mut TcpStream, capture: &Arc<sync::Mutex<Capture>>, msg_id: &Arc<sync::Mutex<u32>>, version: u8) -> Result<(), &'static str> {
+    let local_id = next_msg_id(msg_id);
+    // set reader configuration     - set keepalive
+    write_request(tcp_stream, capture, &requests::set_keepalive(&local_id).version(version))
+}
+
+fn send_set_reader_config(tcp_stream: &mut TcpStream, capture: &Arc<sync::Mutex<Capture>>, msg_id: &Arc<sync::Mutex<u32>>, version: u8, settings: &ReaderSettings) -> Result<(), &'static str> {
+    let local_id = next_msg_id(msg_id);
+    // set reader configuration     - normal config with antenna power and sensitivity
+    write_request(tcp_stream, capture, &requests::set_reader_config(&local_id, &settings.antenna_configurations()).version(version))
+}
+
+fn send_get_reader_capabilities(tcp_stream: &mut TcpStream, capture: &Arc<sync::Mutex<Capture>>, msg_id: &Arc<sync::Mutex<u32>>, version: u8, ext: &dyn Extensions) -> Result<(), &'static str> {
+    let local_id = next_msg_id(msg_id);
+    write_request(tcp_stream, capture, &ext.get_reader_capabilities(&local_id).version(version))
+}
+
+fn send_enable_events_and_reports(tcp_stream: &mut TcpStream, capture: &Arc<sync::Mutex<Capture>>, msg_id: &Arc<sync::Mutex<u32>>, version: u8) -> Result<(), &'static str> {
+    let local_id = next_msg_id(msg_id);
+    // enable events and reports
+    write_request(tcp_stream, capture, &requests::enable_events_and_reports(&local_id).version(version))
+}
+
+fn send_get_report(tcp_stream: &mut TcpStream, capture: &Arc<sync::Mutex<Capture>>, msg_id: &Arc<sync::Mutex<u32>>, version: u8) -> Result<(), &'static str> {
+    let local_id = next_msg_id(msg_id);
+    // ask for any reports the reader held while we weren't connected
+    write_request(tcp_stream, capture, &requests::get_report(&local_id).version(version))
+}
+
+fn send_get_reader_config(tcp_stream: &mut TcpStream, capture: &Arc<sync::Mutex<Capture>>, msg_id: &Arc<sync::Mutex<u32>>, version: u8, ext: &dyn Extensions) -> Result<(), &'static str> {
+    let local_id = next_msg_id(msg_id);
+    write_request(tcp_stream, capture, &ext.get_reader_config(&local_id).version(version))
+}
+
+fn send_delete_access_spec(tcp_stream: &mut TcpStream, capture: &Arc<sync::Mutex<Capture>>, msg_id: &Arc<sync::Mutex<u32>>, version: u8) -> Result<(), &'static str> {
+    let local_id = next_msg_id(msg_id);
+    // delete all access spec
+    write_request(tcp_stream, capture, &requests::delete_access_spec(&local_id, &0).version(version))
+}
+
+fn send_delete_rospec(tcp_stream: &mut TcpStream, capture: &Arc<sync::Mutex<Capture>>, msg_id: &Arc<sync::Mutex<u32>>, version: u8) -> Result<(), &'static str> {
+    let local_id = next_msg_id(msg_id);
+    // delete all rospec
+    write_request(tcp_stream, capture, &requests::delete_rospec(&local_id, &0).version(version))
+}
+
+fn send_set_clock(tcp_stream: &mut TcpStream, capture: &Arc<sync::Mutex<Capture>>, msg_id: &Arc<sync::Mutex<u32>>, version: u8, ext: &dyn Extensions) -> Result<(), &'static str> {
+    let local_id = next_msg_id(msg_id);
+    let now = match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(v) => v.as_micros() as u64,
+        Err(_) => return Err("something went wrong trying to get current time"),
+    };
+    write_request(tcp_stream, capture, &ext.set_clock(&local_id, now)?.version(version))
+}
+
+fn send_add_rospec(tcp_stream: &mut TcpStream, capture: &Arc<sync::Mutex<Capture>>, msg_id: &Arc<sync::Mutex<u32>>, version: u8, ext: &dyn Extensions, settings: &ReaderSettings) -> Result<(), &'static str> {
+    let local_id = next_msg_id(msg_id);
+    let mut rospec = ext.rospec(&settings.rospec_id());
+    let (start_trigger, stop_trigger) = settings.boundary_triggers();
+    rospec.boundary.start_trigger = start_trigger;
+    rospec.boundary.stop_trigger = stop_trigger;
+    // extra tag data we've been asked to report
+    if let Some(report) = rospec.report.as_mut() {
+        if settings.tag_data.report_metadata() {
+            report.content_selector.enable_channel_index = true;
+            report.content_selector.enable_last_seen = true;
+            report.content_selector.enable_tag_seen_count = true;
+        }
+        if settings.tag_data.reads_memory() {
+            report.content_selector.enable_access_spec_id = true;
+        }
+    }
+    // only inventory the antennas that are turned on
+    let antenna_ids = settings.rospec_antenna_ids();
+    // and let the reader drop the tags we don't want before they get to us
+    let filters = settings.c1g2_filters();
+    let transmitter = settings.rospec_transmitter();
+    for ai_spec in rospec.ai_specs.iter_mut() {
+        ai_spec.antenna_ids = antenna_ids.clone();
+        if filters.is_empty() && transmitter.is_none() {
+            continue;
+        }
+        let inventory_command = match filters.is_empty() {
+            true => None,
+            false => Some(C1G2InventoryCommand {
+                tag_inventory_state_aware: false,
+                filters: filters.clone(),
+                rf_control: None,
+                singulation_control: None,
+            }),
+        };
+        for inventory_spec in ai_spec.inventory_specs.iter_mut() {
+            inventory_spec.antenna_configs.push(AntennaConfiguration {
+                antenna_id: 0,
+                receiver: None,
+                transmitter: transmitter.clone(),
+                inventory_command: inventory_command.clone(),
+            });
+        }
+    }
+    write_request(tcp_stream, capture, &requests::add_rospec(&local_id, &rospec).version(version))
+}
+
+fn send_add_access_spec(tcp_stream: &mut TcpStream, capture: &Arc<sync::Mutex<Capture>>, msg_id: &Arc<sync::Mutex<u32>>, version: u8, settings: &ReaderSettings) -> Result<(), &'static str> {
+    let local_id = next_msg_id(msg_id);
+    let access_spec = requests::access_spec(&ACCESS_SPEC_ID, &settings.rospec_id(), settings.tag_data.tid_words(), settings.tag_data.user_memory_words());
+    write_request(tcp_stream, capture, &requests::add_access_spec(&local_id, &access_spec).version(version))
+}
+
+fn send_enable_access_spec(tcp_stream: &mut TcpStream, capture: &Arc<sync::Mutex<Capture>>, msg_id: &Arc<sync::Mutex<u32>>, version: u8) -> Result<(), &'static str> {
+    let local_id = next_msg_id(msg_id);
+    write_request(tcp_stream, capture, &requests::enable_access_spec(&local_id, &ACCESS_SPEC_ID).version(version))
+}
+
+fn send_set_gpo(tcp_stream: &mut TcpStream, capture: &Arc<sync::Mutex<Capture>>, msg_id: &Arc<sync::Mutex<u32>>, version: u8, outputs: &[GPOWriteData]) -> Result<(), &'static str> {
+    if outputs.is_empty() {
+        return Ok(())
+    }
+    let local_id = next_msg_id(msg_id);
+    write_request(tcp_stream, capture, &requests::set_gpo(&local_id, outputs).version(version))
+}
+
+fn send_enable_rospec(tcp_stream: &mut TcpStream, capture: &Arc<sync::Mutex<Capture>>, msg_id: &Arc<sync::Mutex<u32>>, version: u8, settings: &ReaderSettings) -> Result<(), &'static str> {
+    let local_id = next_msg_id(msg_id);
+    write_request(tcp_stream, capture, &requests::enable_rospec(&local_id, &settings.rospec_id()).version(version))
+}
+
+fn send_start_rospec(tcp_stream: &mut TcpStream, capture: &Arc<sync::Mutex<Capture>>, msg_id: &Arc<sync::Mutex<u32>>, version: u8, settings: &ReaderSettings) -> Result<(), &'static str> {
+    let local_id = next_msg_id(msg_id);
+    write_request(tcp_stream, capture, &requests::start_rospec(&local_id, &settings.rospec_id()).version(version))
+}
+
+// Swaps the ROSpec on a reader that's already reading for the one from the active profile.
+// The reader works through the requests in order so we don't wait on each response, it'll log anything it didn't like.
+fn send_profile_change(tcp_stream: &mut TcpStream, capture: &Arc<sync::Mutex<Capture>>, msg_id: &Arc<sync::Mutex<u32>>, ext: &dyn Extensions, settings: &ReaderSettings) -> Result<(), &'static str> {
+    let version = settings.current_version();
+    if settings.tag_data.reads_memory() {
+        send_delete_access_spec(tcp_stream, capture, msg_id, version)?;
+    }
+    send_delete_rospec(tcp_stream, capture, msg_id, version)?;
+    send_add_rospec(tcp_stream, capture, msg_id, version, ext, settings)?;
+    if settings.tag_data.reads_memory() {
+        send_add_access_spec(tcp_stream, capture, msg_id, version, settings)?;
+        send_enable_access_spec(tcp_stream, capture, msg_id, version)?;
+    }
+    send_enable_rospec(tcp_stream, capture, msg_id, version, settings)?;
+    if !settings.starts_on_gpi() {
+        send_start_rospec(tcp_stream, capture, msg_id, version, settings)?;
+    }
+    Ok(())
+}
+
+pub fn stop_reader(reader: &mut super::Reader) -> Result<(), &'static str> {
+    if let Ok(mut r) = reader.status.lock() {
+        if ReaderStatus::Connected != *r {
+            return Err("not reading")
+        }
+        *r = ReaderStatus::StoppingDisableRospec;
+    } else {
+        return Err("unable to check if we're actually reading")
+    }
+    if let Ok(mut att) = reader.status_retries.lock() {
+        *att = 0;
+    }
+    let msg_id = reader.get_next_id();
+    if let Ok(stream) = reader.socket.lock() {
+        match &*stream {
+            Some(s) => {
+                let mut w_stream = match s.try_clone() {
+                    Ok(v) => v,
+                    Err(_) => return Err("unable to copy stream"),
+                };
+                match stop_reading(&mut w_stream, &reader.capture, msg_id, reader.llrp_version()) {
+                    Ok(_) => {
+                        println!("No longer reading from reader {}", reader.nickname());
+                    }
+                    Err(e) => return Err(e),
+                }
+            },
+            None => {
+                return Err("not connected")
+            }
+        }
+        Ok(())
+    } else {
+        Err("unable to get stream mutex")
+    }
+}
+
+fn stop(
+    socket: &mut TcpStream,
+    capture: &Arc<sync::Mutex<Capture>>,
+    version: u8,
+    status: &Arc<Mutex<ReaderStatus>>,
+    nickname: &String,
+    msg_mtx: &Arc<sync::Mutex<u32>>
+) {
+    if let Ok(mut r) = status.lock() {
+        if ReaderStatus::Disconnected == *r || ReaderStatus::Errored == *r {
+            return
+        }
+        *r = ReaderStatus::StoppingDisableRospec;
+    }
+    let mut msg_id = 0;
+    if let Ok(id) = msg_mtx.lock() {
+        msg_id = *id+1;
+    }
+    match stop_reading(socket, capture, msg_id, version) {
+        Ok(_) => println!("No longer reading from reader {}", nickname),
+        Err(_) => (),
+    }
+}
+
+fn save_reads(
+    map: &mut HashMap<(usize, u128), WindowedTag>,
+    settings: &ReaderSettings,
+    control: &Arc<Mutex<control::Control>>,
+    sqlite: &Arc<Mutex<sqlite::SQLite>>,
+    r_name: &str
+) {
+    let mut reads: Vec<read::Read> = Vec::new();
+    for old in map.values().filter(|old| !old.sent) {
+        let mut chip_type = String::from(defaults::DEFAULT_CHIP_TYPE);
+        if let Ok(control) = control.lock() {
+            control.chip_type.clone_into(&mut chip_type);
+        }
+        reads.push(old.tag.to_read(settings, &chip_type, r_name));
+    }
+    if let Ok(control) = control.lock() {
+        control.resolve_bibs(&mut reads);
+    }
+    if reads.len() > 0 {
+        match sqlite.lock() {
+            Ok(mut db) => {
+                match db.save_reads(&reads) {
+                    Ok(_num) => {},
+                    Err(e) => println!("Error saving reads. {e}"),
+                }
+            },
+            Err(e) => {
+                println!("Error saving reads on thread close. {e}");
+            }
+        }
+    }
+}
+
+// Saves the reads the reader held for us while we were disconnected. Their windows are long over so they're
+// all saved at once, leaving out any we already saved before the connection was lost. Returns the reads saved.
+fn save_recovered(
+    tags: &mut Vec<TagData>,
+    settings: &ReaderSettings,
+    control: &Arc<Mutex<control::Control>>,
+    sqlite: &Arc<Mutex<sqlite::SQLite>>,
+    unsaved_reads: &mut Vec<read::Read>,
+    read_saver: &Arc<processor::ReadSaver>,
+    r_name: &str
+) -> Vec<read::Read> {
+    if tags.is_empty() {
+        return Vec::new()
+    }
+    let mut global = defaults::DEFAULT_READ_WINDOW;
+    let mut chip_type = String::from(defaults::DEFAULT_CHIP_TYPE);
+    if let Ok(control) = control.lock() {
+        global = control.read_window;
+        control.chip_type.clone_into(&mut chip_type);
+    }
+    let window = (global as u128) * 100000;
+    let mut reads = window_tags(&mut HashMap::new(), tags, settings, u128::MAX, window, &chip_type, r_name);
+    tags.clear();
+    if let Ok(control) = control.lock() {
+        control.resolve_bibs(&mut reads);
+    }
+    let held = reads.len();
+    // match against the longest window the reader uses so nothing we saved is missed
+    let mut longest = window;
+    if let Ok(windows) = settings.read_windows.lock() {
+        longest = (windows.max_window(global) as u128) * 100000;
+    }
+    if let Ok(db) = sqlite.lock() {
+        match db.get_unknown_reads(&reads, (longest / 1000) as u64) {
+            Ok(unknown) => reads = unknown,
+            Err(e) => println!("Error checking recovered reads against saved reads. {e}"),
+        }
+    }
+    println!("Recovered {} of {} reads held by the reader.", reads.len(), held);
+    if reads.is_empty() {
+        return reads
+    }
+    unsaved_reads.append(&mut reads.clone());
+    if read_saver.save_reads(unsaved_reads).is_err() {
+        println!("something went wrong saving reads");
+    } else {
+        unsaved_reads.clear();
+    }
+    reads
+}
+
+fn reads_saved(tracker: &Arc<sync::Mutex<StatsTracker>>, count: usize) {
+    if let Ok(mut tracker) = tracker.lock() {
+        tracker.reads_saved(stats::now(), count);
+    }
+}
+
+fn send_antennas(
+    reader_name: &str,
+    antennas: &Arc<Mutex<[u8;MAX_ANTENNAS]>>,
+    control_sockets: &Arc<Mutex<[Option<TcpStream>;MAX_CONNECTED+1]>>
+) -> Result<(), &'static str> {
+    let mut no_error = true;
+    if let Ok(sockets) = control_sockets.lock() {
+        if let Ok(ant) = antennas.lock() {
+            for ix in 0..MAX_CONNECTED {
+                match &sockets[ix] {
+                    Some(sock) => {
+                        no_error = no_error && socket::write_reader_antennas(sock, reader_name.to_string(), &*ant)
+                    },
+                    None => {}
+                }
+            }
+        } else {
+            return Err("error getting antennas mutex")
+        }
+    } else {
+        return Err("error getting sockets mutex")
+    }
+    if no_error == false {
+        return Err("error occurred writing to one or more sockets")
+    }
+    Ok(())
+}
+
+fn send_error(
+    error: ReaderError,
+    errors: &Arc<Mutex<ErrorLog>>,
+    control_sockets: &Arc<Mutex<[Option<TcpStream>;MAX_CONNECTED+1]>>
+) -> Result<(), &'static str> {
+    let mut no_error = true;
+    if let Ok(sockets) = control_sockets.lock() {
+        for sock in sockets.iter().take(MAX_CONNECTED).flatten() {
+            no_error = socket::write_response(sock, &Responses::ReaderError {
+                reader_name: error.reader_name.clone(),
+                state: error.state.clone(),
+                code: error.code,
+                description: error.description.clone(),
+            }) && no_error
+        }
+    } else {
+        return Err("error getting sockets mutex")
+    }
+    if let Ok(mut log) = errors.lock() {
+        log.push(error);
+    } else {
+        return Err("error getting errors mutex")
+    }
+    if !no_error {
+        return Err("error occurred writing to one or more sockets")
+    }
+    Ok(())
+}
+
+fn send_clock_warning(
+    status: ClockStatus,
+    control_sockets: &Arc<Mutex<[Option<TcpStream>;MAX_CONNECTED+1]>>
+) -> Result<(), &'static str> {
+    let mut no_error = true;
+    if let Ok(sockets) = control_sockets.lock() {
+        for sock in sockets.iter().take(MAX_CONNECTED).flatten() {
+            no_error = socket::write_response(sock, &Responses::ReaderClockWarning { status: status.clone() }) && no_error
+        }
+    } else {
+        return Err("error getting sockets mutex")
+    }
+    if !no_error {
+        return Err("error occurred writing to one or more sockets")
+    }
+    Ok(())
+}
+
+fn send_new(
+    reads: Vec<read::Read>,
+    control_sockets: &Arc<Mutex<[Option<TcpStream>;MAX_CONNECTED+1]>>,
+    read_repeaters: &Arc<Mutex<[bool;MAX_CONNECTED]>>,
+) -> Result<(), &'static str> {
+    let mut no_error = true;
+    if let Ok(sockets) = control_sockets.lock() {
+        if let Ok(mut repeaters) = read_repeaters.lock() {
+            for ix in 0..MAX_CONNECTED {
+                match &sockets[ix] {
+                    Some(sock) => {
+                        if repeaters[ix] == true {
+                            //println!("Sending reads to subscribed socket {ix}.");
+                            // If write_reads returned false it wasn't able to write the reads due to connection being broken.
+                            let loc_err = socket::write_reads(&sock, &reads);
+                            if !loc_err {
+                                repeaters[ix] = false;
+                                if let Err(e) = sock.shutdown(std::net::Shutdown::Both) {
+                                    println!("Error shutting down closed socket. {e}");
+                                }
+                            }
+                            no_error = no_error && loc_err;
+                        }
+                    },
+                    None => {}
+                }
+            }
+        } else {
+            return Err("error getting repeaters mutex")
+        }
+    } else {
+        return Err("error getting sockets mutex")
+    }
+    if no_error == false {
+        return Err("error occurred writing to one or more sockets")
+    }
+    Ok(())
+}
+
+fn process_tags(
+    map: &mut HashMap<(usize, u128), WindowedTag>,
+    tags: &mut Vec<TagData>,
+    settings: &ReaderSettings,
+    unsaved_reads: &mut Vec<read::Read>,
+    control: &Arc<Mutex<control::Control>>,
+    read_saver: &Arc<processor::ReadSaver>,
+    r_name: &str
+) -> Result<Vec<read::Read>, &'static str> {
+    let since_epoch = match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(v) => v.as_micros(),
+        Err(_) => return Err("something went wrong trying to get current time")
+    };
+    // get the read window from 1/10 of a second to milliseconds
+    let mut window = (defaults::DEFAULT_READ_WINDOW as u128) * 100000;
+    let mut chip_type = String::from(defaults::DEFAULT_CHIP_TYPE);
+    if let Ok(control) = control.lock() {
+        window = (control.read_window as u128) * 100000;
+        control.chip_type.clone_into(&mut chip_type);
+    }
+    let mut reads = window_tags(map, tags, settings, since_epoch, window, &chip_type, r_name);
+    if let Ok(mut control) = control.lock() {
+        control.resolve_bibs(&mut reads);
+        control.bibs_seen(&reads);
+    }
+    if reads.len() > 0 || unsaved_reads.len() > 0 {
+        let cloned_reads = &mut reads.clone();
+        unsaved_reads.append(cloned_reads);
+        // upload reads to database
+        if let Err(_) = read_saver.save_reads(unsaved_reads) {
+            println!("something went wrong saving reads");
+        } else { // was able to add reads to save queue
+            unsaved_reads.clear();
+        }
+    }
+    Ok(reads)
+}
+
+// A tag seen inside its read window and the sighting we're keeping as its read.
+pub(super) struct WindowedTag {
+    first_seen: u128,
+    // Read window of the antenna the tag was first seen on.
+    window: u128,
+    tag: TagData,
+    // The kept sighting was already sent as a read, so it isn't sent again when the window closes.
+    sent: bool,
+}
+
+// Keeps the sighting of each tag the dedup mode picks inside the read window, tags that were first seen more
+// than a second past the window before now are turned into reads and removed from the map. Each dedup group
+// gets its own window, so the map is keyed by group and tag. The window given is the global read window, antennas
+// and readers with their own read window use that instead.
+fn window_tags(
+    map: &mut HashMap<(usize, u128), WindowedTag>,
+    tags: &mut Vec<TagData>,
+    settings: &ReaderSettings,
+    now: u128,
+    window: u128,
+    chip_type: &str,
+    r_name: &str
+) -> Vec<read::Read> {
+    let one_second = 1000000;
+    // the reader shouldn't be reporting reads from antennas we turned off, but just in case
+    let disabled_antennas = settings.disabled_antennas();
+    tags.retain(|tag| !disabled_antennas.contains(&tag.antenna));
+    // reads are reported as the antenna the port is mapped to
+    for tag in tags.iter_mut() {
+        tag.antenna = settings.reported_antenna(tag.antenna);
+    }
+    // sort tags so the earliest seen are first
+    tags.sort_by(|a, b| a.portal_time.cmp(&b.portal_time));
+    let emit_first = settings.dedup.emit_first();
+    let mut reads: Vec<read::Read> = Vec::new();
+    for tag in tags {
+        let group = settings.dedup.group(tag.antenna);
+        let mode = settings.dedup.group_mode(group);
+        // every sighting is a read
+        if mode == DEDUP_MODE_ALL {
+            reads.push(tag.to_read(settings, chip_type, r_name));
+            continue;
+        }
+        let key = (group, tag.tag);
+        // check if the map contains the tag
+        if let Some(old) = map.remove(&key) {
+            // check if we're in the window
+            // First Seen + Window is a value greater than when we've seen this tag
+            // then we are in the window
+            if old.first_seen + old.window > tag.portal_time {
+                let replace = match mode {
+                    DEDUP_MODE_FIRST => false,
+                    DEDUP_MODE_LAST => true,
+                    // if our new tag has a higher rssi we want to record it
+                    _ => tag.rssi > old.tag.rssi,
+                };
+                if replace {
+                    let mut kept = tag.clone();
+                    kept.first_seen = old.first_seen;
+                    // not every read of a tag gets its memory read, so keep what we already have
+                    if kept.tid.is_none() {
+                        kept.tid = old.tag.tid;
+                    }
+                    if kept.user_memory.is_none() {
+                        kept.user_memory = old.tag.user_memory;
+                    }
+                    map.insert(key, WindowedTag { first_seen: old.first_seen, window: old.window, tag: kept, sent: false });
+                } else {
+                    map.insert(key, old);
+                }
+                continue;
+            }
+            // otherwise we can save the old value and start a new one for this tag
+            if !old.sent {
+                reads.push(old.tag.to_read(settings, chip_type, r_name));
+            }
+        }
+        if emit_first {
+            reads.push(tag.to_read(settings, chip_type, r_name));
+        }
+        map.insert(key, WindowedTag { first_seen: tag.portal_time, window: settings.read_window(tag.antenna, window), tag: tag.clone(), sent: emit_first });
+    }
+    let mut removed: Vec<(usize, u128)> = Vec::new();
+    for (key, old) in map.iter() {
+        // if we're 1 second past the window
+        if old.first_seen + old.window + one_second < now {
+            if !old.sent {
+                reads.push(old.tag.to_read(settings, chip_type, r_name));
+            }
+            removed.push(*key);
+        }
+    }
+    for to_remove in removed {
+        map.remove(&to_remove);
+    }
+    reads
+}
+
+pub fn replay(
+    reader: &super::Reader,
+    records: &[capture::Record],
+    sqlite: &sqlite::SQLite,
+    control: &control::Control,
+) -> Result<Vec<read::Read>, &'static str> {
+    replay_with(reader, records, sqlite, control, &NoExtensions)
+}
+
+// Feeds what the reader sent us in a capture through the decoder and the read window the same way the
+// reading loop does. Tags are given the time they were captured at so the windows match what happened.
+pub(super) fn replay_with(
+    reader: &super::Reader,
+    records: &[capture::Record],
+    sqlite: &sqlite::SQLite,
+    control: &control::Control,
+    ext: &dyn Extensions,
+) -> Result<Vec<read::Read>, &'static str> {
+    let settings = ReaderSettings::load(reader, sqlite);
+    let window = (control.read_window as u128) * 100000;
+    let mut decoder = Decoder::new();
+    let mut read_map: HashMap<(usize, u128), WindowedTag> = HashMap::new();
+    let mut output: Vec<read::Read> = Vec::new();
+    for record in records.iter().filter(|r| r.direction == Direction::Received) {
+        decoder.push(&record.bytes()?);
+        let mut tags: Vec<TagData> = Vec::new();
+        while let Some(res) = decoder.next_message() {
+            match res {
+                Ok(msg) if msg.kind == message_types::RO_ACCESS_REPORT => {
+                    match process_tag_reads(&msg, ext) {
+                        Ok(mut t) => tags.append(&mut t),
+                        Err(e) => println!("Error processing tag report. {e}"),
+                    }
+                },
+                Ok(_) => {},
+                Err(e) => println!("Error decoding message from reader. {e}"),
+            }
+        }
+        for tag in tags.iter_mut() {
+            tag.portal_time = record.time as u128;
+        }
+        output.append(&mut window_tags(&mut read_map, &mut tags, &settings, record.time as u128, window, &control.chip_type, reader.nickname()));
+    }
+    // anything left would have been saved once its window was up
+    output.append(&mut window_tags(&mut read_map, &mut Vec::new(), &settings, u128::MAX, window, &control.chip_type, reader.nickname()));
+    Ok(output)
+}
+
+fn stop_reading(t_stream: &mut TcpStream, capture: &Arc<sync::Mutex<Capture>>, msg_id: u32, version: u8) -> Result<(), &'static str> {
+    // disable rospec
+    write_request(t_stream, capture, &requests::disable_rospec(&msg_id, &0).version(version))
+}
+
+fn finalize(
+    t_stream: &mut TcpStream,
+    capture: &Arc<sync::Mutex<Capture>>,
+    version: u8,
+    msg_id: &Arc<sync::Mutex<u32>>,
+    status: &Arc<sync::Mutex<ReaderStatus>>,
+    last_ka_received_at: u64,
+    ext: &dyn Extensions,
+) {
+    // finalize what we're doing
+    let mut fin_id = match msg_id.lock() {
+        Ok(id) => *id,
+        Err(_) => 0,
+    };
+    if let Ok(r) = status.lock() {
+        if ReaderStatus::Disconnected != *r && ReaderStatus::Errored != *r {
+            match stop_reading(t_stream, capture, fin_id, version) {
+                Ok(_) => (),
+                Err(e) => println!("Error trying to stop reading. {e}"),
+            };
+            fin_id = fin_id + 2;
+        }
+    }
+    let close = requests::close_connection(&fin_id).version(version).encode();
+    let buf: &mut [u8; BUFFER_SIZE] = &mut [0;BUFFER_SIZE];
+    let mut decoder = Decoder::new();
+    match t_stream.write_all(&close) {
+        Ok(_) => {
+            capture::record(capture, Direction::Sent, &close);
+            match read(t_stream, capture, version, buf, &mut decoder, last_ka_received_at, ext) {
+                Ok(_) => (),
+                Err(e) => {
+                    match e.kind() {
+                        ErrorKind::ConnectionAborted | ErrorKind::ConnectionReset | ErrorKind::TimedOut | ErrorKind::WouldBlock => (),
+                        _ => println!("Error reading from reader. {e}"),
+                    }
+                }
+            }
+        },
+        Err(e) => {
+            if e.kind() != ErrorKind::BrokenPipe {
+                println!("Error closing connection. {e}")
+            }
+        },
+    }
+}
+
+fn read(
+    tcp_stream: &mut TcpStream,
+    capture: &Arc<sync::Mutex<Capture>>,
+    version: u8,
+    buf: &mut [u8;BUFFER_SIZE],
+    decoder: &mut Decoder,
+    last_ka_received_at: u64,
+    ext: &dyn Extensions,
+) -> Result<ReadData, std::io::Error> {
+    let mut output = ReadData {
+        tags: Vec::new(),
+        antenna_data: false,
+        antennas: [0;MAX_ANTENNAS],
+        capabilities: None,
+        supported_version: None,
+        clock_samples: Vec::new(),
+        keepalive: false,
+        last_ka_received_at,
+        status_messages: Vec::new(),
+    };
+    let num = tcp_stream.read(buf)?;
+    capture::record(capture, Direction::Received, &buf[..num]);
+    decoder.push(&buf[..num]);
+    // message could contain multiple messages, so process them all
+    while let Some(res) = decoder.next_message() {
+        match res {
+            Ok(msg) => process_message(&msg, tcp_stream, capture, version, &mut output, ext),
+            Err(e) => println!("Error decoding message from reader. {e}"),
+        }
+    }
+    Ok(output)
+}
+
+fn process_message(
+    msg: &decoder::Message,
+    tcp_stream: &mut TcpStream,
+    capture: &Arc<sync::Mutex<Capture>>,
+    version: u8,
+    output: &mut ReadData,
+    ext: &dyn Extensions,
+) {
+    match msg.kind {
+        llrp::message_types::KEEPALIVE => {
+            let local_received_at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+            if local_received_at > output.last_ka_received_at {
+                output.last_ka_received_at = local_received_at
+            }
+            output.keepalive = true;
+            let response = requests::keepalive_ack(&msg.id).version(version).encode();
+            match tcp_stream.write_all(&response) {
+                Ok(_) => capture::record(capture, Direction::Sent, &response),
+                Err(e) => {
+                    if e.kind() != ErrorKind::BrokenPipe {
+                        eprintln!("Error responding to keepalive. {e}")
+                    }
+                },
+            }
+        },
+        llrp::message_types::RO_ACCESS_REPORT => {
+            match process_tag_reads(msg, ext) {
+                Ok(mut tags) => {
+                    // the last time the reader saw a tag is closest to when it sent the report
+                    for tag in tags.iter() {
+                        output.clock_samples.push((tag.reader_time.max(tag.last_seen), tag.portal_time));
+                    }
+                    output.tags.append(&mut tags);
+                },
+                Err(e) => {
+                    println!("Error processing tag report. {e}");
+                },
+            };
+            // reports carry no status, but one is how the reader answers GET_REPORT
+            output.status_messages.push((msg.kind, LLRPStatus::default()));
+        },
+        llrp::message_types::GET_READER_CONFIG_RESPONSE => {
+            match process_reader_config(msg, ext) {
+                Ok(antennas) => {
+                    if let Some(ant) = antennas {
+                        output.antennas = ant;
+                        output.antenna_data = true;
+                    };
+                },
+                Err(e) => {
+                    println!("Error processing reader config. {e}");
+                },
+            }
+        },
+        llrp::message_types::GET_READER_CAPABILITIES_RESPONSE => {
+            let status = process_llrp_status_parameter(msg);
+            if status.success() {
+                match capabilities::from_response(msg) {
+                    Ok(caps) => output.capabilities = Some(caps),
+                    Err(e) => println!("Error processing reader capabilities. {e}"),
+                }
+            }
+            output.status_messages.push((msg.kind, status));
+        },
+        llrp::message_types::GET_SUPPORTED_VERSION_RESPONSE => {
+            // the current version comes first, followed by the highest version the reader supports
+            let status = process_llrp_status_parameter(msg);
+            if status.success() {
+                match msg.u8_at(1) {
+                    Ok(supported) => output.supported_version = Some(supported),
+                    Err(e) => println!("Error processing supported version. {e}"),
+                }
+            }
+            output.status_messages.push((msg.kind, status));
+        },
+        llrp::message_types::READER_EVENT_NOTIFICATION => {
+            if let Ok(Some(ant)) = process_reader_event_notification(msg) {
+                output.antennas[ant.0] = ant.1;
+                output.antenna_data = true;
+            }
+            if let Some(time) = msg.find(parameter_types::READER_EVENT_NOTIFICATION_DATA)
+                .and_then(|data| data.find(parameter_types::UTC_TIMESTAMP))
+                .and_then(|timestamp| timestamp.u64_at(0).ok()) {
+                output.clock_samples.push((time as u128, SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_micros()));
+            }
+        }, // Processing of initialization and shutdown commands.
+        llrp::message_types::ADD_ROSPEC_RESPONSE |
+        llrp::message_types::ENABLE_ROSPEC_RESPONSE |
+        llrp::message_types::START_ROSPEC_RESPONSE |
+        llrp::message_types::STOP_ROSPEC_RESPONSE |
+        llrp::message_types::DISABLE_ROSPEC_RESPONSE |
+        llrp::message_types::DELETE_ROSPEC_RESPONSE |
+        llrp::message_types::ADD_ACCESS_SPEC_RESPONSE |
+        llrp::message_types::ENABLE_ACCESS_SPEC_RESPONSE |
+        llrp::message_types::DELETE_ACCESS_SPEC_RESPONSE |
+        llrp::message_types::SET_PROTOCOL_VERSION_RESPONSE |
+        llrp::message_types::SET_READER_CONFIG_RESPONSE => {
+            let status = process_llrp_status_parameter(msg);
+            output.status_messages.push((msg.kind, status));
+        },
+        llrp::message_types::ERROR_MESSAGE => {
+            // the reader couldn't process a message we sent, most likely because it doesn't support it
+            let status = process_llrp_status_parameter(msg);
+            output.status_messages.push((msg.kind, status));
+        },
+        llrp::message_types::CUSTOM_MESSAGE => {
+            match msg.custom_info() {
+                Ok((vendor, subtype)) => {
+                    if ext.is_custom_response(vendor, subtype) {
+                        output.status_messages.push((msg.kind, process_llrp_status_parameter(msg)));
+                    }
+                },
+                Err(e) => println!("Error processing custom message. {e}"),
+            };
+        },
+        _ => {},
+    }
+}
+
+fn process_reader_event_notification(msg: &decoder::Message) -> Result<Option<(usize, u8)>, DecodeError> {
+    let data = match msg.find(parameter_types::READER_EVENT_NOTIFICATION_DATA) {
+        Some(data) => data,
+        None => return Err(DecodeError::MissingParameter(String::from("reader event notification data"))),
+    };
+    let mut output: Option<(usize, u8)> = None;
+    for event in data.find_all(parameter_types::ANTENNA_EVENT) {
+        // byte 0 is the connected bit, 0x00 if not connected, 0x01 if connected
+        // bytes 1 and 2 are the antenna number
+        let mut number = event.u16_at(1)? as usize;
+        if number > MAX_ANTENNAS {
+            return Err(DecodeError::InvalidField(String::from("antenna number greater than the max number of antennas supported")))
+        } else if number > 0 {
+            number -= 1;
+        }
+        output = match event.u8_at(0)? {
+            0x00 => Some((number, ANTENNA_STATUS_DISCONNECTED)),
+            _ => Some((number, ANTENNA_STATUS_CONNECTED)),
+        };
+    }
+    Ok(output)
+}
+
+fn process_llrp_status_parameter(msg: &decoder::Message) -> LLRPStatus {
+    // The LLRPStatus parameter is the first parameter in every response.
+    // It contains a status code, an error description, and optionally FieldError and ParameterError parameters.
+    // A status we can't read is reported as an error so it doesn't get mistaken for a success.
+    let param = match msg.find(parameter_types::LLRP_STATUS) {
+        Some(param) => param,
+        None => {
+            println!("no llrp status parameter found in {:?}", get_message_name(msg.kind));
+            return LLRPStatus {
+                code: parameter_types::M_MISSING_PARAMETER,
+                description: DecodeError::MissingParameter(String::from("llrp status")).to_string(),
+                field_error: None,
+                parameter_error: None,
+            }
+        }
+    };
+    match decoder::llrp_status(param) {
+        Ok(status) => status,
+        Err(e) => LLRPStatus {
+            code: parameter_types::M_PARAMETER_ERROR,
+            description: e.to_string(),
+            field_error: None,
+            parameter_error: None,
+        }
+    }
+}
+
+fn process_reader_config(msg: &decoder::Message, ext: &dyn Extensions) -> Result<Option<[u8;MAX_ANTENNAS]>, DecodeError> {
+    let mut output: [u8;MAX_ANTENNAS] = [0;MAX_ANTENNAS];
+    let mut antenna_found = false;
+    for param in msg.parameters.iter() {
+        match param.kind {
+            parameter_types::ANTENNA_PROPERTIES => {
+                // byte 0 is the connected bit, 0x00 if not connected, 0x80 if connected
+                // bytes 1 and 2 are the antenna number
+                // bytes 3 and 4 are the antenna gain -- ignore
+                let number = param.u16_at(1)? as usize;
+                // antennas past what we support can be ignored
+                if number > 0 && number <= MAX_ANTENNAS {
+                    output[number-1] = match param.u8_at(0)? {
+                        0x00 => ANTENNA_STATUS_DISCONNECTED,
+                        _ => ANTENNA_STATUS_CONNECTED,
+                    };
+                    antenna_found = true;
+                }
+            },
+            parameter_types::ANTENNA_CONFIGURATION => { },
+            parameter_types::READER_EVENT_NOTIFICATION_SPEC => { },
+            parameter_types::RO_REPORT_SPEC => { },
+            parameter_types::ACCESS_REPORT_SPEC => { },
+            parameter_types::LLRP_CONFIGURATION_STATE_VALUE => { },
+            parameter_types::KEEPALIVE_SPEC => { },
+            parameter_types::GPI_PORT_CURRENT_STATE => { },
+            parameter_types::GPO_WRITE_DATA => { },
+            parameter_types::EVENTS_AND_REPORTS => { },
+            parameter_types::LLRP_STATUS => { },
+            parameter_types::IDENTIFICATION => { },
+            parameter_types::CUSTOM_PARAMETER => { },
+            other => {
+                println!("unknown parameter type found: {:?}", other);
+            }
+        }
+    }
+    // vendor parameters get the last word on antenna status
+    for custom in msg.find_all(parameter_types::CUSTOM_PARAMETER) {
+        if ext.process_config_custom_parameter(custom, &mut output) {
+            antenna_found = true;
+        }
+    }
+    if !antenna_found {
+        return Ok(None)
+    }
+    Ok(Some(output))
+}
+
+fn process_tag_reads(msg: &decoder::Message, ext: &dyn Extensions) -> Result<Vec<TagData>, DecodeError> {
+    let mut output: Vec<TagData> = Vec::new();
+    // an RO_ACCESS_REPORT can contain any number of TagReportData parameters
+    for report in msg.find_all(parameter_types::TAG_REPORT_DATA) {
+        output.push(process_tag_report_data(report, ext)?);
+    }
+    Ok(output)
+}
+
+fn process_tag_report_data(report: &decoder::Parameter, ext: &dyn Extensions) -> Result<TagData, DecodeError> {
+    let mut data: TagData = TagData {
+        tag: 0,
+        antenna: 0,
+        rssi: 0,
+        first_seen: 0,
+        last_seen: 0,
+        reader_time: 0,
+        portal_time: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_micros(),
+        channel_index: None,
+        seen_count: None,
+        tid: None,
+        user_memory: None,
+    };
+    for param in report.parameters.iter() {
+        match param.kind {
+            // don't need these next three
+            parameter_types::RO_SPEC_ID => { },
+            parameter_types::C1G2_PC => { },
+            parameter_types::C1G2_CRC => { },
+            // need these
+            parameter_types::EPC_96 if param.tv => {
+                for byte in param.bytes_at(0, 12)? {
+                    data.tag = (data.tag << 8) + *byte as u128;
+                }
+            },
+            parameter_types::EPC_DATA if !param.tv => {
+                // bytes 0 and 1 are the length of the epc in bits
+                let bits = param.u16_at(0)? as usize;
+                let bytes = bits.div_ceil(8);
+                if bytes > 16 {
+                    return Err(DecodeError::InvalidField(String::from("epc too long")))
+                }
+                for byte in param.bytes_at(2, bytes)? {
+                    data.tag = (data.tag << 8) + *byte as u128;
+                }
+            },
+            parameter_types::ANTENNA_ID => {
+                data.antenna = param.u16_at(0)?;
+            },
+            parameter_types::PEAK_RSSI => {
+                data.rssi = param.u8_at(0)? as i8;
+            },
+            parameter_types::FIRST_SEEN_TIMESTAMP_UTC => {
+                data.reader_time = param.u64_at(0)? as u128;
+            },
+            parameter_types::LAST_SEEN_TIMESTAMP_UTC => {
+                data.last_seen = param.u64_at(0)? as u128;
+            },
+            parameter_types::CHANNEL_INDEX => {
+                data.channel_index = Some(param.u16_at(0)?);
+            },
+            parameter_types::TAG_SEEN_COUNT => {
+                data.seen_count = Some(param.u16_at(0)?);
+            },
+            parameter_types::C1G2_READ_OP_SPEC_RESULT => {
+                // result, op spec id, word count, then the words read
+                if param.u8_at(0)? != 0 {
+                    continue;
+                }
+                let words = param.u16_at(3)? as usize;
+                let memory: String = param.bytes_at(5, words * 2)?.iter().map(|b| format!("{:02x}", b)).collect();
+                match param.u16_at(1)? {
+                    requests::OP_SPEC_ID_TID => data.tid = Some(memory),
+                    requests::OP_SPEC_ID_USER_MEMORY => data.user_memory = Some(memory),
+                    _ => {},
+                }
+            },
+            _ => {
+                //println!("Unknown value found.")
+            }
+        }
+    }
+    for custom in report.find_all(parameter_types::CUSTOM_PARAMETER) {
+        ext.process_tag_custom_parameter(custom, &mut data);
+    }
+    Ok(data)
+}
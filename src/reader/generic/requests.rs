@@ -0,0 +1,258 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::llrp::{encoder::{self, AISpec, AISpecStopTrigger, AccessCommand, AccessReportSpec, AccessSpec, AccessSpecStopTrigger, AntennaConfiguration, C1G2EPCMemorySelector, C1G2Read, C1G2TagSpec, C1G2TargetTag, EventNotificationState, EventsAndReports, GPOWriteData, InventoryParameterSpec, KeepaliveSpec, Message, ROBoundarySpec, ROReportSpec, ROSpec, ReaderEventNotificationSpec, TagReportContentSelector}, message_types};
+
+// Op spec ids for the memory reads in our AccessSpec, the results in tag reports carry them.
+pub const OP_SPEC_ID_TID: u16 = 1;
+pub const OP_SPEC_ID_USER_MEMORY: u16 = 2;
+
+// Version management messages are only understood by 1.1 readers, so they're always sent as 1.1.
+// A 1.0.1 reader answers them with an ERROR_MESSAGE.
+pub fn get_supported_version(id: &u32) -> Message {
+    Message::new(message_types::GET_SUPPORTED_VERSION, *id)
+        .version(encoder::VERSION_1_1)
+}
+
+pub fn set_protocol_version(id: &u32, version: u8) -> Message {
+    Message::new(message_types::SET_PROTOCOL_VERSION, *id)
+        .version(encoder::VERSION_1_1)
+        .u8(version)
+}
+
+pub fn get_reader_capabilities(id: &u32) -> Message {
+    // all capabilities
+    Message::new(message_types::GET_READER_CAPABILITIES, *id)
+        .u8(0)
+}
+
+// The ROSpec we use by default. It starts on START_ROSPEC, runs until it is stopped, inventories
+// every antenna, and reports every read to us as it happens.
+pub fn rospec(rospec_id: &u32) -> ROSpec {
+    ROSpec {
+        id: *rospec_id,
+        priority: 0,
+        current_state: encoder::ROSPEC_STATE_DISABLED,
+        boundary: ROBoundarySpec::default(),
+        ai_specs: vec![AISpec {
+            // one antenna with an id of 0 means all antennas
+            antenna_ids: vec![0],
+            stop_trigger: AISpecStopTrigger::default(),
+            inventory_specs: vec![InventoryParameterSpec {
+                id: 19,
+                protocol: encoder::PROTOCOL_EPC_GLOBAL_C1G2,
+                antenna_configs: Vec::new(),
+                custom: Vec::new(),
+            }],
+        }],
+        report: Some(ROReportSpec {
+            // end of rospec and N=1 tells it to report every read to us
+            trigger: encoder::REPORT_TRIGGER_END_OF_RO_SPEC,
+            n: 1,
+            content_selector: TagReportContentSelector {
+                enable_rospec_id: true,
+                enable_antenna_id: true,
+                enable_peak_rssi: true,
+                enable_first_seen: true,
+                memory_selectors: vec![C1G2EPCMemorySelector::default()],
+                ..Default::default()
+            },
+            custom: Vec::new(),
+        }),
+    }
+}
+
+pub fn add_rospec(id: &u32, rospec: &ROSpec) -> Message {
+    Message::new(message_types::ADD_ROSPEC, *id)
+        .param(rospec)
+}
+
+pub fn delete_rospec(id: &u32, rospec_id: &u32) -> Message {
+    with_spec_id(message_types::DELETE_ROSPEC, id, rospec_id)
+}
+
+pub fn start_rospec(id: &u32, rospec_id: &u32) -> Message {
+    with_spec_id(message_types::START_ROSPEC, id, rospec_id)
+}
+
+pub fn stop_rospec(id: &u32, rospec_id: &u32) -> Message {
+    with_spec_id(message_types::STOP_ROSPEC, id, rospec_id)
+}
+
+pub fn enable_rospec(id: &u32, rospec_id: &u32) -> Message {
+    with_spec_id(message_types::ENABLE_ROSPEC, id, rospec_id)
+}
+
+pub fn disable_rospec(id: &u32, rospec_id: &u32) -> Message {
+    with_spec_id(message_types::DISABLE_ROSPEC, id, rospec_id)
+}
+
+pub fn get_rospecs(id: &u32) -> Message {
+    empty(message_types::GET_ROSPECS, id)
+}
+
+pub fn delete_access_spec(id: &u32, as_id: &u32) -> Message {
+    with_spec_id(message_types::DELETE_ACCESS_SPEC, id, as_id)
+}
+
+pub fn get_access_specs(id: &u32) -> Message {
+    empty(message_types::GET_ACCESS_SPECS, id)
+}
+
+// The AccessSpec we use to read TID and user memory from every tag the ROSpec inventories.
+// Memory banks with 0 words aren't read.
+pub fn access_spec(access_spec_id: &u32, rospec_id: &u32, tid_words: u16, user_memory_words: u16) -> AccessSpec {
+    let mut reads: Vec<C1G2Read> = Vec::new();
+    for (op_spec_id, memory_bank, word_count) in [
+        (OP_SPEC_ID_TID, encoder::MEMORY_BANK_TID, tid_words),
+        (OP_SPEC_ID_USER_MEMORY, encoder::MEMORY_BANK_USER, user_memory_words),
+    ] {
+        if word_count == 0 {
+            continue;
+        }
+        reads.push(C1G2Read {
+            op_spec_id,
+            access_password: 0,
+            memory_bank,
+            word_pointer: 0,
+            word_count,
+        });
+    }
+    AccessSpec {
+        id: *access_spec_id,
+        antenna_id: 0,
+        protocol: encoder::PROTOCOL_EPC_GLOBAL_C1G2,
+        current_state: false,
+        rospec_id: *rospec_id,
+        // keep running until it's deleted
+        stop_trigger: AccessSpecStopTrigger::default(),
+        command: AccessCommand {
+            // an empty target matches every tag
+            tag_spec: C1G2TagSpec {
+                targets: vec![C1G2TargetTag {
+                    memory_bank: encoder::MEMORY_BANK_EPC,
+                    matching: true,
+                    ..Default::default()
+                }],
+            },
+            reads,
+        },
+        // results come back in the tag report for the read
+        report: Some(AccessReportSpec { trigger: 0 }),
+    }
+}
+
+pub fn add_access_spec(id: &u32, access_spec: &AccessSpec) -> Message {
+    Message::new(message_types::ADD_ACCESS_SPEC, *id)
+        .param(access_spec)
+}
+
+pub fn enable_access_spec(id: &u32, as_id: &u32) -> Message {
+    with_spec_id(message_types::ENABLE_ACCESS_SPEC, id, as_id)
+}
+
+// config value -
+//      0 all,
+//      1 identification,
+//      2 antenna properties,
+//      3 antenna configuration,
+//      4 ROReportSpec,
+//      5 ReaderEventNotificationSpec,
+//      6 AccessReportSpec,
+//      7 LLRPConfigurationStateValue,
+//      8 KeepaliveSpec,
+//      9 GPIPortCurrentState,
+//      10 GPOWriteData,
+//      11 EventsAndReports
+pub fn get_reader_config(id: &u32, ant_id: &u16, config: &u8, gpi_port: &u16, gpo_port: &u16) -> Message {
+    Message::new(message_types::GET_READER_CONFIG, *id)
+        // antenna - 0 is all
+        .u16(*ant_id)
+        .u8(*config)
+        .u16(*gpi_port)
+        .u16(*gpo_port)
+}
+
+pub fn set_keepalive(id: &u32) -> Message {
+    Message::new(message_types::SET_READER_CONFIG, *id)
+        // Don't restore factory defaults
+        .u8(0)
+        // periodic keepalive every 2 seconds
+        .param(&KeepaliveSpec {
+            kind: encoder::KEEPALIVE_PERIODIC,
+            interval: 2000,
+        })
+}
+
+pub fn set_reader_config(id: &u32, antennas: &[AntennaConfiguration]) -> Message {
+    let mut msg = Message::new(message_types::SET_READER_CONFIG, *id)
+        // Don't restore factory defaults
+        .u8(0)
+        .param(&ReaderEventNotificationSpec {
+            states: vec![
+                // ROSpec event
+                EventNotificationState { event: 2, enabled: true },
+                // Report buffer fill warning
+                EventNotificationState { event: 3, enabled: true },
+                // Reader exception event
+                EventNotificationState { event: 4, enabled: true },
+            ]
+        });
+    // per antenna power and sensitivity
+    for antenna in antennas {
+        msg = msg.param(antenna);
+    }
+    // Hold events and reports upon reconnect
+    msg.param(&EventsAndReports { hold: true })
+}
+
+// Sets GPO ports on the reader, leaving everything else alone.
+pub fn set_gpo(id: &u32, outputs: &[GPOWriteData]) -> Message {
+    let mut msg = Message::new(message_types::SET_READER_CONFIG, *id)
+        // Don't restore factory defaults
+        .u8(0);
+    for output in outputs {
+        msg = msg.param(output);
+    }
+    msg
+}
+
+pub fn close_connection(id: &u32) -> Message {
+    empty(message_types::CLOSE_CONNECTION, id)
+}
+
+pub fn get_report(id: &u32) -> Message {
+    empty(message_types::GET_REPORT, id)
+}
+
+pub fn keepalive_ack(id: &u32) -> Message {
+    empty(message_types::KEEPALIVE_ACK, id)
+}
+
+pub fn enable_events_and_reports(id: &u32) -> Message {
+    empty(message_types::ENABLE_EVENTS_AND_REPORTS, id)
+}
+
+fn with_spec_id(kind: u16, id: &u32, s_id: &u32) -> Message {
+    Message::new(kind, *id)
+        .u32(*s_id)
+}
+
+fn empty(kind: u16, id: &u32) -> Message {
+    Message::new(kind, *id)
+}
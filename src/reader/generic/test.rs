@@ -0,0 +1,573 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::{collections::HashMap, sync::{Arc, Mutex}};
+
+use crate::{llrp::{decoder, encoder, message_types}, objects::{antenna::{self, AntennaConfig, AntennaMapping}, clock::{self, ClockConfig}, dedup::{self, DedupConfig, DedupGroup}, filter::{self, TagFilter}, gpio::{GpioConfig, GpiTrigger}, profile::{ProfileConfig, ReadingProfile}, read_window::{AntennaReadWindow, ReadWindowConfig}, report_buffer::ReportBufferConfig, tag_data::TagDataConfig}, reader::{capabilities::{ReaderCapabilities, TransmitPowerEntry}, clock::ClockTracker, simulator::{self, ReportContents, SimulatedTag}, Reader, ReaderStatus, READER_KIND_IMPINJ, READER_KIND_LLRP, READER_KIND_ZEBRA}, types};
+
+use super::{expected_response, ROSPEC_ID, next_status, process_tag_reads, requests, window_tags, Extensions, NoExtensions, ReaderSettings, TagData, WindowedTag};
+
+fn settings(antennas: Vec<AntennaConfig>, max_antennas: Option<u16>) -> ReaderSettings {
+    ReaderSettings {
+        antennas,
+        antenna_map: Vec::new(),
+        filters: Vec::new(),
+        gpio: GpioConfig::default(),
+        tag_data: TagDataConfig::default(),
+        clock: ClockConfig::default(),
+        clock_tracker: Arc::new(Mutex::new(ClockTracker::new())),
+        report_buffer: ReportBufferConfig::default(),
+        profiles: ProfileConfig::default(),
+        dedup: DedupConfig::default(),
+        read_windows: Arc::new(Mutex::new(ReadWindowConfig::default())),
+        capabilities: Arc::new(Mutex::new(max_antennas.map(|max| ReaderCapabilities {
+            max_antennas: max,
+            transmit_power: vec![
+                TransmitPowerEntry { index: 1, dbm: 10.0 },
+                TransmitPowerEntry { index: 2, dbm: 20.0 },
+                TransmitPowerEntry { index: 3, dbm: 30.0 },
+            ],
+            ..Default::default()
+        }))),
+        llrp_version: Arc::new(Mutex::new(encoder::VERSION_1_0_1)),
+        supported_version: Arc::new(Mutex::new(encoder::VERSION_1_0_1)),
+    }
+}
+
+#[test]
+fn test_antenna_ids() {
+    // nothing saved, every antenna
+    assert_eq!(vec![0], settings(Vec::new(), Some(4)).antenna_ids());
+    // nothing turned off, every antenna
+    assert_eq!(vec![0], settings(vec![AntennaConfig::new(2, true, 0, None, 0)], Some(4)).antenna_ids());
+    // antennas without a configuration are left on
+    let configs = vec![
+        AntennaConfig::new(2, false, 0, None, 0),
+        AntennaConfig::new(3, true, 0, None, 0),
+    ];
+    assert_eq!(vec![1, 3, 4], settings(configs.clone(), Some(4)).antenna_ids());
+    // without the capabilities only the antennas turned on are used
+    assert_eq!(vec![3], settings(configs, None).antenna_ids());
+    // everything off falls back to every antenna
+    let configs = vec![
+        AntennaConfig::new(1, false, 0, None, 0),
+        AntennaConfig::new(2, false, 0, None, 0),
+    ];
+    assert_eq!(vec![0], settings(configs.clone(), Some(2)).antenna_ids());
+    assert_eq!(vec![1, 2], settings(configs, Some(2)).disabled_antennas());
+}
+
+#[test]
+fn test_antenna_configurations() {
+    let configs = vec![
+        // reader defaults, nothing to send
+        AntennaConfig::new(1, true, 0, None, 0),
+        AntennaConfig::new(2, true, 2, None, 0),
+        AntennaConfig::new(3, true, 0, Some(29.0), 1),
+        AntennaConfig::new(4, true, 0, None, 5),
+    ];
+    let output = settings(configs.clone(), Some(4)).antenna_configurations();
+    assert_eq!(3, output.len());
+    assert_eq!(2, output[0].antenna_id);
+    assert_eq!(2, output[0].transmitter.as_ref().unwrap().transmit_power);
+    assert!(output[0].receiver.is_none());
+    // dBm is matched to the closest power table entry
+    assert_eq!(3, output[1].antenna_id);
+    assert_eq!(3, output[1].transmitter.as_ref().unwrap().transmit_power);
+    assert_eq!(1, output[1].receiver.as_ref().unwrap().sensitivity);
+    assert_eq!(4, output[2].antenna_id);
+    assert!(output[2].transmitter.is_none());
+    assert_eq!(5, output[2].receiver.as_ref().unwrap().sensitivity);
+    // without a power table the dBm value can't be used
+    let output = settings(configs, None).antenna_configurations();
+    assert_eq!(3, output.len());
+    assert_eq!(3, output[1].antenna_id);
+    assert!(output[1].transmitter.is_none());
+    assert_eq!(1, output[1].receiver.as_ref().unwrap().sensitivity);
+}
+
+#[test]
+fn test_c1g2_filters() {
+    let mut settings = settings(Vec::new(), None);
+    assert_eq!(0, settings.c1g2_filters().len());
+    settings.filters = vec![
+        TagFilter::new(filter::FILTER_MEMORY_BANK_EPC, 32, String::from("E280"), 0, String::from(filter::FILTER_ACTION_INCLUDE)),
+        TagFilter::new(filter::FILTER_MEMORY_BANK_EPC, 32, String::from("ABC"), 0, String::from(filter::FILTER_ACTION_INCLUDE)),
+        // not hex, skipped
+        TagFilter::new(filter::FILTER_MEMORY_BANK_EPC, 32, String::from("XYZ"), 0, String::from(filter::FILTER_ACTION_INCLUDE)),
+        TagFilter::new(filter::FILTER_MEMORY_BANK_TID, 8, String::from("FF"), 4, String::from(filter::FILTER_ACTION_EXCLUDE)),
+    ];
+    let output = settings.c1g2_filters();
+    assert_eq!(3, output.len());
+    // the first include selects matching tags and unselects the rest
+    assert_eq!(Some(0), output[0].unaware_action);
+    assert_eq!(1, output[0].mask.memory_bank);
+    assert_eq!(32, output[0].mask.pointer);
+    assert_eq!(16, output[0].mask.mask_bits);
+    assert_eq!(vec![0xE2, 0x80], output[0].mask.mask);
+    // later includes only select matching tags, odd digits get padded
+    assert_eq!(Some(1), output[1].unaware_action);
+    assert_eq!(12, output[1].mask.mask_bits);
+    assert_eq!(vec![0xAB, 0xC0], output[1].mask.mask);
+    // later excludes only unselect matching tags
+    assert_eq!(Some(3), output[2].unaware_action);
+    assert_eq!(2, output[2].mask.memory_bank);
+    assert_eq!(4, output[2].mask.mask_bits);
+    // an exclude first unselects matching tags and selects the rest
+    settings.filters.drain(..3);
+    assert_eq!(Some(4), settings.c1g2_filters()[0].unaware_action);
+}
+
+#[test]
+fn test_boundary_triggers() {
+    let mut settings = settings(Vec::new(), None);
+    let (start, stop) = settings.boundary_triggers();
+    assert_eq!(encoder::START_TRIGGER_NULL, start.kind);
+    assert!(start.gpi.is_none());
+    assert_eq!(encoder::STOP_TRIGGER_NULL, stop.kind);
+    assert!(stop.gpi.is_none());
+    assert_eq!(ReaderStatus::ConnectingStartRospec, next_status(&NoExtensions, &settings, &ReaderStatus::ConnectingEnableRospec));
+    settings.gpio = GpioConfig::new(Some(GpiTrigger::new(1, true, 0)), Some(GpiTrigger::new(2, false, 60000)), Vec::new());
+    let (start, stop) = settings.boundary_triggers();
+    assert_eq!(encoder::START_TRIGGER_GPI, start.kind);
+    let gpi = start.gpi.unwrap();
+    assert_eq!((1, true, 0), (gpi.port, gpi.event, gpi.timeout));
+    assert_eq!(encoder::STOP_TRIGGER_GPI, stop.kind);
+    let gpi = stop.gpi.unwrap();
+    assert_eq!((2, false, 60000), (gpi.port, gpi.event, gpi.timeout));
+    // the gpi starts reading so we don't send START_ROSPEC
+    assert_eq!(ReaderStatus::Connected, next_status(&NoExtensions, &settings, &ReaderStatus::ConnectingEnableRospec));
+}
+
+#[test]
+fn test_tag_filter_valid() {
+    assert!(TagFilter::new(filter::FILTER_MEMORY_BANK_EPC, 32, String::from("e280"), 0, String::from(filter::FILTER_ACTION_INCLUDE)).is_valid());
+    assert!(TagFilter::new(filter::FILTER_MEMORY_BANK_USER, 0, String::from("E28"), 9, String::from(filter::FILTER_ACTION_EXCLUDE)).is_valid());
+    // unknown action
+    assert!(!TagFilter::new(filter::FILTER_MEMORY_BANK_EPC, 32, String::from("E280"), 0, String::from("maybe")).is_valid());
+    // reserved memory bank
+    assert!(!TagFilter::new(0, 0, String::from("E280"), 0, String::from(filter::FILTER_ACTION_INCLUDE)).is_valid());
+    // empty or not hex
+    assert!(!TagFilter::new(filter::FILTER_MEMORY_BANK_EPC, 32, String::new(), 0, String::from(filter::FILTER_ACTION_INCLUDE)).is_valid());
+    assert!(!TagFilter::new(filter::FILTER_MEMORY_BANK_EPC, 32, String::from("E2G0"), 0, String::from(filter::FILTER_ACTION_INCLUDE)).is_valid());
+    // more bits than the mask has
+    assert!(!TagFilter::new(filter::FILTER_MEMORY_BANK_EPC, 32, String::from("E2"), 9, String::from(filter::FILTER_ACTION_INCLUDE)).is_valid());
+}
+
+#[test]
+fn test_reported_antenna() {
+    let mut settings = settings(Vec::new(), Some(4));
+    assert_eq!(3, settings.reported_antenna(3));
+    settings.antenna_map = vec![
+        AntennaMapping::new(1, 2),
+        AntennaMapping::new(2, 1),
+    ];
+    assert_eq!(2, settings.reported_antenna(1));
+    assert_eq!(1, settings.reported_antenna(2));
+    // ports without a mapping keep their number
+    assert_eq!(3, settings.reported_antenna(3));
+    assert_eq!(0, settings.reported_antenna(0));
+}
+
+#[test]
+fn test_validate_antenna_map() {
+    assert!(antenna::validate_map(&[]).is_ok());
+    let swapped = vec![
+        AntennaMapping::new(1, 2),
+        AntennaMapping::new(2, 1),
+    ];
+    assert!(antenna::validate_map(&swapped).is_ok());
+    // port 2 would be reported as antenna 2 along with port 1
+    assert!(antenna::validate_map(&swapped[..1]).is_err());
+    assert!(antenna::validate_map(&[AntennaMapping::new(0, 1)]).is_err());
+    assert!(antenna::validate_map(&[AntennaMapping::new(1, 17)]).is_err());
+    assert!(antenna::validate_map(&[AntennaMapping::new(1, 1), AntennaMapping::new(1, 1)]).is_err());
+    let shift: Vec<AntennaMapping> = crate::reader::zebra::ZEBRA_SHIFT_MAP.iter()
+        .map(|(port, antenna)| AntennaMapping::new(*port, *antenna))
+        .collect();
+    assert!(antenna::validate_map(&shift).is_ok());
+}
+
+fn tag(tag: u128, antenna: u16, rssi: i8, time: u128) -> TagData {
+    TagData {
+        tag,
+        antenna,
+        rssi,
+        first_seen: time,
+        last_seen: time,
+        reader_time: time,
+        portal_time: time,
+        channel_index: None,
+        seen_count: None,
+        tid: None,
+        user_memory: None,
+    }
+}
+
+#[test]
+fn test_window_tags() {
+    let mut settings = settings(vec![AntennaConfig::new(4, false, 0, None, 0)], Some(4));
+    settings.antenna_map = vec![
+        AntennaMapping::new(1, 2),
+        AntennaMapping::new(2, 1),
+    ];
+    let mut map: HashMap<(usize, u128), WindowedTag> = HashMap::new();
+    let second = 1000000;
+    // one second window
+    let window = 10 * 100000;
+    let start = 1700000000 * second;
+    let mut tags = vec![
+        tag(1000, 1, -60, start),
+        tag(1000, 2, -50, start + second / 2),
+        tag(2000, 3, -70, start),
+        // antenna turned off
+        tag(3000, 4, -40, start),
+    ];
+    assert!(window_tags(&mut map, &mut tags, &settings, start + second, window, types::TYPE_CHIP_DEC, "Reader").is_empty());
+    assert_eq!(2, map.len());
+    // seen again after the window, the best read from the first window is saved
+    let mut tags = vec![tag(1000, 1, -80, start + 2 * second)];
+    let reads = window_tags(&mut map, &mut tags, &settings, start + 2 * second, window, types::TYPE_CHIP_DEC, "Reader");
+    assert_eq!(1, reads.len());
+    assert_eq!("1000", reads[0].chip());
+    assert_eq!("-50", reads[0].rssi());
+    // port 2 is reported as antenna 1
+    assert_eq!(1, reads[0].antenna());
+    assert_eq!(1700000000, reads[0].seconds());
+    assert_eq!(500, reads[0].milliseconds());
+    // a second past the window the rest are saved
+    let reads = window_tags(&mut map, &mut Vec::new(), &settings, start + 5 * second, window, types::TYPE_CHIP_HEX, "Reader");
+    assert_eq!(2, reads.len());
+    assert!(reads.iter().any(|r| r.chip() == "7d0" && r.antenna() == 3));
+    assert!(reads.iter().any(|r| r.chip() == "3e8" && r.rssi() == "-80" && r.antenna() == 2));
+    assert!(map.is_empty());
+}
+
+#[test]
+fn test_dedup_modes() {
+    let second = 1000000;
+    let window = 10 * 100000;
+    let start = 1700000000 * second;
+    let sightings = || vec![
+        tag(1000, 1, -60, start),
+        tag(1000, 1, -40, start + second / 4),
+        tag(1000, 1, -70, start + second / 2),
+    ];
+    let kept = |mode: &str| {
+        let mut settings = settings(Vec::new(), None);
+        settings.dedup = DedupConfig::new(String::from(mode), false, Vec::new());
+        let mut map: HashMap<(usize, u128), WindowedTag> = HashMap::new();
+        assert!(window_tags(&mut map, &mut sightings(), &settings, start + second / 2, window, types::TYPE_CHIP_DEC, "Reader").is_empty());
+        let reads = window_tags(&mut map, &mut Vec::new(), &settings, start + 3 * second, window, types::TYPE_CHIP_DEC, "Reader");
+        assert_eq!(1, reads.len());
+        String::from(reads[0].rssi())
+    };
+    assert_eq!("-60", kept(dedup::DEDUP_MODE_FIRST));
+    assert_eq!("-70", kept(dedup::DEDUP_MODE_LAST));
+    assert_eq!("-40", kept(dedup::DEDUP_MODE_BEST_RSSI));
+    // every sighting is a read right away
+    let mut settings = settings(Vec::new(), None);
+    settings.dedup = DedupConfig::new(String::from(dedup::DEDUP_MODE_ALL), false, Vec::new());
+    let mut map: HashMap<(usize, u128), WindowedTag> = HashMap::new();
+    assert_eq!(3, window_tags(&mut map, &mut sightings(), &settings, start, window, types::TYPE_CHIP_DEC, "Reader").len());
+    assert!(map.is_empty());
+    // the first sighting is sent right away, and the best one once the window closes
+    settings.dedup = DedupConfig::new(String::from(dedup::DEDUP_MODE_BEST_RSSI), true, Vec::new());
+    let reads = window_tags(&mut map, &mut sightings(), &settings, start + second / 2, window, types::TYPE_CHIP_DEC, "Reader");
+    assert_eq!(1, reads.len());
+    assert_eq!("-60", reads[0].rssi());
+    let reads = window_tags(&mut map, &mut Vec::new(), &settings, start + 3 * second, window, types::TYPE_CHIP_DEC, "Reader");
+    assert_eq!(1, reads.len());
+    assert_eq!("-40", reads[0].rssi());
+    // sending the first sighting in first seen mode leaves nothing to send when the window closes
+    settings.dedup = DedupConfig::new(String::from(dedup::DEDUP_MODE_FIRST), true, Vec::new());
+    assert_eq!(1, window_tags(&mut map, &mut sightings(), &settings, start + second / 2, window, types::TYPE_CHIP_DEC, "Reader").len());
+    assert!(window_tags(&mut map, &mut Vec::new(), &settings, start + 3 * second, window, types::TYPE_CHIP_DEC, "Reader").is_empty());
+    assert!(map.is_empty());
+    // a start mat on antennas 1 and 2 and a finish mat on the rest, each gets its own read
+    settings.dedup = DedupConfig::new(
+        String::from(dedup::DEDUP_MODE_BEST_RSSI),
+        false,
+        vec![DedupGroup::new(vec![1, 2], String::from(dedup::DEDUP_MODE_FIRST))],
+    );
+    let mut tags = vec![
+        tag(1000, 1, -60, start),
+        tag(1000, 2, -40, start + 1),
+        tag(1000, 3, -70, start + 2),
+        tag(1000, 4, -50, start + 3),
+    ];
+    assert!(window_tags(&mut map, &mut tags, &settings, start, window, types::TYPE_CHIP_DEC, "Reader").is_empty());
+    let mut reads = window_tags(&mut map, &mut Vec::new(), &settings, start + 3 * second, window, types::TYPE_CHIP_DEC, "Reader");
+    reads.sort_by_key(|read| read.antenna());
+    assert_eq!(2, reads.len());
+    assert_eq!((1, "-60"), (reads[0].antenna(), reads[0].rssi()));
+    assert_eq!((4, "-50"), (reads[1].antenna(), reads[1].rssi()));
+}
+
+#[test]
+fn test_read_window_overrides() {
+    let second = 1000000;
+    // one second global window
+    let window = 10 * 100000;
+    let start = 1700000000 * second;
+    let settings = settings(Vec::new(), None);
+    // the reader uses three seconds, except antenna 2 which uses half a second
+    *settings.read_windows.lock().unwrap() = ReadWindowConfig::new(Some(30), vec![AntennaReadWindow::new(2, 5)]);
+    let mut map: HashMap<(usize, u128), WindowedTag> = HashMap::new();
+    let mut tags = vec![
+        tag(1000, 1, -60, start),
+        tag(2000, 2, -60, start),
+    ];
+    assert!(window_tags(&mut map, &mut tags, &settings, start, window, types::TYPE_CHIP_DEC, "Reader").is_empty());
+    // still inside the reader's window, but past the antenna's
+    let mut tags = vec![
+        tag(1000, 1, -50, start + 2 * second),
+        tag(2000, 2, -50, start + second),
+    ];
+    let reads = window_tags(&mut map, &mut tags, &settings, start + 2 * second, window, types::TYPE_CHIP_DEC, "Reader");
+    assert_eq!(1, reads.len());
+    assert_eq!("2000", reads[0].chip());
+    assert_eq!("-60", reads[0].rssi());
+    // a second past the reader's window the best read is saved, along with the second window on antenna 2
+    let reads = window_tags(&mut map, &mut Vec::new(), &settings, start + 4 * second + 1, window, types::TYPE_CHIP_DEC, "Reader");
+    assert_eq!(2, reads.len());
+    assert!(reads.iter().any(|r| r.chip() == "1000" && r.rssi() == "-50"));
+    assert!(reads.iter().any(|r| r.chip() == "2000" && r.rssi() == "-50"));
+    // without overrides the global window is used again
+    *settings.read_windows.lock().unwrap() = ReadWindowConfig::default();
+    map.clear();
+    let mut tags = vec![tag(1000, 1, -60, start)];
+    assert!(window_tags(&mut map, &mut tags, &settings, start, window, types::TYPE_CHIP_DEC, "Reader").is_empty());
+    assert_eq!(1, window_tags(&mut map, &mut Vec::new(), &settings, start + 2 * second + 1, window, types::TYPE_CHIP_DEC, "Reader").len());
+    // recovered reads are checked against the longest window
+    let config = ReadWindowConfig::new(Some(30), vec![AntennaReadWindow::new(2, 5), AntennaReadWindow::new(3, 45)]);
+    assert_eq!(45, config.max_window(10));
+    assert_eq!(30, ReadWindowConfig::new(Some(30), vec![AntennaReadWindow::new(2, 5)]).max_window(50));
+    assert_eq!(50, ReadWindowConfig::default().max_window(50));
+    assert!(config.validate().is_ok());
+    assert!(ReadWindowConfig::new(Some(0), Vec::new()).validate().is_err());
+    assert!(ReadWindowConfig::new(None, vec![AntennaReadWindow::new(0, 5)]).validate().is_err());
+    assert!(ReadWindowConfig::new(None, vec![AntennaReadWindow::new(1, 5), AntennaReadWindow::new(1, 6)]).validate().is_err());
+}
+
+#[test]
+fn test_dedup_validate() {
+    assert!(DedupConfig::default().validate().is_ok());
+    assert!(DedupConfig::new(String::from("best"), false, Vec::new()).validate().is_err());
+    let group = |antennas: Vec<u16>| DedupGroup::new(antennas, String::from(dedup::DEDUP_MODE_FIRST));
+    assert!(DedupConfig::new(String::from(dedup::DEDUP_MODE_LAST), true, vec![group(vec![1, 2]), group(vec![3])]).validate().is_ok());
+    assert!(DedupConfig::new(String::from(dedup::DEDUP_MODE_LAST), true, vec![group(vec![1, 2]), group(vec![2])]).validate().is_err());
+    assert!(DedupConfig::new(String::from(dedup::DEDUP_MODE_LAST), true, vec![group(Vec::new())]).validate().is_err());
+    assert!(DedupConfig::new(String::from(dedup::DEDUP_MODE_LAST), true, vec![group(vec![0])]).validate().is_err());
+    assert!(DedupConfig::new(String::from(dedup::DEDUP_MODE_LAST), true, vec![DedupGroup::new(vec![1], String::from("most"))]).validate().is_err());
+}
+
+#[test]
+fn test_tag_data() {
+    let mut settings = settings(Vec::new(), None);
+    assert_eq!(ReaderStatus::ConnectingEnableRospec, next_status(&NoExtensions, &settings, &ReaderStatus::ConnectingAddRospec));
+    // reading memory adds the AccessSpec steps
+    settings.tag_data = TagDataConfig::new(4, 0, false);
+    assert_eq!(ReaderStatus::ConnectingAddAccessSpec, next_status(&NoExtensions, &settings, &ReaderStatus::ConnectingAddRospec));
+    assert_eq!(ReaderStatus::ConnectingEnableAccessSpec, next_status(&NoExtensions, &settings, &ReaderStatus::ConnectingAddAccessSpec));
+    assert_eq!(ReaderStatus::ConnectingEnableRospec, next_status(&NoExtensions, &settings, &ReaderStatus::ConnectingEnableAccessSpec));
+    // metadata alone only changes the report contents
+    settings.tag_data = TagDataConfig::new(0, 0, true);
+    assert_eq!(ReaderStatus::ConnectingEnableRospec, next_status(&NoExtensions, &settings, &ReaderStatus::ConnectingAddRospec));
+    let contents = ReportContents {
+        channel_index: true,
+        last_seen: true,
+        seen_count: true,
+        reads: vec![
+            (requests::OP_SPEC_ID_TID, encoder::MEMORY_BANK_TID, 4),
+            (requests::OP_SPEC_ID_USER_MEMORY, encoder::MEMORY_BANK_USER, 1),
+        ],
+        clock_offset_milliseconds: 0,
+    };
+    let report = simulator::tag_report_with(1, &[SimulatedTag { chip: 0x1234, antenna: 2, rssi: -50 }], &contents);
+    let msg = decoder::decode_message(&report).unwrap();
+    let tags = process_tag_reads(&msg, &NoExtensions).unwrap();
+    assert_eq!(1, tags.len());
+    assert_eq!(0x1234, tags[0].tag);
+    assert_eq!(Some(1), tags[0].channel_index);
+    assert_eq!(Some(1), tags[0].seen_count);
+    assert_eq!(Some(String::from("e2801160")), tags[0].tid.as_ref().map(|tid| tid[..8].to_string()));
+    assert_eq!(16, tags[0].tid.as_ref().unwrap().len());
+    assert_eq!(Some(String::from("0000")), tags[0].user_memory);
+    assert!(tags[0].last_seen > 0);
+    // the details make it to the read, and a better read without memory keeps the memory we have
+    let mut map: HashMap<(usize, u128), WindowedTag> = HashMap::new();
+    let second = 1000000;
+    let start = tags[0].portal_time;
+    let mut first = tags.clone();
+    first[0].portal_time = start;
+    assert!(window_tags(&mut map, &mut first, &settings, start, second, types::TYPE_CHIP_HEX, "Reader").is_empty());
+    let mut better = vec![tag(0x1234, 1, -40, start + 1)];
+    assert!(window_tags(&mut map, &mut better, &settings, start + 1, second, types::TYPE_CHIP_HEX, "Reader").is_empty());
+    let reads = window_tags(&mut map, &mut Vec::new(), &settings, start + 3 * second, second, types::TYPE_CHIP_HEX, "Reader");
+    assert_eq!(1, reads.len());
+    assert_eq!("-40", reads[0].rssi());
+    assert_eq!(tags[0].tid, reads[0].details().tid);
+    assert_eq!(Some(String::from("0000")), reads[0].details().user_memory);
+    // the better read didn't report metadata
+    assert_eq!(None, reads[0].details().channel_index);
+}
+
+#[test]
+fn test_version_negotiation() {
+    let settings = settings(Vec::new(), None);
+    // a reader that only knows 1.0.1 goes straight to the keepalive
+    assert_eq!(ReaderStatus::ConnectingKeepalive, next_status(&NoExtensions, &settings, &ReaderStatus::ConnectingGetSupportedVersion));
+    // a 1.1 reader is asked to switch
+    *settings.supported_version.lock().unwrap() = encoder::VERSION_1_1;
+    assert_eq!(encoder::VERSION_1_1, settings.negotiated_version());
+    assert_eq!(ReaderStatus::ConnectingSetProtocolVersion, next_status(&NoExtensions, &settings, &ReaderStatus::ConnectingGetSupportedVersion));
+    assert_eq!(ReaderStatus::ConnectingKeepalive, next_status(&NoExtensions, &settings, &ReaderStatus::ConnectingSetProtocolVersion));
+    // versions past what we know are capped at the highest we support
+    *settings.supported_version.lock().unwrap() = encoder::MAX_VERSION + 1;
+    assert_eq!(encoder::MAX_VERSION, settings.negotiated_version());
+    // nothing to change once we're on it
+    *settings.llrp_version.lock().unwrap() = encoder::MAX_VERSION;
+    assert_eq!(ReaderStatus::ConnectingKeepalive, next_status(&NoExtensions, &settings, &ReaderStatus::ConnectingGetSupportedVersion));
+}
+
+#[test]
+fn test_read_time() {
+    let mut settings = settings(Vec::new(), None);
+    let second = 1000000;
+    let start = 1700000000 * second;
+    // reader clock is 2 seconds ahead
+    let mut data = tag(1000, 1, -50, start);
+    data.reader_time = start + 2 * second;
+    data.last_seen = data.reader_time;
+    // the portal clock is used unless we're told otherwise
+    let read = data.to_read(&settings, types::TYPE_CHIP_DEC, "Reader");
+    assert_eq!(1700000000, read.seconds());
+    assert_eq!(1700000002, read.reader_seconds());
+    settings.clock = ClockConfig::new(String::from(clock::CLOCK_SOURCE_READER), false, 1000, false);
+    assert_eq!(1700000002, data.to_read(&settings, types::TYPE_CHIP_DEC, "Reader").seconds());
+    // correcting does nothing until we've measured the offset
+    settings.clock = ClockConfig::new(String::from(clock::CLOCK_SOURCE_READER), true, 1000, false);
+    assert_eq!(1700000002, data.to_read(&settings, types::TYPE_CHIP_DEC, "Reader").seconds());
+    {
+        let mut tracker = settings.clock_tracker.lock().unwrap();
+        tracker.sample(start + 2 * second, start);
+        tracker.keepalive(start);
+    }
+    let read = data.to_read(&settings, types::TYPE_CHIP_DEC, "Reader");
+    assert_eq!(1700000000, read.seconds());
+    assert_eq!(1700000002, read.reader_seconds());
+    // reads without a reader timestamp fall back to the portal clock
+    data.reader_time = 0;
+    assert_eq!(1700000000, data.to_read(&settings, types::TYPE_CHIP_DEC, "Reader").seconds());
+    assert!(ClockConfig::new(String::from("gps"), false, 0, false).validate().is_err());
+}
+
+struct ClockExtensions;
+
+impl Extensions for ClockExtensions {
+    fn can_set_clock(&self) -> bool {
+        true
+    }
+}
+
+#[test]
+fn test_set_clock() {
+    let mut settings = settings(Vec::new(), None);
+    assert_eq!(ReaderStatus::ConnectingAddRospec, next_status(&ClockExtensions, &settings, &ReaderStatus::ConnectingDeleteRospec));
+    settings.clock = ClockConfig::new(String::from(clock::CLOCK_SOURCE_PORTAL), false, 1000, true);
+    assert_eq!(ReaderStatus::ConnectingSetClock, next_status(&ClockExtensions, &settings, &ReaderStatus::ConnectingDeleteRospec));
+    assert_eq!(ReaderStatus::ConnectingAddRospec, next_status(&ClockExtensions, &settings, &ReaderStatus::ConnectingSetClock));
+    // readers we don't know how to set the clock on skip it
+    assert_eq!(ReaderStatus::ConnectingAddRospec, next_status(&NoExtensions, &settings, &ReaderStatus::ConnectingDeleteRospec));
+    // and only zebras take the setting
+    for (kind, can_set) in [(READER_KIND_ZEBRA, true), (READER_KIND_IMPINJ, false), (READER_KIND_LLRP, false)] {
+        let reader = Reader::new_no_repeaters(1, String::from(kind), String::from("Reader"), String::from("127.0.0.1"), 5084, 0).unwrap();
+        assert_eq!(can_set, reader.can_set_clock());
+    }
+}
+
+struct PurgeExtensions;
+
+impl Extensions for PurgeExtensions {
+    fn setup_steps(&self) -> &'static [ReaderStatus] {
+        &[ReaderStatus::ConnectingPurgeTags]
+    }
+}
+
+#[test]
+fn test_get_report() {
+    let mut settings = settings(Vec::new(), None);
+    assert_eq!(ReaderStatus::ConnectingSetReaderConfig, next_status(&NoExtensions, &settings, &ReaderStatus::ConnectingGetReaderCapabilities));
+    assert_eq!(ReaderStatus::ConnectingPurgeTags, next_status(&PurgeExtensions, &settings, &ReaderStatus::ConnectingGetReaderCapabilities));
+    settings.report_buffer = ReportBufferConfig::new(true);
+    assert_eq!(ReaderStatus::ConnectingGetReport, next_status(&NoExtensions, &settings, &ReaderStatus::ConnectingGetReaderCapabilities));
+    assert_eq!(ReaderStatus::ConnectingSetReaderConfig, next_status(&NoExtensions, &settings, &ReaderStatus::ConnectingGetReport));
+    // held reports are collected before the vendor steps can purge them
+    assert_eq!(ReaderStatus::ConnectingGetReport, next_status(&PurgeExtensions, &settings, &ReaderStatus::ConnectingGetReaderCapabilities));
+    assert_eq!(ReaderStatus::ConnectingPurgeTags, next_status(&PurgeExtensions, &settings, &ReaderStatus::ConnectingGetReport));
+    assert_eq!(message_types::RO_ACCESS_REPORT, expected_response(&NoExtensions, &ReaderStatus::ConnectingGetReport));
+}
+
+#[test]
+fn test_reading_profiles() {
+    let configs = vec![AntennaConfig::new(2, false, 0, None, 0)];
+    let mut settings = settings(configs, Some(4));
+    // without a profile we read the way we always have
+    assert_eq!(ROSPEC_ID, settings.rospec_id());
+    assert_eq!(vec![1, 3, 4], settings.rospec_antenna_ids());
+    assert!(settings.rospec_transmitter().is_none());
+    settings.profiles = ProfileConfig::new(
+        vec![
+            ReadingProfile::new(String::from("finish line"), Vec::new(), Some(31.0), 0),
+            ReadingProfile::new(String::from("chip check"), vec![2, 3], Some(12.0), 2000),
+        ],
+        Some(String::from("finish line")),
+    );
+    assert_eq!(ROSPEC_ID + 1, settings.rospec_id());
+    assert_eq!(vec![1, 3, 4], settings.rospec_antenna_ids());
+    assert_eq!(3, settings.rospec_transmitter().unwrap().transmit_power);
+    assert_eq!(encoder::STOP_TRIGGER_NULL, settings.boundary_triggers().1.kind);
+    // each profile has its own ROSpec, disabled antennas stay off
+    settings.profiles.set_active(Some(String::from("chip check")));
+    assert_eq!(ROSPEC_ID + 2, settings.rospec_id());
+    assert_eq!(vec![3], settings.rospec_antenna_ids());
+    assert_eq!(1, settings.rospec_transmitter().unwrap().transmit_power);
+    let (_, stop) = settings.boundary_triggers();
+    assert_eq!(encoder::STOP_TRIGGER_DURATION, stop.kind);
+    assert_eq!(2000, stop.duration);
+    // a duration replaces a gpi stop trigger
+    settings.gpio = GpioConfig::new(None, Some(GpiTrigger::new(2, false, 0)), Vec::new());
+    assert_eq!(encoder::STOP_TRIGGER_DURATION, settings.boundary_triggers().1.kind);
+    // unknown profiles read the default way
+    settings.profiles.set_active(Some(String::from("start line")));
+    assert_eq!(ROSPEC_ID, settings.rospec_id());
+    assert!(settings.profiles.validate(4).is_err());
+    // names have to be unique and antennas have to be on the reader
+    settings.profiles.set_active(None);
+    assert!(settings.profiles.validate(4).is_ok());
+    assert!(settings.profiles.validate(2).is_err());
+    let duplicate = ProfileConfig::new(
+        vec![
+            ReadingProfile::new(String::from("finish line"), Vec::new(), None, 0),
+            ReadingProfile::new(String::from("finish line"), Vec::new(), None, 0),
+        ],
+        None,
+    );
+    assert!(duplicate.validate(0).is_err());
+}
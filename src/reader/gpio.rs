@@ -0,0 +1,107 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+
+use std::{collections::BTreeMap, sync::Mutex, time::{SystemTime, UNIX_EPOCH}};
+
+use crate::{llrp::encoder::GPOWriteData, objects::gpio::{GpoOutput, GPO_EVENT_CONNECTED, GPO_EVENT_READER_LOST, GPO_EVENT_READING}};
+
+#[cfg(test)]
+pub mod test;
+
+// When a reader on the portal last lost its connection, in milliseconds since the epoch.
+static LAST_LOST: Mutex<u128> = Mutex::new(0);
+
+pub fn now() -> u128 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis()
+}
+
+// Lets every reader with a reader_lost output know a reader lost its connection.
+pub fn connection_lost() {
+    if let Ok(mut last) = LAST_LOST.lock() {
+        *last = now();
+    }
+}
+
+pub fn last_lost() -> u128 {
+    match LAST_LOST.lock() {
+        Ok(last) => *last,
+        Err(_) => 0,
+    }
+}
+
+// Tracks what the GPO ports on a reader should be set to and what we've last told the reader.
+pub struct Outputs {
+    outputs: Vec<GpoOutput>,
+    states: BTreeMap<u16, bool>,
+    last_tags: u128,
+}
+
+impl Outputs {
+    pub fn new(outputs: Vec<GpoOutput>) -> Outputs {
+        Outputs {
+            outputs,
+            states: BTreeMap::new(),
+            last_tags: 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.outputs.is_empty()
+    }
+
+    pub fn tags_received(&mut self, now: u128) {
+        self.last_tags = now;
+    }
+
+    // The ports that need to change while the reader is reading. A port is on if any output on it is on.
+    // Ports we haven't set yet are always written so nothing is left over from before we connected.
+    pub fn update(&mut self, now: u128, last_lost: u128) -> Vec<GPOWriteData> {
+        let mut wanted: BTreeMap<u16, bool> = BTreeMap::new();
+        for output in self.outputs.iter() {
+            let on = match output.event() {
+                GPO_EVENT_CONNECTED => true,
+                GPO_EVENT_READING => self.last_tags > 0 && now < self.last_tags + output.hold() as u128,
+                GPO_EVENT_READER_LOST => last_lost > 0 && now < last_lost + output.hold() as u128,
+                _ => false,
+            };
+            let state = wanted.entry(output.port()).or_insert(false);
+            *state = *state || on;
+        }
+        let mut output: Vec<GPOWriteData> = Vec::new();
+        for (port, data) in wanted {
+            if self.states.get(&port) != Some(&data) {
+                self.states.insert(port, data);
+                output.push(GPOWriteData { port, data });
+            }
+        }
+        output
+    }
+
+    // Turns off every port we've turned on, used when we stop reading.
+    pub fn all_off(&mut self) -> Vec<GPOWriteData> {
+        let mut output: Vec<GPOWriteData> = Vec::new();
+        for (port, data) in self.states.iter_mut() {
+            if *data {
+                *data = false;
+                output.push(GPOWriteData { port: *port, data: false });
+            }
+        }
+        output
+    }
+}
@@ -0,0 +1,76 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+
+use crate::{llrp::encoder::GPOWriteData, objects::gpio::{GpioConfig, GpiTrigger, GpoOutput, GPO_EVENT_CONNECTED, GPO_EVENT_READER_LOST, GPO_EVENT_READING}};
+
+use super::Outputs;
+
+fn write(port: u16, data: bool) -> GPOWriteData {
+    GPOWriteData { port, data }
+}
+
+#[test]
+fn test_outputs_reading() {
+    let mut outputs = Outputs::new(vec![GpoOutput::new(1, String::from(GPO_EVENT_READING), 500)]);
+    // the port is set off to start with
+    assert_eq!(vec![write(1, false)], outputs.update(1000, 0));
+    assert_eq!(0, outputs.update(1100, 0).len());
+    outputs.tags_received(1200);
+    assert_eq!(vec![write(1, true)], outputs.update(1200, 0));
+    // more tags keep it on
+    outputs.tags_received(1600);
+    assert_eq!(0, outputs.update(1800, 0).len());
+    assert_eq!(vec![write(1, false)], outputs.update(2100, 0));
+    outputs.tags_received(2200);
+    assert_eq!(vec![write(1, true)], outputs.update(2200, 0));
+    assert_eq!(vec![write(1, false)], outputs.all_off());
+    assert_eq!(0, outputs.all_off().len());
+}
+
+#[test]
+fn test_outputs_shared_port() {
+    let mut outputs = Outputs::new(vec![
+        GpoOutput::new(2, String::from(GPO_EVENT_READER_LOST), 0),
+        GpoOutput::new(2, String::from(GPO_EVENT_READING), 0),
+        GpoOutput::new(3, String::from(GPO_EVENT_CONNECTED), 0),
+    ]);
+    assert_eq!(vec![write(2, false), write(3, true)], outputs.update(10000, 0));
+    // the reader lost default is 5 seconds
+    assert_eq!(vec![write(2, true)], outputs.update(10000, 9000));
+    outputs.tags_received(13500);
+    assert_eq!(0, outputs.update(14000, 9000).len());
+    // still on from the tags, reading defaults to 1 second
+    assert_eq!(0, outputs.update(14200, 9000).len());
+    assert_eq!(vec![write(2, false)], outputs.update(14600, 9000));
+    assert_eq!(vec![write(3, false)], outputs.all_off());
+}
+
+#[test]
+fn test_validate() {
+    let trigger = |port| Some(GpiTrigger::new(port, true, 0));
+    let output = |port, event: &str| vec![GpoOutput::new(port, String::from(event), 0)];
+    assert!(GpioConfig::default().validate(0, 0).is_ok());
+    assert!(GpioConfig::new(trigger(4), trigger(1), output(4, GPO_EVENT_READING)).validate(4, 4).is_ok());
+    // port counts we don't know aren't checked
+    assert!(GpioConfig::new(trigger(9), None, output(9, GPO_EVENT_CONNECTED)).validate(0, 0).is_ok());
+    assert!(GpioConfig::new(trigger(0), None, Vec::new()).validate(0, 0).is_err());
+    assert!(GpioConfig::new(None, trigger(5), Vec::new()).validate(4, 4).is_err());
+    assert!(GpioConfig::new(None, None, output(5, GPO_EVENT_READING)).validate(4, 4).is_err());
+    assert!(GpioConfig::new(None, None, output(1, "flashing")).validate(4, 4).is_err());
+}
@@ -16,7 +16,7 @@ You should have received a copy of the GNU Affero General Public License
 along with this program.  If not, see <https://www.gnu.org/licenses/>.
  */
 
-use super::{ANTENNA_STATUS_CONNECTED, ANTENNA_STATUS_DISCONNECTED};
+use super::{stats::ReaderStats, ANTENNA_STATUS_CONNECTED, ANTENNA_STATUS_DISCONNECTED};
 
 pub fn antenna_status_str(status: u8) -> &'static str {
     if status == ANTENNA_STATUS_CONNECTED {
@@ -27,3 +27,14 @@ pub fn antenna_status_str(status: u8) -> &'static str {
     }
     return "";
 }
+
+// Tag rate and time since the last tag for the reading screen.
+pub fn stats_str(stats: &ReaderStats) -> String {
+    let last = match stats.milliseconds_since_last_tag {
+        Some(ms) if ms >= 3600000 => format!("{}h", ms / 3600000),
+        Some(ms) if ms >= 60000 => format!("{}m", ms / 60000),
+        Some(ms) => format!("{}s", ms / 1000),
+        None => String::from("-"),
+    };
+    format!("{:.0} t/s last {}", stats.tags_per_second, last)
+}
@@ -0,0 +1,150 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::{net::TcpStream, sync::{self, Arc, Mutex}, thread::JoinHandle};
+
+use crate::{control::{self, sound::SoundNotifier}, database::sqlite, llrp::{decoder::{self, Fields}, encoder::{Message, ROSpec}, message_types, parameter_types}, notifier, objects::read, processor};
+
+use super::{capture::{self, Capture}, generic::{self, Extensions, TagData}, reconnector::Reconnector, ReaderStatus, ANTENNA_STATUS_DISCONNECTED, MAX_ANTENNAS};
+
+pub mod requests;
+
+pub const DEFAULT_IMPINJ_PORT: u16 = 5084;
+// Each antenna hub supports 8 antennas, hub 1 is antennas 1-8, hub 2 is 9-16, etc.
+pub const ANTENNAS_PER_HUB: usize = 8;
+
+// Impinj readers need their extensions enabled before they'll accept any Impinj parameters. Once enabled
+// we ask for the higher resolution peak rssi in tag reports and the antenna hub status in the reader config.
+struct ImpinjExtensions;
+
+impl Extensions for ImpinjExtensions {
+    fn setup_steps(&self) -> &'static [ReaderStatus] {
+        &[ReaderStatus::ConnectingEnableExtensions]
+    }
+
+    fn send_setup_step(&mut self, status: &ReaderStatus, tcp_stream: &mut TcpStream, capture: &Arc<sync::Mutex<Capture>>, msg_id: &Arc<sync::Mutex<u32>>, version: u8) -> Result<(), &'static str> {
+        match status {
+            ReaderStatus::ConnectingEnableExtensions => {
+                send_enable_extensions(tcp_stream, capture, msg_id, version)?;
+                println!("-- Enable Impinj Extensions request on connection sent.");
+            },
+            _ => return Err("unknown setup step"),
+        }
+        Ok(())
+    }
+
+    fn setup_step_response(&self, _status: &ReaderStatus) -> u16 {
+        // CUSTOM_MESSAGE is the proper response for EnableExtensions
+        message_types::CUSTOM_MESSAGE
+    }
+
+    fn rospec(&self, rospec_id: &u32) -> ROSpec {
+        requests::rospec(rospec_id)
+    }
+
+    fn get_reader_config(&self, id: &u32) -> Message {
+        // get all configuration, this includes antenna properties and antenna hub configuration
+        requests::get_reader_config(id)
+    }
+
+    fn is_custom_response(&self, vendor: u32, subtype: u16) -> bool {
+        matches!((vendor, subtype),
+            (parameter_types::IMPINJ_VENDOR_ID, parameter_types::IMPINJ_ENABLE_EXTENSIONS_RESPONSE) |
+            (parameter_types::IMPINJ_VENDOR_ID, parameter_types::IMPINJ_SAVE_SETTINGS_RESPONSE)
+        )
+    }
+
+    fn process_tag_custom_parameter(&self, param: &decoder::Parameter, data: &mut TagData) {
+        // fields are the vendor, the subtype, then the value
+        if let Ok((parameter_types::IMPINJ_VENDOR_ID, subtype)) = param.custom_info() {
+            if subtype == parameter_types::IMPINJ_PEAK_RSSI as u32 {
+                // impinj reports peak rssi in hundredths of a dBm, prefer it over the standard value
+                if let Ok(rssi) = param.u16_at(8) {
+                    data.rssi = ((rssi as i16 as f32) / 100.0).round() as i8;
+                }
+            }
+        }
+    }
+
+    fn process_config_custom_parameter(&self, param: &decoder::Parameter, antennas: &mut [u8;MAX_ANTENNAS]) -> bool {
+        // fields are the vendor, the subtype, then the hub id, connected status, and fault status, each two bytes
+        match param.custom_info() {
+            Ok((parameter_types::IMPINJ_VENDOR_ID, subtype)) if subtype == parameter_types::IMPINJ_HUB_CONFIGURATION as u32 => {},
+            _ => return false,
+        }
+        let (hub_id, connected, fault) = match (param.u16_at(8), param.u16_at(10), param.u16_at(12)) {
+            (Ok(hub_id), Ok(connected), Ok(fault)) => (hub_id, connected, fault),
+            _ => return false,
+        };
+        if hub_id < 1 {
+            return false
+        }
+        if connected == parameter_types::IMPINJ_HUB_CONNECTED_CONNECTED && fault == parameter_types::IMPINJ_HUB_FAULT_NONE {
+            return false
+        }
+        // any antennas on a hub that isn't connected or is reporting a fault can't be read from
+        println!("Antenna hub {hub_id} is not available. Connected: {connected} Fault: {fault}");
+        let first = (hub_id as usize - 1) * ANTENNAS_PER_HUB;
+        let mut updated = false;
+        for antenna in antennas.iter_mut().skip(first).take(ANTENNAS_PER_HUB) {
+            *antenna = ANTENNA_STATUS_DISCONNECTED;
+            updated = true;
+        }
+        updated
+    }
+}
+
+pub fn connect(
+    reader: &mut super::Reader,
+    sqlite: &Arc<Mutex<sqlite::SQLite>>,
+    control: &Arc<Mutex<control::Control>>,
+    read_saver: &Arc<processor::ReadSaver>,
+    sound: Arc<SoundNotifier>,
+    reconnector: Option<Reconnector>,
+    notifier: notifier::Notifier,
+) -> Result<JoinHandle<()>, &'static str> {
+    generic::connect_with(reader, sqlite, control, read_saver, sound, reconnector, notifier, Box::new(ImpinjExtensions))
+}
+
+pub fn start(
+    reader: &mut super::Reader,
+    tcp_stream: TcpStream,
+    sqlite: &Arc<Mutex<sqlite::SQLite>>,
+    control: &Arc<Mutex<control::Control>>,
+    read_saver: &Arc<processor::ReadSaver>,
+    sound: Arc<SoundNotifier>,
+    reconnector: Option<Reconnector>,
+    notifier: notifier::Notifier,
+) -> Result<JoinHandle<()>, &'static str> {
+    generic::start_with(reader, tcp_stream, sqlite, control, read_saver, sound, reconnector, notifier, Box::new(ImpinjExtensions))
+}
+
+pub fn replay(
+    reader: &super::Reader,
+    records: &[capture::Record],
+    sqlite: &sqlite::SQLite,
+    control: &control::Control,
+) -> Result<Vec<read::Read>, &'static str> {
+    generic::replay_with(reader, records, sqlite, control, &ImpinjExtensions)
+}
+
+fn send_enable_extensions(tcp_stream: &mut TcpStream, capture: &Arc<sync::Mutex<Capture>>, msg_id: &Arc<sync::Mutex<u32>>, version: u8) -> Result<(), &'static str> {
+    let local_id = generic::next_msg_id(msg_id);
+    // enable impinj extensions, required before the reader will accept any impinj parameters
+    generic::write_request(tcp_stream, capture, &requests::enable_extensions(&local_id).version(version))
+}
@@ -0,0 +1,64 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::llrp::{encoder::{CustomParameter, Message, ROSpec}, parameter_types};
+
+use crate::reader::generic::requests as generic;
+
+pub fn enable_extensions(id: &u32) -> Message {
+    // vendor ID (impinj - 25588), message subtype (21)
+    Message::custom(parameter_types::IMPINJ_VENDOR_ID, parameter_types::IMPINJ_ENABLE_EXTENSIONS as u8, *id)
+        // reserved bytes
+        .u32(0)
+}
+
+// The default ROSpec with the Impinj tag report content selector asking for peak rssi.
+pub fn rospec(rospec_id: &u32) -> ROSpec {
+    let mut rospec = generic::rospec(rospec_id);
+    add_tag_report_content_selector(&mut rospec);
+    rospec
+}
+
+pub fn add_tag_report_content_selector(rospec: &mut ROSpec) {
+    if let Some(report) = rospec.report.as_mut() {
+        report.custom.push(CustomParameter {
+            vendor: parameter_types::IMPINJ_VENDOR_ID,
+            subtype: parameter_types::IMPINJ_TAG_REPORT_CONTENT_SELECTOR as u32,
+            data: Vec::new(),
+            parameters: vec![CustomParameter {
+                vendor: parameter_types::IMPINJ_VENDOR_ID,
+                subtype: parameter_types::IMPINJ_ENABLE_PEAK_RSSI as u32,
+                // peak rssi mode - 0 disabled, 1 enabled
+                data: vec![0x00, 0x01],
+                parameters: Vec::new(),
+            }],
+        });
+    }
+}
+
+pub fn get_reader_config(id: &u32) -> Message {
+    // config value - 0 all, this gives us antenna properties along with everything else
+    generic::get_reader_config(id, &0, &0, &0, &0)
+        .param(&CustomParameter {
+            vendor: parameter_types::IMPINJ_VENDOR_ID,
+            subtype: parameter_types::IMPINJ_REQUESTED_DATA as u32,
+            // requested data - all configuration (2000), this includes the antenna hub configuration
+            data: parameter_types::IMPINJ_REQUESTED_DATA_ALL_CONFIGURATION.to_be_bytes().to_vec(),
+            parameters: Vec::new(),
+        })
+}
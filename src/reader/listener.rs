@@ -0,0 +1,359 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::{env, io::{ErrorKind, Read}, net::{SocketAddr, TcpListener, TcpStream}, sync::{Arc, Mutex}, thread::{self, JoinHandle}, time::{Duration, Instant}};
+
+use crate::{control::{self, socket::{self, MAX_CONNECTED}, sound::{SoundNotifier, SoundType}}, database::{sqlite, DBError, Database}, llrp::{decoder::{self, DecodeError, Decoder, Fields}, message_types, parameter_types}, notifier, processor};
+
+use super::{capabilities, capture::Capture, generic::{self, requests}, Reader, ReaderStatus, AUTO_CONNECT_FALSE, READER_KIND_IMPINJ, READER_KIND_LLRP, READER_KIND_ZEBRA};
+
+#[cfg(test)]
+pub mod test;
+
+// Set to the port to listen on, or any other value to listen on the default LLRP port.
+pub const LISTEN_ENV: &str = "PORTAL_LLRP_LISTEN";
+pub const DEFAULT_LISTEN_PORT: u16 = 5084;
+
+// How long a reader has to tell us who it is after it connects.
+const IDENTIFY_TIMEOUT_SECONDS: u64 = 10;
+const READ_TIMEOUT_MILLISECONDS: u64 = 500;
+const ACCEPT_WAIT_MILLISECONDS: u64 = 500;
+
+// The port to listen for reader connections on, None if we shouldn't listen.
+pub fn listen_port() -> Option<u16> {
+    match env::var(LISTEN_ENV) {
+        Ok(val) if !val.is_empty() => Some(val.parse().unwrap_or(DEFAULT_LISTEN_PORT)),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Identity {
+    // MAC address or EPC from the readers Identification parameter.
+    pub identifier: String,
+    // IANA private enterprise number of the manufacturer, 0 if the reader didn't tell us.
+    pub manufacturer: u32,
+}
+
+// Accepts connections that readers open to us. Readers on DHCP or behind a switch can be set to connect
+// to the portal instead of us connecting to them.
+#[derive(Clone)]
+pub struct Listener {
+    port: u16,
+    keepalive: Arc<Mutex<bool>>,
+    readers: Arc<Mutex<Vec<Reader>>>,
+    joiners: Arc<Mutex<Vec<JoinHandle<()>>>>,
+    control_sockets: Arc<Mutex<[Option<TcpStream>;MAX_CONNECTED + 1]>>,
+    read_repeaters: Arc<Mutex<[bool;MAX_CONNECTED]>>,
+    control: Arc<Mutex<control::Control>>,
+    sqlite: Arc<Mutex<sqlite::SQLite>>,
+    read_saver: Arc<processor::ReadSaver>,
+    sound: Arc<SoundNotifier>,
+    notifier: notifier::Notifier,
+}
+
+impl Listener {
+    pub fn new(
+        port: u16,
+        keepalive: Arc<Mutex<bool>>,
+        readers: Arc<Mutex<Vec<Reader>>>,
+        joiners: Arc<Mutex<Vec<JoinHandle<()>>>>,
+        control_sockets: Arc<Mutex<[Option<TcpStream>;MAX_CONNECTED + 1]>>,
+        read_repeaters: Arc<Mutex<[bool;MAX_CONNECTED]>>,
+        control: Arc<Mutex<control::Control>>,
+        sqlite: Arc<Mutex<sqlite::SQLite>>,
+        read_saver: Arc<processor::ReadSaver>,
+        sound: Arc<SoundNotifier>,
+        notifier: notifier::Notifier,
+    ) -> Listener {
+        Listener {
+            port,
+            keepalive,
+            readers,
+            joiners,
+            control_sockets,
+            read_repeaters,
+            control,
+            sqlite,
+            read_saver,
+            sound,
+            notifier,
+        }
+    }
+
+    pub fn run(&self) {
+        let listener = match TcpListener::bind(SocketAddr::from(([0, 0, 0, 0], self.port))) {
+            Ok(listener) => listener,
+            Err(e) => {
+                println!("Unable to listen for reader connections on port {}. {e}", self.port);
+                return
+            }
+        };
+        // don't block so we can check if we've been told to quit
+        if let Err(e) = listener.set_nonblocking(true) {
+            println!("Unable to set reader listener to non-blocking. {e}");
+            return
+        }
+        println!("Listening for reader connections on port {}.", self.port);
+        loop {
+            if let Ok(keepalive) = self.keepalive.lock() {
+                if !*keepalive {
+                    break;
+                }
+            }
+            match listener.accept() {
+                Ok((stream, addr)) => {
+                    println!("Reader connection received from {addr}.");
+                    let t_listener = self.clone();
+                    thread::spawn(move|| {
+                        if let Err(e) = t_listener.accept(stream, addr) {
+                            println!("Unable to start reader connected from {addr}. {e}");
+                        }
+                    });
+                },
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                    thread::sleep(Duration::from_millis(ACCEPT_WAIT_MILLISECONDS));
+                },
+                Err(e) => {
+                    println!("Error accepting reader connection. {e}");
+                    thread::sleep(Duration::from_millis(ACCEPT_WAIT_MILLISECONDS));
+                }
+            }
+        }
+        println!("Reader listener closed.");
+    }
+
+    fn accept(&self, mut stream: TcpStream, addr: SocketAddr) -> Result<(), &'static str> {
+        if stream.set_nonblocking(false).is_err() {
+            return Err("unable to set stream to blocking")
+        }
+        let identity = identify(&mut stream)?;
+        println!("Reader at {addr} identified as {}.", identity.identifier);
+        let ip_address = addr.ip().to_string();
+        let mut sq = match self.sqlite.lock() {
+            Ok(sq) => sq,
+            Err(_) => return Err("error getting database mutex")
+        };
+        let mut u_readers = match self.readers.lock() {
+            Ok(readers) => readers,
+            Err(_) => return Err("error getting readers mutex")
+        };
+        let mut created = false;
+        let reader_id = match sq.get_reader_by_identifier(&identity.identifier) {
+            Ok(reader) => reader.id(),
+            Err(DBError::NotFound) => {
+                // readers we already know about are matched on their address the first time they connect to us
+                let id = match u_readers.iter().find(|r| r.ip_address() == ip_address) {
+                    Some(reader) => reader.id(),
+                    None => {
+                        let mut reader = match Reader::new(
+                            0,
+                            String::from(reader_kind(identity.manufacturer)),
+                            reader_nickname(&identity.identifier),
+                            ip_address.clone(),
+                            DEFAULT_LISTEN_PORT,
+                            AUTO_CONNECT_FALSE,
+                            self.control_sockets.clone(),
+                            self.read_repeaters.clone(),
+                            self.readers.clone(),
+                        ) {
+                            Ok(reader) => reader,
+                            Err(_) => return Err("unable to create reader")
+                        };
+                        match sq.save_reader(&reader) {
+                            Ok(id) => {
+                                println!("Saved new reader {} for {}.", reader.nickname(), identity.identifier);
+                                reader.set_id(id);
+                                u_readers.push(reader);
+                                created = true;
+                                id
+                            },
+                            Err(e) => {
+                                println!("Error saving reader. {e}");
+                                return Err("unable to save reader")
+                            }
+                        }
+                    }
+                };
+                if let Err(e) = sq.save_reader_identifier(&id, &identity.identifier) {
+                    println!("Error saving reader identifier. {e}");
+                }
+                id
+            },
+            Err(e) => {
+                println!("Error looking up reader identifier. {e}");
+                return Err("unable to look up reader")
+            }
+        };
+        let ix = match u_readers.iter().position(|r| r.id() == reader_id) {
+            Some(ix) => ix,
+            None => return Err("reader not found")
+        };
+        if let Ok(stat) = u_readers[ix].status.lock() {
+            if *stat != ReaderStatus::Disconnected {
+                return Err("reader is already connected")
+            }
+        }
+        let old_reader = u_readers.remove(ix);
+        let mut reader = match Reader::new(
+            old_reader.id(),
+            String::from(old_reader.kind()),
+            String::from(old_reader.nickname()),
+            ip_address,
+            old_reader.port(),
+            old_reader.auto_connect(),
+            self.control_sockets.clone(),
+            self.read_repeaters.clone(),
+            self.readers.clone(),
+        ) {
+            Ok(reader) => reader,
+            Err(_) => {
+                u_readers.push(old_reader);
+                return Err("unable to create reader")
+            }
+        };
+        reader.errors = old_reader.errors.clone();
+        reader.capture = old_reader.capture.clone();
+        // keep the saved address up to date for readers that move around
+        if reader.ip_address() != old_reader.ip_address() {
+            if let Err(e) = sq.save_reader(&reader) {
+                println!("Error updating reader address. {e}");
+            }
+        }
+        drop(sq);
+        // the reader will connect to us again if the connection drops, so there's nothing to reconnect
+        let result = reader.start(
+            stream,
+            &self.sqlite,
+            &self.control,
+            &self.read_saver,
+            self.sound.clone(),
+            None,
+            self.notifier.clone(),
+        );
+        u_readers.push(reader);
+        if created {
+            if let Ok(c_socks) = self.control_sockets.lock() {
+                for sock in c_socks.iter().take(MAX_CONNECTED).flatten() {
+                    _ = socket::write_reader_list(sock, &u_readers);
+                }
+            }
+        }
+        match result {
+            Ok(j) => {
+                if let Ok(mut join) = self.joiners.lock() {
+                    join.push(j);
+                }
+                self.sound.notify_custom(SoundType::Connected);
+                Ok(())
+            },
+            Err(e) => Err(e)
+        }
+    }
+}
+
+// Asks a reader that just connected to us who it is and who made it.
+pub fn identify(stream: &mut TcpStream) -> Result<Identity, &'static str> {
+    if stream.set_read_timeout(Some(Duration::from_millis(READ_TIMEOUT_MILLISECONDS))).is_err() {
+        return Err("unable to set read timeout")
+    }
+    // nothing is captured until we know which reader connected
+    let capture = Arc::new(Mutex::new(Capture::new()));
+    // config value 1 is the identification parameter
+    generic::write_request(stream, &capture, &requests::get_reader_config(&1, &0, &1, &0, &0))?;
+    generic::write_request(stream, &capture, &requests::get_reader_capabilities(&2))?;
+    let mut decoder = Decoder::new();
+    let mut buf = [0u8; 4096];
+    let mut identifier: Option<String> = None;
+    let mut manufacturer: Option<u32> = None;
+    let started = Instant::now();
+    while identifier.is_none() || manufacturer.is_none() {
+        if started.elapsed() > Duration::from_secs(IDENTIFY_TIMEOUT_SECONDS) {
+            return Err("reader did not identify itself")
+        }
+        match stream.read(&mut buf) {
+            Ok(0) => return Err("reader closed the connection"),
+            Ok(n) => decoder.push(&buf[..n]),
+            Err(e) if e.kind() == ErrorKind::WouldBlock || e.kind() == ErrorKind::TimedOut => continue,
+            Err(_) => return Err("error reading from reader"),
+        }
+        while let Some(next) = decoder.next_message() {
+            let msg = match next {
+                Ok(msg) => msg,
+                Err(e) => {
+                    println!("Error decoding message from reader. {e}");
+                    continue;
+                }
+            };
+            match msg.kind {
+                message_types::GET_READER_CONFIG_RESPONSE => {
+                    match identifier_from_response(&msg) {
+                        Ok(id) => identifier = Some(id),
+                        Err(e) => {
+                            println!("Error reading reader identification. {e}");
+                            return Err("reader did not identify itself")
+                        }
+                    }
+                },
+                message_types::GET_READER_CAPABILITIES_RESPONSE => {
+                    // not knowing who made the reader only means we fall back to plain LLRP
+                    manufacturer = Some(capabilities::from_response(&msg).map(|caps| caps.manufacturer).unwrap_or(0));
+                },
+                message_types::ERROR_MESSAGE => {
+                    manufacturer = Some(manufacturer.unwrap_or(0));
+                },
+                // connection attempt events and keepalives
+                _ => {},
+            }
+        }
+    }
+    match (identifier, manufacturer) {
+        (Some(identifier), Some(manufacturer)) => Ok(Identity { identifier, manufacturer }),
+        _ => Err("reader did not identify itself")
+    }
+}
+
+// Reads the Identification parameter out of a GET_READER_CONFIG_RESPONSE.
+pub fn identifier_from_response(msg: &decoder::Message) -> Result<String, DecodeError> {
+    let param = match msg.find(parameter_types::IDENTIFICATION) {
+        Some(param) => param,
+        None => return Err(DecodeError::MissingParameter(String::from("identification")))
+    };
+    // byte 0 is the id type, 0 for a MAC address and 1 for an EPC
+    // bytes 1 and 2 are the number of bytes in the id that follows
+    let count = param.u16_at(1)? as usize;
+    let id = param.bytes_at(3, count)?;
+    if id.is_empty() {
+        return Err(DecodeError::InvalidField(String::from("empty reader identification")))
+    }
+    Ok(id.iter().map(|b| format!("{:02X}", b)).collect::<Vec<String>>().join(":"))
+}
+
+pub fn reader_kind(manufacturer: u32) -> &'static str {
+    match manufacturer {
+        parameter_types::MOTOROLA_VENDOR_ID => READER_KIND_ZEBRA,
+        parameter_types::IMPINJ_VENDOR_ID => READER_KIND_IMPINJ,
+        _ => READER_KIND_LLRP,
+    }
+}
+
+// Name for readers we've never seen before, the end of a MAC address is usually printed on the reader.
+pub fn reader_nickname(identifier: &str) -> String {
+    let digits: String = identifier.chars().filter(|c| *c != ':').collect();
+    format!("Reader {}", &digits[digits.len().saturating_sub(6)..])
+}
@@ -0,0 +1,102 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::{io::Write, net::{TcpListener, TcpStream}, thread};
+
+use crate::{llrp::{decoder::{decode_message, DecodeError}, parameter_types}, reader::{READER_KIND_IMPINJ, READER_KIND_LLRP, READER_KIND_ZEBRA}};
+
+use super::{identifier_from_response, identify, reader_kind, reader_nickname, Identity};
+
+// GET_READER_CONFIG_RESPONSE with an 8 byte MAC address identification
+const READER_CONFIG: [u8; 33] = [
+    0x04, 0x0C, 0x00, 0x00, 0x00, 0x21, 0x00, 0x00, 0x00, 0x01,
+    // LLRPStatus, success
+    0x01, 0x1F, 0x00, 0x08, 0x00, 0x00, 0x00, 0x00,
+    // Identification, length 15, MAC address, 8 bytes
+    0x00, 0xDA, 0x00, 0x0F, 0x00, 0x00, 0x08,
+        0x00, 0x16, 0x25, 0xFF, 0xFE, 0x12, 0xA1, 0xB2,
+];
+
+// GET_READER_CAPABILITIES_RESPONSE with only the general device capabilities, Motorola, no firmware version
+const CAPABILITIES: [u8; 36] = [
+    0x04, 0x0B, 0x00, 0x00, 0x00, 0x24, 0x00, 0x00, 0x00, 0x02,
+    // LLRPStatus, success
+    0x01, 0x1F, 0x00, 0x08, 0x00, 0x00, 0x00, 0x00,
+    // GeneralDeviceCapabilities, length 18
+    0x00, 0x89, 0x00, 0x12,
+        0x00, 0x04, 0xC0, 0x00,
+        0x00, 0x00, 0x00, 0xA1,
+        0x00, 0x00, 0x25, 0x80,
+        0x00, 0x00,
+];
+
+// READER_EVENT_NOTIFICATION with a successful ConnectionAttemptEvent, sent by readers when they connect
+const CONNECTION_ATTEMPT: [u8; 32] = [
+    0x04, 0x3F, 0x00, 0x00, 0x00, 0x20, 0x00, 0x00, 0x00, 0x00,
+    // ReaderEventNotificationData, length 22
+    0x00, 0xF6, 0x00, 0x16,
+        // UTCTimestamp
+        0x00, 0x80, 0x00, 0x0C, 0x00, 0x05, 0xF8, 0x3B, 0x3C, 0x22, 0x10, 0x00,
+        // ConnectionAttemptEvent, success
+        0x01, 0x00, 0x00, 0x06, 0x00, 0x00,
+];
+
+#[test]
+fn test_identifier_from_response() {
+    let msg = decode_message(&READER_CONFIG).unwrap();
+    assert_eq!("00:16:25:FF:FE:12:A1:B2", identifier_from_response(&msg).unwrap());
+    let msg = decode_message(&CAPABILITIES).unwrap();
+    match identifier_from_response(&msg) {
+        Err(DecodeError::MissingParameter(_)) => {},
+        other => panic!("expected missing parameter, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_reader_kind() {
+    assert_eq!(READER_KIND_ZEBRA, reader_kind(parameter_types::MOTOROLA_VENDOR_ID));
+    assert_eq!(READER_KIND_IMPINJ, reader_kind(parameter_types::IMPINJ_VENDOR_ID));
+    assert_eq!(READER_KIND_LLRP, reader_kind(0));
+}
+
+#[test]
+fn test_reader_nickname() {
+    assert_eq!("Reader 12A1B2", reader_nickname("00:16:25:FF:FE:12:A1:B2"));
+    assert_eq!("Reader A1B2", reader_nickname("A1:B2"));
+}
+
+#[test]
+fn test_identify() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    // the reader connects to us, tells us it connected, then answers our requests
+    let reader = thread::spawn(move|| {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(&CONNECTION_ATTEMPT).unwrap();
+        stream.write_all(&CAPABILITIES).unwrap();
+        stream.write_all(&READER_CONFIG).unwrap();
+        stream
+    });
+    let (mut stream, _) = listener.accept().unwrap();
+    let identity = identify(&mut stream).unwrap();
+    assert_eq!(Identity {
+        identifier: String::from("00:16:25:FF:FE:12:A1:B2"),
+        manufacturer: parameter_types::MOTOROLA_VENDOR_ID,
+    }, identity);
+    drop(reader.join().unwrap());
+}
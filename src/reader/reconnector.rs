@@ -16,14 +16,12 @@ You should have received a copy of the GNU Affero General Public License
 along with this program.  If not, see <https://www.gnu.org/licenses/>.
  */
 
-use std::{net::TcpStream, sync::{Arc, Mutex}, thread::{self, JoinHandle}, time::Duration};
+#[cfg(test)]
+pub mod test;
 
-use crate::{control::{self, socket::{self, MAX_CONNECTED}, sound::SoundNotifier}, database::sqlite, notifier, processor::{self}};
+use std::{net::TcpStream, sync::{Arc, Mutex}, thread::{self, JoinHandle}, time::Duration};
 
-// Total potential time before reconnect attempts are stopped
-// is WAITING_PERIOD_SECONDS * RECONNECT_ATTEMPTS
-pub const WAITING_PERIOD_SECONDS: u64 = 1;
-pub const RECONNECT_ATTEMPTS: i32 = 30;
+use crate::{control::{self, socket::{self, responses::Responses, MAX_CONNECTED}, sound::SoundNotifier}, database::{sqlite, Database}, notifier, objects::reconnect::ReconnectPolicy, processor::{self}};
 
 #[derive(Clone)]
 pub struct Reconnector {
@@ -36,7 +34,7 @@ pub struct Reconnector {
     read_saver: Arc<processor::ReadSaver>,
     sound: Arc<SoundNotifier>,
     id: i64,
-    count: i32,
+    count: u32,
     notifier: notifier::Notifier,
 }
 
@@ -52,7 +50,7 @@ impl Reconnector {
         read_saver: Arc<processor::ReadSaver>,
         sound: Arc<SoundNotifier>,
         id: i64,
-        count: i32,
+        count: u32,
         notifier: notifier::Notifier,
     ) -> Reconnector {
         Reconnector {
@@ -71,111 +69,127 @@ impl Reconnector {
     }
 
     pub fn run(self) {
-        // Try to connect at most 5 times.
-        if self.count > RECONNECT_ATTEMPTS {
-            return;
-        }
-        println!("Attempting to reconnect to reader. Attempt {0}.", self.count);
-        if let Ok(mut readers) = self.readers.lock() {
-            match readers.iter().position(|x| x.id() == self.id) {
-                Some(ix) => {
-                    let mut old_reader = readers.remove(ix);
-                    println!("Reconnecting to reader {}.", old_reader.nickname());
-                    old_reader.set_control_sockets(self.control_sockets.clone());
-                    old_reader.set_readers(self.readers.clone());
-                    old_reader.set_read_repeaters(self.read_repeaters.clone());
-                    let reconnector = Reconnector::new(
-                        self.readers.clone(),
-                        self.joiners.clone(),
-                        self.control_sockets.clone(),
-                        self.read_repeaters.clone(),
-                        self.control.clone(),
-                        self.sqlite.clone(),
-                        self.read_saver.clone(),
+        let mut attempt = self.count;
+        loop {
+            // the policy is loaded for every attempt so changes made while we're reconnecting are used
+            let policy = match self.sqlite.lock() {
+                Ok(sq) => match sq.get_reconnect_policy(&self.id) {
+                    Ok(policy) => policy,
+                    Err(e) => {
+                        println!("Error getting reconnect policy from database: {e}");
+                        ReconnectPolicy::default()
+                    }
+                },
+                Err(_) => ReconnectPolicy::default(),
+            };
+            let mut delay: Option<u64> = None;
+            if let Ok(mut readers) = self.readers.lock() {
+                let ix = match readers.iter().position(|x| x.id() == self.id) {
+                    Some(ix) => ix,
+                    None => return,
+                };
+                // the reader was disconnected while we were waiting to try again
+                if attempt > self.count && readers[ix].reconnecting() != Some(attempt - 1) {
+                    println!("Reader {} was disconnected, no longer attempting to reconnect.", readers[ix].nickname());
+                    return;
+                }
+                if policy.gives_up(attempt) {
+                    println!("Giving up on reconnecting to reader {} after {} attempts.", readers[ix].nickname(), attempt - 1);
+                    if let Ok(mut reconnecting) = readers[ix].reconnect_attempt.lock() {
+                        *reconnecting = 0;
+                    }
+                    if let Ok(c_socks) = self.control_sockets.lock() {
+                        for sock in c_socks.iter().take(MAX_CONNECTED).flatten() {
+                            _ = socket::write_response(sock, &Responses::ReaderReconnectFailed { reader_name: String::from(readers[ix].nickname()), attempts: attempt - 1 });
+                        }
+                    }
+                    return;
+                }
+                println!("Attempting to reconnect to reader. Attempt {attempt}.");
+                if let Ok(mut reconnecting) = readers[ix].reconnect_attempt.lock() {
+                    *reconnecting = attempt;
+                }
+                if let Ok(c_socks) = self.control_sockets.lock() {
+                    for sock in c_socks.iter().take(MAX_CONNECTED).flatten() {
+                        _ = socket::write_response(sock, &Responses::ReaderReconnecting { reader_name: String::from(readers[ix].nickname()), attempt, max_attempts: policy.max_attempts() });
+                    }
+                }
+                let mut old_reader = readers.remove(ix);
+                println!("Reconnecting to reader {}.", old_reader.nickname());
+                old_reader.set_control_sockets(self.control_sockets.clone());
+                old_reader.set_readers(self.readers.clone());
+                old_reader.set_read_repeaters(self.read_repeaters.clone());
+                let reconnector = Reconnector::new(
+                    self.readers.clone(),
+                    self.joiners.clone(),
+                    self.control_sockets.clone(),
+                    self.read_repeaters.clone(),
+                    self.control.clone(),
+                    self.sqlite.clone(),
+                    self.read_saver.clone(),
+                    self.sound.clone(),
+                    self.id,
+                    1,
+                    self.notifier.clone(),
+                );
+                println!("Initializing reader.");
+                match old_reader.connect(
+                        &self.sqlite.clone(),
+                        &self.control.clone(),
+                        &self.read_saver.clone(),
                         self.sound.clone(),
-                        self.id,
-                        1,
+                        Some(reconnector),
                         self.notifier.clone(),
-                    );
-                    println!("Initializing reader.");
-                    match old_reader.connect(
-                            &self.sqlite.clone(),
-                            &self.control.clone(),
-                            &self.read_saver.clone(),
-                            self.sound.clone(),
-                            Some(reconnector),
-                            self.notifier.clone(),
-                        ) {
-                        Ok(j) => {
-                            if let Ok(mut join) = self.joiners.lock() {
-                                join.push(j);
+                    ) {
+                    Ok(j) => {
+                        if let Ok(mut join) = self.joiners.lock() {
+                            join.push(j);
+                        }
+                        // wait for a few milliseconds before checking if we're reading
+                        thread::sleep(Duration::from_millis(socket::CONNECTION_CHANGE_PAUSE));
+                        if old_reader.is_reading() != Some(true) {
+                            match old_reader.disconnect() {
+                                Ok(_) => {},
+                                Err(_) => {
+                                    eprintln!("error attempting to disconnect from reader before reconnect attempt")
+                                },
+                            }
+                            // disconnecting clears the attempt, but this one is ours so we're still reconnecting
+                            if let Ok(mut reconnecting) = old_reader.reconnect_attempt.lock() {
+                                *reconnecting = attempt;
                             }
-                        },
-                        Err(e) => {
-                            println!("Error connecting to reader: {e}");
-                            // wait for a few seconds before retrying
-                            readers.push(old_reader);
-                            thread::sleep(Duration::from_secs(WAITING_PERIOD_SECONDS));
-                            let new_reconnector = Reconnector::new(
-                                self.readers.clone(),
-                                self.joiners.clone(),
-                                self.control_sockets.clone(),
-                                self.read_repeaters.clone(),
-                                self.control.clone(),
-                                self.sqlite.clone(),
-                                self.read_saver.clone(),
-                                self.sound.clone(),
-                                self.id,
-                                self.count + 1,
-                                self.notifier.clone(),
-                            );
-                            drop(readers);
-                            new_reconnector.run();
-                            // return so only on success will the control sockets be notified of changes
-                            return;
+                            delay = Some(policy.delay(attempt));
                         }
+                    },
+                    Err(e) => {
+                        println!("Error connecting to reader: {e}");
+                        delay = Some(policy.delay(attempt));
                     }
-                    // wait for a few milliseconds before checking if we're reading
-                    thread::sleep(Duration::from_millis(socket::CONNECTION_CHANGE_PAUSE));
-                    if old_reader.is_reading() != Some(true) {
-                        match old_reader.disconnect() {
-                            Ok(_) => {},
-                            Err(_) => {
-                                eprintln!("error attempting to disconnect from reader before reconnect attempt")
-                            },
-                        }
-                        readers.push(old_reader);
-                        thread::sleep(Duration::from_secs(WAITING_PERIOD_SECONDS));
-                        let new_reconnector = Reconnector::new(
-                            self.readers.clone(),
-                            self.joiners.clone(),
-                            self.control_sockets.clone(),
-                            self.read_repeaters.clone(),
-                            self.control.clone(),
-                            self.sqlite.clone(),
-                            self.read_saver.clone(),
-                            self.sound.clone(),
-                            self.id,
-                            self.count + 1,
-                            self.notifier.clone(),
-                        );
-                        drop(readers);
-                        new_reconnector.run();
-                        // return so only on success will the control sockets be notified of changes
-                        return;
+                }
+                if delay.is_none() {
+                    if let Ok(mut reconnecting) = old_reader.reconnect_attempt.lock() {
+                        *reconnecting = 0;
                     }
-                    readers.push(old_reader);
-                },
-                None => {  },
-            }
-            println!("Sending reader updates to connected sockets.");
-            if let Ok(c_socks) = self.control_sockets.lock() {
-                for sock in c_socks.iter() {
-                    if let Some(sock) = sock {
-                        _ = socket::write_reader_list(&sock, &readers);
+                }
+                readers.push(old_reader);
+                // only on success will the control sockets be notified of changes
+                if delay.is_none() {
+                    println!("Sending reader updates to connected sockets.");
+                    if let Ok(c_socks) = self.control_sockets.lock() {
+                        for sock in c_socks.iter() {
+                            if let Some(sock) = sock {
+                                _ = socket::write_reader_list(&sock, &readers);
+                            }
+                        }
                     }
                 }
             }
+            // the readers lock is released while we wait so the reader can still be stopped or changed
+            match delay {
+                Some(milliseconds) => thread::sleep(Duration::from_millis(milliseconds)),
+                None => return,
+            }
+            attempt += 1;
         }
     }
 }
@@ -0,0 +1,49 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::objects::reconnect::ReconnectPolicy;
+
+#[test]
+fn test_policy_delay() {
+    // the default waits a second between each of 30 attempts
+    let policy = ReconnectPolicy::default();
+    assert_eq!(1000, policy.delay(1));
+    assert_eq!(1000, policy.delay(30));
+    assert!(!policy.gives_up(30));
+    assert!(policy.gives_up(31));
+    // backing off doubles the wait until it hits the cap
+    let policy = ReconnectPolicy::new(0, 500, 2.0, 5000);
+    assert_eq!(500, policy.delay(1));
+    assert_eq!(1000, policy.delay(2));
+    assert_eq!(4000, policy.delay(4));
+    assert_eq!(5000, policy.delay(5));
+    assert_eq!(5000, policy.delay(u32::MAX));
+    // 0 attempts never gives up
+    assert!(!policy.gives_up(u32::MAX));
+}
+
+#[test]
+fn test_policy_validate() {
+    assert!(ReconnectPolicy::default().validate().is_ok());
+    assert!(ReconnectPolicy::new(0, 1000, 1.5, 60000).validate().is_ok());
+    assert!(ReconnectPolicy::new(10, 1000, 0.5, 60000).validate().is_err());
+    assert!(ReconnectPolicy::new(10, 1000, f64::NAN, 60000).validate().is_err());
+    assert!(ReconnectPolicy::new(10, 1000, 11.0, 60000).validate().is_err());
+    assert!(ReconnectPolicy::new(10, 5000, 2.0, 1000).validate().is_err());
+    assert!(ReconnectPolicy::new(10, 1000, 2.0, 4000000).validate().is_err());
+}
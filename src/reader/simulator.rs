@@ -0,0 +1,826 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+
+use std::{collections::{BTreeMap, BTreeSet}, io::{self, ErrorKind, Read, Write}, net::{SocketAddr, TcpListener, TcpStream}, sync::{Arc, Mutex}, thread::{self, JoinHandle}, time::{Duration, Instant, SystemTime, UNIX_EPOCH}};
+
+use crate::llrp::{decoder::{self, Decoder, Fields}, encoder::{tlv, tv, Message, KEEPALIVE_PERIODIC, MEMORY_BANK_TID, START_TRIGGER_GPI, STOP_TRIGGER_GPI, VERSION_1_0_1, VERSION_1_1}, message_types, parameter_types};
+
+#[cfg(test)]
+pub mod test;
+
+pub const DEFAULT_SIMULATOR_PORT: u16 = 5084;
+
+const BUFFER_SIZE: usize = 4096;
+const READ_TIMEOUT_MILLISECONDS: u64 = 20;
+const ACCEPT_WAIT_MILLISECONDS: u64 = 50;
+
+// The manufacturer a simulated reader of the kind given reports, so the portal uses the same vendor extensions.
+pub fn manufacturer(kind: &str) -> u32 {
+    match kind {
+        super::READER_KIND_ZEBRA => parameter_types::MOTOROLA_VENDOR_ID,
+        super::READER_KIND_IMPINJ => parameter_types::IMPINJ_VENDOR_ID,
+        _ => 0,
+    }
+}
+
+// A tag the simulator reports, in the order given.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulatedTag {
+    pub chip: u128,
+    pub antenna: u16,
+    pub rssi: i8,
+}
+
+// What goes in each tag report besides the EPC, antenna, peak RSSI and first seen time.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReportContents {
+    pub channel_index: bool,
+    pub last_seen: bool,
+    pub seen_count: bool,
+    // Memory reads from the enabled AccessSpec as op spec id, memory bank and word count.
+    pub reads: Vec<(u16, u8, u16)>,
+    // How far the reader's clock is ahead of the real time, moves the timestamps on each tag.
+    pub clock_offset_milliseconds: i64,
+}
+
+// Things a real reader does to us that we want to be able to reproduce.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Fault {
+    // Stop sending keepalives once this many have been sent on a connection. Cleared once the connection closes.
+    DropKeepalives { after: u32 },
+    // Reset the connection once this many tag reports have been sent on it.
+    ResetConnection { after_reports: u32 },
+    // Answer the next count requests of this message type with the status code given.
+    ErrorResponse { kind: u16, code: u16, count: u32 },
+    // Answer every request of this message type with an ERROR_MESSAGE, the way readers do for messages they don't support.
+    UnsupportedMessage { kind: u16 },
+}
+
+#[derive(Debug, Clone)]
+pub struct SimulatorConfig {
+    // IANA private enterprise number reported in the capabilities, Motorola for a Zebra or Impinj for an Impinj.
+    pub manufacturer: u32,
+    pub model: u32,
+    pub firmware: String,
+    pub max_antennas: u16,
+    pub gpis: u16,
+    pub gpos: u16,
+    // Antennas that are plugged in, random tags are read on these.
+    pub antennas: Vec<u16>,
+    // MAC address reported in the Identification parameter.
+    pub identifier: Vec<u8>,
+    // Highest LLRP version supported. A 1.0.1 reader answers the version messages with an ERROR_MESSAGE.
+    pub version: u8,
+    // Time between tag reports while reading, and the number of tags in each report.
+    pub report_interval_ms: u64,
+    pub tags_per_report: usize,
+    // Tags are reported from the script in order, over and over. Random tags are reported when it's empty.
+    pub script: Vec<SimulatedTag>,
+    // Random tags are picked from the chip_count chips starting at first_chip.
+    pub first_chip: u128,
+    pub chip_count: u32,
+    pub faults: Vec<Fault>,
+    // How far the reader's clock is ahead of ours, negative when it's behind.
+    pub clock_offset_milliseconds: i64,
+    // Keep reading when the connection is lost while reading, holding the reports until GET_REPORT asks for them.
+    pub buffer_reports: bool,
+}
+
+impl Default for SimulatorConfig {
+    fn default() -> Self {
+        SimulatorConfig {
+            manufacturer: 0,
+            model: 1,
+            firmware: String::from("simulator"),
+            max_antennas: 4,
+            gpis: 4,
+            gpos: 4,
+            antennas: vec![1, 2, 3, 4],
+            identifier: vec![0x00, 0x16, 0x25, 0x00, 0x00, 0x01],
+            version: VERSION_1_1,
+            report_interval_ms: 250,
+            tags_per_report: 1,
+            script: Vec::new(),
+            first_chip: 1000,
+            chip_count: 100,
+            faults: Vec::new(),
+            clock_offset_milliseconds: 0,
+            buffer_reports: false,
+        }
+    }
+}
+
+// What the simulator has seen and done, for checking the portal behaved.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SimulatorStats {
+    pub connections: u32,
+    // Message types received, in order.
+    pub received: Vec<u16>,
+    pub keepalives_sent: u32,
+    pub keepalive_acks: u32,
+    pub reports_sent: u32,
+    pub chips_sent: BTreeSet<u128>,
+    // Chips read while nobody was connected, sent once GET_REPORT asks for them.
+    pub chips_held: BTreeSet<u128>,
+    pub reading: bool,
+    // LLRP version in use on the last connection.
+    pub version: u8,
+    // Times the reader's clock was set.
+    pub clock_sets: u32,
+    // The last value written to each GPO port.
+    pub gpo: BTreeMap<u16, bool>,
+    // ROSpecs added, in order, with the antennas each one reads from.
+    pub rospecs: Vec<(u32, Vec<u16>)>,
+}
+
+impl SimulatorStats {
+    pub fn received_count(&self, kind: u16) -> usize {
+        self.received.iter().filter(|k| **k == kind).count()
+    }
+}
+
+// State for the connection currently open to the simulator.
+struct Connection {
+    keepalive_interval: Option<Duration>,
+    last_keepalive: Instant,
+    keepalives_sent: u32,
+    dropped_keepalives: bool,
+    last_report: Instant,
+    reports_sent: u32,
+    reading: bool,
+    // Whether the ROSpec is enabled, and the GPI port states that start and stop it if it has GPI triggers.
+    enabled: bool,
+    start_gpi: Option<(u16, bool)>,
+    stop_gpi: Option<(u16, bool)>,
+    next_id: u32,
+    script_ix: usize,
+    contents: ReportContents,
+    // Memory reads from an AccessSpec that hasn't been enabled yet.
+    access_reads: Vec<(u16, u8, u16)>,
+    // Every connection starts on 1.0.1 until the portal asks for something else.
+    version: u8,
+}
+
+// Reading carried on after the connection was lost.
+struct Offline {
+    last_report: Instant,
+    script_ix: usize,
+    contents: ReportContents,
+}
+
+// An LLRP reader that speaks enough of the protocol to take the portal through connecting, reading and stopping.
+// Only one connection is served at a time, the same as a real reader.
+pub struct Simulator {
+    listener: TcpListener,
+    config: SimulatorConfig,
+    faults: Arc<Mutex<Vec<Fault>>>,
+    stats: Arc<Mutex<SimulatorStats>>,
+    gpi: Arc<Mutex<BTreeMap<u16, bool>>>,
+    keepalive: Arc<Mutex<bool>>,
+    offline: Mutex<Option<Offline>>,
+    // TagReportData parameters waiting for GET_REPORT.
+    held: Mutex<Vec<u8>>,
+}
+
+impl Simulator {
+    pub fn bind(addr: SocketAddr, config: SimulatorConfig) -> io::Result<Simulator> {
+        let listener = TcpListener::bind(addr)?;
+        // don't block so we can check if we've been told to quit
+        listener.set_nonblocking(true)?;
+        let faults = Arc::new(Mutex::new(config.faults.clone()));
+        Ok(Simulator {
+            listener,
+            config,
+            faults,
+            stats: Arc::new(Mutex::new(SimulatorStats::default())),
+            gpi: Arc::new(Mutex::new(BTreeMap::new())),
+            keepalive: Arc::new(Mutex::new(true)),
+            offline: Mutex::new(None),
+            held: Mutex::new(Vec::new()),
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    pub fn stats(&self) -> Arc<Mutex<SimulatorStats>> {
+        self.stats.clone()
+    }
+
+    pub fn faults(&self) -> Arc<Mutex<Vec<Fault>>> {
+        self.faults.clone()
+    }
+
+    // GPI port states, ports that haven't been set are low.
+    pub fn gpi(&self) -> Arc<Mutex<BTreeMap<u16, bool>>> {
+        self.gpi.clone()
+    }
+
+    // Set to false to close the simulator.
+    pub fn keepalive(&self) -> Arc<Mutex<bool>> {
+        self.keepalive.clone()
+    }
+
+    pub fn spawn(self) -> JoinHandle<()> {
+        thread::spawn(move|| self.run())
+    }
+
+    pub fn run(&self) {
+        loop {
+            if !self.running() {
+                break;
+            }
+            match self.listener.accept() {
+                Ok((stream, addr)) => {
+                    println!("Simulator connection received from {addr}.");
+                    // the portal replaces the ROSpec once it's connected again
+                    if let Ok(mut offline) = self.offline.lock() {
+                        *offline = None;
+                    }
+                    if let Ok(mut stats) = self.stats.lock() {
+                        stats.connections += 1;
+                    }
+                    if let Err(e) = self.serve(stream) {
+                        println!("Simulator connection closed. {e}");
+                    }
+                    if let Ok(mut stats) = self.stats.lock() {
+                        stats.reading = false;
+                    }
+                },
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                    self.read_offline();
+                    thread::sleep(Duration::from_millis(ACCEPT_WAIT_MILLISECONDS));
+                },
+                Err(e) => {
+                    println!("Error accepting simulator connection. {e}");
+                    thread::sleep(Duration::from_millis(ACCEPT_WAIT_MILLISECONDS));
+                }
+            }
+        }
+        println!("Simulator closed.");
+    }
+
+    fn running(&self) -> bool {
+        match self.keepalive.lock() {
+            Ok(keepalive) => *keepalive,
+            Err(_) => false,
+        }
+    }
+
+    fn serve(&self, mut stream: TcpStream) -> io::Result<()> {
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(Duration::from_millis(READ_TIMEOUT_MILLISECONDS)))?;
+        let mut conn = Connection {
+            keepalive_interval: None,
+            last_keepalive: Instant::now(),
+            keepalives_sent: 0,
+            dropped_keepalives: false,
+            last_report: Instant::now(),
+            reports_sent: 0,
+            reading: false,
+            enabled: false,
+            start_gpi: None,
+            stop_gpi: None,
+            next_id: 1,
+            script_ix: 0,
+            contents: ReportContents {
+                clock_offset_milliseconds: self.config.clock_offset_milliseconds,
+                ..Default::default()
+            },
+            access_reads: Vec::new(),
+            version: VERSION_1_0_1,
+        };
+        if let Ok(mut stats) = self.stats.lock() {
+            stats.version = conn.version;
+        }
+        let result = self.serve_connection(&mut stream, &mut conn);
+        // a reader that buffers reports doesn't stop reading because we lost the portal
+        if conn.reading && self.config.buffer_reports {
+            if let Ok(mut offline) = self.offline.lock() {
+                *offline = Some(Offline {
+                    last_report: Instant::now(),
+                    script_ix: conn.script_ix,
+                    contents: conn.contents.clone(),
+                });
+            }
+        }
+        // the portal gave up on the connection so the keepalives don't need to be dropped anymore
+        if conn.dropped_keepalives {
+            if let Ok(mut faults) = self.faults.lock() {
+                faults.retain(|f| !matches!(f, Fault::DropKeepalives { .. }));
+            }
+        }
+        result
+    }
+
+    fn serve_connection(&self, stream: &mut TcpStream, conn: &mut Connection) -> io::Result<()> {
+        let mut buf = [0u8; BUFFER_SIZE];
+        let mut decoder = Decoder::new();
+        loop {
+            if !self.running() {
+                return Ok(())
+            }
+            match stream.read(&mut buf) {
+                Ok(0) => return Ok(()),
+                Ok(num) => {
+                    decoder.push(&buf[..num]);
+                    while let Some(res) = decoder.next_message() {
+                        match res {
+                            Ok(msg) => {
+                                if !self.handle_message(&msg, stream, conn)? {
+                                    return Ok(())
+                                }
+                            },
+                            Err(e) => println!("Simulator unable to decode message. {e}"),
+                        }
+                    }
+                },
+                Err(e) if e.kind() == ErrorKind::WouldBlock || e.kind() == ErrorKind::TimedOut => {},
+                Err(e) => return Err(e),
+            }
+            if let Some(interval) = conn.keepalive_interval {
+                if conn.last_keepalive.elapsed() >= interval {
+                    conn.last_keepalive = Instant::now();
+                    if self.drop_keepalive(conn.keepalives_sent) {
+                        conn.dropped_keepalives = true;
+                    } else {
+                        stream.write_all(&Message::new(message_types::KEEPALIVE, self.next_id(conn)).version(conn.version).encode())?;
+                        conn.keepalives_sent += 1;
+                        if let Ok(mut stats) = self.stats.lock() {
+                            stats.keepalives_sent += 1;
+                        }
+                    }
+                }
+            }
+            if conn.enabled {
+                self.check_gpi(conn);
+            }
+            if conn.reading && conn.last_report.elapsed() >= Duration::from_millis(self.config.report_interval_ms) {
+                conn.last_report = Instant::now();
+                if self.reset_connection(conn.reports_sent) {
+                    println!("Simulator resetting connection.");
+                    // the report that was due never makes it to the portal
+                    if self.config.buffer_reports {
+                        self.hold(&self.next_tags(&mut conn.script_ix), &conn.contents);
+                    }
+                    // no linger means the connection is reset instead of closed normally
+                    socket2::SockRef::from(&*stream).set_linger(Some(Duration::ZERO))?;
+                    return Ok(())
+                }
+                let tags = self.next_tags(&mut conn.script_ix);
+                let report = Message::new(message_types::RO_ACCESS_REPORT, self.next_id(conn))
+                    .version(conn.version)
+                    .bytes(&tag_report_data(&tags, &conn.contents))
+                    .encode();
+                stream.write_all(&report)?;
+                conn.reports_sent += 1;
+                if let Ok(mut stats) = self.stats.lock() {
+                    stats.reports_sent += 1;
+                    stats.chips_sent.extend(tags.iter().map(|t| t.chip));
+                }
+            }
+        }
+    }
+
+    // Reads tags while nobody is connected, holding the reports for GET_REPORT.
+    fn read_offline(&self) {
+        if let Ok(mut offline) = self.offline.lock() {
+            if let Some(offline) = offline.as_mut() {
+                if offline.last_report.elapsed() < Duration::from_millis(self.config.report_interval_ms) {
+                    return
+                }
+                offline.last_report = Instant::now();
+                let tags = self.next_tags(&mut offline.script_ix);
+                self.hold(&tags, &offline.contents);
+            }
+        }
+    }
+
+    fn hold(&self, tags: &[SimulatedTag], contents: &ReportContents) {
+        if let Ok(mut held) = self.held.lock() {
+            held.extend_from_slice(&tag_report_data(tags, contents));
+        }
+        if let Ok(mut stats) = self.stats.lock() {
+            stats.chips_held.extend(tags.iter().map(|t| t.chip));
+        }
+    }
+
+    // Starts or stops reading when the GPI ports match a trigger. Triggers are checked against the port level.
+    fn check_gpi(&self, conn: &mut Connection) {
+        let gpi = match self.gpi.lock() {
+            Ok(gpi) => gpi.clone(),
+            Err(_) => return,
+        };
+        let matches = |trigger: Option<(u16, bool)>| match trigger {
+            Some((port, event)) => gpi.get(&port).copied().unwrap_or(false) == event,
+            None => false,
+        };
+        let reading = if conn.reading { !matches(conn.stop_gpi) } else { matches(conn.start_gpi) };
+        if reading != conn.reading {
+            conn.reading = reading;
+            if let Ok(mut stats) = self.stats.lock() {
+                stats.reading = reading;
+            }
+        }
+    }
+
+    fn next_id(&self, conn: &mut Connection) -> u32 {
+        conn.next_id += 1;
+        conn.next_id - 1
+    }
+
+    fn drop_keepalive(&self, sent: u32) -> bool {
+        match self.faults.lock() {
+            Ok(faults) => faults.iter().any(|f| matches!(f, Fault::DropKeepalives { after } if sent >= *after)),
+            Err(_) => false,
+        }
+    }
+
+    fn reset_connection(&self, sent: u32) -> bool {
+        if let Ok(mut faults) = self.faults.lock() {
+            if let Some(ix) = faults.iter().position(|f| matches!(f, Fault::ResetConnection { after_reports } if sent >= *after_reports)) {
+                faults.remove(ix);
+                return true
+            }
+        }
+        false
+    }
+
+    // The status to answer a request with, None if the message isn't supported.
+    fn response_status(&self, kind: u16) -> Option<u16> {
+        if let Ok(mut faults) = self.faults.lock() {
+            if faults.iter().any(|f| matches!(f, Fault::UnsupportedMessage { kind: k } if *k == kind)) {
+                return None
+            }
+            for fault in faults.iter_mut() {
+                if let Fault::ErrorResponse { kind: k, code, count } = fault {
+                    if *k == kind && *count > 0 {
+                        *count -= 1;
+                        return Some(*code)
+                    }
+                }
+            }
+            faults.retain(|f| !matches!(f, Fault::ErrorResponse { count: 0, .. }));
+        }
+        Some(parameter_types::M_SUCCESS)
+    }
+
+    fn next_tags(&self, script_ix: &mut usize) -> Vec<SimulatedTag> {
+        let mut output: Vec<SimulatedTag> = Vec::new();
+        for _ in 0..self.config.tags_per_report {
+            if self.config.script.is_empty() {
+                let antenna = match self.config.antennas.len() {
+                    0 => 1,
+                    count => self.config.antennas[rand::random::<u32>() as usize % count],
+                };
+                output.push(SimulatedTag {
+                    chip: self.config.first_chip + (rand::random::<u32>() % self.config.chip_count.max(1)) as u128,
+                    antenna,
+                    rssi: -40 - (rand::random::<u8>() % 40) as i8,
+                });
+            } else {
+                output.push(self.config.script[*script_ix % self.config.script.len()].clone());
+                *script_ix += 1;
+            }
+        }
+        output
+    }
+
+    // Handles a message from the portal, returns false once the connection should be closed.
+    fn handle_message(&self, msg: &decoder::Message, stream: &mut TcpStream, conn: &mut Connection) -> io::Result<bool> {
+        if let Ok(mut stats) = self.stats.lock() {
+            stats.received.push(msg.kind);
+        }
+        let version_message = msg.kind == message_types::GET_SUPPORTED_VERSION || msg.kind == message_types::SET_PROTOCOL_VERSION;
+        if version_message && self.config.version < VERSION_1_1 {
+            stream.write_all(&error_message(msg.id, conn.version, parameter_types::M_UNSUPPORTED_MESSAGE))?;
+            return Ok(true)
+        }
+        // everything but the version messages has to be sent with the version in use
+        if !version_message && msg.version != conn.version {
+            stream.write_all(&error_message(msg.id, conn.version, parameter_types::M_UNSUPPORTED_VERSION))?;
+            return Ok(true)
+        }
+        let response_kind = match msg.kind {
+            message_types::KEEPALIVE_ACK => {
+                if let Ok(mut stats) = self.stats.lock() {
+                    stats.keepalive_acks += 1;
+                }
+                return Ok(true)
+            },
+            // readers don't answer this one
+            message_types::ENABLE_EVENTS_AND_REPORTS => return Ok(true),
+            // answered with everything held since the connection was lost, which may be nothing
+            message_types::GET_REPORT => {
+                if self.response_status(msg.kind).is_none() {
+                    stream.write_all(&error_message(msg.id, conn.version, parameter_types::M_UNSUPPORTED_MESSAGE))?;
+                    return Ok(true)
+                }
+                let held: Vec<u8> = match self.held.lock() {
+                    Ok(mut held) => held.drain(..).collect(),
+                    Err(_) => Vec::new(),
+                };
+                stream.write_all(&Message::new(message_types::RO_ACCESS_REPORT, self.next_id(conn)).version(conn.version).bytes(&held).encode())?;
+                return Ok(true)
+            },
+            message_types::CUSTOM_MESSAGE => message_types::CUSTOM_MESSAGE,
+            message_types::GET_READER_CAPABILITIES => message_types::GET_READER_CAPABILITIES_RESPONSE,
+            message_types::GET_READER_CONFIG => message_types::GET_READER_CONFIG_RESPONSE,
+            message_types::SET_READER_CONFIG => message_types::SET_READER_CONFIG_RESPONSE,
+            message_types::CLOSE_CONNECTION => message_types::CLOSE_CONNECTION_RESPONSE,
+            message_types::ADD_ROSPEC => message_types::ADD_ROSPEC_RESPONSE,
+            message_types::DELETE_ROSPEC => message_types::DELETE_ROSPEC_RESPONSE,
+            message_types::START_ROSPEC => message_types::START_ROSPEC_RESPONSE,
+            message_types::STOP_ROSPEC => message_types::STOP_ROSPEC_RESPONSE,
+            message_types::ENABLE_ROSPEC => message_types::ENABLE_ROSPEC_RESPONSE,
+            message_types::DISABLE_ROSPEC => message_types::DISABLE_ROSPEC_RESPONSE,
+            message_types::ADD_ACCESS_SPEC => message_types::ADD_ACCESS_SPEC_RESPONSE,
+            message_types::DELETE_ACCESS_SPEC => message_types::DELETE_ACCESS_SPEC_RESPONSE,
+            message_types::ENABLE_ACCESS_SPEC => message_types::ENABLE_ACCESS_SPEC_RESPONSE,
+            message_types::DISABLE_ACCESS_SPEC => message_types::DISABLE_ACCESS_SPEC_RESPONSE,
+            message_types::GET_SUPPORTED_VERSION => message_types::GET_SUPPORTED_VERSION_RESPONSE,
+            message_types::SET_PROTOCOL_VERSION => message_types::SET_PROTOCOL_VERSION_RESPONSE,
+            _ => {
+                stream.write_all(&error_message(msg.id, conn.version, parameter_types::M_UNSUPPORTED_MESSAGE))?;
+                return Ok(true)
+            },
+        };
+        let mut code = match self.response_status(msg.kind) {
+            Some(code) => code,
+            None => {
+                stream.write_all(&error_message(msg.id, conn.version, parameter_types::M_UNSUPPORTED_MESSAGE))?;
+                return Ok(true)
+            }
+        };
+        // the version asked for has to be one we support
+        if msg.kind == message_types::SET_PROTOCOL_VERSION && code == parameter_types::M_SUCCESS {
+            match msg.u8_at(0) {
+                Ok(version) if (VERSION_1_0_1..=self.config.version).contains(&version) => conn.version = version,
+                _ => code = parameter_types::M_UNSUPPORTED_VERSION,
+            }
+            if let Ok(mut stats) = self.stats.lock() {
+                stats.version = conn.version;
+            }
+        }
+        let mut response = match msg.custom_info() {
+            // vendor responses are the subtype after the request
+            Ok((vendor, subtype)) => Message::custom(vendor, subtype as u8 + 1, msg.id),
+            Err(_) => Message::new(response_kind, msg.id),
+        };
+        // responses go out with the version of the request
+        response = response.version(msg.version);
+        // the current and supported versions come before the status
+        if msg.kind == message_types::GET_SUPPORTED_VERSION {
+            response = response.u8(conn.version).u8(self.config.version);
+        }
+        response = response.bytes(&status(code));
+        if code == parameter_types::M_SUCCESS {
+            match msg.kind {
+                message_types::GET_READER_CAPABILITIES => response = response.bytes(&self.capabilities()),
+                message_types::GET_READER_CONFIG => response = response.bytes(&self.reader_config()),
+                message_types::SET_READER_CONFIG => {
+                    if let Some(spec) = msg.find(parameter_types::KEEPALIVE_SPEC) {
+                        conn.keepalive_interval = match (spec.u8_at(0), spec.u32_at(1)) {
+                            (Ok(KEEPALIVE_PERIODIC), Ok(interval)) => Some(Duration::from_millis(interval as u64)),
+                            _ => None,
+                        };
+                        conn.last_keepalive = Instant::now();
+                    }
+                    // Zebra readers take the time in a UTCTimestamp inside the Motorola UTC clock parameter
+                    let clock = msg.parameters.iter()
+                        .filter(|_| self.config.manufacturer == parameter_types::MOTOROLA_VENDOR_ID)
+                        .find(|param| param.custom_info().ok() == Some((parameter_types::MOTOROLA_VENDOR_ID, parameter_types::MOTO_UTC_CLOCK as u32)))
+                        .and_then(|param| param.u64_at(12).ok());
+                    if let Some(time) = clock {
+                        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|v| v.as_micros() as i64).unwrap_or(0);
+                        conn.contents.clock_offset_milliseconds = (time as i64 - now) / 1000;
+                        if let Ok(mut stats) = self.stats.lock() {
+                            stats.clock_sets += 1;
+                        }
+                    }
+                    if let Ok(mut stats) = self.stats.lock() {
+                        for gpo in msg.find_all(parameter_types::GPO_WRITE_DATA) {
+                            if let (Ok(port), Ok(data)) = (gpo.u16_at(0), gpo.u8_at(2)) {
+                                stats.gpo.insert(port, data & 0x80 != 0);
+                            }
+                        }
+                    }
+                },
+                message_types::ADD_ROSPEC => {
+                    if let Some(rospec) = msg.find(parameter_types::RO_SPEC) {
+                        let antennas: Vec<u16> = match rospec.find(parameter_types::AI_SPEC) {
+                            Some(ai_spec) => {
+                                let count = ai_spec.u16_at(0).unwrap_or(0) as usize;
+                                (0..count).filter_map(|ix| ai_spec.u16_at(2 + ix * 2).ok()).collect()
+                            },
+                            None => Vec::new(),
+                        };
+                        if let (Ok(id), Ok(mut stats)) = (rospec.u32_at(0), self.stats.lock()) {
+                            stats.rospecs.push((id, antennas));
+                        }
+                    }
+                    let boundary = msg.find(parameter_types::RO_SPEC)
+                        .and_then(|rospec| rospec.find(parameter_types::RO_BOUNDARY_SPEC));
+                    if let Some(boundary) = boundary {
+                        conn.start_gpi = gpi_trigger(boundary.find(parameter_types::RO_SPEC_START_TRIGGER), START_TRIGGER_GPI);
+                        conn.stop_gpi = gpi_trigger(boundary.find(parameter_types::RO_SPEC_STOP_TRIGGER), STOP_TRIGGER_GPI);
+                    }
+                    let selector = msg.find(parameter_types::RO_SPEC)
+                        .and_then(|rospec| rospec.find(parameter_types::RO_REPORT_SPEC))
+                        .and_then(|report| report.find(parameter_types::TAG_REPORT_CONTENT_SELECTOR))
+                        .and_then(|selector| selector.u16_at(0).ok())
+                        .unwrap_or(0);
+                    // channel index, last seen and seen count are the fifth, eighth and ninth flags
+                    conn.contents.channel_index = selector & 0x0800 != 0;
+                    conn.contents.last_seen = selector & 0x0100 != 0;
+                    conn.contents.seen_count = selector & 0x0080 != 0;
+                },
+                message_types::ADD_ACCESS_SPEC => {
+                    conn.access_reads = msg.find(parameter_types::ACCESS_SPEC)
+                        .and_then(|spec| spec.find(parameter_types::ACCESS_COMMAND))
+                        .map(|command| command.find_all(parameter_types::C1G2_READ))
+                        .unwrap_or_default()
+                        .iter()
+                        .filter_map(|read| Some((read.u16_at(0).ok()?, read.u8_at(6).ok()? >> 6, read.u16_at(9).ok()?)))
+                        .collect();
+                },
+                message_types::ENABLE_ACCESS_SPEC => conn.contents.reads = conn.access_reads.clone(),
+                message_types::DISABLE_ACCESS_SPEC => conn.contents.reads.clear(),
+                message_types::DELETE_ACCESS_SPEC => {
+                    conn.contents.reads.clear();
+                    conn.access_reads.clear();
+                },
+                message_types::ENABLE_ROSPEC => conn.enabled = true,
+                message_types::START_ROSPEC => conn.reading = true,
+                message_types::STOP_ROSPEC => conn.reading = false,
+                message_types::DISABLE_ROSPEC |
+                message_types::DELETE_ROSPEC => {
+                    conn.reading = false;
+                    conn.enabled = false;
+                },
+                _ => {},
+            }
+        }
+        if let Ok(mut stats) = self.stats.lock() {
+            stats.reading = conn.reading;
+        }
+        stream.write_all(&response.encode())?;
+        Ok(msg.kind != message_types::CLOSE_CONNECTION)
+    }
+
+    fn capabilities(&self) -> Vec<u8> {
+        let mut buf: Vec<u8> = Vec::new();
+        tlv(&mut buf, parameter_types::GENERAL_DEVICE_CAPABILITIES, |buf| {
+            buf.extend_from_slice(&self.config.max_antennas.to_be_bytes());
+            // can set antenna properties and has a utc clock
+            buf.extend_from_slice(&[0xC0, 0x00]);
+            buf.extend_from_slice(&self.config.manufacturer.to_be_bytes());
+            buf.extend_from_slice(&self.config.model.to_be_bytes());
+            buf.extend_from_slice(&(self.config.firmware.len() as u16).to_be_bytes());
+            buf.extend_from_slice(self.config.firmware.as_bytes());
+            tlv(buf, parameter_types::GPIO_CAPABILITIES, |buf| {
+                buf.extend_from_slice(&self.config.gpis.to_be_bytes());
+                buf.extend_from_slice(&self.config.gpos.to_be_bytes());
+            });
+        });
+        tlv(&mut buf, parameter_types::REGULATORY_CAPABILITIES, |buf| {
+            // United States, FCC part 15
+            buf.extend_from_slice(&840u16.to_be_bytes());
+            buf.extend_from_slice(&1u16.to_be_bytes());
+            tlv(buf, parameter_types::UHF_BAND_CAPABILITIES, |buf| {
+                // 10, 20 and 30 dBm in hundredths of a dBm
+                for (index, power) in [(1u16, 1000u16), (2, 2000), (3, 3000)] {
+                    tlv(buf, parameter_types::TRANSMIT_POWER_LEVEL_TABLE_ENTRY, |buf| {
+                        buf.extend_from_slice(&index.to_be_bytes());
+                        buf.extend_from_slice(&power.to_be_bytes());
+                    });
+                }
+            });
+        });
+        buf
+    }
+
+    fn reader_config(&self) -> Vec<u8> {
+        let mut buf: Vec<u8> = Vec::new();
+        tlv(&mut buf, parameter_types::IDENTIFICATION, |buf| {
+            // MAC address
+            buf.push(0);
+            buf.extend_from_slice(&(self.config.identifier.len() as u16).to_be_bytes());
+            buf.extend_from_slice(&self.config.identifier);
+        });
+        for antenna in 1..=self.config.max_antennas {
+            tlv(&mut buf, parameter_types::ANTENNA_PROPERTIES, |buf| {
+                buf.push(if self.config.antennas.contains(&antenna) { 0x80 } else { 0x00 });
+                buf.extend_from_slice(&antenna.to_be_bytes());
+                buf.extend_from_slice(&0u16.to_be_bytes());
+            });
+        }
+        buf
+    }
+}
+
+fn status(code: u16) -> Vec<u8> {
+    let description = match code {
+        parameter_types::M_SUCCESS => "",
+        _ => "simulated error",
+    };
+    let mut buf: Vec<u8> = Vec::new();
+    tlv(&mut buf, parameter_types::LLRP_STATUS, |buf| {
+        buf.extend_from_slice(&code.to_be_bytes());
+        buf.extend_from_slice(&(description.len() as u16).to_be_bytes());
+        buf.extend_from_slice(description.as_bytes());
+    });
+    buf
+}
+
+fn error_message(id: u32, version: u8, code: u16) -> Vec<u8> {
+    Message::new(message_types::ERROR_MESSAGE, id)
+        .version(version)
+        .bytes(&status(code))
+        .encode()
+}
+
+// The GPI port and event for a ROSpec start or stop trigger of the GPI kind.
+fn gpi_trigger(trigger: Option<&decoder::Parameter>, gpi_kind: u8) -> Option<(u16, bool)> {
+    let trigger = trigger?;
+    if trigger.u8_at(0).ok()? != gpi_kind {
+        return None
+    }
+    let value = trigger.find(parameter_types::GPI_TRIGGER_VALUE)?;
+    Some((value.u16_at(0).ok()?, value.u8_at(2).ok()? & 0x80 != 0))
+}
+
+// Made up memory for a tag. The TID starts with an Impinj class and model followed by the chip as the serial,
+// user memory is the chip over and over.
+pub fn simulated_memory(chip: u128, memory_bank: u8, words: u16) -> Vec<u8> {
+    let chip_bytes = chip.to_be_bytes();
+    let source: Vec<u8> = match memory_bank {
+        MEMORY_BANK_TID => [&[0xE2, 0x80, 0x11, 0x60], &chip_bytes[8..]].concat(),
+        _ => chip_bytes[8..].to_vec(),
+    };
+    source.iter().cycle().take(words as usize * 2).copied().collect()
+}
+
+pub fn tag_report(id: u32, tags: &[SimulatedTag]) -> Vec<u8> {
+    tag_report_with(id, tags, &ReportContents { last_seen: true, ..Default::default() })
+}
+
+pub fn tag_report_with(id: u32, tags: &[SimulatedTag], contents: &ReportContents) -> Vec<u8> {
+    Message::new(message_types::RO_ACCESS_REPORT, id)
+        .bytes(&tag_report_data(tags, contents))
+        .encode()
+}
+
+// The TagReportData parameters for the tags, read now.
+fn tag_report_data(tags: &[SimulatedTag], contents: &ReportContents) -> Vec<u8> {
+    let now = match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(v) => (v.as_micros() as i64 + contents.clock_offset_milliseconds * 1000) as u64,
+        Err(_) => 0,
+    };
+    let mut buf: Vec<u8> = Vec::new();
+    for tag in tags {
+        tlv(&mut buf, parameter_types::TAG_REPORT_DATA, |buf| {
+            // EPC-96 is the last 12 bytes of the chip
+            tv(buf, parameter_types::EPC_96, &tag.chip.to_be_bytes()[4..]);
+            tv(buf, parameter_types::ANTENNA_ID, &tag.antenna.to_be_bytes());
+            tv(buf, parameter_types::PEAK_RSSI, &tag.rssi.to_be_bytes());
+            if contents.channel_index {
+                tv(buf, parameter_types::CHANNEL_INDEX, &1u16.to_be_bytes());
+            }
+            tv(buf, parameter_types::FIRST_SEEN_TIMESTAMP_UTC, &now.to_be_bytes());
+            if contents.last_seen {
+                tv(buf, parameter_types::LAST_SEEN_TIMESTAMP_UTC, &now.to_be_bytes());
+            }
+            if contents.seen_count {
+                tv(buf, parameter_types::TAG_SEEN_COUNT, &1u16.to_be_bytes());
+            }
+            for (op_spec_id, memory_bank, words) in contents.reads.iter() {
+                tlv(buf, parameter_types::C1G2_READ_OP_SPEC_RESULT, |buf| {
+                    // success
+                    buf.push(0);
+                    buf.extend_from_slice(&op_spec_id.to_be_bytes());
+                    buf.extend_from_slice(&words.to_be_bytes());
+                    buf.extend_from_slice(&simulated_memory(tag.chip, *memory_bank, *words));
+                });
+            }
+        });
+    }
+    buf
+}
@@ -0,0 +1,787 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+
+use std::{collections::BTreeMap, fs, io::{BufRead, BufReader}, net::{SocketAddr, TcpListener, TcpStream}, sync::{Arc, Mutex}, thread::{self, JoinHandle}, time::{Duration, Instant, SystemTime, UNIX_EPOCH}};
+
+use crate::{control::{self, socket::MAX_CONNECTED, sound::SoundNotifier}, database::{sqlite::{self, tests::setup_tests}, Database}, llrp::{decoder::{decode_message, Fields}, encoder::{MEMORY_BANK_TID, VERSION_1_0_1, VERSION_1_1}, message_types, parameter_types}, notifier, objects::{bibchip::BibChip, clock::{ClockConfig, CLOCK_SOURCE_READER}, gpio::{GpioConfig, GpiTrigger, GpoOutput, GPO_EVENT_CONNECTED, GPO_EVENT_READING}, profile::{ProfileConfig, ReadingProfile}, reconnect::ReconnectPolicy, report_buffer::ReportBufferConfig, tag_data::TagDataConfig}, processor, reader::{generic::ROSPEC_ID, reconnector::Reconnector, stats::{self, ReaderStats}, Reader, ReaderStatus, ANTENNA_STATUS_CONNECTED, ANTENNA_STATUS_DISCONNECTED, AUTO_CONNECT_FALSE, READER_KIND_LLRP, READER_KIND_ZEBRA}};
+
+use super::{manufacturer, simulated_memory, tag_report, Fault, SimulatedTag, Simulator, SimulatorConfig, SimulatorStats};
+
+// Everything the portal needs to run a reader against the simulator.
+struct Portal {
+    path: String,
+    keepalive: Arc<Mutex<bool>>,
+    sqlite: Arc<Mutex<sqlite::SQLite>>,
+    control: Arc<Mutex<control::Control>>,
+    read_saver: Arc<processor::ReadSaver>,
+    saver: JoinHandle<()>,
+    sound: Arc<SoundNotifier>,
+    notifier: notifier::Notifier,
+    readers: Arc<Mutex<Vec<Reader>>>,
+    joiners: Arc<Mutex<Vec<JoinHandle<()>>>>,
+    control_sockets: Arc<Mutex<[Option<TcpStream>;MAX_CONNECTED + 1]>>,
+    read_repeaters: Arc<Mutex<[bool;MAX_CONNECTED]>>,
+}
+
+impl Portal {
+    fn new(path: &str) -> Portal {
+        let mut db = setup_tests(path);
+        let mut control = control::Control::new(&mut db).unwrap();
+        // half a second so reads show up quickly
+        control.read_window = 5;
+        let keepalive = Arc::new(Mutex::new(true));
+        let sqlite = Arc::new(Mutex::new(db));
+        let control = Arc::new(Mutex::new(control));
+        let read_saver = Arc::new(processor::ReadSaver::new(sqlite.clone(), keepalive.clone()));
+        let t_saver = read_saver.clone();
+        let saver = thread::spawn(move|| t_saver.start());
+        Portal {
+            path: String::from(path),
+            notifier: notifier::Notifier::new(keepalive.clone(), control.clone()),
+            keepalive,
+            sqlite,
+            control,
+            read_saver,
+            saver,
+            sound: Arc::new(SoundNotifier::new()),
+            readers: Arc::new(Mutex::new(Vec::new())),
+            joiners: Arc::new(Mutex::new(Vec::new())),
+            control_sockets: Arc::new(Mutex::new(Default::default())),
+            read_repeaters: Arc::new(Mutex::new(Default::default())),
+        }
+    }
+
+    // Saves a reader for the simulator and connects to it the way the control socket does.
+    fn connect(&self, kind: &str, addr: SocketAddr) -> i64 {
+        let id = self.save_reader(kind, addr);
+        self.connect_saved(id, kind, addr);
+        id
+    }
+
+    fn save_reader(&self, kind: &str, addr: SocketAddr) -> i64 {
+        let saved = Reader::new_no_repeaters(0, String::from(kind), String::from("Simulator"), addr.ip().to_string(), addr.port(), AUTO_CONNECT_FALSE).unwrap();
+        self.sqlite.lock().unwrap().save_reader(&saved).unwrap()
+    }
+
+    fn connect_saved(&self, id: i64, kind: &str, addr: SocketAddr) {
+        let mut reader = Reader::new(
+            id,
+            String::from(kind),
+            String::from("Simulator"),
+            addr.ip().to_string(),
+            addr.port(),
+            AUTO_CONNECT_FALSE,
+            self.control_sockets.clone(),
+            self.read_repeaters.clone(),
+            self.readers.clone(),
+        ).unwrap();
+        let reconnector = Reconnector::new(
+            self.readers.clone(),
+            self.joiners.clone(),
+            self.control_sockets.clone(),
+            self.read_repeaters.clone(),
+            self.control.clone(),
+            self.sqlite.clone(),
+            self.read_saver.clone(),
+            self.sound.clone(),
+            id,
+            1,
+            self.notifier.clone(),
+        );
+        let mut readers = self.readers.lock().unwrap();
+        let joiner = reader.connect(&self.sqlite, &self.control, &self.read_saver, self.sound.clone(), Some(reconnector), self.notifier.clone()).unwrap();
+        self.joiners.lock().unwrap().push(joiner);
+        readers.push(reader);
+    }
+
+    fn status(&self, id: i64) -> Option<ReaderStatus> {
+        let readers = self.readers.lock().ok()?;
+        let reader = readers.iter().find(|r| r.id() == id)?;
+        let status = reader.status.lock().ok()?;
+        Some(status.clone())
+    }
+
+    fn stats(&self, id: i64) -> ReaderStats {
+        let readers = self.readers.lock().unwrap();
+        let reader = readers.iter().find(|r| r.id() == id).unwrap();
+        let tracker = reader.stats.lock().unwrap();
+        tracker.stats(stats::now())
+    }
+
+    fn reconnecting(&self, id: i64) -> Option<u32> {
+        let readers = self.readers.lock().unwrap();
+        readers.iter().find(|r| r.id() == id)?.reconnecting()
+    }
+
+    // A control socket for reading what the portal tells control clients.
+    fn control_client(&self) -> BufReader<TcpStream> {
+        let listener = TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], 0))).unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        client.set_read_timeout(Some(Duration::from_secs(10))).unwrap();
+        let (server, _) = listener.accept().unwrap();
+        self.control_sockets.lock().unwrap()[0] = Some(server);
+        BufReader::new(client)
+    }
+
+    fn stop(&self, id: i64) {
+        let mut readers = self.readers.lock().unwrap();
+        let reader = readers.iter_mut().find(|r| r.id() == id).unwrap();
+        reader.stop().unwrap();
+    }
+
+    fn chips_read(&self) -> Vec<String> {
+        let mut chips: Vec<String> = self.sqlite.lock().unwrap().get_all_reads().unwrap()
+            .iter()
+            .map(|r| String::from(r.chip()))
+            .collect();
+        chips.sort();
+        chips.dedup();
+        chips
+    }
+
+    fn close(self) {
+        if let Ok(mut readers) = self.readers.lock() {
+            for reader in readers.iter_mut() {
+                _ = reader.disconnect();
+            }
+        }
+        let joiners: Vec<JoinHandle<()>> = self.joiners.lock().unwrap().drain(..).collect();
+        for joiner in joiners {
+            joiner.join().unwrap();
+        }
+        *self.keepalive.lock().unwrap() = false;
+        // wake the read saver so it sees we're done
+        _ = self.read_saver.save_reads(&Vec::new());
+        self.saver.join().unwrap();
+        _ = fs::remove_file(&self.path);
+    }
+}
+
+// A running simulator and the handles the tests check it with.
+struct Sim {
+    addr: SocketAddr,
+    stats: Arc<Mutex<SimulatorStats>>,
+    gpi: Arc<Mutex<BTreeMap<u16, bool>>>,
+    keepalive: Arc<Mutex<bool>>,
+    thread: JoinHandle<()>,
+}
+
+impl Sim {
+    fn start(config: SimulatorConfig) -> Sim {
+        let simulator = Simulator::bind(SocketAddr::from(([127, 0, 0, 1], 0)), config).unwrap();
+        Sim {
+            addr: simulator.local_addr().unwrap(),
+            stats: simulator.stats(),
+            gpi: simulator.gpi(),
+            keepalive: simulator.keepalive(),
+            thread: simulator.spawn(),
+        }
+    }
+
+    fn stop(self) {
+        *self.keepalive.lock().unwrap() = false;
+        self.thread.join().unwrap();
+    }
+}
+
+// Checks the condition until it's true or we run out of time.
+fn wait_for<F: Fn() -> bool>(seconds: u64, check: F) -> bool {
+    let start = Instant::now();
+    while start.elapsed() < Duration::from_secs(seconds) {
+        if check() {
+            return true
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+    check()
+}
+
+fn script() -> Vec<SimulatedTag> {
+    vec![
+        SimulatedTag { chip: 1000, antenna: 1, rssi: -50 },
+        SimulatedTag { chip: 1001, antenna: 2, rssi: -60 },
+    ]
+}
+
+#[test]
+fn test_tag_report() {
+    let report = tag_report(7, &script());
+    let msg = decode_message(&report).unwrap();
+    assert_eq!(message_types::RO_ACCESS_REPORT, msg.kind);
+    assert_eq!(7, msg.id);
+    let tags = msg.find_all(parameter_types::TAG_REPORT_DATA);
+    assert_eq!(2, tags.len());
+    let antenna = tags[1].find(parameter_types::ANTENNA_ID).unwrap();
+    assert_eq!(2, antenna.u16_at(0).unwrap());
+}
+
+#[test]
+fn test_manufacturer() {
+    assert_eq!(parameter_types::MOTOROLA_VENDOR_ID, manufacturer(READER_KIND_ZEBRA));
+    assert_eq!(0, manufacturer(READER_KIND_LLRP));
+}
+
+#[test]
+fn test_connect_read_stop() {
+    let sim = Sim::start(SimulatorConfig {
+        antennas: vec![1, 2],
+        script: script(),
+        ..Default::default()
+    });
+    let portal = Portal::new("./test_simulator_connect.sqlite");
+    let id = portal.connect(READER_KIND_LLRP, sim.addr);
+    assert!(wait_for(5, || portal.status(id) == Some(ReaderStatus::Connected)));
+    {
+        let readers = portal.readers.lock().unwrap();
+        let reader = readers.iter().find(|r| r.id() == id).unwrap();
+        let caps = reader.capabilities.lock().unwrap().clone().unwrap();
+        assert_eq!("simulator", caps.firmware);
+        assert_eq!(3, caps.transmit_power.len());
+        let antennas = reader.antennas.lock().unwrap();
+        assert_eq!(ANTENNA_STATUS_CONNECTED, antennas[0]);
+        assert_eq!(ANTENNA_STATUS_CONNECTED, antennas[1]);
+        assert_eq!(ANTENNA_STATUS_DISCONNECTED, antennas[2]);
+    }
+    // keepalives are every 2 seconds
+    assert!(wait_for(5, || sim.stats.lock().unwrap().keepalive_acks > 0));
+    assert!(sim.stats.lock().unwrap().reading);
+    portal.stop(id);
+    assert!(wait_for(5, || portal.status(id) == Some(ReaderStatus::Disconnected)));
+    assert!(wait_for(5, || sim.stats.lock().unwrap().received_count(message_types::CLOSE_CONNECTION) == 1));
+    let received = sim.stats.lock().unwrap().clone();
+    assert!(!received.reading);
+    assert!(received.reports_sent > 0);
+    assert_eq!(1, received.connections);
+    assert!(received.received_count(message_types::DISABLE_ROSPEC) >= 1);
+    assert!(received.received_count(message_types::DELETE_ROSPEC) >= 2);
+    assert_eq!(1, received.received_count(message_types::START_ROSPEC));
+    assert_eq!(vec!["1000", "1001"], portal.chips_read());
+    portal.close();
+    sim.stop();
+}
+
+#[test]
+fn test_zebra_connect() {
+    let sim = Sim::start(SimulatorConfig {
+        manufacturer: manufacturer(READER_KIND_ZEBRA),
+        script: script(),
+        ..Default::default()
+    });
+    let portal = Portal::new("./test_simulator_zebra.sqlite");
+    let id = portal.connect(READER_KIND_ZEBRA, sim.addr);
+    assert!(wait_for(5, || portal.status(id) == Some(ReaderStatus::Connected)));
+    // purge tags
+    assert!(sim.stats.lock().unwrap().received_count(message_types::CUSTOM_MESSAGE) >= 1);
+    portal.close();
+    sim.stop();
+}
+
+#[test]
+fn test_reconnect_after_reset() {
+    let sim = Sim::start(SimulatorConfig {
+        script: script(),
+        faults: vec![Fault::ResetConnection { after_reports: 2 }],
+        ..Default::default()
+    });
+    let portal = Portal::new("./test_simulator_reset.sqlite");
+    let id = portal.connect(READER_KIND_LLRP, sim.addr);
+    assert!(wait_for(10, || sim.stats.lock().unwrap().connections == 2));
+    assert!(wait_for(5, || portal.status(id) == Some(ReaderStatus::Connected)));
+    assert!(wait_for(5, || sim.stats.lock().unwrap().reading));
+    // the stats carry over to the new connection
+    assert!(wait_for(2, || portal.stats(id).status == "Connected"));
+    let reader_stats = portal.stats(id);
+    assert_eq!(1, reader_stats.reconnects);
+    assert_eq!(stats::RECONNECT_CONNECTION_LOST, reader_stats.reconnect_history[0].reason);
+    assert!(reader_stats.total_tags >= 2);
+    assert!(reader_stats.milliseconds_since_last_tag.is_some());
+    assert!(reader_stats.status_milliseconds.contains_key("Disconnected"));
+    portal.close();
+    sim.stop();
+}
+
+#[test]
+fn test_reconnect_after_dropped_keepalives() {
+    let sim = Sim::start(SimulatorConfig {
+        script: script(),
+        faults: vec![Fault::DropKeepalives { after: 1 }],
+        ..Default::default()
+    });
+    let portal = Portal::new("./test_simulator_keepalive.sqlite");
+    let id = portal.connect(READER_KIND_LLRP, sim.addr);
+    assert!(wait_for(5, || portal.status(id) == Some(ReaderStatus::Connected)));
+    // the portal gives up on the reader 5 seconds after the last keepalive
+    assert!(wait_for(15, || sim.stats.lock().unwrap().connections == 2));
+    assert!(wait_for(5, || portal.status(id) == Some(ReaderStatus::Connected)));
+    assert!(sim.stats.lock().unwrap().keepalives_sent > 0);
+    let reader_stats = portal.stats(id);
+    assert_eq!(1, reader_stats.reconnects);
+    assert_eq!(stats::RECONNECT_KEEPALIVE_TIMEOUT, reader_stats.reconnect_history[0].reason);
+    portal.close();
+    sim.stop();
+}
+
+#[test]
+fn test_keepalive_timeout_closes_held_reports() {
+    let sim = Sim::start(SimulatorConfig {
+        script: script(),
+        faults: vec![Fault::DropKeepalives { after: 1 }],
+        buffer_reports: true,
+        ..Default::default()
+    });
+    let portal = Portal::new("./test_simulator_keepalive_held.sqlite");
+    let id = portal.save_reader(READER_KIND_LLRP, sim.addr);
+    portal.sqlite.lock().unwrap().save_report_buffer_config(&id, &ReportBufferConfig::new(true)).unwrap();
+    portal.connect_saved(id, READER_KIND_LLRP, sim.addr);
+    assert!(wait_for(5, || portal.status(id) == Some(ReaderStatus::Connected)));
+    // only a lost connection leaves the reader reading, a reader that stops talking to us is stopped and closed
+    assert!(wait_for(15, || sim.stats.lock().unwrap().connections == 2));
+    let stats = sim.stats.lock().unwrap().clone();
+    assert!(stats.received_count(message_types::DISABLE_ROSPEC) > 0);
+    assert_eq!(1, stats.received_count(message_types::CLOSE_CONNECTION));
+    portal.close();
+    sim.stop();
+}
+
+#[test]
+fn test_error_responses() {
+    let sim = Sim::start(SimulatorConfig {
+        script: script(),
+        faults: vec![
+            Fault::UnsupportedMessage { kind: message_types::GET_READER_CAPABILITIES },
+            Fault::ErrorResponse { kind: message_types::ADD_ROSPEC, code: parameter_types::M_FIELD_ERROR, count: 2 },
+        ],
+        ..Default::default()
+    });
+    let portal = Portal::new("./test_simulator_errors.sqlite");
+    let id = portal.connect(READER_KIND_LLRP, sim.addr);
+    // the reader not telling us its capabilities and failing to add the ROSpec a few times shouldn't stop us
+    assert!(wait_for(5, || portal.status(id) == Some(ReaderStatus::Connected)));
+    assert_eq!(3, sim.stats.lock().unwrap().received_count(message_types::ADD_ROSPEC));
+    {
+        let readers = portal.readers.lock().unwrap();
+        let reader = readers.iter().find(|r| r.id() == id).unwrap();
+        assert!(reader.capabilities.lock().unwrap().is_none());
+        let errors = reader.errors.lock().unwrap().errors();
+        assert_eq!(3, errors.len());
+        assert_eq!(parameter_types::M_UNSUPPORTED_MESSAGE, errors[0].code);
+        assert_eq!(parameter_types::M_FIELD_ERROR, errors[1].code);
+    }
+    portal.close();
+    sim.stop();
+}
+
+#[test]
+fn test_gpio() {
+    let sim = Sim::start(SimulatorConfig {
+        script: script(),
+        ..Default::default()
+    });
+    let portal = Portal::new("./test_simulator_gpio.sqlite");
+    let id = portal.save_reader(READER_KIND_LLRP, sim.addr);
+    portal.sqlite.lock().unwrap().save_gpio_config(&id, &GpioConfig::new(
+        Some(GpiTrigger::new(1, true, 0)),
+        Some(GpiTrigger::new(1, false, 0)),
+        vec![
+            GpoOutput::new(2, String::from(GPO_EVENT_READING), 500),
+            GpoOutput::new(3, String::from(GPO_EVENT_CONNECTED), 0),
+        ],
+    )).unwrap();
+    portal.connect_saved(id, READER_KIND_LLRP, sim.addr);
+    assert!(wait_for(5, || portal.status(id) == Some(ReaderStatus::Connected)));
+    // the reader waits for the gpi instead of being told to start
+    assert!(wait_for(2, || sim.stats.lock().unwrap().gpo.get(&3) == Some(&true)));
+    assert_eq!(0, sim.stats.lock().unwrap().received_count(message_types::START_ROSPEC));
+    assert!(!sim.stats.lock().unwrap().reading);
+    assert_eq!(Some(&false), sim.stats.lock().unwrap().gpo.get(&2));
+    {
+        let readers = portal.readers.lock().unwrap();
+        let reader = readers.iter().find(|r| r.id() == id).unwrap();
+        let caps = reader.capabilities.lock().unwrap().clone().unwrap();
+        assert_eq!((4, 4), (caps.gpis, caps.gpos));
+    }
+    sim.gpi.lock().unwrap().insert(1, true);
+    assert!(wait_for(2, || sim.stats.lock().unwrap().reading));
+    assert!(wait_for(2, || sim.stats.lock().unwrap().gpo.get(&2) == Some(&true)));
+    sim.gpi.lock().unwrap().insert(1, false);
+    assert!(wait_for(2, || !sim.stats.lock().unwrap().reading));
+    // the light goes out once tags stop coming in
+    assert!(wait_for(3, || sim.stats.lock().unwrap().gpo.get(&2) == Some(&false)));
+    portal.stop(id);
+    assert!(wait_for(5, || portal.status(id) == Some(ReaderStatus::Disconnected)));
+    assert!(wait_for(5, || sim.stats.lock().unwrap().received_count(message_types::CLOSE_CONNECTION) == 1));
+    assert_eq!(Some(&false), sim.stats.lock().unwrap().gpo.get(&3));
+    assert_eq!("1000", portal.chips_read()[0]);
+    portal.close();
+    sim.stop();
+}
+
+#[test]
+fn test_tag_data() {
+    let sim = Sim::start(SimulatorConfig {
+        script: script(),
+        ..Default::default()
+    });
+    let portal = Portal::new("./test_simulator_tag_data.sqlite");
+    let id = portal.save_reader(READER_KIND_LLRP, sim.addr);
+    portal.sqlite.lock().unwrap().save_tag_data_config(&id, &TagDataConfig::new(4, 1, true)).unwrap();
+    portal.connect_saved(id, READER_KIND_LLRP, sim.addr);
+    assert!(wait_for(5, || portal.status(id) == Some(ReaderStatus::Connected)));
+    {
+        let stats = sim.stats.lock().unwrap();
+        assert_eq!(1, stats.received_count(message_types::ADD_ACCESS_SPEC));
+        assert_eq!(1, stats.received_count(message_types::ENABLE_ACCESS_SPEC));
+    }
+    assert!(wait_for(5, || sim.stats.lock().unwrap().reports_sent >= 2));
+    portal.stop(id);
+    assert!(wait_for(5, || portal.status(id) == Some(ReaderStatus::Disconnected)));
+    let reads = portal.sqlite.lock().unwrap().get_all_reads().unwrap();
+    assert!(!reads.is_empty());
+    for read in reads.iter() {
+        let chip = read.chip().parse::<u128>().unwrap();
+        let tid: String = simulated_memory(chip, MEMORY_BANK_TID, 4).iter().map(|b| format!("{:02x}", b)).collect();
+        assert_eq!(Some(tid), read.details().tid);
+        assert_eq!(Some(4), read.details().user_memory.as_ref().map(|m| m.len()));
+        assert_eq!(Some(1), read.details().channel_index);
+        assert_eq!(Some(1), read.details().seen_count);
+        assert!(read.details().last_seen_seconds.is_some());
+    }
+    portal.close();
+    sim.stop();
+}
+
+#[test]
+fn test_version_negotiation() {
+    // a 1.1 reader is switched to 1.1 and everything after is sent with it
+    let sim = Sim::start(SimulatorConfig {
+        script: script(),
+        ..Default::default()
+    });
+    let portal = Portal::new("./test_simulator_version.sqlite");
+    let id = portal.connect(READER_KIND_LLRP, sim.addr);
+    assert!(wait_for(5, || portal.status(id) == Some(ReaderStatus::Connected)));
+    assert!(wait_for(5, || sim.stats.lock().unwrap().reading));
+    {
+        let stats = sim.stats.lock().unwrap();
+        assert_eq!(VERSION_1_1, stats.version);
+        assert_eq!(message_types::GET_SUPPORTED_VERSION, stats.received[0]);
+        assert_eq!(message_types::SET_PROTOCOL_VERSION, stats.received[1]);
+        let readers = portal.readers.lock().unwrap();
+        let reader = readers.iter().find(|r| r.id() == id).unwrap();
+        assert_eq!(VERSION_1_1, reader.llrp_version());
+        assert!(reader.errors.lock().unwrap().errors().is_empty());
+    }
+    portal.close();
+    sim.stop();
+    // a 1.0.1 reader doesn't know the version messages and stays on 1.0.1
+    let sim = Sim::start(SimulatorConfig {
+        script: script(),
+        version: VERSION_1_0_1,
+        ..Default::default()
+    });
+    let portal = Portal::new("./test_simulator_version_old.sqlite");
+    let id = portal.connect(READER_KIND_LLRP, sim.addr);
+    assert!(wait_for(5, || portal.status(id) == Some(ReaderStatus::Connected)));
+    assert!(wait_for(5, || sim.stats.lock().unwrap().reading));
+    {
+        let stats = sim.stats.lock().unwrap();
+        assert_eq!(VERSION_1_0_1, stats.version);
+        assert_eq!(0, stats.received_count(message_types::SET_PROTOCOL_VERSION));
+        let readers = portal.readers.lock().unwrap();
+        let reader = readers.iter().find(|r| r.id() == id).unwrap();
+        assert_eq!(VERSION_1_0_1, reader.llrp_version());
+        assert!(reader.errors.lock().unwrap().errors().is_empty());
+    }
+    portal.close();
+    sim.stop();
+}
+
+#[test]
+fn test_clock_offset() {
+    // reader clock is 5 seconds fast
+    let sim = Sim::start(SimulatorConfig {
+        script: script(),
+        clock_offset_milliseconds: 5000,
+        ..Default::default()
+    });
+    let portal = Portal::new("./test_simulator_clock.sqlite");
+    let id = portal.save_reader(READER_KIND_LLRP, sim.addr);
+    portal.sqlite.lock().unwrap().save_clock_config(&id, &ClockConfig::new(String::from(CLOCK_SOURCE_READER), false, 1000, false)).unwrap();
+    portal.connect_saved(id, READER_KIND_LLRP, sim.addr);
+    assert!(wait_for(5, || portal.status(id) == Some(ReaderStatus::Connected)));
+    // measured once the first keepalive comes in after some reads
+    let offset = || {
+        let readers = portal.readers.lock().unwrap();
+        let reader = readers.iter().find(|r| r.id() == id).unwrap();
+        let tracker = reader.clock.lock().unwrap();
+        tracker.status("Simulator").map(|s| s.offset_milliseconds)
+    };
+    assert!(wait_for(10, || offset().is_some()));
+    let offset = offset().unwrap();
+    assert!(offset > 4000 && offset <= 5000, "offset {offset}");
+    // reads are saved with the reader's clock
+    assert!(wait_for(5, || !portal.sqlite.lock().unwrap().get_all_reads().unwrap().is_empty()));
+    for read in portal.sqlite.lock().unwrap().get_all_reads().unwrap() {
+        assert_eq!(read.reader_seconds(), read.seconds());
+        assert_eq!(read.reader_milliseconds(), read.milliseconds());
+    }
+    portal.close();
+    sim.stop();
+}
+
+#[test]
+fn test_set_clock() {
+    // a zebra that lost its clock
+    let sim = Sim::start(SimulatorConfig {
+        manufacturer: manufacturer(READER_KIND_ZEBRA),
+        script: script(),
+        clock_offset_milliseconds: -3600000,
+        ..Default::default()
+    });
+    let portal = Portal::new("./test_simulator_set_clock.sqlite");
+    let id = portal.save_reader(READER_KIND_ZEBRA, sim.addr);
+    portal.sqlite.lock().unwrap().save_clock_config(&id, &ClockConfig::new(String::from(CLOCK_SOURCE_READER), false, 1000, true)).unwrap();
+    portal.connect_saved(id, READER_KIND_ZEBRA, sim.addr);
+    assert!(wait_for(5, || portal.status(id) == Some(ReaderStatus::Connected)));
+    assert_eq!(1, sim.stats.lock().unwrap().clock_sets);
+    // reads come back with the right time
+    assert!(wait_for(5, || !portal.sqlite.lock().unwrap().get_all_reads().unwrap().is_empty()));
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64;
+    for read in portal.sqlite.lock().unwrap().get_all_reads().unwrap() {
+        assert!((now - read.reader_seconds()).abs() < 5);
+    }
+    portal.close();
+    sim.stop();
+}
+
+#[test]
+fn test_recover_held_reports() {
+    let sim = Sim::start(SimulatorConfig {
+        manufacturer: manufacturer(READER_KIND_ZEBRA),
+        script: (2000..2040).map(|chip| SimulatedTag { chip, antenna: 1, rssi: -50 }).collect(),
+        faults: vec![Fault::ResetConnection { after_reports: 2 }],
+        buffer_reports: true,
+        ..Default::default()
+    });
+    let portal = Portal::new("./test_simulator_recover.sqlite");
+    let id = portal.save_reader(READER_KIND_ZEBRA, sim.addr);
+    portal.sqlite.lock().unwrap().save_report_buffer_config(&id, &ReportBufferConfig::new(true)).unwrap();
+    portal.connect_saved(id, READER_KIND_ZEBRA, sim.addr);
+    assert!(wait_for(10, || sim.stats.lock().unwrap().connections == 2));
+    assert!(wait_for(5, || portal.status(id) == Some(ReaderStatus::Connected)));
+    let stats = sim.stats.lock().unwrap().clone();
+    assert!(!stats.chips_held.is_empty());
+    assert_eq!(2, stats.received_count(message_types::GET_REPORT));
+    // the held reports are asked for before the tags are purged
+    let received: Vec<u16> = stats.received.iter().copied().filter(|k| *k != message_types::KEEPALIVE_ACK).collect();
+    for ix in 0..received.len() {
+        if received[ix] == message_types::GET_REPORT {
+            assert_eq!(Some(&message_types::CUSTOM_MESSAGE), received.get(ix + 1));
+        }
+    }
+    // everything read while we were disconnected was saved
+    let chips = portal.chips_read();
+    for chip in stats.chips_held.iter() {
+        assert!(chips.contains(&chip.to_string()), "missing held chip {chip}");
+    }
+    portal.close();
+    sim.stop();
+}
+
+#[test]
+fn test_reading_profiles() {
+    let sim = Sim::start(SimulatorConfig {
+        antennas: vec![1, 2, 3, 4],
+        script: script(),
+        ..Default::default()
+    });
+    let portal = Portal::new("./test_simulator_reading_profiles.sqlite");
+    let id = portal.save_reader(READER_KIND_LLRP, sim.addr);
+    let mut profiles = ProfileConfig::new(
+        vec![
+            ReadingProfile::new(String::from("finish line"), Vec::new(), Some(30.0), 0),
+            ReadingProfile::new(String::from("chip check"), vec![2], Some(10.0), 0),
+        ],
+        Some(String::from("finish line")),
+    );
+    portal.sqlite.lock().unwrap().save_profile_config(&id, &profiles).unwrap();
+    portal.connect_saved(id, READER_KIND_LLRP, sim.addr);
+    assert!(wait_for(5, || portal.status(id) == Some(ReaderStatus::Connected)));
+    assert_eq!(vec![(ROSPEC_ID + 1, vec![0])], sim.stats.lock().unwrap().rospecs);
+    assert!(sim.stats.lock().unwrap().reading);
+    // switching while reading swaps the ROSpec without reconnecting
+    profiles.set_active(Some(String::from("chip check")));
+    portal.sqlite.lock().unwrap().save_profile_config(&id, &profiles).unwrap();
+    {
+        let readers = portal.readers.lock().unwrap();
+        readers.iter().find(|r| r.id() == id).unwrap().change_profiles(profiles.clone());
+    }
+    assert!(wait_for(5, || sim.stats.lock().unwrap().rospecs.len() == 2));
+    assert!(wait_for(2, || sim.stats.lock().unwrap().reading));
+    {
+        let stats = sim.stats.lock().unwrap();
+        assert_eq!((ROSPEC_ID + 2, vec![2]), stats.rospecs[1]);
+        assert_eq!(2, stats.received_count(message_types::START_ROSPEC));
+        assert_eq!(1, stats.connections);
+    }
+    assert_eq!(Some(ReaderStatus::Connected), portal.status(id));
+    portal.stop(id);
+    assert!(wait_for(5, || portal.status(id) == Some(ReaderStatus::Disconnected)));
+    // the next connection starts on the profile we switched to
+    portal.readers.lock().unwrap().clear();
+    portal.connect_saved(id, READER_KIND_LLRP, sim.addr);
+    assert!(wait_for(5, || portal.status(id) == Some(ReaderStatus::Connected)));
+    assert_eq!((ROSPEC_ID + 2, vec![2]), sim.stats.lock().unwrap().rospecs[2]);
+    portal.close();
+    sim.stop();
+}
+
+#[test]
+fn test_reconnect_policy_gives_up() {
+    let sim = Sim::start(SimulatorConfig {
+        script: script(),
+        ..Default::default()
+    });
+    let portal = Portal::new("./test_simulator_reconnect_gives_up.sqlite");
+    let id = portal.save_reader(READER_KIND_LLRP, sim.addr);
+    portal.sqlite.lock().unwrap().save_reconnect_policy(&id, &ReconnectPolicy::new(3, 100, 2.0, 1000)).unwrap();
+    portal.connect_saved(id, READER_KIND_LLRP, sim.addr);
+    assert!(wait_for(5, || portal.status(id) == Some(ReaderStatus::Connected)));
+    let mut client = portal.control_client();
+    // the reader loses power
+    sim.stop();
+    let mut attempts: Vec<u32> = Vec::new();
+    let mut line = String::new();
+    loop {
+        line.clear();
+        assert!(client.read_line(&mut line).unwrap() > 0);
+        let message: serde_json::Value = serde_json::from_str(&line).unwrap();
+        match message["command"].as_str() {
+            Some("reader_reconnecting") => {
+                assert_eq!(3, message["max_attempts"]);
+                attempts.push(message["attempt"].as_u64().unwrap() as u32);
+            },
+            Some("reader_reconnect_failed") => {
+                assert_eq!(3, message["attempts"]);
+                break;
+            },
+            _ => {},
+        }
+    }
+    assert_eq!(vec![1, 2, 3], attempts);
+    assert_eq!(None, portal.reconnecting(id));
+    assert_eq!(Some(ReaderStatus::Disconnected), portal.status(id));
+    portal.close();
+}
+
+#[test]
+fn test_reconnect_policy_keeps_trying() {
+    let sim = Sim::start(SimulatorConfig {
+        script: script(),
+        ..Default::default()
+    });
+    let addr = sim.addr;
+    let portal = Portal::new("./test_simulator_reconnect_keeps_trying.sqlite");
+    let id = portal.save_reader(READER_KIND_LLRP, addr);
+    portal.sqlite.lock().unwrap().save_reconnect_policy(&id, &ReconnectPolicy::new(0, 50, 1.5, 200)).unwrap();
+    portal.connect_saved(id, READER_KIND_LLRP, addr);
+    assert!(wait_for(5, || portal.status(id) == Some(ReaderStatus::Connected)));
+    sim.stop();
+    // well past the 30 attempts the reader used to get
+    assert!(wait_for(20, || portal.reconnecting(id).is_some_and(|attempt| attempt > 35)));
+    // power comes back
+    let simulator = Simulator::bind(addr, SimulatorConfig {
+        script: script(),
+        ..Default::default()
+    }).unwrap();
+    let keepalive = simulator.keepalive();
+    let thread = simulator.spawn();
+    assert!(wait_for(5, || portal.status(id) == Some(ReaderStatus::Connected)));
+    assert!(wait_for(2, || portal.reconnecting(id).is_none()));
+    portal.close();
+    *keepalive.lock().unwrap() = false;
+    thread.join().unwrap();
+}
+
+#[test]
+fn test_reconnect_stops_on_disconnect() {
+    let sim = Sim::start(SimulatorConfig {
+        script: script(),
+        ..Default::default()
+    });
+    let portal = Portal::new("./test_simulator_reconnect_stops.sqlite");
+    let id = portal.save_reader(READER_KIND_LLRP, sim.addr);
+    portal.sqlite.lock().unwrap().save_reconnect_policy(&id, &ReconnectPolicy::new(0, 100, 1.0, 100)).unwrap();
+    portal.connect_saved(id, READER_KIND_LLRP, sim.addr);
+    assert!(wait_for(5, || portal.status(id) == Some(ReaderStatus::Connected)));
+    sim.stop();
+    // the portal notices the reader is gone once keepalives stop
+    assert!(wait_for(15, || portal.reconnecting(id).is_some_and(|attempt| attempt >= 2)));
+    {
+        let mut readers = portal.readers.lock().unwrap();
+        _ = readers.iter_mut().find(|r| r.id() == id).unwrap().disconnect();
+    }
+    assert_eq!(None, portal.reconnecting(id));
+    // no more attempts are made once the reader has been disconnected
+    thread::sleep(Duration::from_millis(500));
+    assert_eq!(None, portal.reconnecting(id));
+    portal.close();
+}
+
+#[test]
+fn test_reads_carry_bib() {
+    let sim = Sim::start(SimulatorConfig {
+        antennas: vec![1, 2],
+        script: script(),
+        ..Default::default()
+    });
+    let portal = Portal::new("./test_simulator_bibs.sqlite");
+    portal.control.lock().unwrap().set_bibchips(&[BibChip::new(String::from("101"), String::from("1000"))]);
+    let mut client = portal.control_client();
+    portal.read_repeaters.lock().unwrap()[0] = true;
+    let id = portal.connect(READER_KIND_LLRP, sim.addr);
+    assert!(wait_for(5, || portal.status(id) == Some(ReaderStatus::Connected)));
+    // the subscription feed has the bib of every chip we know
+    let mut line = String::new();
+    loop {
+        line.clear();
+        assert!(client.read_line(&mut line).unwrap() > 0);
+        let message: serde_json::Value = serde_json::from_str(&line).unwrap();
+        if message["command"] != "reads" {
+            continue;
+        }
+        let list = message["list"].as_array().unwrap();
+        if let Some(read) = list.iter().find(|r| r["identifier"] == "1000") {
+            assert_eq!("101", read["bib"]);
+            break;
+        }
+        assert!(list.iter().all(|r| r.get("bib").is_none()));
+    }
+    assert!(wait_for(5, || portal.chips_read() == vec!["1000", "1001"]));
+    portal.stop(id);
+    for read in portal.sqlite.lock().unwrap().get_all_reads().unwrap() {
+        match read.chip() {
+            "1000" => assert_eq!(Some("101"), read.bib()),
+            _ => assert_eq!(None, read.bib()),
+        }
+    }
+    assert_eq!(Some(String::from("101")), portal.control.lock().unwrap().last_bib);
+    portal.close();
+    sim.stop();
+}
@@ -0,0 +1,196 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::{collections::{BTreeMap, VecDeque}, time::{SystemTime, UNIX_EPOCH}};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use super::ReaderStatus;
+
+#[cfg(test)]
+pub mod test;
+
+// Read rates are worked out over this many milliseconds.
+pub const RATE_WINDOW_MILLISECONDS: u64 = 10000;
+// Number of reconnects kept for each reader, older ones are dropped as new ones come in.
+pub const MAX_RECONNECTS: usize = 20;
+
+// Reasons a reader's connection was dropped and reconnected.
+pub const RECONNECT_CONNECTION_LOST: &str = "connection_lost";
+pub const RECONNECT_KEEPALIVE_TIMEOUT: &str = "keepalive_timeout";
+pub const RECONNECT_READER_ERROR: &str = "reader_error";
+
+pub fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Reconnect {
+    pub reason: String,
+    pub time: String,
+}
+
+// How a reader has been doing, sent to clients when asked.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ReaderStats {
+    // Tags the reader sent us and the reads left once they've gone through the read window.
+    pub tags_per_second: f64,
+    pub reads_per_second: f64,
+    pub total_tags: u64,
+    pub total_reads: u64,
+    pub milliseconds_since_last_tag: Option<u64>,
+    // Time between the keepalives the reader sent us.
+    pub last_keepalive_gap_milliseconds: Option<u64>,
+    pub max_keepalive_gap_milliseconds: Option<u64>,
+    pub average_keepalive_gap_milliseconds: Option<u64>,
+    pub reconnects: u32,
+    // Most recent reconnects, oldest first.
+    pub reconnect_history: Vec<Reconnect>,
+    pub status: String,
+    // Total time spent in each status.
+    pub status_milliseconds: BTreeMap<String, u64>,
+}
+
+// Keeps the numbers behind ReaderStats. Every time is in milliseconds since the epoch.
+#[derive(Debug, Default, Clone)]
+pub struct StatsTracker {
+    tags: VecDeque<(u64, u64)>,
+    reads: VecDeque<(u64, u64)>,
+    total_tags: u64,
+    total_reads: u64,
+    last_tag: Option<u64>,
+    last_keepalive: Option<u64>,
+    last_gap: Option<u64>,
+    max_gap: Option<u64>,
+    gap_total: u64,
+    gap_count: u64,
+    reconnects: u32,
+    reconnect_history: VecDeque<Reconnect>,
+    // The status we're in and when we moved to it.
+    status: Option<(ReaderStatus, u64)>,
+    status_milliseconds: BTreeMap<String, u64>,
+}
+
+impl StatsTracker {
+    pub fn new() -> StatsTracker {
+        StatsTracker::default()
+    }
+
+    pub fn tags_received(&mut self, now: u64, count: usize) {
+        if count == 0 {
+            return
+        }
+        self.total_tags += count as u64;
+        self.last_tag = Some(now);
+        self.tags.push_back((now, count as u64));
+        trim(&mut self.tags, now);
+    }
+
+    pub fn reads_saved(&mut self, now: u64, count: usize) {
+        if count == 0 {
+            return
+        }
+        self.total_reads += count as u64;
+        self.reads.push_back((now, count as u64));
+        trim(&mut self.reads, now);
+    }
+
+    pub fn keepalive(&mut self, now: u64) {
+        if let Some(last) = self.last_keepalive {
+            let gap = now.saturating_sub(last);
+            self.last_gap = Some(gap);
+            self.max_gap = Some(self.max_gap.map_or(gap, |max| max.max(gap)));
+            self.gap_total += gap;
+            self.gap_count += 1;
+        }
+        self.last_keepalive = Some(now);
+    }
+
+    // The time between connections isn't a keepalive gap.
+    pub fn connection_closed(&mut self) {
+        self.last_keepalive = None;
+    }
+
+    pub fn reconnect(&mut self, reason: &str) {
+        let date_time: DateTime<Local> = Local::now();
+        self.reconnects += 1;
+        while self.reconnect_history.len() >= MAX_RECONNECTS {
+            self.reconnect_history.pop_front();
+        }
+        self.reconnect_history.push_back(Reconnect {
+            reason: String::from(reason),
+            time: format!("{}", date_time.format("%Y/%m/%d %T")),
+        });
+    }
+
+    // Called with the current status whenever we check it, only changes are recorded.
+    pub fn status(&mut self, now: u64, status: &ReaderStatus) {
+        if let Some((current, since)) = &self.status {
+            if current == status {
+                return
+            }
+            *self.status_milliseconds.entry(format!("{:?}", current)).or_insert(0) += now.saturating_sub(*since);
+        }
+        self.status = Some((status.clone(), now));
+    }
+
+    pub fn stats(&self, now: u64) -> ReaderStats {
+        let mut status_milliseconds = self.status_milliseconds.clone();
+        let mut status = format!("{:?}", ReaderStatus::Disconnected);
+        if let Some((current, since)) = &self.status {
+            status = format!("{:?}", current);
+            *status_milliseconds.entry(status.clone()).or_insert(0) += now.saturating_sub(*since);
+        }
+        ReaderStats {
+            tags_per_second: rate(&self.tags, now),
+            reads_per_second: rate(&self.reads, now),
+            total_tags: self.total_tags,
+            total_reads: self.total_reads,
+            milliseconds_since_last_tag: self.last_tag.map(|last| now.saturating_sub(last)),
+            last_keepalive_gap_milliseconds: self.last_gap,
+            max_keepalive_gap_milliseconds: self.max_gap,
+            average_keepalive_gap_milliseconds: match self.gap_count {
+                0 => None,
+                count => Some(self.gap_total / count),
+            },
+            reconnects: self.reconnects,
+            reconnect_history: self.reconnect_history.iter().cloned().collect(),
+            status,
+            status_milliseconds,
+        }
+    }
+}
+
+// Drops the counts that have aged out of the rate window.
+fn trim(counts: &mut VecDeque<(u64, u64)>, now: u64) {
+    while let Some((time, _)) = counts.front() {
+        if now.saturating_sub(*time) < RATE_WINDOW_MILLISECONDS {
+            break;
+        }
+        counts.pop_front();
+    }
+}
+
+fn rate(counts: &VecDeque<(u64, u64)>, now: u64) -> f64 {
+    let total: u64 = counts.iter()
+        .filter(|(time, _)| now.saturating_sub(*time) < RATE_WINDOW_MILLISECONDS)
+        .map(|(_, count)| count)
+        .sum();
+    total as f64 * 1000.0 / RATE_WINDOW_MILLISECONDS as f64
+}
@@ -0,0 +1,97 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::reader::ReaderStatus;
+
+use super::{StatsTracker, MAX_RECONNECTS, RATE_WINDOW_MILLISECONDS, RECONNECT_CONNECTION_LOST, RECONNECT_KEEPALIVE_TIMEOUT};
+
+#[test]
+fn test_rates() {
+    let mut tracker = StatsTracker::new();
+    let stats = tracker.stats(1000);
+    assert_eq!(0.0, stats.tags_per_second);
+    assert_eq!(None, stats.milliseconds_since_last_tag);
+    tracker.tags_received(1000, 40);
+    tracker.tags_received(5000, 60);
+    // nothing in a report doesn't count as a tag
+    tracker.tags_received(6000, 0);
+    tracker.reads_saved(6000, 20);
+    let stats = tracker.stats(7000);
+    assert_eq!(10.0, stats.tags_per_second);
+    assert_eq!(2.0, stats.reads_per_second);
+    assert_eq!(100, stats.total_tags);
+    assert_eq!(20, stats.total_reads);
+    assert_eq!(Some(2000), stats.milliseconds_since_last_tag);
+    // older tags fall out of the rate but not the totals
+    let stats = tracker.stats(1000 + RATE_WINDOW_MILLISECONDS);
+    assert_eq!(6.0, stats.tags_per_second);
+    assert_eq!(100, stats.total_tags);
+}
+
+#[test]
+fn test_keepalive_gaps() {
+    let mut tracker = StatsTracker::new();
+    tracker.keepalive(1000);
+    assert_eq!(None, tracker.stats(1000).last_keepalive_gap_milliseconds);
+    tracker.keepalive(3000);
+    tracker.keepalive(7000);
+    let stats = tracker.stats(7000);
+    assert_eq!(Some(4000), stats.last_keepalive_gap_milliseconds);
+    assert_eq!(Some(4000), stats.max_keepalive_gap_milliseconds);
+    assert_eq!(Some(3000), stats.average_keepalive_gap_milliseconds);
+    // the time spent reconnecting isn't a gap
+    tracker.connection_closed();
+    tracker.keepalive(60000);
+    tracker.keepalive(62000);
+    let stats = tracker.stats(62000);
+    assert_eq!(Some(2000), stats.last_keepalive_gap_milliseconds);
+    assert_eq!(Some(4000), stats.max_keepalive_gap_milliseconds);
+}
+
+#[test]
+fn test_reconnects() {
+    let mut tracker = StatsTracker::new();
+    tracker.reconnect(RECONNECT_CONNECTION_LOST);
+    tracker.reconnect(RECONNECT_KEEPALIVE_TIMEOUT);
+    let stats = tracker.stats(0);
+    assert_eq!(2, stats.reconnects);
+    assert_eq!(RECONNECT_CONNECTION_LOST, stats.reconnect_history[0].reason);
+    assert_eq!(RECONNECT_KEEPALIVE_TIMEOUT, stats.reconnect_history[1].reason);
+    for _ in 0..MAX_RECONNECTS {
+        tracker.reconnect(RECONNECT_CONNECTION_LOST);
+    }
+    let stats = tracker.stats(0);
+    assert_eq!(MAX_RECONNECTS as u32 + 2, stats.reconnects);
+    assert_eq!(MAX_RECONNECTS, stats.reconnect_history.len());
+}
+
+#[test]
+fn test_status_time() {
+    let mut tracker = StatsTracker::new();
+    assert_eq!("Disconnected", tracker.stats(0).status);
+    tracker.status(1000, &ReaderStatus::ConnectingGetSupportedVersion);
+    tracker.status(1500, &ReaderStatus::ConnectingGetSupportedVersion);
+    tracker.status(2000, &ReaderStatus::Connected);
+    tracker.status(10000, &ReaderStatus::Disconnected);
+    tracker.status(12000, &ReaderStatus::Connected);
+    let stats = tracker.stats(15000);
+    assert_eq!("Connected", stats.status);
+    assert_eq!(Some(&1000), stats.status_milliseconds.get("ConnectingGetSupportedVersion"));
+    assert_eq!(Some(&11000), stats.status_milliseconds.get("Connected"));
+    assert_eq!(Some(&2000), stats.status_milliseconds.get("Disconnected"));
+}
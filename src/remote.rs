@@ -20,3 +20,6 @@ pub mod requests;
 pub mod responses;
 pub mod uploader;
 pub mod remote_util;
+
+#[cfg(test)]
+pub mod test;
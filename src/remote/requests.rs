@@ -25,7 +25,27 @@ pub struct UploadReadsRequest {
     pub reads: Vec<read::Read>
 }
 
+impl UploadReadsRequest {
+    // The bib and tag details are only for the control socket feed, the remote api doesn't take them.
+    pub fn new(reads: &[read::Read]) -> UploadReadsRequest {
+        let mut reads = reads.to_vec();
+        for read in reads.iter_mut() {
+            read.set_bib(None);
+            read.set_details(read::ReadDetails::default());
+        }
+        UploadReadsRequest {
+            reads
+        }
+    }
+}
+
 #[derive(Serialize, Debug, Clone)]
 pub struct SaveNotificationRequest {
     pub notification: RemoteNotification
 }
+
+#[derive(Serialize, Debug, Clone)]
+pub struct GetBibChipsRequest {
+    pub slug: String,
+    pub year: String
+}
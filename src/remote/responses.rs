@@ -18,7 +18,14 @@ along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 use serde::{Deserialize, Serialize};
 
+use crate::objects::bibchip::BibChip;
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct UploadReadsResponse {
     pub(crate) count: usize
 }
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct GetBibChipsResponse {
+    pub(crate) bibchips: Vec<BibChip>
+}
@@ -0,0 +1,74 @@
+/*
+Chronokeep Desktop - Race Scoring Software
+Copyright (C) 2026 James Sentinella
+
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU Affero General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+GNU Affero General Public License for more details.
+
+You should have received a copy of the GNU Affero General Public License
+along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::objects::{bibchip::BibChip, read};
+
+use super::{requests::{GetBibChipsRequest, UploadReadsRequest}, responses::GetBibChipsResponse};
+
+#[test]
+fn test_get_bibchips_request() {
+    let request = GetBibChipsRequest {
+        slug: String::from("test-event"),
+        year: String::from("2026"),
+    };
+    let value = serde_json::to_value(&request).unwrap();
+    assert_eq!(serde_json::json!({"slug": "test-event", "year": "2026"}), value);
+}
+
+#[test]
+fn test_get_bibchips_response() {
+    let response: GetBibChipsResponse = serde_json::from_str(r#"{"bibchips":[{"bib":"101","chip":"E2001A"},{"bib":"102","chip":"1002"}],"count":2}"#).unwrap();
+    assert_eq!(vec![
+        BibChip::new(String::from("101"), String::from("E2001A")),
+        BibChip::new(String::from("102"), String::from("1002")),
+    ], response.bibchips);
+    assert!(serde_json::from_str::<GetBibChipsResponse>(r#"{"count":0}"#).is_err());
+}
+
+#[test]
+fn test_upload_reads_request() {
+    let mut reads = vec![
+        read::Read::new(0, String::from("1001"), 100, 0, 100, 0, 1, String::from("reader"), String::from("-50"), 0),
+        read::Read::new(0, String::from("1002"), 101, 0, 101, 0, 1, String::from("reader"), String::from("-50"), 0),
+    ];
+    reads[0].set_bib(Some(String::from("101")));
+    reads[1].set_details(read::ReadDetails {
+        tid: Some(String::from("E2801100")),
+        user_memory: Some(String::from("0102")),
+        channel_index: Some(3),
+        seen_count: Some(4),
+        last_seen_seconds: Some(101),
+        last_seen_milliseconds: Some(500),
+    });
+    let request = UploadReadsRequest::new(&reads);
+    let value = serde_json::to_value(&request).unwrap();
+    let uploaded = value["reads"].as_array().unwrap();
+    assert_eq!(2, uploaded.len());
+    for read in uploaded {
+        for field in ["bib", "tid", "user_memory", "channel_index", "seen_count", "last_seen_seconds", "last_seen_milliseconds"] {
+            assert!(read.get(field).is_none());
+        }
+    }
+    assert_eq!("1001", uploaded[0]["identifier"]);
+    // the reads we were given keep their bib and details for the control socket feed
+    assert_eq!(Some("101"), reads[0].bib());
+    assert_eq!(Some(4), reads[1].details().seen_count);
+    // the control socket feed still carries it
+    let value = serde_json::to_value(&reads[0]).unwrap();
+    assert_eq!("101", value["bib"]);
+}
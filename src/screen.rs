@@ -19,7 +19,7 @@ along with this program.  If not, see <https://www.gnu.org/licenses/>.
 use std::{net::TcpStream, sync::{Arc, Mutex}, thread::JoinHandle};
 use crate::{control::{Control, socket::MAX_CONNECTED, sound::SoundNotifier}, database::sqlite, notifier, processor, reader::{self, auto_connect}, remote::uploader::{Uploader, info::UploadInfo}};
 #[cfg(target_os = "linux")]
-use crate::{types};
+use crate::{database::Database, objects::read_window::ReadWindowConfig, types};
 
 mod ada;
 mod pcf;
@@ -47,18 +47,22 @@ pub const MAIN_RESTART: u8 = 4;
 pub const MAIN_SHUTDOWN: u8 = 5;
 
 pub const SETTINGS_MENU_READ_WINDOW: u8 = 0;
-pub const SETTINGS_MENU_CHIP_TYPE: u8 = 1;
-pub const SETTINGS_MENU_PLAY_SOUND: u8 = 2;
-pub const SETTINGS_MENU_VOLUME: u8 = 3;
-pub const SETTINGS_MENU_BEEP_IGNORE: u8 = 4;
-pub const SETTINGS_MENU_VOICE: u8 = 5;
-pub const SETTINGS_MENU_AUTO_UPLOAD: u8 = 6;
-pub const SETTINGS_MENU_MANUAL_UPLOAD: u8 = 7;
-pub const SETTINGS_MENU_UPLOAD_INTERVAL: u8 = 8;
-pub const SETTINGS_MENU_ENABLE_NTFY: u8 = 9;
-pub const SETTINGS_MENU_DELETE_CHIP_READS: u8 = 10;
-pub const SETTINGS_MENU_SET_TIME_WEB: u8 = 11;
-pub const SETTINGS_MENU_SET_TIME_MANUAL: u8 = 12;
+pub const SETTINGS_MENU_WINDOW_READER: u8 = 1;
+pub const SETTINGS_MENU_WINDOW_ANTENNA: u8 = 2;
+pub const SETTINGS_MENU_READER_WINDOW: u8 = 3;
+pub const SETTINGS_MENU_CHIP_TYPE: u8 = 4;
+pub const SETTINGS_MENU_PLAY_SOUND: u8 = 5;
+pub const SETTINGS_MENU_VOLUME: u8 = 6;
+pub const SETTINGS_MENU_BEEP_IGNORE: u8 = 7;
+pub const SETTINGS_MENU_VOICE: u8 = 8;
+pub const SETTINGS_MENU_AUTO_UPLOAD: u8 = 9;
+pub const SETTINGS_MENU_MANUAL_UPLOAD: u8 = 10;
+pub const SETTINGS_MENU_UPLOAD_INTERVAL: u8 = 11;
+pub const SETTINGS_MENU_ENABLE_NTFY: u8 = 12;
+pub const SETTINGS_MENU_READING_PROFILE: u8 = 13;
+pub const SETTINGS_MENU_DELETE_CHIP_READS: u8 = 14;
+pub const SETTINGS_MENU_SET_TIME_WEB: u8 = 15;
+pub const SETTINGS_MENU_SET_TIME_MANUAL: u8 = 16;
 
 pub const TIME_MENU_YEAR: u8 = 0;
 pub const TIME_MENU_MONTH: u8 = 1;
@@ -99,6 +103,9 @@ pub struct CharacterDisplay {
     seconds: u8,
     volume: u8,
     beep_ignore: u8,
+    // Reader and antenna (0 for the whole reader) whose read window the settings menu is changing.
+    window_reader: usize,
+    window_antenna: u16,
 }
 
 #[allow(unused)]
@@ -171,6 +178,8 @@ impl CharacterDisplay {
             seconds: 0,
             volume: 10,
             beep_ignore: 60,
+            window_reader: 0,
+            window_antenna: 0,
             upload_info: up_info,
         }
     }
@@ -196,8 +205,167 @@ impl CharacterDisplay {
         }
     }
 
+    // Names of the reading profiles saved for any of the readers, and the one being read with.
+    #[cfg(target_os = "linux")]
+    fn reading_profiles(&self) -> (Vec<String>, Option<String>) {
+        let mut names: Vec<String> = Vec::new();
+        let mut active: Option<String> = None;
+        if let Ok(sq) = self.sqlite.lock() {
+            if let Ok(u_readers) = self.readers.lock() {
+                for reader in u_readers.iter() {
+                    match sq.get_profile_config(&reader.id()) {
+                        Ok(config) => {
+                            for profile in config.profiles() {
+                                if !names.iter().any(|name| name == profile.name()) {
+                                    names.push(String::from(profile.name()));
+                                }
+                            }
+                            if active.is_none() {
+                                active = config.active().map(String::from);
+                            }
+                        },
+                        Err(e) => println!("Error getting reading profiles: {e}"),
+                    }
+                }
+            }
+        }
+        (names, active)
+    }
+
+    // Moves every reader with the next (or previous) profile over to it, the default profile sits before the first one.
+    #[cfg(target_os = "linux")]
+    fn cycle_profile(&self, forward: bool) {
+        let (names, active) = self.reading_profiles();
+        let mut options: Vec<Option<String>> = vec![None];
+        options.extend(names.into_iter().map(Some));
+        let current = options.iter().position(|option| *option == active).unwrap_or(0);
+        let next = if forward {
+            (current + 1) % options.len()
+        } else {
+            (current + options.len() - 1) % options.len()
+        };
+        let next = options[next].clone();
+        if let Ok(mut sq) = self.sqlite.lock() {
+            if let Ok(u_readers) = self.readers.lock() {
+                for reader in u_readers.iter() {
+                    let mut config = match sq.get_profile_config(&reader.id()) {
+                        Ok(config) => config,
+                        Err(e) => {
+                            println!("Error getting reading profiles: {e}");
+                            continue;
+                        }
+                    };
+                    // readers without the profile keep reading the way they were
+                    if let Some(name) = &next {
+                        if !config.profiles().iter().any(|profile| profile.name() == name) {
+                            continue;
+                        }
+                    }
+                    if config.active() == next.as_deref() {
+                        continue;
+                    }
+                    config.set_active(next.clone());
+                    match sq.save_profile_config(&reader.id(), &config) {
+                        Ok(_) => reader.change_profiles(config),
+                        Err(e) => println!("Error saving reading profiles: {e}"),
+                    }
+                }
+            }
+        }
+    }
+
+    // The reader whose read window the settings menu is changing, and its read windows.
+    #[cfg(target_os = "linux")]
+    fn window_reader(&self) -> Option<(i64, String, ReadWindowConfig)> {
+        if let Ok(sq) = self.sqlite.lock() {
+            if let Ok(u_readers) = self.readers.lock() {
+                let reader = u_readers.get(self.window_reader)?;
+                return match sq.get_read_window_config(&reader.id()) {
+                    Ok(config) => Some((reader.id(), String::from(reader.nickname()), config)),
+                    Err(e) => {
+                        println!("Error getting read windows: {e}");
+                        None
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    #[cfg(target_os = "linux")]
+    fn next_window_reader(&self, forward: bool) -> usize {
+        let count = match self.readers.lock() {
+            Ok(u_readers) => u_readers.len(),
+            Err(_) => 0,
+        };
+        if count == 0 {
+            return 0
+        }
+        if forward {
+            (self.window_reader + 1) % count
+        } else {
+            (self.window_reader + count - 1) % count
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn next_window_antenna(&self, forward: bool) -> u16 {
+        let count = reader::MAX_ANTENNAS as u16 + 1;
+        if forward {
+            (self.window_antenna + 1) % count
+        } else {
+            (self.window_antenna + count - 1) % count
+        }
+    }
+
+    // Raises or lowers the read window for the reader and antenna picked in the settings menu. Lowering it
+    // past the minimum removes it, so the antenna goes back to the reader's window and the reader to the global one.
+    #[cfg(target_os = "linux")]
+    fn change_read_window(&self, up: bool, global: u8) {
+        let (id, _, mut config) = match self.window_reader() {
+            Some(v) => v,
+            None => return,
+        };
+        let (current, fallback) = if self.window_antenna == 0 {
+            (config.read_window(), global)
+        } else {
+            (config.antenna_read_window(self.window_antenna), config.read_window().unwrap_or(global))
+        };
+        let next = match (current, up) {
+            (None, true) => Some(fallback.clamp(5, 50)),
+            (Some(window), true) if window < 50 => Some(window + 1),
+            (Some(window), false) if window > 5 => Some(window - 1),
+            (Some(_), false) => None,
+            (current, _) => current,
+        };
+        if next == current {
+            return
+        }
+        if self.window_antenna == 0 {
+            config.set_read_window(next);
+        } else {
+            config.set_antenna_read_window(self.window_antenna, next);
+        }
+        if let Ok(mut sq) = self.sqlite.lock() {
+            match sq.save_read_window_config(&id, &config) {
+                Ok(_) => {
+                    if let Ok(u_readers) = self.readers.lock() {
+                        if let Some(reader) = u_readers.iter().find(|r| r.id() == id) {
+                            if let Ok(mut windows) = reader.read_windows.lock() {
+                                *windows = config;
+                            }
+                        }
+                    }
+                },
+                Err(e) => println!("Error saving read windows: {e}"),
+            }
+        }
+    }
+
     #[cfg(target_os = "linux")]
     fn update_settings(&mut self) {
+        let (_, active_profile) = self.reading_profiles();
+        let window_reader = self.window_reader();
         if let Ok(mut info) = self.info.lock() {
             info.settings_menu.clear();
             if let Ok(control) = self.control.lock() {
@@ -216,6 +384,25 @@ impl CharacterDisplay {
                 self.volume = (control.volume * 10.0) as u8;
                 self.beep_ignore = control.beep_ignore;
                 info.settings_menu.push(format!("   Read Window {:>4} ", control.read_window));
+                let (reader_name, reader_window) = match &window_reader {
+                    Some((_, name, config)) => {
+                        let window = if self.window_antenna == 0 {
+                            config.read_window()
+                        } else {
+                            config.antenna_read_window(self.window_antenna)
+                        };
+                        (name.chars().take(8).collect(), window.map(|w| w.to_string()).unwrap_or(String::from("-")))
+                    },
+                    None => (String::from("none"), String::from("-")),
+                };
+                let antenna = if self.window_antenna == 0 { String::from("all") } else { self.window_antenna.to_string() };
+                info.settings_menu.push(format!("   Reader  {:>8} ", reader_name));
+                info.settings_menu.push(format!("   Antenna     {:>4} ", antenna));
+                if self.window_antenna == 0 {
+                    info.settings_menu.push(format!("   Rdr Window  {:>4} ", reader_window));
+                } else {
+                    info.settings_menu.push(format!("   Ant Window  {:>4} ", reader_window));
+                }
                 info.settings_menu.push(format!("   Chip Type   {:>4} ", control.chip_type));
                 info.settings_menu.push(format!("   Play Sounds {:>4} ", play_sound));
                 info.settings_menu.push(format!("   Volume      {:>4} ", self.volume));
@@ -225,6 +412,8 @@ impl CharacterDisplay {
                 info.settings_menu.push(format!("   Manual Upload    "));
                 info.settings_menu.push(format!("   Upload Int  {:>4} ", control.upload_interval));
                 info.settings_menu.push(format!("   Enable NTFY {:>4} ", enable_ntfy));
+                let profile: String = active_profile.unwrap_or(String::from("default")).chars().take(8).collect();
+                info.settings_menu.push(format!("   Profile {:>8} ", profile));
                 info.settings_menu.push(String::from("   Delete Reads     "));
                 info.settings_menu.push(String::from("   Set Time (Web)   "));
                 info.settings_menu.push(String::from("   Set Time (Manual)"));
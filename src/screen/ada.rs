@@ -35,7 +35,7 @@ use crate::{reader::{self, auto_connect}, remote::uploader::Status};
 #[cfg(target_os = "linux")]
 use crate::{control::{SETTING_AUTO_REMOTE, SETTING_CHIP_TYPE, SETTING_ENABLE_NTFY, SETTING_PLAY_SOUND, SETTING_READ_WINDOW, SETTING_UPLOAD_INTERVAL, SETTING_VOICE, SETTING_VOLUME, SETTING_BEEP_IGNORE, socket::{self, CONNECTION_CHANGE_PAUSE, UPDATE_SCRIPT_ENV}, sound::SoundType}, database::Database, network::api, objects::{read, setting::Setting}, reader::reconnector::Reconnector, remote::remote_util, sound_board::Voice, types};
 #[cfg(target_os = "linux")]
-use crate::screen::{ButtonPress, ABOUT_MENU, DELETE_READS_MENU, DELETE_READS_MENU_TWO, MAIN_ABOUT, MAIN_MENU, MAIN_RESTART, MAIN_SETTINGS, MAIN_SHUTDOWN, MAIN_START_READING, MAIN_UPDATE, MANUAL_TIME_MENU, READING_MENU, RESTART_MENU, SCREEN_OFF, SETTINGS_MENU_AUTO_UPLOAD, SETTINGS_MENU_CHIP_TYPE, SETTINGS_MENU_DELETE_CHIP_READS, SETTINGS_MENU_ENABLE_NTFY, SETTINGS_MENU_MANUAL_UPLOAD, SETTINGS_MENU, SETTINGS_MENU_PLAY_SOUND, SETTINGS_MENU_READ_WINDOW, SETTINGS_MENU_SET_TIME_MANUAL, SETTINGS_MENU_SET_TIME_WEB, SETTINGS_MENU_UPLOAD_INTERVAL, SETTINGS_MENU_VOICE, SETTINGS_MENU_VOLUME, SETTINGS_MENU_BEEP_IGNORE, SHUTDOWN_MENU, STARTUP_MENU, TIME_MENU_DAY, TIME_MENU_HOUR, TIME_MENU_MINUTE, TIME_MENU_MONTH, TIME_MENU_SECOND, TIME_MENU_YEAR, UPDATE_MENU, READING_MENU_STOP, READING_MENU_NIL, READING_MENU_UPLOAD};
+use crate::screen::{ButtonPress, ABOUT_MENU, DELETE_READS_MENU, DELETE_READS_MENU_TWO, MAIN_ABOUT, MAIN_MENU, MAIN_RESTART, MAIN_SETTINGS, MAIN_SHUTDOWN, MAIN_START_READING, MAIN_UPDATE, MANUAL_TIME_MENU, READING_MENU, RESTART_MENU, SCREEN_OFF, SETTINGS_MENU_AUTO_UPLOAD, SETTINGS_MENU_CHIP_TYPE, SETTINGS_MENU_DELETE_CHIP_READS, SETTINGS_MENU_ENABLE_NTFY, SETTINGS_MENU_MANUAL_UPLOAD, SETTINGS_MENU, SETTINGS_MENU_PLAY_SOUND, SETTINGS_MENU_READ_WINDOW, SETTINGS_MENU_READER_WINDOW, SETTINGS_MENU_READING_PROFILE, SETTINGS_MENU_SET_TIME_MANUAL, SETTINGS_MENU_SET_TIME_WEB, SETTINGS_MENU_UPLOAD_INTERVAL, SETTINGS_MENU_VOICE, SETTINGS_MENU_VOLUME, SETTINGS_MENU_BEEP_IGNORE, SETTINGS_MENU_WINDOW_ANTENNA, SETTINGS_MENU_WINDOW_READER, SHUTDOWN_MENU, STARTUP_MENU, TIME_MENU_DAY, TIME_MENU_HOUR, TIME_MENU_MINUTE, TIME_MENU_MONTH, TIME_MENU_SECOND, TIME_MENU_YEAR, UPDATE_MENU, READING_MENU_STOP, READING_MENU_NIL, READING_MENU_UPLOAD};
 
 use super::CharacterDisplay;
 
@@ -525,6 +525,19 @@ impl CharacterDisplay {
                                                     }
                                                 }
                                             }
+                                            SETTINGS_MENU_WINDOW_READER => {  // Reader for Read Window
+                                                self.window_reader = self.next_window_reader(false);
+                                                self.window_antenna = 0;
+                                            }
+                                            SETTINGS_MENU_WINDOW_ANTENNA => {  // Antenna for Read Window
+                                                self.window_antenna = self.next_window_antenna(false);
+                                            }
+                                            SETTINGS_MENU_READER_WINDOW => {  // Reader/Antenna Read Window
+                                                self.change_read_window(false, control.read_window);
+                                            }
+                                            SETTINGS_MENU_READING_PROFILE => {  // Reading Profile
+                                                self.cycle_profile(false);
+                                            }
                                             _ => {}
                                         }
                                     }
@@ -911,6 +924,19 @@ impl CharacterDisplay {
                                                     }
                                                 }
                                             }
+                                            SETTINGS_MENU_WINDOW_READER => {  // Reader for Read Window
+                                                self.window_reader = self.next_window_reader(true);
+                                                self.window_antenna = 0;
+                                            }
+                                            SETTINGS_MENU_WINDOW_ANTENNA => {  // Antenna for Read Window
+                                                self.window_antenna = self.next_window_antenna(true);
+                                            }
+                                            SETTINGS_MENU_READER_WINDOW => {  // Reader/Antenna Read Window
+                                                self.change_read_window(true, control.read_window);
+                                            }
+                                            SETTINGS_MENU_READING_PROFILE => {  // Reading Profile
+                                                self.cycle_profile(true);
+                                            }
                                             _ => {}
                                         }
                                     }
@@ -1445,8 +1471,22 @@ impl CharacterDisplay {
                                                     reader::helpers::antenna_status_str(ants[7]),
                                                 ));
                                         }
+                                        if let Ok(tracker) = read.stats.lock() {
+                                            info.reader_info.push(reader::helpers::stats_str(&tracker.stats(reader::stats::now())));
+                                        }
+                                        continue;
                                     }
                                 }
+                                if let Some(attempt) = read.reconnecting() {
+                                    info.reader_info.push(format!("{} reconnecting", read.nickname()));
+                                    info.reader_info.push(format!("(attempt {attempt})"));
+                                }
+                            }
+                        }
+                        // bibs can be longer than the display, so only show what fits
+                        if let Ok(control) = self.control.lock() {
+                            if let Some(bib) = &control.last_bib {
+                                info.reader_info.push(format!("Last Bib {:.11}", bib));
                             }
                         }
                         // END update_readers() code